@@ -1,8 +1,9 @@
 use clap::Parser;
-use m13_linux::{TunDevice, LinuxUdp, LinuxHsm, LinuxClock};
-use m13_ulk::{M13Kernel, KernelConfig};
-use m13_mem::SlabAllocator;
-use m13_pqc::DsaKeypair;
+use m13_linux::{TunDevice, LinuxUdp, LinuxHsm, LinuxClock, RuntimeConfig};
+use m13_ulk::{M13Kernel, KernelConfig, DEFAULT_REKEY_INTERVAL_GENS, DEFAULT_SESSION_IDLE_TIMEOUT_US, DEFAULT_KEEPALIVE_INTERVAL_US};
+use m13_ulk::{DEFAULT_SYMBOL_SIZE, DEFAULT_CBR_FLOOR_BPS};
+use m13_mem::{SlabAllocator, SlabClass, FRAME_SIZE, MTU_FRAME_SIZE, SMALL_FRAME_SIZE};
+use m13_pqc::{DsaKeypair, KemProfile};
 use log::{info, warn};
 
 // [PHYSICS] PLATFORM SPECIFIC IMPORTS (LINUX ONLY)
@@ -26,11 +27,36 @@ static GLOBAL: Jemalloc = Jemalloc;
 struct Cli {
     #[arg(long, default_value = "0.0.0.0:443")] bind: String,
     #[arg(long, default_value = "m13hub0")] iface: String, 
+    /// TOML file of tunables (slab sizes, symbol size, CBR floor, GSO segment, idle timeout).
+    #[arg(long)] config: Option<std::path::PathBuf>,
 }
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
     let cli = Cli::parse();
+    // Fail on a bad config before touching the system.
+    let tunables = match &cli.config {
+        Some(path) => RuntimeConfig::load(path)?,
+        None => RuntimeConfig::default(),
+    };
+
+    let config = KernelConfig {
+        is_hub: true,
+        enable_encryption: true,
+        hybrid_kex: false, // Hub auto-detects hybrid ClientHellos
+        kem_profile: KemProfile::default(), // Hub auto-detects the offered profile
+        rekey_interval_gens: DEFAULT_REKEY_INTERVAL_GENS,
+        session_idle_timeout_us: tunables.session_idle_timeout_us.unwrap_or(DEFAULT_SESSION_IDLE_TIMEOUT_US),
+        jitter_buffer: false, // Bulk VPN traffic: No playout delay
+        keepalive_interval_us: DEFAULT_KEEPALIVE_INTERVAL_US,
+        chaff: false, // Cover traffic would hold egress at the CBR floor even when idle
+        symbol_size: tunables.symbol_size.unwrap_or(DEFAULT_SYMBOL_SIZE),
+        cbr_floor_bps: tunables.cbr_floor_bps.unwrap_or(DEFAULT_CBR_FLOOR_BPS),
+        gso_segment_size: tunables.gso_segment_size.unwrap_or(0),
+        relay: false, // Terminates traffic; never recodes it onward
+        reliable_fragments: false, // Lost handshake replies are recovered by ClientHello retransmission
+    };
+    config.validate().map_err(|e| anyhow::anyhow!("invalid kernel config: {e}"))?;
 
     // [COSMETIC UPDATE] v0.3.0 Identity
     info!(">>> M13 HUB: v0.3.0 (System Physics & Egress Offload) <<<");
@@ -94,17 +120,25 @@ fn main() -> anyhow::Result<()> {
     m13_linux::setup::configure_hub(tun.name(), "10.13.13.1/24")?;
 
     let phy = LinuxUdp::new(&cli.bind, None)?;
-    let mem = SlabAllocator::new(8192); 
+    let mem = SlabAllocator::with_classes(&[
+        SlabClass::fixed(SMALL_FRAME_SIZE, tunables.slab_small_frames.unwrap_or(1024)), // KeepAlive / ACK
+        SlabClass::fixed(MTU_FRAME_SIZE, tunables.slab_mtu_frames.unwrap_or(512)),    // Handshake fragments
+        SlabClass::fixed(FRAME_SIZE, tunables.slab_large_frames.unwrap_or(8192)),
+    ]);
     let mut rng = rand::thread_rng();
     let identity = DsaKeypair::generate(&mut rng)?; 
 
-    let config = KernelConfig {
-        is_hub: true,
-        enable_encryption: true,
+    // [IDLE] Sleep on the socket and TUN between bursts instead of spinning a core.
+    #[cfg(target_os = "linux")]
+    let mut reactor = {
+        let mut r = m13_linux::Reactor::new()?;
+        r.register(std::os::unix::io::AsRawFd::as_raw_fd(&phy))?;
+        r.register(tun.fd())?;
+        r
     };
 
     let mut kernel = M13Kernel::new(
-        Box::new(phy), Box::new(LinuxHsm), Box::new(LinuxClock::new()), 
+        Box::new(phy), Box::new(LinuxHsm::new()), Box::new(LinuxClock::new()), 
         mem, config, identity
     );
 
@@ -134,8 +168,11 @@ fn main() -> anyhow::Result<()> {
             work_done = true;
         }
 
-        // 4. ADAPTIVE YIELD
+        // 4. IDLE: Wait for traffic or the kernel's next timer.
         if !work_done {
+            #[cfg(target_os = "linux")]
+            { let _ = reactor.wait_until(kernel.next_deadline_us(), kernel.now_us()); }
+            #[cfg(not(target_os = "linux"))]
             std::thread::yield_now();
         }
     }
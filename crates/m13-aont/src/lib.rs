@@ -8,17 +8,33 @@ use m13_math::{GfSymbol};
 use zeroize::Zeroize;
 use rand_core::{RngCore, CryptoRng};
 
-mod matrix;
-mod solver;
+pub mod matrix;
+pub mod solver;
 use matrix::generate_cauchy_matrix;
 
 /// Appendix B.1: Mode B Size Limit (64 bytes).
 const MODE_B_MAX_SIZE: usize = 64;
 
+/// Streaming Mode B: Per-block length prefix (u16 BE, transformed size).
+const STREAM_PREFIX_LEN: usize = 2;
+/// Golden-ratio stride for per-block seed derivation.
+const STREAM_SEED_STRIDE: u32 = 0x9E37_79B9;
+/// Domain separator between a block's Mode B matrix and its stream mask matrix.
+const STREAM_MASK_TWEAK: u32 = 0x5A5A_5A5A;
+
+/// Authenticated Mode A: Truncated HMAC-SHA256 of the plaintext, mixed in with it.
+pub const AUTH_TAG_LEN: usize = 16;
+/// Domain separator between the Mode A matrix and the tag key drawn from the same seed.
+const AUTH_KEY_TWEAK: u32 = 0xA7A7_A7A7;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PrivacyMode {
     ModeA, // Bulk
     ModeB, // Critical
+    /// Mode A over `[M || tag]`. The tag sits inside the all-or-nothing mix: It cannot
+    /// be stripped without destroying M, and any flipped bit fails `recover` with `AuthFail`.
+    /// Payloads up to `128 - AUTH_TAG_LEN` bytes.
+    ModeAAuth, // Bulk + Integrity
 }
 
 pub struct AontTransform;
@@ -40,6 +56,19 @@ impl AontTransform {
                 let output_gf = mat.mul_vec(&input)?;
                 Ok(output_gf.iter().map(|s| s.0).collect())
             },
+
+            PrivacyMode::ModeAAuth => {
+                let mut key = auth_key(seed);
+                let tag = m13_cipher::mac_tag(&key, payload);
+                key.zeroize();
+
+                let mut framed = Vec::with_capacity(size + AUTH_TAG_LEN);
+                framed.extend_from_slice(payload);
+                framed.extend_from_slice(&tag);
+                let out = Self::transform(&framed, seed, PrivacyMode::ModeA, rng);
+                framed.zeroize();
+                out
+            },
             
             PrivacyMode::ModeB => {
                 // Entropy Guard
@@ -62,20 +91,9 @@ impl AontTransform {
 
                 // 4. Mix: Y = L * V
                 let mat = generate_cauchy_matrix(size * 2, seed)?;
-                let mut output = Vec::with_capacity(size * 2);
-
-                // CRITICAL: Manual Constant-Time Loop
-                let dim = size * 2;
-                for r in 0..dim {
-                    let mut acc = GfSymbol::ZERO;
-                    for c in 0..dim {
-                        let coeff = mat.get(r, c).ok_or(M13Error::InvalidState)?;
-                        let val = v_vec[c];
-                        acc = acc + coeff.mul_safe(val); // Constant Time
-                    }
-                    output.push(acc.0);
-                }
-                Ok(output)
+                let output = mul_ct(&mat, &v_vec);
+                v_vec.zeroize();
+                output
             }
         }
     }
@@ -92,14 +110,31 @@ impl AontTransform {
                 let out = inv.mul_vec(&input)?;
                 Ok(out.iter().map(|s| s.0).collect())
             },
+            PrivacyMode::ModeAAuth => {
+                if size < AUTH_TAG_LEN { return Err(M13Error::WireFormatError); }
+                let mut framed = Self::recover(transformed, seed, PrivacyMode::ModeA)?;
+                let (body, tag) = framed.split_at(size - AUTH_TAG_LEN);
+                let tag: [u8; AUTH_TAG_LEN] = tag.try_into().map_err(|_| M13Error::WireFormatError)?;
+
+                let mut key = auth_key(seed);
+                let ok = m13_cipher::mac_verify(&key, body, &tag);
+                key.zeroize();
+
+                if !ok {
+                    framed.zeroize();
+                    return Err(M13Error::AuthFail);
+                }
+                framed.truncate(size - AUTH_TAG_LEN);
+                Ok(framed)
+            },
             PrivacyMode::ModeB => {
                 // Mode B matrix is 2N x 2N
                 let mat = generate_cauchy_matrix(size, seed)?;
-                let inv = solver::invert_matrix(&mat)?;
+                let inv = solver::invert_matrix_ct(&mat)?;
                 
-                // Recover V = [C | R]
+                // Recover V = [C | R] (constant time: V holds the OTP)
                 let input: Vec<GfSymbol> = transformed.iter().map(|&b| GfSymbol(b)).collect();
-                let v = inv.mul_vec(&input)?;
+                let mut v = mul_ct(&inv, &input)?;
                 
                 if v.len() % 2 != 0 { return Err(M13Error::WireFormatError); }
                 let mid = v.len() / 2;
@@ -110,10 +145,208 @@ impl AontTransform {
                 // Unmask M = C ^ R
                 let mut m = Vec::with_capacity(mid);
                 for i in 0..mid {
-                    m.push((c_part[i] + r_part[i]).0);
+                    m.push(c_part[i] ^ r_part[i]);
                 }
+                v.zeroize();
                 Ok(m)
             }
         }
     }
-}
\ No newline at end of file
+
+    /// `transform` over many payloads. Mode A generates one Cauchy matrix per distinct
+    /// payload length and mixes every payload of that length in a single `GfMatrix::mul`.
+    /// Mode B draws a fresh OTP per payload and ModeAAuth tags each one, so they stay
+    /// one `transform` each.
+    pub fn transform_batch<R: RngCore + CryptoRng>(
+        payloads: &[&[u8]],
+        seed: u32,
+        mode: PrivacyMode,
+        rng: &mut R
+    ) -> M13Result<Vec<Vec<u8>>> {
+        match mode {
+            PrivacyMode::ModeA => {
+                apply_batched(payloads, |size| generate_cauchy_matrix(size, seed))
+            },
+            PrivacyMode::ModeB | PrivacyMode::ModeAAuth => {
+                payloads.iter().map(|p| Self::transform(p, seed, mode, rng)).collect()
+            }
+        }
+    }
+
+    /// Inverse of `transform_batch`. Mode A inverts each distinct matrix once.
+    pub fn recover_batch(transformed: &[&[u8]], seed: u32, mode: PrivacyMode) -> M13Result<Vec<Vec<u8>>> {
+        match mode {
+            PrivacyMode::ModeA => {
+                apply_batched(transformed, |size| solver::invert_matrix(&generate_cauchy_matrix(size, seed)?))
+            },
+            PrivacyMode::ModeB | PrivacyMode::ModeAAuth => {
+                transformed.iter().map(|y| Self::recover(y, seed, mode)).collect()
+            }
+        }
+    }
+
+    /// Streaming Mode B for payloads above `MODE_B_MAX_SIZE`.
+    ///
+    /// Each 64-byte block gets its own OTP and 2N x 2N Cauchy mix (`transform`).
+    /// Blocks are first masked with `Cauchy_i * S` for a random 64-byte stream secret S.
+    /// A trailing key block carries `S ^ D`, where D folds every transformed byte, so a
+    /// single corrupted output byte yields a wrong S and garbles every block.
+    ///
+    /// Wire format: `[len u16 BE][block]*` with the key block last.
+    pub fn transform_stream<R: RngCore + CryptoRng>(
+        payload: &[u8],
+        seed: u32,
+        rng: &mut R
+    ) -> M13Result<Vec<u8>> {
+        let blocks = payload.len().div_ceil(MODE_B_MAX_SIZE);
+        let mut out = Vec::with_capacity(payload.len() * 2 + (blocks + 1) * (STREAM_PREFIX_LEN + MODE_B_MAX_SIZE));
+
+        // 1. Stream Secret (S)
+        let mut secret = [0u8; MODE_B_MAX_SIZE];
+        rng.fill_bytes(&mut secret);
+        let secret_gf: Vec<GfSymbol> = secret.iter().map(|&b| GfSymbol(b)).collect();
+
+        let mut digest = [0u8; MODE_B_MAX_SIZE];
+
+        for (i, chunk) in payload.chunks(MODE_B_MAX_SIZE).enumerate() {
+            let block_seed = stream_block_seed(seed, i);
+
+            // 2. Chain: M'_i = M_i ^ (Cauchy_i * S)
+            let mut mask = stream_mask(&secret_gf, block_seed)?;
+            let mut masked: Vec<u8> = chunk.iter().zip(mask.iter()).map(|(m, k)| m ^ k).collect();
+            mask.zeroize();
+
+            // 3. Per-block Mode B (fresh OTP, constant-time mix)
+            let y = Self::transform(&masked, block_seed, PrivacyMode::ModeB, rng)?;
+            masked.zeroize();
+
+            fold_digest(&mut digest, &y);
+            out.extend_from_slice(&(y.len() as u16).to_be_bytes());
+            out.extend_from_slice(&y);
+        }
+
+        // 4. Key Block: T = S ^ D
+        let mut key_block = [0u8; MODE_B_MAX_SIZE];
+        for k in 0..MODE_B_MAX_SIZE { key_block[k] = secret[k] ^ digest[k]; }
+        secret.zeroize();
+
+        let y = Self::transform(&key_block, stream_block_seed(seed, blocks), PrivacyMode::ModeB, rng)?;
+        key_block.zeroize();
+        out.extend_from_slice(&(y.len() as u16).to_be_bytes());
+        out.extend_from_slice(&y);
+
+        Ok(out)
+    }
+
+    /// Inverse of `transform_stream`. Requires every byte of every block.
+    pub fn recover_stream(stream: &[u8], seed: u32) -> M13Result<Vec<u8>> {
+        // 1. Split into blocks
+        let mut blocks: Vec<&[u8]> = Vec::new();
+        let mut cursor = 0;
+        while cursor < stream.len() {
+            if cursor + STREAM_PREFIX_LEN > stream.len() { return Err(M13Error::WireFormatError); }
+            let len = u16::from_be_bytes([stream[cursor], stream[cursor + 1]]) as usize;
+            cursor += STREAM_PREFIX_LEN;
+            if len == 0 || !len.is_multiple_of(2) || len > MODE_B_MAX_SIZE * 2 || cursor + len > stream.len() {
+                return Err(M13Error::WireFormatError);
+            }
+            blocks.push(&stream[cursor..cursor + len]);
+            cursor += len;
+        }
+
+        let (key_y, data_blocks) = blocks.split_last().ok_or(M13Error::WireFormatError)?;
+        if key_y.len() != MODE_B_MAX_SIZE * 2 { return Err(M13Error::WireFormatError); }
+
+        // 2. Recover S = T ^ D (needs all transformed bytes)
+        let mut digest = [0u8; MODE_B_MAX_SIZE];
+        for y in data_blocks { fold_digest(&mut digest, y); }
+
+        let mut key_block = Self::recover(key_y, stream_block_seed(seed, data_blocks.len()), PrivacyMode::ModeB)?;
+        let mut secret_gf: Vec<GfSymbol> = key_block.iter().zip(digest.iter()).map(|(t, d)| GfSymbol(t ^ d)).collect();
+        key_block.zeroize();
+
+        // 3. Unwind each block
+        let mut out = Vec::with_capacity(data_blocks.len() * MODE_B_MAX_SIZE);
+        for (i, y) in data_blocks.iter().enumerate() {
+            let block_seed = stream_block_seed(seed, i);
+            let mut masked = Self::recover(y, block_seed, PrivacyMode::ModeB)?;
+            let mut mask = stream_mask(&secret_gf, block_seed)?;
+            out.extend(masked.iter().zip(mask.iter()).map(|(m, k)| m ^ k));
+            masked.zeroize();
+            mask.zeroize();
+        }
+        secret_gf.zeroize();
+
+        Ok(out)
+    }
+}
+
+/// Mode A batching: Payloads of length n become the columns of an n x count matrix B,
+/// mixed at once as `matrix_for(n) * B`. Output order matches input order.
+fn apply_batched<F>(inputs: &[&[u8]], mut matrix_for: F) -> M13Result<Vec<Vec<u8>>>
+where
+    F: FnMut(usize) -> M13Result<m13_math::GfMatrix>,
+{
+    let mut outputs: Vec<Vec<u8>> = alloc::vec![Vec::new(); inputs.len()];
+    let mut done = alloc::vec![false; inputs.len()];
+
+    for first in 0..inputs.len() {
+        if done[first] { continue; }
+        let size = inputs[first].len();
+        let group: Vec<usize> = (first..inputs.len()).filter(|&i| !done[i] && inputs[i].len() == size).collect();
+        for &i in &group { done[i] = true; }
+        if size == 0 { continue; }
+
+        let mat = matrix_for(size)?;
+        let mut columns = m13_math::GfMatrix::new(size, group.len());
+        for (c, &i) in group.iter().enumerate() {
+            for (r, &b) in inputs[i].iter().enumerate() { columns.set(r, c, GfSymbol(b)); }
+        }
+        let mixed = mat.mul(&columns)?;
+        columns.zeroize();
+
+        for (c, &i) in group.iter().enumerate() {
+            outputs[i] = (0..size).map(|r| mixed.get(r, c).map(|s| s.0).ok_or(M13Error::InvalidState)).collect::<M13Result<_>>()?;
+        }
+    }
+    Ok(outputs)
+}
+
+/// CRITICAL: Manual Constant-Time Loop (Y = L * V)
+fn mul_ct(mat: &m13_math::GfMatrix, v: &[GfSymbol]) -> M13Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(mat.rows);
+    for r in 0..mat.rows {
+        let mut acc = GfSymbol::ZERO;
+        for (c, &val) in v.iter().enumerate().take(mat.cols) {
+            let coeff = mat.get(r, c).ok_or(M13Error::InvalidState)?;
+            acc = acc + coeff.mul_safe(val); // Constant Time
+        }
+        output.push(acc.0);
+    }
+    Ok(output)
+}
+
+/// ModeAAuth tag key: Keystream under a tweaked seed, independent of the mixing matrix.
+fn auth_key(seed: u32) -> [u8; 32] {
+    let mut stream = m13_cipher::generate_coefficients(seed ^ AUTH_KEY_TWEAK, 0, 32);
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&stream);
+    stream.zeroize();
+    key
+}
+
+fn stream_block_seed(seed: u32, index: usize) -> u32 {
+    seed ^ (index as u32).wrapping_add(1).wrapping_mul(STREAM_SEED_STRIDE)
+}
+
+/// Cauchy_i * S: Every mask byte depends on every byte of S.
+fn stream_mask(secret: &[GfSymbol], block_seed: u32) -> M13Result<Vec<u8>> {
+    let mat = generate_cauchy_matrix(MODE_B_MAX_SIZE, block_seed ^ STREAM_MASK_TWEAK)?;
+    mul_ct(&mat, secret)
+}
+
+fn fold_digest(digest: &mut [u8; MODE_B_MAX_SIZE], data: &[u8]) {
+    for (j, &b) in data.iter().enumerate() {
+        digest[j % MODE_B_MAX_SIZE] ^= b;
+    }
+}
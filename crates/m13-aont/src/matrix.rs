@@ -5,11 +5,17 @@ use m13_cipher::generate_coefficients;
 extern crate alloc;
 use alloc::vec::Vec;
 
+/// Largest Cauchy matrix GF(2^8) can supply: X and Y need `2 * size` distinct nodes.
+pub const MAX_CAUCHY_SIZE: usize = 128;
+
 /// Generates a guaranteed invertible Cauchy Matrix from a seed.
 /// L[i,j] = 1 / (x[i] + y[j])
+/// With the x nodes distinct, the y nodes distinct and X, Y disjoint, every square Cauchy
+/// matrix is non-singular: Invertibility holds for every seed, not just most of them.
+/// `EntropyExhaustion` when `size > MAX_CAUCHY_SIZE`: The field has run out of nodes.
 pub fn generate_cauchy_matrix(size: usize, seed: u32) -> M13Result<GfMatrix> {
-    if size * 2 > 256 {
-        return Err(M13Error::InvalidState); // Matrix too large for field
+    if size > MAX_CAUCHY_SIZE {
+        return Err(M13Error::EntropyExhaustion);
     }
 
     let mut mat = GfMatrix::new(size, size);
@@ -27,14 +33,15 @@ pub fn generate_cauchy_matrix(size: usize, seed: u32) -> M13Result<GfMatrix> {
     }
 
     // 2. Split into X and Y sets (Disjoint by definition)
+    // A permutation never repeats a node, so both sets are distinct and X ∩ Y = ∅.
     let x_set = &elements[0..size];
     let y_set = &elements[size..2*size];
 
     // 3. Fill Matrix
-    for r in 0..size {
-        for c in 0..size {
-            let x = GfSymbol(x_set[r]);
-            let y = GfSymbol(y_set[c]);
+    for (r, &xr) in x_set.iter().enumerate() {
+        for (c, &yc) in y_set.iter().enumerate() {
+            let x = GfSymbol(xr);
+            let y = GfSymbol(yc);
             
             // Cauchy Denominator: x + y (XOR in GF2^8)
             // Since X and Y are disjoint, x != y, so sum != 0. Division is safe.
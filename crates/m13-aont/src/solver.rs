@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
 use m13_math::{GfMatrix, GfSymbol};
 use m13_core::{M13Error, M13Result};
+use zeroize::Zeroize;
 
 /// Inverts a square GF(2^8) matrix using Gaussian Elimination.
 pub fn invert_matrix(matrix: &GfMatrix) -> M13Result<GfMatrix> {
@@ -52,17 +54,90 @@ pub fn invert_matrix(matrix: &GfMatrix) -> M13Result<GfMatrix> {
             if row != i {
                 let factor = a.get(row, i).unwrap();
                 if factor != GfSymbol::ZERO {
-                    for col in 0..n {
-                        let val_a = a.get(row, col).unwrap() - (factor * a.get(i, col).unwrap());
-                        a.set(row, col, val_a);
-                        
-                        let val_inv = inv.get(row, col).unwrap() - (factor * inv.get(i, col).unwrap());
-                        inv.set(row, col, val_inv);
-                    }
+                    a.row_add_scaled_inplace(row, i, factor)?;
+                    inv.row_add_scaled_inplace(row, i, factor)?;
                 }
             }
         }
     }
 
     Ok(inv)
-}
\ No newline at end of file
+}
+
+/// 0xFF if `x == 0`, else 0x00. No branch.
+#[inline(always)]
+fn ct_is_zero(x: u8) -> u8 {
+    ((x as u16).wrapping_sub(1) >> 8) as u8
+}
+
+/// `dest ^= src * factor`, every byte, `mul_safe` only.
+#[inline(always)]
+fn ct_row_add_scaled(dest: &mut [GfSymbol], src: &[GfSymbol], factor: GfSymbol) {
+    for (d, &s) in dest.iter_mut().zip(src) { *d = *d + s.mul_safe(factor); }
+}
+
+/// CRITICAL: Constant-time variant of `invert_matrix`, for secret-adjacent matrices (AONT Mode B).
+/// No table lookups (`mul_safe`/`inv_safe` only), and no branch or early return on matrix
+/// contents: Pivoting is a masked row add, every row and column is processed, and a
+/// singular matrix is only reported once the whole elimination has run.
+pub fn invert_matrix_ct(matrix: &GfMatrix) -> M13Result<GfMatrix> {
+    if matrix.rows != matrix.cols {
+        return Err(M13Error::InvalidState);
+    }
+    let n = matrix.rows;
+
+    // Flat row-major scratch: Row i = [i * n .. (i + 1) * n].
+    let mut a: Vec<GfSymbol> = Vec::with_capacity(n * n);
+    for r in 0..n {
+        for c in 0..n { a.push(matrix.get(r, c).ok_or(M13Error::InvalidState)?); }
+    }
+    let mut inv = alloc::vec![GfSymbol::ZERO; n * n];
+    for i in 0..n { inv[i * n + i] = GfSymbol::ONE; }
+
+    let mut singular = 0u8;
+    let mut row_i = alloc::vec![GfSymbol::ZERO; n];
+    let mut inv_i = alloc::vec![GfSymbol::ZERO; n];
+
+    for i in 0..n {
+        // 1. Pivot: While a[i][i] is zero, fold in each lower row. Adding (not swapping)
+        // keeps the row space, and the mask makes every candidate cost the same.
+        for r in (i + 1)..n {
+            let mask = GfSymbol(ct_is_zero(a[i * n + i].0));
+            for c in 0..n {
+                a[i * n + c] = a[i * n + c] + GfSymbol(a[r * n + c].0 & mask.0);
+                inv[i * n + c] = inv[i * n + c] + GfSymbol(inv[r * n + c].0 & mask.0);
+            }
+        }
+        singular |= ct_is_zero(a[i * n + i].0);
+
+        // 2. Normalize (a zero pivot stays zero: The result is discarded anyway)
+        let pivot_inv = a[i * n + i].inv_safe();
+        for c in 0..n {
+            a[i * n + c] = a[i * n + c].mul_safe(pivot_inv);
+            inv[i * n + c] = inv[i * n + c].mul_safe(pivot_inv);
+        }
+        row_i.copy_from_slice(&a[i * n..(i + 1) * n]);
+        inv_i.copy_from_slice(&inv[i * n..(i + 1) * n]);
+
+        // 3. Eliminate: Every other row, zero factor or not.
+        for r in (0..n).filter(|&r| r != i) {
+            let factor = a[r * n + i];
+            ct_row_add_scaled(&mut a[r * n..(r + 1) * n], &row_i, factor);
+            ct_row_add_scaled(&mut inv[r * n..(r + 1) * n], &inv_i, factor);
+        }
+    }
+
+    let mut out = GfMatrix::new(n, n);
+    for r in 0..n {
+        for c in 0..n { out.set(r, c, inv[r * n + c]); }
+    }
+    a.zeroize();
+    inv.zeroize();
+    row_i.zeroize();
+    inv_i.zeroize();
+
+    if singular != 0 {
+        return Err(M13Error::CryptoFailure); // Singular Matrix
+    }
+    Ok(out)
+}
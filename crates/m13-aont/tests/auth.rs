@@ -0,0 +1,53 @@
+use m13_aont::{AontTransform, PrivacyMode, AUTH_TAG_LEN};
+use m13_core::M13Error;
+use rand_core::OsRng;
+
+#[test]
+fn test_mode_a_auth_roundtrip() {
+    let seed = 0x1313_A0A0;
+    for len in [0, 1, 31, 128 - AUTH_TAG_LEN] {
+        let payload: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+        let enc = AontTransform::transform(&payload, seed, PrivacyMode::ModeAAuth, &mut OsRng).unwrap();
+        assert_eq!(enc.len(), len + AUTH_TAG_LEN);
+
+        let dec = AontTransform::recover(&enc, seed, PrivacyMode::ModeAAuth).unwrap();
+        assert_eq!(dec, payload);
+    }
+}
+
+#[test]
+fn test_mode_a_auth_detects_tampering() {
+    let payload = b"Bundle 0x42: launch window T+90s";
+    let seed = 0xFEED_F00D;
+    let enc = AontTransform::transform(payload, seed, PrivacyMode::ModeAAuth, &mut OsRng).unwrap();
+
+    // Any single bit, anywhere (body or tag), fails verification.
+    for i in 0..enc.len() {
+        for bit in [0x01, 0x80] {
+            let mut bad = enc.clone();
+            bad[i] ^= bit;
+            assert!(matches!(
+                AontTransform::recover(&bad, seed, PrivacyMode::ModeAAuth),
+                Err(M13Error::AuthFail)
+            ), "flip {:#x} at {} went undetected", bit, i);
+        }
+    }
+
+    // Wrong seed: Wrong matrix and wrong key.
+    assert!(matches!(AontTransform::recover(&enc, seed ^ 1, PrivacyMode::ModeAAuth), Err(M13Error::AuthFail)));
+
+    // Truncated below the tag.
+    assert!(matches!(
+        AontTransform::recover(&enc[..AUTH_TAG_LEN - 1], seed, PrivacyMode::ModeAAuth),
+        Err(M13Error::WireFormatError)
+    ));
+}
+
+#[test]
+fn test_mode_a_auth_capacity() {
+    let payload = [0u8; 128 - AUTH_TAG_LEN + 1];
+    assert!(matches!(
+        AontTransform::transform(&payload, 7, PrivacyMode::ModeAAuth, &mut OsRng),
+        Err(M13Error::EntropyExhaustion)
+    ));
+}
@@ -0,0 +1,52 @@
+use m13_aont::{AontTransform, PrivacyMode};
+use rand_core::OsRng;
+
+fn pattern(len: usize, salt: usize) -> Vec<u8> {
+    (0..len).map(|j| (salt * 31 + j * 7) as u8).collect()
+}
+
+fn payloads(count: usize, len: usize) -> Vec<Vec<u8>> {
+    (0..count).map(|i| pattern(len, i)).collect()
+}
+
+#[test]
+fn test_mode_a_batch_matches_individual_transforms() {
+    let seed = 0xB00C_1E55;
+    let owned = payloads(6, 96);
+    let batch: Vec<&[u8]> = owned.iter().map(|p| p.as_slice()).collect();
+
+    let mixed = AontTransform::transform_batch(&batch, seed, PrivacyMode::ModeA, &mut OsRng).unwrap();
+    assert_eq!(mixed.len(), batch.len());
+    for (p, y) in batch.iter().zip(&mixed) {
+        assert_eq!(*y, AontTransform::transform(p, seed, PrivacyMode::ModeA, &mut OsRng).unwrap());
+    }
+
+    let mixed_refs: Vec<&[u8]> = mixed.iter().map(|y| y.as_slice()).collect();
+    assert_eq!(AontTransform::recover_batch(&mixed_refs, seed, PrivacyMode::ModeA).unwrap(), owned);
+}
+
+#[test]
+fn test_mode_a_batch_mixed_lengths_keep_order() {
+    let seed = 0x0DD5_12E5;
+    let owned = vec![pattern(40, 1), pattern(17, 2), pattern(40, 3), Vec::new(), pattern(17, 4)];
+    let batch: Vec<&[u8]> = owned.iter().map(|p| p.as_slice()).collect();
+
+    let mixed = AontTransform::transform_batch(&batch, seed, PrivacyMode::ModeA, &mut OsRng).unwrap();
+    for (p, y) in batch.iter().zip(&mixed) {
+        assert_eq!(*y, AontTransform::transform(p, seed, PrivacyMode::ModeA, &mut OsRng).unwrap());
+    }
+    let mixed_refs: Vec<&[u8]> = mixed.iter().map(|y| y.as_slice()).collect();
+    assert_eq!(AontTransform::recover_batch(&mixed_refs, seed, PrivacyMode::ModeA).unwrap(), owned);
+}
+
+#[test]
+fn test_mode_b_batch_round_trips() {
+    let seed = 0xCAFE_F00D;
+    let owned = payloads(4, 48);
+    let batch: Vec<&[u8]> = owned.iter().map(|p| p.as_slice()).collect();
+
+    let mixed = AontTransform::transform_batch(&batch, seed, PrivacyMode::ModeB, &mut OsRng).unwrap();
+    assert!(mixed.iter().all(|y| y.len() == 96));
+    let mixed_refs: Vec<&[u8]> = mixed.iter().map(|y| y.as_slice()).collect();
+    assert_eq!(AontTransform::recover_batch(&mixed_refs, seed, PrivacyMode::ModeB).unwrap(), owned);
+}
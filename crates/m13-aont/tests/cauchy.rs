@@ -0,0 +1,40 @@
+use m13_aont::matrix::{generate_cauchy_matrix, MAX_CAUCHY_SIZE};
+use m13_aont::solver::invert_matrix;
+use m13_core::M13Error;
+use m13_math::{GfMatrix, GfSymbol};
+
+fn is_identity(m: &GfMatrix) -> bool {
+    (0..m.rows).all(|r| (0..m.cols).all(|c| {
+        let want = if r == c { GfSymbol::ONE } else { GfSymbol::ZERO };
+        m.get(r, c) == Some(want)
+    }))
+}
+
+#[test]
+fn test_every_seed_inverts() {
+    // Invertible by construction: No (size, seed) pair may leave a bundle unrecoverable.
+    for size in [2, 16, 48] {
+        for i in 0..128u32 {
+            let seed = i.wrapping_mul(0x9E37_79B9) ^ size as u32;
+            let l = generate_cauchy_matrix(size, seed).unwrap();
+            let inv = invert_matrix(&l)
+                .unwrap_or_else(|_| panic!("singular Cauchy matrix (size {}, seed {:#x})", size, seed));
+            assert!(is_identity(&l.mul(&inv).unwrap()), "size {}, seed {:#x}", size, seed);
+        }
+    }
+}
+
+#[test]
+fn test_full_field_inverts() {
+    // At the cap X and Y partition the whole field.
+    for seed in [0, 1, 0xDEAD_BEEF, u32::MAX] {
+        let l = generate_cauchy_matrix(MAX_CAUCHY_SIZE, seed).unwrap();
+        assert!(invert_matrix(&l).is_ok(), "seed {:#x}", seed);
+    }
+}
+
+#[test]
+fn test_oversize_exhausts_nodes() {
+    assert!(matches!(generate_cauchy_matrix(MAX_CAUCHY_SIZE + 1, 7), Err(M13Error::EntropyExhaustion)));
+    assert!(matches!(generate_cauchy_matrix(4096, 7), Err(M13Error::EntropyExhaustion)));
+}
@@ -0,0 +1,86 @@
+use m13_aont::matrix::generate_cauchy_matrix;
+use m13_aont::solver::{invert_matrix, invert_matrix_ct};
+use m13_core::M13Error;
+use m13_math::{GfMatrix, GfSymbol};
+
+fn is_identity(m: &GfMatrix) -> bool {
+    (0..m.rows).all(|r| (0..m.cols).all(|c| {
+        let want = if r == c { GfSymbol::ONE } else { GfSymbol::ZERO };
+        m.get(r, c) == Some(want)
+    }))
+}
+
+#[test]
+fn test_matrix_times_inverse_is_identity() {
+    for size in [1, 7, 64, 128] {
+        let l = generate_cauchy_matrix(size, 0x1313_0000 + size as u32).unwrap();
+        let inv = invert_matrix(&l).unwrap();
+
+        assert!(is_identity(&l.mul(&inv).unwrap()), "L * L^-1 != I (size {})", size);
+        assert!(is_identity(&inv.mul(&l).unwrap()), "L^-1 * L != I (size {})", size);
+    }
+}
+
+#[test]
+fn test_cached_inverse_batch_matches_mul_vec() {
+    // One inverse applied to many vectors at once (columns of B).
+    let l = generate_cauchy_matrix(32, 0xC0FFEE).unwrap();
+    let inv = invert_matrix(&l).unwrap();
+
+    let mut b = GfMatrix::new(32, 5);
+    for r in 0..32 {
+        for c in 0..5 { b.set(r, c, GfSymbol((r * 7 + c * 31 + 1) as u8)); }
+    }
+    let batched = inv.mul(&b).unwrap();
+
+    for c in 0..5 {
+        let column: Vec<GfSymbol> = (0..32).map(|r| b.get(r, c).unwrap()).collect();
+        let single = inv.mul_vec(&column).unwrap();
+        for (r, &s) in single.iter().enumerate() { assert_eq!(batched.get(r, c), Some(s)); }
+    }
+}
+
+#[test]
+fn test_mul_dimension_mismatch() {
+    let a = GfMatrix::new(3, 4);
+    let b = GfMatrix::new(3, 4);
+    assert!(matches!(a.mul(&b), Err(M13Error::InvalidState)));
+
+    let c = GfMatrix::new(4, 2);
+    let p = a.mul(&c).unwrap();
+    assert_eq!((p.rows, p.cols), (3, 2));
+}
+
+#[test]
+fn test_ct_inverse_matches_fast_inverse() {
+    for size in [1, 2, 7, 33, 64, 128] {
+        let l = generate_cauchy_matrix(size, 0x5EED_0000 + size as u32).unwrap();
+        let fast = invert_matrix(&l).unwrap();
+        let ct = invert_matrix_ct(&l).unwrap();
+        for r in 0..size {
+            for c in 0..size { assert_eq!(ct.get(r, c), fast.get(r, c), "size {} at ({}, {})", size, r, c); }
+        }
+    }
+}
+
+#[test]
+fn test_ct_inverse_pivots_and_rejects_singular() {
+    // Zero leading entry: Needs a pivot from below.
+    let mut m = GfMatrix::new(3, 3);
+    for (r, c, v) in [(0, 1, 3), (0, 2, 1), (1, 0, 5), (1, 2, 7), (2, 0, 2), (2, 1, 9), (2, 2, 4)] {
+        m.set(r, c, GfSymbol(v));
+    }
+    let inv = invert_matrix_ct(&m).unwrap();
+    assert!(is_identity(&m.mul(&inv).unwrap()));
+
+    // Row 2 = Row 0 + Row 1: Singular.
+    let mut s = GfMatrix::new(3, 3);
+    for c in 0..3 {
+        s.set(0, c, GfSymbol(c as u8 + 1));
+        s.set(1, c, GfSymbol(c as u8 * 5 + 2));
+        s.set(2, c, GfSymbol((c as u8 + 1) ^ (c as u8 * 5 + 2)));
+    }
+    assert!(matches!(invert_matrix(&s), Err(M13Error::CryptoFailure)));
+    assert!(matches!(invert_matrix_ct(&s), Err(M13Error::CryptoFailure)));
+    assert!(matches!(invert_matrix_ct(&GfMatrix::new(2, 3)), Err(M13Error::InvalidState)));
+}
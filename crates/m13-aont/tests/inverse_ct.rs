@@ -0,0 +1,27 @@
+use std::sync::atomic::Ordering;
+use m13_aont::matrix::generate_cauchy_matrix;
+use m13_aont::solver::{invert_matrix, invert_matrix_ct};
+use m13_math::{GfMatrix, TABLE_LOOKUPS};
+
+fn same(a: &GfMatrix, b: &GfMatrix) -> bool {
+    a.rows == b.rows && a.cols == b.cols
+        && (0..a.rows).all(|r| (0..a.cols).all(|c| a.get(r, c) == b.get(r, c)))
+}
+
+// One test per binary: TABLE_LOOKUPS is process-wide, so nothing may run alongside.
+#[test]
+fn test_ct_inverse_never_touches_tables() {
+    let matrices: Vec<_> = [1, 16, 64, 128].iter()
+        .map(|&n| generate_cauchy_matrix(n, 0xA0A0_0000 + n as u32).unwrap())
+        .collect();
+
+    let before = TABLE_LOOKUPS.load(Ordering::SeqCst);
+    let ct: Vec<_> = matrices.iter().map(|m| invert_matrix_ct(m).unwrap()).collect();
+    assert_eq!(TABLE_LOOKUPS.load(Ordering::SeqCst), before, "invert_matrix_ct read the log/exp tables");
+
+    // The counter works: The fast path does read them.
+    let fast: Vec<_> = matrices.iter().map(|m| invert_matrix(m).unwrap()).collect();
+    assert!(TABLE_LOOKUPS.load(Ordering::SeqCst) > before);
+    for (c, f) in ct.iter().zip(&fast) { assert!(same(c, f)); }
+
+}
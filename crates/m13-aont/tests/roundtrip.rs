@@ -48,4 +48,35 @@ fn test_mode_a_bulk() {
     ).unwrap();
 
     assert_eq!(payload, dec.as_slice());
-}
\ No newline at end of file
+}
+#[test]
+fn test_stream_roundtrip_1kb() {
+    let payload: Vec<u8> = (0..1024u32).map(|i| (i * 31 + 7) as u8).collect();
+    let seed = 0xA0A0_1313;
+    let mut rng = OsRng;
+
+    let enc = AontTransform::transform_stream(&payload, seed, &mut rng).unwrap();
+    // 16 data blocks + 1 key block, each 2 x 64 bytes + 2-byte prefix.
+    assert_eq!(enc.len(), 17 * (2 + 128));
+
+    let dec = AontTransform::recover_stream(&enc, seed).unwrap();
+    assert_eq!(dec, payload);
+}
+
+#[test]
+fn test_stream_all_or_nothing() {
+    let payload: Vec<u8> = (0..1024u32).map(|i| (i ^ 0x5A) as u8).collect();
+    let seed = 0x0BAD_F00D;
+    let mut rng = OsRng;
+
+    let mut enc = AontTransform::transform_stream(&payload, seed, &mut rng).unwrap();
+    // Flip one byte inside the 10th data block (skip its length prefix).
+    enc[9 * 130 + 2 + 17] ^= 0x01;
+
+    let dec = AontTransform::recover_stream(&enc, seed).unwrap();
+    assert_eq!(dec.len(), payload.len());
+    // Every block, not just the corrupted one, must be destroyed.
+    for (got, want) in dec.chunks(64).zip(payload.chunks(64)) {
+        assert_ne!(got, want);
+    }
+}
@@ -1,10 +1,12 @@
 #![no_std]
 #![forbid(unsafe_code)]
+extern crate alloc;
 
 pub mod merkle;
 
+use alloc::vec::Vec;
 use m13_core::{M13Error, M13Result};
-use m13_pqc::{verify as verify_pqc, DsaKeypair};
+use m13_pqc::{dsa_sign, dsa_verify, DsaKeypair, DSA_CTX_ATTEST};
 use m13_hal::SecurityModule;
 use sha2::{Sha256, Digest};
 use zeroize::Zeroize;
@@ -21,18 +23,71 @@ pub struct PcrBank {
     pub pcr7_debug: [u8; 32],
 }
 
+/// Register selector for `PcrBank::extend`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcrIndex {
+    Root = 0,
+    Firmware = 1,
+    Kernel = 2,
+    Policy = 4,
+    Debug = 7,
+}
+
 impl PcrBank {
+    /// All registers zero (TPM reset state).
+    pub fn new() -> Self {
+        Self {
+            pcr0_root: [0u8; 32],
+            pcr1_fw: [0u8; 32],
+            pcr2_kernel: [0u8; 32],
+            pcr4_policy: [0u8; 32],
+            pcr7_debug: [0u8; 32],
+        }
+    }
+
+    pub fn register(&self, index: PcrIndex) -> &[u8; 32] {
+        match index {
+            PcrIndex::Root => &self.pcr0_root,
+            PcrIndex::Firmware => &self.pcr1_fw,
+            PcrIndex::Kernel => &self.pcr2_kernel,
+            PcrIndex::Policy => &self.pcr4_policy,
+            PcrIndex::Debug => &self.pcr7_debug,
+        }
+    }
+
+    /// TPM-style extend: PCR = SHA256(PCR || SHA256(measurement)).
+    /// Order-sensitive, so the register commits to the whole boot sequence.
+    pub fn extend(&mut self, index: PcrIndex, measurement: &[u8]) {
+        let pcr = match index {
+            PcrIndex::Root => &mut self.pcr0_root,
+            PcrIndex::Firmware => &mut self.pcr1_fw,
+            PcrIndex::Kernel => &mut self.pcr2_kernel,
+            PcrIndex::Policy => &mut self.pcr4_policy,
+            PcrIndex::Debug => &mut self.pcr7_debug,
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(&pcr[..]);
+        hasher.update(Sha256::digest(measurement));
+        *pcr = hasher.finalize().into();
+    }
+
     pub fn digest(&self) -> [u8; 32] {
         let mut hasher = Sha256::new();
-        hasher.update(&self.pcr0_root);
-        hasher.update(&self.pcr1_fw);
-        hasher.update(&self.pcr2_kernel);
-        hasher.update(&self.pcr4_policy);
-        hasher.update(&self.pcr7_debug);
+        hasher.update(self.pcr0_root);
+        hasher.update(self.pcr1_fw);
+        hasher.update(self.pcr2_kernel);
+        hasher.update(self.pcr4_policy);
+        hasher.update(self.pcr7_debug);
         hasher.finalize().into()
     }
 }
 
+impl Default for PcrBank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// The Epoch 0 Composite Frame (§6.3.2).
 #[derive(Debug, Clone, Zeroize)]
 #[zeroize(drop)]
@@ -55,6 +110,60 @@ pub struct Epoch0Frame {
     pub sig_legacy_len: usize,
 }
 
+/// Wire size of an `Epoch0Frame`:
+/// [PQC PK 2592][AIK PK 65][PCRs 5x32][SIG PQC 4627][SIG LEGACY 256][SIG LEGACY LEN u16 BE]
+pub const EPOCH0_FRAME_LEN: usize = 2592 + 65 + 5 * 32 + 4627 + 256 + 2;
+
+impl Epoch0Frame {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(EPOCH0_FRAME_LEN);
+        out.extend_from_slice(&self.pqc_pub_key);
+        out.extend_from_slice(&self.legacy_aik_pub);
+        for pcr in [&self.pcrs.pcr0_root, &self.pcrs.pcr1_fw, &self.pcrs.pcr2_kernel, &self.pcrs.pcr4_policy, &self.pcrs.pcr7_debug] {
+            out.extend_from_slice(pcr);
+        }
+        out.extend_from_slice(&self.sig_pqc);
+        out.extend_from_slice(&self.sig_legacy);
+        out.extend_from_slice(&(self.sig_legacy_len as u16).to_be_bytes());
+        out
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> M13Result<Self> {
+        if buf.len() != EPOCH0_FRAME_LEN { return Err(M13Error::WireFormatError); }
+        // Lengths checked above: Every `try_into` below is infallible.
+        let mut off = 0;
+        let mut take = |n: usize| { let s = &buf[off..off + n]; off += n; s };
+
+        let pqc_pub_key = take(2592).try_into().map_err(|_| M13Error::WireFormatError)?;
+        let legacy_aik_pub = take(65).try_into().map_err(|_| M13Error::WireFormatError)?;
+        let mut pcr = || -> M13Result<[u8; 32]> { take(32).try_into().map_err(|_| M13Error::WireFormatError) };
+        let pcrs = PcrBank {
+            pcr0_root: pcr()?,
+            pcr1_fw: pcr()?,
+            pcr2_kernel: pcr()?,
+            pcr4_policy: pcr()?,
+            pcr7_debug: pcr()?,
+        };
+        let sig_pqc = take(4627).try_into().map_err(|_| M13Error::WireFormatError)?;
+        let sig_legacy = take(256).try_into().map_err(|_| M13Error::WireFormatError)?;
+        let len_bytes = take(2);
+        let sig_legacy_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+        if sig_legacy_len > 256 { return Err(M13Error::WireFormatError); }
+
+        Ok(Self { pqc_pub_key, legacy_aik_pub, pcrs, sig_pqc, sig_legacy, sig_legacy_len })
+    }
+}
+
+/// Handshake binding: The attested nonce commits to the hub's challenge AND the KEM
+/// ciphertext, so a quote cannot be lifted from one key exchange into another.
+pub fn bind_nonce(challenge: &[u8; 32], kem_ct: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"M13-ATTEST-v1");
+    hasher.update(challenge);
+    hasher.update(kem_ct);
+    hasher.finalize().into()
+}
+
 /// PROVER: Generates the binding. Run by the Node.
 pub fn generate_attestation<R: RngCore + CryptoRng>(
     nonce: &[u8; 32],
@@ -64,12 +173,12 @@ pub fn generate_attestation<R: RngCore + CryptoRng>(
     rng: &mut R
 ) -> M13Result<Epoch0Frame> {
     // 1. PQC Liveness
-    let sig_pqc = pqc_id.sign(nonce, rng)?;
+    let sig_pqc = dsa_sign(nonce, &pqc_id.secret, DSA_CTX_ATTEST, rng).map_err(|_| M13Error::CryptoFailure)?;
 
     // 2. Legacy Binding
     let mut hasher = Sha256::new();
-    hasher.update(&pcrs.digest()); // State
-    hasher.update(&Sha256::digest(&pqc_id.public)); // Identity
+    hasher.update(pcrs.digest()); // State
+    hasher.update(Sha256::digest(pqc_id.public)); // Identity
     hasher.update(nonce); // Time
     let binding_msg = hasher.finalize();
 
@@ -78,7 +187,7 @@ pub fn generate_attestation<R: RngCore + CryptoRng>(
 
     Ok(Epoch0Frame {
         pqc_pub_key: pqc_id.public,
-        legacy_aik_pub: [0u8; 65], // Filled by caller/HAL lookup
+        legacy_aik_pub: hal.attestation_key().unwrap_or([0u8; 65]), // Else filled by the caller
         pcrs,
         sig_pqc,
         sig_legacy,
@@ -86,36 +195,90 @@ pub fn generate_attestation<R: RngCore + CryptoRng>(
     })
 }
 
+/// Which Epoch 0 check rejected a frame.
+/// `PcrMismatch` is an ops problem (unexpected firmware); the rest are security events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttestError {
+    /// Reported PCRs match no golden profile.
+    PcrMismatch,
+    /// ML-DSA signature over the nonce does not verify.
+    PqcLivenessFail,
+    /// AIK signature over (PCRs, PQC identity, nonce) is malformed or does not verify.
+    LegacyBindingFail,
+    /// Legacy AIK public key is not a valid SEC1 P-256 point.
+    MalformedKey,
+}
+
+impl From<AttestError> for M13Error {
+    fn from(e: AttestError) -> Self {
+        match e {
+            AttestError::PcrMismatch => M13Error::InvalidState,
+            AttestError::PqcLivenessFail | AttestError::LegacyBindingFail => M13Error::CryptoFailure,
+            AttestError::MalformedKey => M13Error::WireFormatError,
+        }
+    }
+}
+
 /// VERIFIER: Validates the binding. Run by the Hub.
 pub fn verify_epoch0(
     frame: &Epoch0Frame,
     nonce: &[u8; 32],
     golden_pcrs: &PcrBank
 ) -> M13Result<()> {
+    verify_epoch0_detailed(frame, nonce, golden_pcrs).map_err(M13Error::from)
+}
+
+/// VERIFIER: As `verify_epoch0`, reporting which check failed (for triage).
+pub fn verify_epoch0_detailed(
+    frame: &Epoch0Frame,
+    nonce: &[u8; 32],
+    golden_pcrs: &PcrBank
+) -> Result<(), AttestError> {
+    verify_epoch0_profiles(frame, nonce, &[golden_pcrs]).map(|_| ())
+}
+
+/// VERIFIER: As `verify_epoch0`, accepting any of several golden profiles (staged rollouts).
+/// Returns the index of the matching profile.
+pub fn verify_epoch0_multi(
+    frame: &Epoch0Frame,
+    nonce: &[u8; 32],
+    golden_pcrs: &[&PcrBank]
+) -> M13Result<usize> {
+    verify_epoch0_profiles(frame, nonce, golden_pcrs).map_err(M13Error::from)
+}
+
+fn verify_epoch0_profiles(
+    frame: &Epoch0Frame,
+    nonce: &[u8; 32],
+    golden_pcrs: &[&PcrBank]
+) -> Result<usize, AttestError> {
     // 1. Verify PCR State (Firmware Integrity)
-    if frame.pcrs != *golden_pcrs {
-        return Err(M13Error::InvalidState);
-    }
+    let profile = golden_pcrs.iter()
+        .position(|golden| frame.pcrs == **golden)
+        .ok_or(AttestError::PcrMismatch)?;
 
     // 2. Verify PQC Liveness (Quantum Proof)
-    verify_pqc(&frame.pqc_pub_key, nonce, &frame.sig_pqc)
-        .map_err(|_| M13Error::CryptoFailure)?;
+    dsa_verify(&frame.pqc_pub_key, &frame.sig_pqc, nonce, DSA_CTX_ATTEST)
+        .map_err(|_| AttestError::PqcLivenessFail)?;
 
     // 3. Verify Legacy Binding (Hardware Proof)
     let mut hasher = Sha256::new();
-    hasher.update(&frame.pcrs.digest());
-    hasher.update(&Sha256::digest(&frame.pqc_pub_key));
+    hasher.update(frame.pcrs.digest());
+    hasher.update(Sha256::digest(frame.pqc_pub_key));
     hasher.update(nonce);
     let binding_msg = hasher.finalize();
 
     let vk = VerifyingKey::from_sec1_bytes(&frame.legacy_aik_pub)
-        .map_err(|_| M13Error::WireFormatError)?;
+        .map_err(|_| AttestError::MalformedKey)?;
     
-    let sig_bytes = &frame.sig_legacy[..frame.sig_legacy_len];
+    let sig_bytes = frame.sig_legacy.get(..frame.sig_legacy_len)
+        .ok_or(AttestError::LegacyBindingFail)?;
     let sig = Signature::from_der(sig_bytes)
         .or_else(|_| Signature::from_slice(sig_bytes))
-        .map_err(|_| M13Error::WireFormatError)?;
+        .map_err(|_| AttestError::LegacyBindingFail)?;
 
     vk.verify(&binding_msg, &sig)
-        .map_err(|_| M13Error::CryptoFailure)
-}
\ No newline at end of file
+        .map_err(|_| AttestError::LegacyBindingFail)?;
+
+    Ok(profile)
+}
@@ -1,4 +1,5 @@
 #![forbid(unsafe_code)]
+use alloc::vec::Vec;
 use m13_core::{M13Error, M13Result};
 use sha2::{Sha384, Digest};
 
@@ -9,7 +10,7 @@ pub type Hash = [u8; HASH_SIZE];
 /// Computes the leaf hash. H(0x00 || Data)
 pub fn merkle_leaf(data: &[u8]) -> Hash {
     let mut hasher = Sha384::new();
-    hasher.update(&[0x00]); // RFC 6962 Leaf Prefix
+    hasher.update([0x00]); // RFC 6962 Leaf Prefix
     hasher.update(data);
     hasher.finalize().into()
 }
@@ -17,23 +18,43 @@ pub fn merkle_leaf(data: &[u8]) -> Hash {
 /// Computes the parent hash. H(0x01 || left || right)
 fn merkle_parent(left: &Hash, right: &Hash) -> Hash {
     let mut hasher = Sha384::new();
-    hasher.update(&[0x01]); // RFC 6962 Node Prefix
+    hasher.update([0x01]); // RFC 6962 Node Prefix
     hasher.update(left);
     hasher.update(right);
     hasher.finalize().into()
 }
 
-/// Verifies a Merkle Inclusion Proof (§10.2.2).
+/// Commits the leaf count into the root. H(0x02 || count || top)
+/// Odd levels pair the last node with itself (CVE-2012-2459): Without the count, `n` leaves and
+/// `n` plus a copy of the last would share a root, and proofs for the phantom index would verify.
+fn merkle_root(top: &Hash, leaf_count: usize) -> Hash {
+    let mut hasher = Sha384::new();
+    hasher.update([0x02]);
+    hasher.update((leaf_count as u64).to_be_bytes());
+    hasher.update(top);
+    hasher.finalize().into()
+}
+
+/// Levels between the leaves and the top of a tree over `leaf_count` leaves.
+fn merkle_depth(leaf_count: usize) -> usize {
+    (leaf_count.next_power_of_two().trailing_zeros() as usize).max(1)
+}
+
+/// Verifies a Merkle Inclusion Proof (§10.2.2) against a tree of `leaf_count` leaves.
 pub fn verify_inclusion_proof(
     root: &Hash,
     leaf: &Hash,
     mut index: usize,
+    leaf_count: usize,
     proof: &[Hash]
 ) -> M13Result<()> {
+    if index >= leaf_count || proof.len() != merkle_depth(leaf_count) {
+        return Err(M13Error::CryptoFailure);
+    }
     let mut computed = *leaf;
 
     for sibling in proof {
-        if index % 2 == 0 {
+        if index.is_multiple_of(2) {
             computed = merkle_parent(&computed, sibling);
         } else {
             computed = merkle_parent(sibling, &computed);
@@ -41,9 +62,83 @@ pub fn verify_inclusion_proof(
         index /= 2;
     }
 
-    if computed == *root {
+    if merkle_root(&computed, leaf_count) == *root {
         Ok(())
     } else {
         Err(M13Error::CryptoFailure)
     }
+}
+
+/// Audit path for one leaf: Siblings from the leaf level up to (excluding) the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub siblings: Vec<Hash>,
+}
+
+impl InclusionProof {
+    pub fn verify(&self, leaf: &Hash, root: &Hash) -> bool {
+        verify_inclusion_proof(root, leaf, self.index, self.leaf_count, &self.siblings).is_ok()
+    }
+}
+
+/// Merkle Tree over the event log (§10.2.2).
+/// Odd levels pair the last node with itself; the root commits the leaf count (see `merkle_root`).
+pub struct MerkleTree {
+    // levels[0] = leaf hashes, levels.last() = [root]
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Build from leaf hashes (see `merkle_leaf`).
+    pub fn new(leaves: Vec<Hash>) -> M13Result<Self> {
+        if leaves.is_empty() { return Err(M13Error::InvalidState); }
+
+        let mut levels = alloc::vec![leaves];
+        // A single leaf is still hashed once, so the root is never a bare leaf hash.
+        while levels.len() == 1 || levels[levels.len() - 1].len() > 1 {
+            let below = &levels[levels.len() - 1];
+            let above = below.chunks(2)
+                .map(|pair| merkle_parent(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            levels.push(above);
+        }
+        Ok(Self { levels })
+    }
+
+    /// Build from raw measurements (each hashed with `merkle_leaf`).
+    pub fn from_data(measurements: &[&[u8]]) -> M13Result<Self> {
+        Self::new(measurements.iter().map(|m| merkle_leaf(m)).collect())
+    }
+
+    pub fn root(&self) -> Hash {
+        merkle_root(&self.levels[self.levels.len() - 1][0], self.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    pub fn leaf(&self, index: usize) -> Option<&Hash> {
+        self.levels[0].get(index)
+    }
+
+    pub fn prove(&self, index: usize) -> M13Result<InclusionProof> {
+        if index >= self.len() { return Err(M13Error::InvalidState); }
+
+        let mut siblings = Vec::with_capacity(self.levels.len() - 1);
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            // Missing right sibling: The node was paired with itself.
+            let sibling = level.get(i ^ 1).unwrap_or(&level[i]);
+            siblings.push(*sibling);
+            i /= 2;
+        }
+        Ok(InclusionProof { index, leaf_count: self.len(), siblings })
+    }
 }
\ No newline at end of file
@@ -1,16 +1,19 @@
-use m13_attest::{merkle, PcrBank}; // verify_epoch0 omitted as it requires complex P256 mocking
+use m13_attest::{merkle, PcrBank, PcrIndex}; // verify_epoch0 omitted as it requires complex P256 mocking
 use sha2::{Sha384, Digest};
 
 #[test]
 fn test_merkle_suite_b() {
     let leaf = merkle::merkle_leaf(b"FirmwareV1");
-    // Manual Root: H(0x01 || leaf || leaf)
+    // Manual Root: H(0x02 || 1 || H(0x01 || leaf || leaf))
     let mut h = Sha384::new();
-    h.update(&[0x01]); h.update(&leaf); h.update(&leaf);
+    h.update([0x01]); h.update(leaf); h.update(leaf);
+    let top: [u8; 48] = h.finalize().into();
+    let mut h = Sha384::new();
+    h.update([0x02]); h.update(1u64.to_be_bytes()); h.update(top);
     let root: [u8; 48] = h.finalize().into();
 
     let proof = vec![leaf];
-    assert!(merkle::verify_inclusion_proof(&root, &leaf, 0, &proof).is_ok());
+    assert!(merkle::verify_inclusion_proof(&root, &leaf, 0, 1, &proof).is_ok());
 }
 
 #[test]
@@ -24,4 +27,82 @@ fn test_pcr_hashing() {
     };
     let _digest = bank.digest();
     // Simply ensure it compiles and runs without panic
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pcr_extend_is_order_sensitive() {
+    let boot = [(PcrIndex::Firmware, &b"bootloader"[..]), (PcrIndex::Firmware, b"firmware-v2"), (PcrIndex::Kernel, b"vmlinuz")];
+
+    let mut a = PcrBank::new();
+    let mut b = PcrBank::new();
+    for (idx, m) in boot { a.extend(idx, m); b.extend(idx, m); }
+    assert_eq!(a, b);
+    assert_eq!(a.digest(), b.digest());
+    assert_ne!(a.register(PcrIndex::Firmware), &[0u8; 32]);
+    assert_eq!(a.register(PcrIndex::Debug), &[0u8; 32]);
+
+    // Same measurements, firmware stages swapped.
+    let mut c = PcrBank::new();
+    c.extend(PcrIndex::Firmware, b"firmware-v2");
+    c.extend(PcrIndex::Firmware, b"bootloader");
+    c.extend(PcrIndex::Kernel, b"vmlinuz");
+    assert_ne!(a.digest(), c.digest());
+    assert_eq!(a.register(PcrIndex::Kernel), c.register(PcrIndex::Kernel));
+}
+#[test]
+fn test_merkle_tree_prove_leaf_3_of_8() {
+    let data: Vec<Vec<u8>> = (0..8).map(|i| format!("measurement-{}", i).into_bytes()).collect();
+    let refs: Vec<&[u8]> = data.iter().map(|d| d.as_slice()).collect();
+    let tree = merkle::MerkleTree::from_data(&refs).unwrap();
+    let root = tree.root();
+
+    let leaf = merkle::merkle_leaf(b"measurement-3");
+    let proof = tree.prove(3).unwrap();
+    assert_eq!(proof.siblings.len(), 3);
+    assert!(proof.verify(&leaf, &root));
+
+    // Wrong leaf, tampered sibling, wrong position: All rejected.
+    assert!(!proof.verify(&merkle::merkle_leaf(b"measurement-4"), &root));
+    let mut tampered = proof.clone();
+    tampered.siblings[1][0] ^= 0x01;
+    assert!(!tampered.verify(&leaf, &root));
+    let mut moved = proof.clone();
+    moved.index = 2;
+    assert!(!moved.verify(&leaf, &root));
+
+    assert!(tree.prove(8).is_err());
+}
+
+#[test]
+fn test_merkle_tree_odd_sizes() {
+    for n in 1..=9usize {
+        let leaves: Vec<merkle::Hash> = (0..n).map(|i| merkle::merkle_leaf(&[i as u8])).collect();
+        let tree = merkle::MerkleTree::new(leaves.clone()).unwrap();
+        for (i, leaf) in leaves.iter().enumerate() {
+            assert!(tree.prove(i).unwrap().verify(leaf, &tree.root()), "n={} i={}", n, i);
+        }
+    }
+    assert!(merkle::MerkleTree::new(Vec::new()).is_err());
+}
+
+#[test]
+fn test_merkle_tree_rejects_duplicated_last_leaf() {
+    let leaves: Vec<merkle::Hash> = (0..3u8).map(|i| merkle::merkle_leaf(&[i])).collect();
+    let tree = merkle::MerkleTree::new(leaves.clone()).unwrap();
+    let root = tree.root();
+
+    // Leaf 2 is paired with itself: Its audit path also fits a phantom leaf 3.
+    let proof = tree.prove(2).unwrap();
+    assert!(proof.verify(&leaves[2], &root));
+    let mut phantom = proof.clone();
+    phantom.index = 3;
+    assert!(!phantom.verify(&leaves[2], &root), "Proof for a missing index verified");
+    phantom.leaf_count = 4;
+    assert!(!phantom.verify(&leaves[2], &root), "Leaf count not bound into the root");
+    assert!(tree.prove(3).is_err());
+
+    // Three leaves and the same three with the last repeated are different trees.
+    let mut padded = leaves.clone();
+    padded.push(leaves[2]);
+    assert_ne!(merkle::MerkleTree::new(padded).unwrap().root(), root);
+}
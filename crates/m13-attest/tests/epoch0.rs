@@ -0,0 +1,94 @@
+use m13_attest::{generate_attestation, verify_epoch0, verify_epoch0_detailed, verify_epoch0_multi, AttestError, Epoch0Frame, PcrBank, PcrIndex};
+use m13_core::{M13Error, M13Result};
+use m13_hal::SecurityModule;
+use m13_pqc::DsaKeypair;
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey, signature::Signer};
+use rand_core::OsRng;
+
+/// TPM stand-in: Signs digests with a fixed P-256 AIK.
+struct AikSec(SigningKey);
+impl SecurityModule for AikSec {
+    fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()> { buf.fill(0x5A); Ok(()) }
+    fn sign_digest(&mut self, digest: &[u8], signature: &mut [u8]) -> M13Result<usize> {
+        let sig: Signature = self.0.sign(digest);
+        let bytes = sig.to_bytes();
+        signature[..bytes.len()].copy_from_slice(&bytes);
+        Ok(bytes.len())
+    }
+    fn panic_and_sanitize(&self) -> ! { panic!("PANIC") }
+}
+
+fn firmware(image: &[u8]) -> PcrBank {
+    let mut bank = PcrBank::new();
+    bank.extend(PcrIndex::Root, b"root-of-trust");
+    bank.extend(PcrIndex::Firmware, image);
+    bank.extend(PcrIndex::Kernel, b"kernel");
+    bank
+}
+
+fn attest(pcrs: PcrBank, nonce: &[u8; 32]) -> Epoch0Frame {
+    let aik = SigningKey::from_slice(&[0x42; 32]).unwrap();
+    let aik_pub = VerifyingKey::from(&aik).to_encoded_point(false);
+    let identity = DsaKeypair::generate(&mut OsRng).unwrap();
+    let mut frame = generate_attestation(nonce, &identity, pcrs, &mut AikSec(aik), &mut OsRng).unwrap();
+    frame.legacy_aik_pub.copy_from_slice(aik_pub.as_bytes());
+    frame
+}
+
+#[test]
+fn test_multi_profile_returns_matching_index() {
+    let nonce = [0x11; 32];
+    let (v1, v2, v3) = (firmware(b"fw-1.0"), firmware(b"fw-1.1"), firmware(b"fw-2.0"));
+    let frame = attest(firmware(b"fw-1.1"), &nonce);
+
+    assert_eq!(verify_epoch0_multi(&frame, &nonce, &[&v1, &v2, &v3]).unwrap(), 1);
+    assert!(verify_epoch0(&frame, &nonce, &v2).is_ok());
+
+    // No profile matches.
+    assert!(matches!(verify_epoch0_multi(&frame, &nonce, &[&v1, &v3]), Err(M13Error::InvalidState)));
+    assert!(matches!(verify_epoch0_multi(&frame, &nonce, &[]), Err(M13Error::InvalidState)));
+
+    // A matching profile does not excuse a stale nonce.
+    assert!(verify_epoch0_multi(&frame, &[0x22; 32], &[&v1, &v2, &v3]).is_err());
+}
+
+#[test]
+fn test_frame_wire_roundtrip() {
+    let nonce = [0x33; 32];
+    let golden = firmware(b"fw-1.0");
+    let frame = attest(golden.clone(), &nonce);
+
+    let decoded = Epoch0Frame::from_bytes(&frame.to_bytes()).unwrap();
+    assert!(verify_epoch0(&decoded, &nonce, &golden).is_ok());
+    assert!(Epoch0Frame::from_bytes(&frame.to_bytes()[1..]).is_err());
+}
+
+#[test]
+fn test_detailed_error_per_check() {
+    let nonce = [0x44; 32];
+    let golden = firmware(b"fw-1.0");
+    let frame = attest(golden.clone(), &nonce);
+    assert_eq!(verify_epoch0_detailed(&frame, &nonce, &golden), Ok(()));
+
+    // Ops: Unexpected firmware.
+    assert_eq!(verify_epoch0_detailed(&frame, &nonce, &firmware(b"fw-0.9")), Err(AttestError::PcrMismatch));
+    assert!(matches!(verify_epoch0(&frame, &nonce, &firmware(b"fw-0.9")), Err(M13Error::InvalidState)));
+
+    // Security: Forged PQC liveness.
+    let mut forged = frame.clone();
+    forged.sig_pqc[100] ^= 0x01;
+    assert_eq!(verify_epoch0_detailed(&forged, &nonce, &golden), Err(AttestError::PqcLivenessFail));
+    assert!(matches!(verify_epoch0(&forged, &nonce, &golden), Err(M13Error::CryptoFailure)));
+
+    // Security: Forged legacy binding.
+    let mut forged = frame.clone();
+    forged.sig_legacy[10] ^= 0x01;
+    assert_eq!(verify_epoch0_detailed(&forged, &nonce, &golden), Err(AttestError::LegacyBindingFail));
+    assert!(matches!(verify_epoch0(&forged, &nonce, &golden), Err(M13Error::CryptoFailure)));
+
+    // Garbage AIK.
+    let mut forged = frame.clone();
+    forged.legacy_aik_pub = [0u8; 65];
+    assert_eq!(verify_epoch0_detailed(&forged, &nonce, &golden), Err(AttestError::MalformedKey));
+    assert!(matches!(verify_epoch0(&forged, &nonce, &golden), Err(M13Error::WireFormatError)));
+}
@@ -0,0 +1,55 @@
+//! Coefficient derivation: The keystream PRF against the AEAD-of-zeros path it replaced.
+//! `cargo bench -p m13-cipher --bench coefficients`
+use m13_cipher::{expand_coefficients, M13Cipher, SessionKey};
+use m13_core::{M13Header, PacketType, M13_MAGIC};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// L = K + S for a full 256-symbol generation.
+const L: usize = 256 + 16;
+const SYMBOLS: u32 = 256;
+const ROUNDS: u32 = 200;
+
+/// The pre-PRF derivation: Encrypt zeros under a throwaway AEAD, keep the ciphertext.
+fn legacy_coefficients(seed: u32, gen_id: u16, out: &mut [u8]) {
+    let mut key_bytes = [0u8; 32];
+    key_bytes[0..4].copy_from_slice(&seed.to_be_bytes());
+    let cipher = M13Cipher::new(&SessionKey(key_bytes));
+    let header = M13Header {
+        magic: M13_MAGIC,
+        version: 1,
+        packet_type: PacketType::Data,
+        gen_id,
+        symbol_id: seed,
+        payload_len: 0,
+        recoder_rank: 0,
+        reserved: 0,
+        auth_tag: [0u8; 16],
+    };
+    out.fill(0);
+    cipher.encrypt_detached(&header, out).unwrap();
+}
+
+/// Best of `ROUNDS` passes, each deriving one row per symbol of a 256-symbol generation.
+fn best_generation(mut derive: impl FnMut(u32, &mut [u8])) -> Duration {
+    let mut out = vec![0u8; L];
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        for sym in SYMBOLS..2 * SYMBOLS {
+            derive(black_box(sym), &mut out);
+            black_box(&out);
+        }
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+fn main() {
+    let legacy = best_generation(|sym, out| legacy_coefficients(sym, 42, out));
+    let prf = best_generation(|sym, out| expand_coefficients(sym, 42, L, out).unwrap());
+
+    println!("coefficients/legacy  {:>10.1?} per {}-symbol generation", legacy, SYMBOLS);
+    println!("coefficients/prf     {:>10.1?} per {}-symbol generation", prf, SYMBOLS);
+    println!("coefficients/speedup {:>10.2}x", legacy.as_secs_f64() / prf.as_secs_f64());
+}
@@ -3,24 +3,109 @@
 
 extern crate alloc;
 use alloc::vec::Vec;
-use m13_core::{M13Error, M13Result, M13Header};
+use m13_core::{M13Error, M13Result, M13Header, M13HeaderRef};
 use chacha20poly1305::{
     aead::{AeadInPlace, KeyInit},
     ChaCha20Poly1305, Key, Nonce, Tag
 };
+use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use chacha20::ChaCha20;
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct SessionKey(pub [u8; 32]);
 
+/// HKDF info label for the rekey ratchet.
+const REKEY_KDF_INFO: &[u8] = b"M13-REKEY-v1";
+/// HKDF info label for the epoch 0 session key.
+const SESSION_KDF_INFO: &[u8] = b"M13-SESSION-v1";
+/// Transcript hash domain separator.
+const TRANSCRIPT_LABEL: &[u8] = b"M13-TRANSCRIPT-v1";
+/// HKDF info labels for the per-direction keys.
+const HUB_TX_KDF_INFO: &[u8] = b"M13-HUB-TX-v1";
+const NODE_TX_KDF_INFO: &[u8] = b"M13-NODE-TX-v1";
+/// HKDF info label for end-to-end (mesh) generation keys.
+const MESH_KDF_INFO: &[u8] = b"M13-MESH-v1";
+
+/// [MESH] Random salt opening every end-to-end sealed generation.
+pub const MESH_SALT_LEN: usize = 16;
+/// [MESH] Bytes `mesh_seal` adds: Salt plus Poly1305 tag.
+pub const MESH_SEAL_OVERHEAD: usize = MESH_SALT_LEN + 16;
+
+/// Epoch 0 key: HKDF-SHA256(salt = H(transcript), ikm = KEM secret, info = "M13-SESSION-v1").
+/// The transcript is `ClientHello || ServerHello` exactly as reassembled on each side, so
+/// any tampering with either leg (KEM key, ciphertext, challenge, signature) splits the keys.
+pub fn derive_session_key(shared_secret: &[u8; 32], client_hello: &[u8], server_hello: &[u8]) -> SessionKey {
+    let mut hasher = Sha256::new();
+    hasher.update(TRANSCRIPT_LABEL);
+    // Length-prefixed: The split point between the two legs is bound too.
+    hasher.update((client_hello.len() as u32).to_be_bytes());
+    hasher.update(client_hello);
+    hasher.update(server_hello);
+    let salt = hasher.finalize();
+
+    let hk = Hkdf::<Sha256>::new(Some(&salt), shared_secret);
+    let mut okm = SessionKey([0u8; 32]);
+    // 32 bytes is always a valid HKDF-SHA256 output length.
+    let _ = hk.expand(SESSION_KDF_INFO, &mut okm.0);
+    okm
+}
+
+/// One key per direction, expanded from the session key. Each side seals with its own
+/// TX key and opens with the peer's, so the two directions never share a (key, nonce)
+/// pair and a frame reflected back at its sender does not authenticate.
+pub struct DirectionalKeys {
+    pub hub_tx: SessionKey,
+    pub node_tx: SessionKey,
+}
+
+impl DirectionalKeys {
+    pub fn derive(session: &SessionKey) -> Self {
+        let hk = Hkdf::<Sha256>::new(None, &session.0);
+        let mut keys = Self { hub_tx: SessionKey([0u8; 32]), node_tx: SessionKey([0u8; 32]) };
+        // 32 bytes is always a valid HKDF-SHA256 output length.
+        let _ = hk.expand(HUB_TX_KDF_INFO, &mut keys.hub_tx.0);
+        let _ = hk.expand(NODE_TX_KDF_INFO, &mut keys.node_tx.0);
+        keys
+    }
+
+    /// (TX, RX) ciphers for one side.
+    pub fn ciphers(&self, is_hub: bool) -> (M13Cipher, M13Cipher) {
+        let (tx, rx) = if is_hub { (&self.hub_tx, &self.node_tx) } else { (&self.node_tx, &self.hub_tx) };
+        (M13Cipher::new(tx), M13Cipher::new(rx))
+    }
+}
+
 pub struct M13Cipher {
     cipher: ChaCha20Poly1305,
+    key: SessionKey, // Retained only to derive the next epoch.
 }
 
 impl M13Cipher {
     pub fn new(key: &SessionKey) -> Self {
         let key_generic = Key::from_slice(&key.0);
-        Self { cipher: ChaCha20Poly1305::new(key_generic) }
+        Self { cipher: ChaCha20Poly1305::new(key_generic), key: SessionKey(key.0) }
+    }
+
+    /// Key straight from a KEM shared secret. Scrubs `secret` in place: The only copy
+    /// left is the one inside the cipher.
+    pub fn from_secret(secret: &mut [u8; 32]) -> Self {
+        let key = SessionKey(*secret);
+        secret.zeroize();
+        Self::new(&key)
+    }
+
+    /// Next key epoch: HKDF-SHA256(ikm = current key, info = "M13-REKEY-v1").
+    /// Deterministic, so both peers ratchet to the same key without a round trip.
+    /// One-way: Compromise of epoch n+1 does not expose epoch n.
+    pub fn ratchet(&self) -> M13Cipher {
+        let hk = Hkdf::<Sha256>::new(None, &self.key.0);
+        let mut okm = SessionKey([0u8; 32]);
+        // 32 bytes is always a valid HKDF-SHA256 output length.
+        let _ = hk.expand(REKEY_KDF_INFO, &mut okm.0);
+        M13Cipher::new(&okm)
     }
 
     // [FIX] Sprint 27 Nonce Construction
@@ -32,11 +117,19 @@ impl M13Cipher {
         *Nonce::from_slice(&nonce_bytes)
     }
 
+    /// AAD = the serialized header with the tag region (16..32) zeroed.
+    /// Binds magic, version, packet_type, gen_id, symbol_id, payload_len,
+    /// recoder_rank and reserved: Flipping `Data` <-> `Coded` or K breaks the tag.
+    fn construct_aad(header: &M13Header) -> M13Result<[u8; M13Header::SIZE]> {
+        let mut aad = [0u8; M13Header::SIZE];
+        header.to_bytes(&mut aad)?;
+        aad[16..32].fill(0);
+        Ok(aad)
+    }
+
     pub fn encrypt_detached(&self, header: &M13Header, payload: &mut [u8]) -> M13Result<[u8; 16]> {
         let nonce = Self::construct_nonce(header);
-        let mut aad = [0u8; M13Header::SIZE];
-        header.to_bytes(&mut aad).map_err(|_| M13Error::WireFormatError)?;
-        aad[16..32].fill(0); 
+        let aad = Self::construct_aad(header)?;
 
         let tag = self.cipher.encrypt_in_place_detached(&nonce, &aad, payload)
             .map_err(|_| M13Error::CryptoFailure)?;
@@ -48,37 +141,118 @@ impl M13Cipher {
 
     pub fn decrypt_detached(&self, header: &M13Header, payload: &mut [u8]) -> M13Result<()> {
         let nonce = Self::construct_nonce(header);
-        let mut aad = [0u8; M13Header::SIZE];
-        header.to_bytes(&mut aad).map_err(|_| M13Error::WireFormatError)?;
-        aad[16..32].fill(0); 
+        let aad = Self::construct_aad(header)?;
 
         let tag = Tag::from_slice(&header.auth_tag);
         self.cipher.decrypt_in_place_detached(&nonce, &aad, payload, tag)
             .map_err(|_| M13Error::AuthFail)
     }
+
+    /// `decrypt_detached` straight off the received bytes: Nonce, AAD and tag come from
+    /// the borrowed header, with no parse-and-reserialize round trip.
+    pub fn decrypt_detached_ref(&self, header: &M13HeaderRef<'_>, payload: &mut [u8]) -> M13Result<()> {
+        let raw = header.as_bytes();
+        let mut nonce_bytes = [0u8; 12];
+        nonce_bytes[0..6].copy_from_slice(&raw[6..12]); // gen_id || symbol_id, already BE
+
+        let mut aad = [0u8; M13Header::SIZE];
+        aad[..16].copy_from_slice(&raw[..16]);
+
+        let tag = Tag::from_slice(header.auth_tag());
+        self.cipher.decrypt_in_place_detached(Nonce::from_slice(&nonce_bytes), &aad, payload, tag)
+            .map_err(|_| M13Error::AuthFail)
+    }
 }
 
-pub fn generate_coefficients(seed: u32, gen_id: u16, count: usize) -> Vec<u8> {
+/// Byte offset of the first keystream byte the AEAD spends on data.
+/// ChaCha20-Poly1305 burns block 0 for the Poly1305 key; keeping the same offset
+/// keeps `expand_coefficients` byte-identical to the legacy AEAD-derived stream.
+const COEFF_STREAM_OFFSET: u64 = 64;
+
+/// Deterministic coefficient PRF: Raw ChaCha20 keystream.
+/// Key = seed (BE) || 0^28, Nonce = gen_id (BE) || seed (BE) || 0^6.
+/// Writes `count` bytes into `out` without allocating.
+pub fn expand_coefficients(seed: u32, gen_id: u16, count: usize, out: &mut [u8]) -> M13Result<()> {
+    let out = out.get_mut(..count).ok_or(M13Error::InvalidState)?;
+
     let mut key_bytes = [0u8; 32];
     key_bytes[0..4].copy_from_slice(&seed.to_be_bytes());
-    let session_key = SessionKey(key_bytes);
-    
-    let cipher = M13Cipher::new(&session_key);
-    let dummy_header = M13Header {
-        magic: m13_core::M13_MAGIC,
-        version: 1,
-        // [FIX] Changed 'Control' (removed) to 'Data' (valid).
-        // This header is only used to churn the RNG state, so type is irrelevant.
-        packet_type: m13_core::PacketType::Data, 
-        gen_id,
-        symbol_id: seed,
-        payload_len: 0,
-        recoder_rank: 0,
-        reserved: 0,
-        auth_tag: [0u8; 16],
-    };
-    
+    let mut nonce_bytes = [0u8; 12];
+    nonce_bytes[0..2].copy_from_slice(&gen_id.to_be_bytes());
+    nonce_bytes[2..6].copy_from_slice(&seed.to_be_bytes());
+
+    let mut stream = ChaCha20::new(&key_bytes.into(), &nonce_bytes.into());
+    stream.seek(COEFF_STREAM_OFFSET);
+    out.fill(0);
+    stream.apply_keystream(out);
+    key_bytes.zeroize();
+    Ok(())
+}
+
+pub fn generate_coefficients(seed: u32, gen_id: u16, count: usize) -> Vec<u8> {
     let mut buffer = alloc::vec![0u8; count];
-    cipher.encrypt_detached(&dummy_header, &mut buffer).ok();
+    // Infallible: Buffer is exactly `count` long.
+    let _ = expand_coefficients(seed, gen_id, count, &mut buffer);
     buffer
 }
+
+// [MESH] One key per salt: HKDF-SHA256(salt, ikm = mesh key, info = "M13-MESH-v1").
+fn mesh_cipher(mesh_key: &[u8; 32], salt: &[u8]) -> ChaCha20Poly1305 {
+    let hk = Hkdf::<Sha256>::new(Some(salt), mesh_key);
+    let mut okm = SessionKey([0u8; 32]);
+    // 32 bytes is always a valid HKDF-SHA256 output length.
+    let _ = hk.expand(MESH_KDF_INFO, &mut okm.0);
+    ChaCha20Poly1305::new(Key::from_slice(&okm.0))
+}
+
+/// [MESH] End-to-end seal: [ SALT | CT | TAG ] under a key fresh per salt, so the fixed
+/// zero nonce never repeats under one key. Relays between the two ends hold only their
+/// hop keys: They can mix and forward the result, never read it.
+pub fn mesh_seal(mesh_key: &[u8; 32], salt: &[u8; MESH_SALT_LEN], plaintext: &[u8]) -> M13Result<Vec<u8>> {
+    let mut sealed = Vec::with_capacity(MESH_SEAL_OVERHEAD + plaintext.len());
+    sealed.extend_from_slice(salt);
+    sealed.extend_from_slice(plaintext);
+    let tag = mesh_cipher(mesh_key, salt)
+        .encrypt_in_place_detached(&Nonce::default(), &[], &mut sealed[MESH_SALT_LEN..])
+        .map_err(|_| M13Error::CryptoFailure)?;
+    sealed.extend_from_slice(&tag);
+    Ok(sealed)
+}
+
+/// [MESH] Inverse of `mesh_seal`. `AuthFail` on a wrong key or any tampering,
+/// `WireFormatError` if shorter than the overhead.
+pub fn mesh_open(mesh_key: &[u8; 32], sealed: &[u8]) -> M13Result<Vec<u8>> {
+    if sealed.len() < MESH_SEAL_OVERHEAD { return Err(M13Error::WireFormatError); }
+    let (salt, rest) = sealed.split_at(MESH_SALT_LEN);
+    let (ct, tag) = rest.split_at(rest.len() - 16);
+    let mut plaintext = ct.to_vec();
+    mesh_cipher(mesh_key, salt)
+        .decrypt_in_place_detached(&Nonce::default(), &[], &mut plaintext, Tag::from_slice(tag))
+        .map_err(|_| M13Error::AuthFail)?;
+    Ok(plaintext)
+}
+
+/// HMAC-SHA256(key, msg), truncated to 128 bits.
+/// HKDF-Extract with `key` as the salt is exactly that HMAC.
+pub fn mac_tag(key: &[u8; 32], msg: &[u8]) -> [u8; 16] {
+    let (prk, _) = Hkdf::<Sha256>::extract(Some(key), msg);
+    let mut tag = [0u8; 16];
+    tag.copy_from_slice(&prk[..16]);
+    tag
+}
+
+/// Constant-time check of a presented tag against `mac_tag(key, msg)`.
+pub fn mac_verify(key: &[u8; 32], msg: &[u8], presented: &[u8; 16]) -> bool {
+    let expected = mac_tag(key, msg);
+    expected.iter().zip(presented).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// [DOS] Stateless retry cookie: `mac_tag` under the hub's cookie secret.
+pub fn cookie_tag(secret: &[u8; 32], msg: &[u8]) -> [u8; 16] {
+    mac_tag(secret, msg)
+}
+
+/// Constant-time check of a presented cookie against `cookie_tag(secret, msg)`.
+pub fn cookie_verify(secret: &[u8; 32], msg: &[u8], presented: &[u8; 16]) -> bool {
+    mac_verify(secret, msg, presented)
+}
@@ -0,0 +1,69 @@
+use m13_cipher::{expand_coefficients, generate_coefficients, M13Cipher, SessionKey};
+use m13_core::{M13Header, PacketType, M13_MAGIC};
+
+/// L = K + S for a full 256-symbol generation.
+const L: usize = 256 + 16;
+
+/// The pre-PRF derivation: Encrypt zeros under a throwaway AEAD, keep the ciphertext.
+fn legacy_coefficients(seed: u32, gen_id: u16, count: usize) -> Vec<u8> {
+    let mut key_bytes = [0u8; 32];
+    key_bytes[0..4].copy_from_slice(&seed.to_be_bytes());
+    let cipher = M13Cipher::new(&SessionKey(key_bytes));
+    let header = M13Header {
+        magic: M13_MAGIC,
+        version: 1,
+        packet_type: PacketType::Data,
+        gen_id,
+        symbol_id: seed,
+        payload_len: 0,
+        recoder_rank: 0,
+        reserved: 0,
+        auth_tag: [0u8; 16],
+    };
+    let mut buffer = vec![0u8; count];
+    cipher.encrypt_detached(&header, &mut buffer).unwrap();
+    buffer
+}
+
+#[test]
+fn test_prf_matches_legacy_stream() {
+    let mut out = vec![0u8; L];
+    for gen_id in [0u16, 1, 0xBEEF] {
+        for seed in [0u32, 255, 256, 511, (7 << 16) | 300] {
+            expand_coefficients(seed, gen_id, L, &mut out).unwrap();
+            assert_eq!(out, legacy_coefficients(seed, gen_id, L), "seed {} gen {}", seed, gen_id);
+            assert_eq!(generate_coefficients(seed, gen_id, L), out);
+        }
+    }
+    // Lengths that do not land on a ChaCha block boundary.
+    for count in [0usize, 1, 63, 64, 65, 1000] {
+        let mut buf = vec![0xAAu8; count + 3];
+        expand_coefficients(9, 9, count, &mut buf).unwrap();
+        assert_eq!(&buf[..count], &legacy_coefficients(9, 9, count)[..]);
+        assert_eq!(&buf[count..], &[0xAA; 3], "Wrote past `count`");
+    }
+}
+
+#[test]
+fn test_prf_rejects_short_buffer() {
+    let mut out = [0u8; 8];
+    assert!(expand_coefficients(1, 1, 9, &mut out).is_err());
+}
+
+#[test]
+fn test_prf_matches_legacy_over_256_symbol_generation() {
+    // One coefficient row per repair symbol of a 256-symbol generation.
+    let mut legacy_sum = 0u64;
+    for sym in 256..512u32 {
+        legacy_sum += legacy_coefficients(sym, 42, L).iter().map(|&b| b as u64).sum::<u64>();
+    }
+
+    let mut out = vec![0u8; L];
+    let mut prf_sum = 0u64;
+    for sym in 256..512u32 {
+        expand_coefficients(sym, 42, L, &mut out).unwrap();
+        prf_sum += out.iter().map(|&b| b as u64).sum::<u64>();
+    }
+
+    assert_eq!(legacy_sum, prf_sum);
+}
@@ -0,0 +1,88 @@
+use m13_core::{M13Header, PacketType, M13_MAGIC};
+use m13_cipher::{M13Cipher, SessionKey, DirectionalKeys, derive_session_key};
+
+fn header() -> M13Header {
+    M13Header {
+        magic: M13_MAGIC,
+        version: 1,
+        packet_type: PacketType::Data,
+        gen_id: 7,
+        symbol_id: 3,
+        payload_len: 5,
+        recoder_rank: 0,
+        reserved: 0,
+        auth_tag: [0u8; 16],
+    }
+}
+
+#[test]
+fn test_from_secret_scrubs_source() {
+    // The handshake hands its KEM shared secret over by reference: Nothing may survive.
+    let mut secret = [0x5Au8; 32];
+    let cipher = M13Cipher::from_secret(&mut secret);
+    assert_eq!(secret, [0u8; 32]);
+
+    // Same key as the long-hand construction.
+    let peer = M13Cipher::new(&SessionKey([0x5A; 32]));
+    let mut h = header();
+    let mut payload = b"hello".to_vec();
+    h.auth_tag = cipher.encrypt_detached(&h, &mut payload).unwrap();
+    peer.decrypt_detached(&h, &mut payload).unwrap();
+    assert_eq!(payload, b"hello");
+}
+
+#[test]
+fn test_session_key_binds_transcript() {
+    let ss = [0x11u8; 32];
+    let hello = vec![0xA0u8; 1568];
+    let reply = vec![0xB0u8; 1568 + 32 + 4627];
+
+    // Both peers, same transcript: Same key.
+    let hub = derive_session_key(&ss, &hello, &reply);
+    let node = derive_session_key(&ss, &hello, &reply);
+    assert_eq!(hub.0, node.0);
+
+    // Never the raw KEM secret.
+    assert_ne!(hub.0, ss);
+
+    // One flipped bit in either leg diverges.
+    let mut bad_hello = hello.clone();
+    bad_hello[7] ^= 1;
+    assert_ne!(derive_session_key(&ss, &bad_hello, &reply).0, hub.0);
+    let mut bad_reply = reply.clone();
+    bad_reply[reply.len() - 1] ^= 1;
+    assert_ne!(derive_session_key(&ss, &hello, &bad_reply).0, hub.0);
+
+    // Moving the split point between the legs diverges too.
+    let (h2, r2) = ([&hello[..], &reply[..1]].concat(), reply[1..].to_vec());
+    assert_ne!(derive_session_key(&ss, &h2, &r2).0, hub.0);
+
+    // And so does a different secret.
+    assert_ne!(derive_session_key(&[0x12; 32], &hello, &reply).0, hub.0);
+}
+
+#[test]
+fn test_directional_keys_reject_reflection() {
+    let keys = DirectionalKeys::derive(&derive_session_key(&[0x11; 32], b"hello", b"reply"));
+    let (hub_tx, hub_rx) = keys.ciphers(true);
+    let (node_tx, node_rx) = keys.ciphers(false);
+    assert_ne!(keys.hub_tx.0, keys.node_tx.0);
+
+    // Hub -> node opens; the same frame reflected back at the hub does not.
+    let mut h = header();
+    let mut payload = b"hello".to_vec();
+    h.auth_tag = hub_tx.encrypt_detached(&h, &mut payload).unwrap();
+    let sealed = payload.clone();
+    assert!(hub_rx.decrypt_detached(&h, &mut payload).is_err());
+    assert_eq!(payload, sealed, "Failed open touched the payload");
+    node_rx.decrypt_detached(&h, &mut payload).unwrap();
+    assert_eq!(payload, b"hello");
+
+    // Same (gen_id, symbol_id) in the other direction: A different keystream.
+    let mut back = b"hello".to_vec();
+    let mut h2 = header();
+    h2.auth_tag = node_tx.encrypt_detached(&h2, &mut back).unwrap();
+    assert_ne!(back, sealed);
+    assert!(node_rx.decrypt_detached(&h2, &mut back.clone()).is_err());
+    hub_rx.decrypt_detached(&h2, &mut back).unwrap();
+}
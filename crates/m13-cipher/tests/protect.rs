@@ -1,5 +1,5 @@
-use m13_core::{M13Header, PacketType, M13_MAGIC};
-use m13_cipher::{M13Cipher, SessionKey};
+use m13_core::{M13Error, M13Header, M13HeaderRef, PacketType, M13_MAGIC};
+use m13_cipher::{M13Cipher, SessionKey, mesh_seal, mesh_open, MESH_SEAL_OVERHEAD};
 
 #[test]
 fn test_round_trip() {
@@ -53,4 +53,76 @@ fn test_aad_tamper() {
     // Decrypt should fail (Poly1305 Auth Fail because AAD changed)
     let res = cipher.decrypt_detached(&header, &mut payload);
     assert!(res.is_err());
-}
\ No newline at end of file
+}
+
+/// Seal a `Data` packet, apply `tamper` to the header, and attempt to open it.
+fn open_after(tamper: impl Fn(&mut M13Header)) -> bool {
+    let cipher = M13Cipher::new(&SessionKey([0x42; 32]));
+    let mut payload = b"Routing Sensitive".to_vec();
+    let mut header = M13Header {
+        magic: M13_MAGIC, version: 1, packet_type: PacketType::Data,
+        gen_id: 7, symbol_id: 3, payload_len: payload.len() as u16, recoder_rank: 0, reserved: 2,
+        auth_tag: [0u8; 16]
+    };
+    header.auth_tag = cipher.encrypt_detached(&header, &mut payload).unwrap();
+
+    tamper(&mut header);
+    cipher.decrypt_detached(&header, &mut payload).is_ok()
+}
+
+#[test]
+fn test_aad_covers_type_and_reserved() {
+    // Control: Untouched header opens.
+    assert!(open_after(|_| {}));
+
+    // Data -> Coded would reroute the plaintext through the repair path.
+    assert!(!open_after(|h| h.packet_type = PacketType::Coded), "packet_type not authenticated");
+    // `reserved` carries K for Fountain generations.
+    assert!(!open_after(|h| h.reserved = 200), "reserved not authenticated");
+    assert!(!open_after(|h| h.version = 2), "version not authenticated");
+    assert!(!open_after(|h| h.recoder_rank = 1), "recoder_rank not authenticated");
+    assert!(!open_after(|h| h.payload_len = 1), "payload_len not authenticated");
+}
+
+#[test]
+fn test_decrypt_from_borrowed_header() {
+    let cipher = M13Cipher::new(&SessionKey([0x42; 32]));
+    let mut header = M13Header { payload_len: 6, reserved: 4, ..M13Header::new(PacketType::Coded, 9, 77) };
+    let mut payload = b"Secret".to_vec();
+    header.auth_tag = cipher.encrypt_detached(&header, &mut payload).unwrap();
+
+    // Decrypt straight off the received bytes.
+    let mut frame = vec![0u8; M13Header::SIZE];
+    header.to_bytes(&mut frame).unwrap();
+    let view = M13HeaderRef::parse(&frame).unwrap();
+    let mut opened = payload.clone();
+    cipher.decrypt_detached_ref(&view, &mut opened).unwrap();
+    assert_eq!(opened, b"Secret");
+
+    // AAD is still bound: Flip `reserved` on the wire.
+    frame[15] ^= 1;
+    let view = M13HeaderRef::parse(&frame).unwrap();
+    assert!(cipher.decrypt_detached_ref(&view, &mut payload.clone()).is_err());
+}
+
+#[test]
+fn test_mesh_seal_round_trip_and_tamper() {
+    let key = [0x5Au8; 32];
+    let plaintext = b"Relays never read this";
+    let sealed = mesh_seal(&key, &[1; 16], plaintext).unwrap();
+    assert_eq!(sealed.len(), plaintext.len() + MESH_SEAL_OVERHEAD);
+    assert!(!sealed.windows(plaintext.len()).any(|w| w == plaintext));
+    assert_eq!(mesh_open(&key, &sealed).unwrap(), plaintext);
+
+    // A fresh salt is a fresh key: Same plaintext, unrelated ciphertext.
+    let resealed = mesh_seal(&key, &[2; 16], plaintext).unwrap();
+    assert_ne!(resealed[16..], sealed[16..]);
+
+    assert!(matches!(mesh_open(&[0xA5; 32], &sealed), Err(M13Error::AuthFail)));
+    for i in [0, 16, sealed.len() - 1] {
+        let mut bad = sealed.clone();
+        bad[i] ^= 1;
+        assert!(matches!(mesh_open(&key, &bad), Err(M13Error::AuthFail)), "Flip at {} accepted", i);
+    }
+    assert!(matches!(mesh_open(&key, &sealed[..MESH_SEAL_OVERHEAD - 1]), Err(M13Error::WireFormatError)));
+}
@@ -15,19 +15,33 @@ pub const KYBER_PK_LEN_1024: usize = KYBER_PUBLIC_KEY_SIZE;
 pub const KYBER_CT_LEN_1024: usize = KYBER_CIPHERTEXT_SIZE;
 pub const DILITHIUM_SIG_LEN_87: usize = DILITHIUM_SIGNATURE_SIZE;
 
+// Lightweight Handshake Profile (ML-KEM-768)
+pub const KYBER_PK_LEN_768: usize = 1184;
+pub const KYBER_CT_LEN_768: usize = 1088;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum PacketType {
     Data = 0x01,
     Ack = 0x02,
+    Goodbye = 0x03,
     Handshake = 0xF0,
     KeepAlive = 0xFF,
     Coded = 0x10,
     ClientHello = 0x11, 
     HandshakeInit = 0x12,
     HandshakeAuth = 0x13,
+    Cookie = 0x14,
+    Nack = 0x15,
+    /// [MESH] A relay's combination of a Raptor generation: [ source gen_id | GEV over L | symbol ].
+    Recoded = 0x16,
 }
 
+/// `recoder_rank` bits 0..=5: Basis size (rank) of the RLNC relay that recoded the packet,
+/// so a receiver can tell how much innovation that relay holds. 0 from an original encoder.
+/// Bits 6..=7 are per-packet flags owned by the sender.
+pub const RECODER_RANK_MASK: u8 = 0x3F;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C, packed)]
 pub struct M13Header {
@@ -45,8 +59,37 @@ pub struct M13Header {
 impl M13Header {
     pub const SIZE: usize = 32;
 
-    pub fn to_bytes(&self, buf: &mut [u8]) -> Result<(), ()> {
-        if buf.len() < Self::SIZE { return Err(()); }
+    /// Header with the defaults every sender wants: `M13_MAGIC`, version 1, no payload,
+    /// zero rank/reserved and a zero tag. Set the rest with struct update syntax:
+    /// `M13Header { payload_len: n, ..M13Header::new(PacketType::Ack, gen_id, symbol_id) }`.
+    pub const fn new(packet_type: PacketType, gen_id: u16, symbol_id: u32) -> Self {
+        Self {
+            magic: M13_MAGIC,
+            version: 1,
+            packet_type,
+            gen_id,
+            symbol_id,
+            payload_len: 0,
+            recoder_rank: 0,
+            reserved: 0,
+            auth_tag: [0u8; 16],
+        }
+    }
+
+    /// Relay rank advertised in `recoder_rank` (flag bits masked off).
+    pub fn advertised_rank(&self) -> u8 {
+        self.recoder_rank & RECODER_RANK_MASK
+    }
+
+    /// Advertise `rank` (saturating at `RECODER_RANK_MASK`), keeping the flag bits.
+    pub fn set_advertised_rank(&mut self, rank: usize) {
+        let rank = core::cmp::min(rank, RECODER_RANK_MASK as usize) as u8;
+        self.recoder_rank = (self.recoder_rank & !RECODER_RANK_MASK) | rank;
+    }
+
+    /// `WireFormatError` if `buf` is shorter than `SIZE`.
+    pub fn to_bytes(&self, buf: &mut [u8]) -> M13Result<()> {
+        if buf.len() < Self::SIZE { return Err(M13Error::WireFormatError); }
         buf[0..4].copy_from_slice(&self.magic.to_be_bytes());
         buf[4] = self.version;
         buf[5] = self.packet_type as u8;
@@ -59,22 +102,13 @@ impl M13Header {
         Ok(())
     }
 
-    pub fn from_bytes(buf: &[u8]) -> Result<Self, ()> {
-        if buf.len() < Self::SIZE { return Err(()); }
+    /// `WireFormatError` if `buf` is short or the magic is wrong,
+    /// `UnknownPacketType` if the type byte is not one we speak.
+    pub fn from_bytes(buf: &[u8]) -> M13Result<Self> {
+        if buf.len() < Self::SIZE { return Err(M13Error::WireFormatError); }
         let magic = u32::from_be_bytes(buf[0..4].try_into().unwrap());
-        if magic != M13_MAGIC { return Err(()); }
-        
-        let packet_type = match buf[5] {
-            0x01 => PacketType::Data,
-            0x02 => PacketType::Ack,
-            0xF0 => PacketType::Handshake,
-            0xFF => PacketType::KeepAlive,
-            0x10 => PacketType::Coded,
-            0x11 => PacketType::ClientHello,
-            0x12 => PacketType::HandshakeInit,
-            0x13 => PacketType::HandshakeAuth,
-            _ => return Err(()),
-        };
+        if magic != M13_MAGIC { return Err(M13Error::WireFormatError); }
+        let packet_type = PacketType::from_u8(buf[5])?;
 
         Ok(Self {
             magic,
@@ -90,6 +124,80 @@ impl M13Header {
     }
 }
 
+impl PacketType {
+    pub fn from_u8(b: u8) -> M13Result<Self> {
+        Ok(match b {
+            0x01 => PacketType::Data,
+            0x02 => PacketType::Ack,
+            0x03 => PacketType::Goodbye,
+            0xF0 => PacketType::Handshake,
+            0xFF => PacketType::KeepAlive,
+            0x10 => PacketType::Coded,
+            0x11 => PacketType::ClientHello,
+            0x12 => PacketType::HandshakeInit,
+            0x13 => PacketType::HandshakeAuth,
+            0x14 => PacketType::Cookie,
+            0x15 => PacketType::Nack,
+            0x16 => PacketType::Recoded,
+            other => return Err(M13Error::UnknownPacketType(other)),
+        })
+    }
+}
+
+/// [PERF] Zero-copy header: Borrows the first 32 bytes of a received frame.
+/// Validated once at `parse` (same rules as `M13Header::from_bytes`); accessors decode on
+/// demand and `as_bytes` feeds the AEAD without re-serializing. RX path only: Build
+/// outgoing headers with `M13Header`.
+#[derive(Debug, Clone, Copy)]
+pub struct M13HeaderRef<'a> {
+    bytes: &'a [u8; M13Header::SIZE],
+    packet_type: PacketType,
+}
+
+impl<'a> M13HeaderRef<'a> {
+    pub fn parse(buf: &'a [u8]) -> M13Result<Self> {
+        let bytes: &'a [u8; M13Header::SIZE] = buf.get(..M13Header::SIZE)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(M13Error::WireFormatError)?;
+        if bytes[0..4] != M13_MAGIC.to_be_bytes() { return Err(M13Error::WireFormatError); }
+        let packet_type = PacketType::from_u8(bytes[5])?;
+        Ok(Self { bytes, packet_type })
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8; M13Header::SIZE] { self.bytes }
+    pub fn magic(&self) -> u32 { M13_MAGIC }
+    pub fn version(&self) -> u8 { self.bytes[4] }
+    pub fn packet_type(&self) -> PacketType { self.packet_type }
+    pub fn gen_id(&self) -> u16 { u16::from_be_bytes([self.bytes[6], self.bytes[7]]) }
+    pub fn symbol_id(&self) -> u32 {
+        u32::from_be_bytes([self.bytes[8], self.bytes[9], self.bytes[10], self.bytes[11]])
+    }
+    pub fn payload_len(&self) -> u16 { u16::from_be_bytes([self.bytes[12], self.bytes[13]]) }
+    pub fn recoder_rank(&self) -> u8 { self.bytes[14] }
+    pub fn advertised_rank(&self) -> u8 { self.bytes[14] & RECODER_RANK_MASK }
+    pub fn reserved(&self) -> u8 { self.bytes[15] }
+    pub fn auth_tag(&self) -> &'a [u8; 16] {
+        let (_, tag) = self.bytes.split_at(16);
+        // Infallible: 32 - 16.
+        tag.try_into().unwrap()
+    }
+
+    /// Owned copy, for the rare consumer that must keep the header past the frame.
+    pub fn to_header(&self) -> M13Header {
+        M13Header {
+            magic: M13_MAGIC,
+            version: self.version(),
+            packet_type: self.packet_type,
+            gen_id: self.gen_id(),
+            symbol_id: self.symbol_id(),
+            payload_len: self.payload_len(),
+            recoder_rank: self.recoder_rank(),
+            reserved: self.reserved(),
+            auth_tag: *self.auth_tag(),
+        }
+    }
+}
+
 pub type M13Result<T> = Result<T, M13Error>;
 
 #[derive(Debug)]
@@ -102,6 +210,8 @@ pub enum M13Error {
     RngFailure,
     HalError, 
     EntropyExhaustion,
+    /// Well-formed header with a packet type byte we do not recognize.
+    UnknownPacketType(u8),
 }
 
 impl core::fmt::Display for M13Error {
@@ -0,0 +1,115 @@
+use m13_core::{M13Error, M13Header, M13HeaderRef, PacketType, M13_MAGIC};
+
+fn header(packet_type: PacketType) -> M13Header {
+    M13Header {
+        magic: M13_MAGIC, version: 1, packet_type,
+        gen_id: 0, symbol_id: 0x8000_0007, payload_len: 0,
+        recoder_rank: 0, reserved: 0, auth_tag: [0x5A; 16],
+    }
+}
+
+#[test]
+fn test_goodbye_round_trip() {
+    let mut buf = [0u8; M13Header::SIZE];
+    header(PacketType::Goodbye).to_bytes(&mut buf).unwrap();
+    assert_eq!(buf[5], 0x03);
+
+    let parsed = M13Header::from_bytes(&buf).unwrap();
+    assert_eq!(parsed, header(PacketType::Goodbye));
+}
+
+#[test]
+fn test_unknown_packet_type_rejected() {
+    let mut buf = [0u8; M13Header::SIZE];
+    header(PacketType::Goodbye).to_bytes(&mut buf).unwrap();
+    buf[5] = 0x04;
+    assert!(matches!(M13Header::from_bytes(&buf), Err(M13Error::UnknownPacketType(0x04))));
+}
+
+#[test]
+fn test_short_buffer_rejected() {
+    let mut buf = [0u8; M13Header::SIZE];
+    header(PacketType::Data).to_bytes(&mut buf).unwrap();
+    assert!(matches!(M13Header::from_bytes(&buf[..M13Header::SIZE - 1]), Err(M13Error::WireFormatError)));
+    assert!(matches!(header(PacketType::Data).to_bytes(&mut [0u8; 31]), Err(M13Error::WireFormatError)));
+}
+
+#[test]
+fn test_bad_magic_rejected() {
+    let mut buf = [0u8; M13Header::SIZE];
+    header(PacketType::Data).to_bytes(&mut buf).unwrap();
+    buf[0] ^= 0xFF;
+    assert!(matches!(M13Header::from_bytes(&buf), Err(M13Error::WireFormatError)));
+
+    // Magic is checked before the type byte.
+    buf[5] = 0x04;
+    assert!(matches!(M13Header::from_bytes(&buf), Err(M13Error::WireFormatError)));
+}
+
+#[test]
+fn test_cookie_round_trip() {
+    let mut buf = [0u8; M13Header::SIZE];
+    header(PacketType::Cookie).to_bytes(&mut buf).unwrap();
+    assert_eq!(buf[5], 0x14);
+    assert_eq!(M13Header::from_bytes(&buf).unwrap(), header(PacketType::Cookie));
+}
+
+#[test]
+fn test_new_defaults() {
+    let h = M13Header::new(PacketType::Ack, 0x1234, 0xDEAD_BEEF);
+    let explicit = M13Header {
+        magic: M13_MAGIC, version: 1, packet_type: PacketType::Ack,
+        gen_id: 0x1234, symbol_id: 0xDEAD_BEEF, payload_len: 0,
+        recoder_rank: 0, reserved: 0, auth_tag: [0; 16],
+    };
+    assert_eq!(h, explicit);
+
+    // Struct update keeps the defaults for every field left out.
+    let data = M13Header { payload_len: 900, reserved: 4, ..M13Header::new(PacketType::Data, 7, 3) };
+    let mut buf = [0u8; M13Header::SIZE];
+    data.to_bytes(&mut buf).unwrap();
+    assert_eq!(&buf[0..4], &M13_MAGIC.to_be_bytes());
+    assert_eq!(buf[4], 1);
+    assert_eq!(M13Header::from_bytes(&buf).unwrap(), data);
+}
+
+#[test]
+fn test_ref_matches_owned_parse() {
+    let samples = [
+        M13Header { payload_len: 1200, reserved: 16, recoder_rank: 3, auth_tag: [0xA5; 16], ..M13Header::new(PacketType::Coded, 0xBEEF, 0x0102_0304) },
+        M13Header { version: 7, ..M13Header::new(PacketType::KeepAlive, 0, 0x8000_0001) },
+        header(PacketType::Cookie),
+    ];
+    for h in samples {
+        let mut buf = [0u8; M13Header::SIZE + 8];
+        h.to_bytes(&mut buf).unwrap();
+
+        let owned = M13Header::from_bytes(&buf).unwrap();
+        let view = M13HeaderRef::parse(&buf).unwrap();
+        let (magic, version, gen_id, symbol_id, payload_len) = (owned.magic, owned.version, owned.gen_id, owned.symbol_id, owned.payload_len);
+        assert_eq!(view.magic(), magic);
+        assert_eq!(view.version(), version);
+        assert_eq!(view.packet_type(), owned.packet_type);
+        assert_eq!(view.gen_id(), gen_id);
+        assert_eq!(view.symbol_id(), symbol_id);
+        assert_eq!(view.payload_len(), payload_len);
+        assert_eq!(view.recoder_rank(), owned.recoder_rank);
+        assert_eq!(view.reserved(), owned.reserved);
+        assert_eq!(view.auth_tag(), &owned.auth_tag);
+        assert_eq!(view.as_bytes()[..], buf[..M13Header::SIZE]);
+        assert_eq!(view.to_header(), owned);
+    }
+}
+
+#[test]
+fn test_ref_rejects_like_owned() {
+    let mut buf = [0u8; M13Header::SIZE];
+    header(PacketType::Data).to_bytes(&mut buf).unwrap();
+    assert!(matches!(M13HeaderRef::parse(&buf[..31]), Err(M13Error::WireFormatError)));
+
+    buf[5] = 0x04;
+    assert!(matches!(M13HeaderRef::parse(&buf), Err(M13Error::UnknownPacketType(0x04))));
+
+    buf[0] ^= 0xFF;
+    assert!(matches!(M13HeaderRef::parse(&buf), Err(M13Error::WireFormatError)));
+}
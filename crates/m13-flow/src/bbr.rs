@@ -9,13 +9,67 @@ use crate::filter::{WindowedMaxFilter, WindowedMinFilter};
 
 const BTL_BW_WINDOW: u64 = 10_000_000; 
 
+
+
 const RT_PROP_WINDOW: u64 = 10_000_000;
 
 
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// [BBR] Gains are fixed-point percent (289 = 2.89x).
+
+const STARTUP_GAIN: u64 = 289;
+
+
+
+const DRAIN_GAIN: u64 = 35; // 1 / 2.89
+
+
+
+const UNITY_GAIN: u64 = 100;
+
+
+
+/// ProbeBW 8-phase gain cycle: Probe up, drain the probe, cruise x6.
+const PROBE_BW_GAINS: [u64; 8] = [125, 75, 100, 100, 100, 100, 100, 100];
+
+
+
+/// full_pipe: BtlBw must grow >= 25% per round, else a strike.
+const FULL_BW_THRESH: u64 = 125;
+
+
+
+const FULL_BW_ROUNDS: u32 = 3;
+
+
+
+const PROBE_RTT_DURATION: u64 = 200_000;
+
+
+
+// [BBR] In-flight cap = cwnd gain x BDP. 2.89x while filling the pipe, 2x in steady state.
+
+const STARTUP_CWND_GAIN: u64 = 289;
+
 
-#[allow(dead_code)]
+
+const CWND_GAIN: u64 = 200;
+
+
+
+// ProbeRtt drains to a few packets so the min RTT sample is queue-free.
+
+const MIN_INFLIGHT_BYTES: u64 = 4 * 1500;
+
+
+
+// [ECN] Gain ceiling for the rest of a round that saw a CE mark (drains the standing queue).
+
+const ECN_BACKOFF_GAIN: u64 = 85;
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 
 pub enum BbrState { Startup, Drain, ProbeBw, ProbeRtt }
 
@@ -23,26 +77,46 @@ pub enum BbrState { Startup, Drain, ProbeBw, ProbeRtt }
 
 pub struct RateEstimator {
 
-    #[allow(dead_code)]
-
     state: BbrState,
 
     btl_bw_filter: WindowedMaxFilter,
 
     rt_prop_filter: WindowedMinFilter,
 
-    #[allow(dead_code)]
+    // Time the min RTT was last refreshed (ProbeRtt trigger).
 
     last_rtt_probe: u64,
 
+    min_rtt_us: u64,
+
     pacing_gain: u64, 
 
+    // full_pipe detection
+
+    full_bw: u64,
+
+    full_bw_count: u32,
+
+    filled_pipe: bool,
+
+    // Round = one min RTT of wall time.
+
+    round_start_us: u64,
+
+    round_count: u64,
+
+    cycle_index: usize,
+
+    probe_rtt_done_us: u64,
+
 }
 
 
 
 impl RateEstimator {
 
+
+
     pub fn new() -> Self {
 
         Self {
@@ -55,7 +129,23 @@ impl RateEstimator {
 
             last_rtt_probe: 0,
 
-            pacing_gain: 289, 
+            min_rtt_us: u64::MAX,
+
+            pacing_gain: STARTUP_GAIN, 
+
+            full_bw: 0,
+
+            full_bw_count: 0,
+
+            filled_pipe: false,
+
+            round_start_us: 0,
+
+            round_count: 0,
+
+            cycle_index: 0,
+
+            probe_rtt_done_us: 0,
 
         }
 
@@ -63,12 +153,246 @@ impl RateEstimator {
 
 
 
-    pub fn on_ack(&mut self, delivered_bps: u64, rtt_us: u64, now: u64) {
+    /// `ce_marked`: The acknowledged data crossed a congested (ECN CE) queue.
+    pub fn on_ack(&mut self, delivered_bps: u64, rtt_us: u64, ce_marked: bool, now: u64) {
 
         self.btl_bw_filter.update(delivered_bps, now);
 
         self.rt_prop_filter.update(rtt_us, now);
 
+        // 1. Min RTT tracking (expiry drives ProbeRtt)
+
+        let rtt_expired = self.round_count > 0 && now.saturating_sub(self.last_rtt_probe) > RT_PROP_WINDOW;
+
+        if rtt_us <= self.min_rtt_us || rtt_expired {
+
+            self.min_rtt_us = rtt_us;
+
+            self.last_rtt_probe = now;
+
+        }
+
+        // 2. Round accounting
+
+        let round_start = self.round_count == 0
+
+            || now.saturating_sub(self.round_start_us) >= self.min_rtt_us;
+
+        if round_start {
+
+            self.round_start_us = now;
+
+            self.round_count += 1;
+
+        }
+
+        // 3. State transitions
+
+        match self.state {
+
+            BbrState::Startup => {
+
+                if round_start { self.check_full_pipe(now); }
+
+                // [ECN] A marking queue means the pipe is already full: Stop doubling.
+
+                if ce_marked { self.filled_pipe = true; }
+
+                if self.filled_pipe { self.enter_drain(now); }
+
+            }
+
+            BbrState::Drain => {
+
+                // One round at 1/2.89 empties the queue Startup built.
+
+                if round_start { self.enter_probe_bw(now); }
+
+            }
+
+            BbrState::ProbeBw => {
+
+                if round_start {
+
+                    self.cycle_index = (self.cycle_index + 1) % PROBE_BW_GAINS.len();
+
+                    self.pacing_gain = PROBE_BW_GAINS[self.cycle_index];
+
+                }
+
+            }
+
+            BbrState::ProbeRtt => {
+
+                if now >= self.probe_rtt_done_us {
+
+                    self.last_rtt_probe = now;
+
+                    if self.filled_pipe { self.enter_probe_bw(now); } else { self.enter_startup(); }
+
+                }
+
+            }
+
+        }
+
+        if rtt_expired && self.state != BbrState::ProbeRtt {
+
+            self.state = BbrState::ProbeRtt;
+
+            self.pacing_gain = UNITY_GAIN;
+
+            self.probe_rtt_done_us = now + PROBE_RTT_DURATION;
+
+        }
+
+        // 4. [ECN] Back off until the next round start restores the cycle gain.
+
+        if ce_marked {
+
+            self.pacing_gain = core::cmp::min(self.pacing_gain, ECN_BACKOFF_GAIN);
+
+        }
+
+    }
+
+
+
+    fn check_full_pipe(&mut self, now: u64) {
+
+        let bw = self.btl_bw_filter.get_best(now);
+
+        if bw * 100 >= self.full_bw * FULL_BW_THRESH {
+
+            self.full_bw = bw;
+
+            self.full_bw_count = 0;
+
+            return;
+
+        }
+
+        self.full_bw_count += 1;
+
+        if self.full_bw_count >= FULL_BW_ROUNDS {
+
+            self.filled_pipe = true;
+
+        }
+
+    }
+
+
+
+    fn enter_startup(&mut self) {
+
+        self.state = BbrState::Startup;
+
+        self.pacing_gain = STARTUP_GAIN;
+
+    }
+
+
+
+    fn enter_drain(&mut self, now: u64) {
+
+        self.state = BbrState::Drain;
+
+        self.pacing_gain = DRAIN_GAIN;
+
+        self.round_start_us = now;
+
+    }
+
+
+
+    fn enter_probe_bw(&mut self, now: u64) {
+
+        self.state = BbrState::ProbeBw;
+
+        self.cycle_index = 0;
+
+        self.pacing_gain = PROBE_BW_GAINS[0];
+
+        self.round_start_us = now;
+
+    }
+
+
+
+    pub fn state(&self) -> BbrState {
+
+        self.state
+
+    }
+
+
+
+    /// Current pacing gain in percent (289 = 2.89x).
+    pub fn pacing_gain(&self) -> u64 {
+
+        self.pacing_gain
+
+    }
+
+
+
+    /// Bottleneck bandwidth estimate (0 = no delivery samples yet).
+    pub fn btl_bw_bps(&self, now: u64) -> u64 {
+
+        self.btl_bw_filter.get_best(now)
+
+    }
+
+
+
+    /// Round-trip propagation estimate (100ms default before the first sample).
+    pub fn rt_prop_us(&self, now: u64) -> u64 {
+
+        self.rt_prop_filter.get_best(now)
+
+    }
+
+
+
+    /// Bandwidth-delay product (bytes) = BtlBw x RTprop. None until the first ACK.
+    pub fn bdp_bytes(&self, now: u64) -> Option<u64> {
+
+        let btl_bw = self.btl_bw_filter.get_best(now);
+
+        if btl_bw == 0 || self.min_rtt_us == u64::MAX { return None; }
+
+        let bdp = (btl_bw as u128 * self.rt_prop_filter.get_best(now) as u128) / 8_000_000;
+
+        Some(bdp as u64)
+
+    }
+
+
+
+    /// Unacknowledged bytes the sender may have outstanding (u64::MAX until the first ACK).
+    pub fn inflight_cap_bytes(&self, now: u64) -> u64 {
+
+        let bdp = match self.bdp_bytes(now) {
+
+            Some(b) => b,
+
+            None => return u64::MAX,
+
+        };
+
+        let gain = match self.state {
+
+            BbrState::Startup | BbrState::Drain => STARTUP_CWND_GAIN,
+
+            BbrState::ProbeBw => CWND_GAIN,
+
+            BbrState::ProbeRtt => return MIN_INFLIGHT_BYTES,
+
+        };
+
+        core::cmp::max(bdp.saturating_mul(gain) / 100, MIN_INFLIGHT_BYTES)
+
     }
 
 
@@ -98,4 +422,3 @@ impl RateEstimator {
     }
 
 }
-
@@ -2,9 +2,12 @@
 
 extern crate alloc;
 use alloc::vec::Vec;
-use m13_core::{M13Header, PacketType, M13_MAGIC};
+use m13_core::{M13Header, PacketType};
 use rand_core::{RngCore, CryptoRng};
 
+/// `M13Header::reserved` value marking chaff. AAD-bound, so only a keyholder can set or strip it.
+pub const CHAFF_MARKER: u8 = 0xFF;
+
 /// Generates a Chaff Packet (Spec §10.3.2).
 /// The payload is cryptographic noise.
 /// The header marks it as 'Data' to an observer, preventing filtering.
@@ -16,18 +19,12 @@ pub fn generate_chaff<R: RngCore + CryptoRng>(
     let mut payload = alloc::vec![0u8; size];
     rng.fill_bytes(&mut payload);
 
+    // Chaff masquerades as Data to defeat Deep Packet Inspection.
+    // Random Symbol ID prevents replay detection logic from blocking it too early.
     let header = M13Header {
-        magic: M13_MAGIC,
-        version: 1,
-        // Chaff masquerades as Data to defeat Deep Packet Inspection
-        packet_type: PacketType::Data, 
-        gen_id,
-        // Random Symbol ID prevents replay detection logic from blocking it too early
-        symbol_id: rng.next_u32(), 
         payload_len: size as u16,
-        recoder_rank: 0,
-        reserved: 0xFF, // Internal Marker: "Ignore Me"
-        auth_tag: [0u8; 16], 
+        reserved: CHAFF_MARKER, // Internal Marker: "Ignore Me"
+        ..M13Header::new(PacketType::Data, gen_id, rng.next_u32())
     };
 
     (header, payload)
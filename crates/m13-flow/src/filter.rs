@@ -39,7 +39,6 @@ impl WindowedMaxFilter {
 
 /// Tracks the Minimum value over a time window.
 /// Used for Round-Trip Propagation (RTprop).
-#[allow(dead_code)] // Logic exists for future BDP calc, currently unused in Lite.
 #[derive(Debug, Clone)]
 pub struct WindowedMinFilter {
     window_us: u64,
@@ -47,7 +46,6 @@ pub struct WindowedMinFilter {
     idx: usize,
 }
 
-#[allow(dead_code)]
 impl WindowedMinFilter {
     pub fn new(window_us: u64) -> Self {
         Self {
@@ -5,6 +5,6 @@ mod bbr;
 mod chaff;
 mod pacer;
 
-pub use bbr::RateEstimator;
-pub use pacer::Pacer;
-pub use chaff::generate_chaff;
\ No newline at end of file
+pub use bbr::{RateEstimator, BbrState};
+pub use pacer::{Pacer, SEGMENT_OVERHEAD};
+pub use chaff::{generate_chaff, CHAFF_MARKER};
\ No newline at end of file
@@ -5,6 +5,22 @@ use crate::bbr::RateEstimator;
 
 
 
+/// IPv4 (20) + UDP (8): Wire bytes per datagram that the payload length does not show.
+pub const SEGMENT_OVERHEAD: usize = 28;
+
+
+
+// [BBR] Send quantum: ~1ms of pacing rate per burst, never below two full-size
+// datagrams (keeps ACK clocking alive) nor above what a shallow buffer absorbs.
+
+const SEND_QUANTUM_US: u64 = 1_000;
+
+const MIN_SEND_QUANTUM: usize = 2 * 1500;
+
+const MAX_SEND_QUANTUM: usize = 16 * 1024;
+
+
+
 /// The Token Bucket Traffic Shaper.
 
 pub struct Pacer {
@@ -17,6 +33,12 @@ pub struct Pacer {
 
     min_rate_floor: u64, // CBR Floor (Bytes/sec)
 
+    // [CHAFF] Second bucket at the CBR floor only. Real egress drains it too,
+
+    // so cover traffic tops the link up to the floor instead of adding to it.
+
+    floor_tokens: i64,
+
 }
 
 
@@ -35,6 +57,8 @@ impl Pacer {
 
             min_rate_floor: min_cbr_bps / 8,
 
+            floor_tokens: 0,
+
         }
 
     }
@@ -109,6 +133,16 @@ impl Pacer {
 
 
 
+        // [CHAFF] Small cap: After a busy spell the floor resumes at rate, not as a burst.
+
+        const FLOOR_BURST_LIMIT: i64 = 16 * 1024;
+
+        let floor_refill = (self.min_rate_floor as u128 * delta as u128) / 1_000_000;
+
+        self.floor_tokens = core::cmp::min(self.floor_tokens + floor_refill as i64, FLOOR_BURST_LIMIT);
+
+
+
         if self.tokens > 0 { self.tokens as u64 } else { 0 }
 
     }
@@ -119,6 +153,29 @@ impl Pacer {
 
         self.tokens -= bytes as i64;
 
+        self.floor_tokens = core::cmp::max(self.floor_tokens - bytes as i64, 0);
+
+    }
+
+
+
+    /// Datagrams of up to `segment_len` payload bytes the bucket can pay for now (at most `max`).
+    /// Sizes a GSO burst up front, so nothing is popped that cannot be sent.
+    pub fn affordable_segments(&self, segment_len: usize, max: usize) -> usize {
+
+        if self.tokens <= 0 { return 0; }
+
+        core::cmp::min(self.tokens as usize / (segment_len + SEGMENT_OVERHEAD), max)
+
+    }
+
+
+
+    /// Charge a GSO send: `payload_bytes` sliced into `segments` datagrams.
+    pub fn consume_segments(&mut self, payload_bytes: usize, segments: usize) {
+
+        self.consume(payload_bytes + segments * SEGMENT_OVERHEAD);
+
     }
 
 
@@ -129,13 +186,109 @@ impl Pacer {
 
     }
 
+
+
+    /// True when egress has fallen below the CBR floor by a full packet and the
+    /// BBR budget still allows one: Send cover traffic now.
+    pub fn chaff_due(&self, packet_len: usize) -> bool {
+
+        self.floor_tokens >= packet_len as i64 && self.chaff_needed(packet_len)
+
+    }
+
+
+
+    /// [IDLE] Earliest time (us) the bucket holds `bytes`, at the current rate.
+    /// Lets the runtime sleep until the next send instead of spinning on `tick`.
+    pub fn ready_at_us(&self, bytes: usize, now_us: u64) -> u64 {
+
+        let rate = core::cmp::max(self.estimator.get_pacing_rate_bps(now_us) / 8, self.min_rate_floor);
+
+        core::cmp::max(refill_at_us(self.tokens, bytes, rate, self.last_update_us), now_us)
+
+    }
+
+
+
+    /// [CHAFF] Earliest time (us) `chaff_due(packet_len)` turns true.
+    pub fn chaff_due_at_us(&self, packet_len: usize, now_us: u64) -> u64 {
+
+        let floor = refill_at_us(self.floor_tokens, packet_len, self.min_rate_floor, self.last_update_us);
+
+        core::cmp::max(self.ready_at_us(packet_len, now_us), floor)
+
+    }
+
     
 
-    pub fn on_ack(&mut self, delivered_bps: u64, rtt_us: u64, now: u64) {
+    pub fn on_ack(&mut self, delivered_bps: u64, rtt_us: u64, ce_marked: bool, now: u64) {
+
+        self.estimator.on_ack(delivered_bps, rtt_us, ce_marked, now);
+
+    }
+
+
+
+    /// Per-poll burst cap in bytes. The bucket may allow far more; the quantum spreads it out.
+    pub fn send_quantum(&self, now: u64) -> usize {
+
+        let per_slice = (self.pacing_rate_bps(now) as u128 * SEND_QUANTUM_US as u128) / 8_000_000;
+
+        (per_slice as usize).clamp(MIN_SEND_QUANTUM, MAX_SEND_QUANTUM)
+
+    }
+
+
+
+    /// Round-trip propagation estimate (us). 100ms before the first ACK.
+    pub fn rt_prop_us(&self, now: u64) -> u64 {
+
+        self.estimator.rt_prop_us(now)
+
+    }
+
 
-        self.estimator.on_ack(delivered_bps, rtt_us, now);
+
+    /// BBR in-flight cap (bytes). New data waits while unacknowledged bytes exceed it.
+    pub fn inflight_cap_bytes(&self, now: u64) -> u64 {
+
+        self.estimator.inflight_cap_bytes(now)
+
+    }
+
+
+
+    /// Effective egress rate (bits/sec) after applying the CBR floor.
+    pub fn pacing_rate_bps(&self, now: u64) -> u64 {
+
+        core::cmp::max(self.estimator.get_pacing_rate_bps(now), self.min_rate_floor * 8)
+
+    }
+
+
+
+    /// Measured bottleneck bandwidth (bits/sec). 0 until the first ACK arrives.
+    pub fn bandwidth_estimate_bps(&self, now: u64) -> u64 {
+
+        self.estimator.btl_bw_bps(now)
 
     }
 
 }
 
+
+
+// When a bucket holding `tokens` at `from_us` reaches `bytes`, refilling at `rate` bytes/sec.
+fn refill_at_us(tokens: i64, bytes: usize, rate: u64, from_us: u64) -> u64 {
+
+    let deficit = bytes as i64 - tokens;
+
+    if deficit <= 0 { return from_us; }
+
+    if rate == 0 { return u64::MAX; }
+
+    let wait = (deficit as u128 * 1_000_000).div_ceil(rate as u128);
+
+    from_us.saturating_add(wait.min(u64::MAX as u128) as u64)
+
+}
@@ -0,0 +1,158 @@
+use m13_flow::{RateEstimator, BbrState};
+
+const RTT: u64 = 10_000; // 10ms
+const BW: u64 = 100_000_000; // 100 Mbps
+
+/// Feed one ACK per RTT (one BBR round each) at a fixed delivery rate.
+fn feed_rounds(bbr: &mut RateEstimator, now: &mut u64, rounds: usize, bw: u64, rtt: u64) {
+    for _ in 0..rounds {
+        bbr.on_ack(bw, rtt, false, *now);
+        *now += rtt;
+    }
+}
+
+/// Drive a fresh estimator through Startup and Drain into ProbeBW.
+fn reach_probe_bw(bbr: &mut RateEstimator, now: &mut u64) {
+    feed_rounds(bbr, now, 4, BW, RTT); // 1 sample + 3 plateau rounds
+    assert_eq!(bbr.state(), BbrState::Drain);
+    feed_rounds(bbr, now, 1, BW, RTT);
+    assert_eq!(bbr.state(), BbrState::ProbeBw);
+}
+
+#[test]
+fn test_startup_holds_while_bandwidth_grows() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    let mut bw = 10_000_000;
+    for _ in 0..8 {
+        feed_rounds(&mut bbr, &mut now, 1, bw, RTT);
+        bw *= 2;
+    }
+    assert_eq!(bbr.state(), BbrState::Startup);
+    assert_eq!(bbr.pacing_gain(), 289);
+}
+
+#[test]
+fn test_plateau_enters_drain() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+
+    // Round 1 sets full_bw; rounds 2 and 3 are strikes.
+    feed_rounds(&mut bbr, &mut now, 3, BW, RTT);
+    assert_eq!(bbr.state(), BbrState::Startup);
+
+    // Third non-growing round: Pipe is full.
+    feed_rounds(&mut bbr, &mut now, 1, BW, RTT);
+    assert_eq!(bbr.state(), BbrState::Drain);
+    assert_eq!(bbr.pacing_gain(), 35);
+    assert_eq!(bbr.get_pacing_rate_bps(now), BW * 35 / 100);
+}
+
+#[test]
+fn test_sub_threshold_growth_counts_as_plateau() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    // +5% per round stays below the 25% full_pipe threshold (measured against full_bw).
+    let mut bw = BW;
+    for _ in 0..4 {
+        feed_rounds(&mut bbr, &mut now, 1, bw, RTT);
+        bw = bw * 105 / 100;
+    }
+    assert_eq!(bbr.state(), BbrState::Drain);
+}
+
+#[test]
+fn test_probe_bw_gain_cycle() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    reach_probe_bw(&mut bbr, &mut now);
+
+    let expected = [125, 75, 100, 100, 100, 100, 100, 100, 125, 75];
+    for gain in expected {
+        assert_eq!(bbr.state(), BbrState::ProbeBw);
+        assert_eq!(bbr.pacing_gain(), gain);
+        feed_rounds(&mut bbr, &mut now, 1, BW, RTT);
+    }
+}
+
+#[test]
+fn test_probe_rtt_after_stale_min_rtt() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    reach_probe_bw(&mut bbr, &mut now);
+
+    // Queueing inflates RTT: min RTT is never refreshed for 10s.
+    let inflated = 2 * RTT;
+    let deadline = now + 10_000_000;
+    while now <= deadline {
+        assert_ne!(bbr.state(), BbrState::ProbeRtt);
+        feed_rounds(&mut bbr, &mut now, 1, BW, inflated);
+    }
+    feed_rounds(&mut bbr, &mut now, 1, BW, inflated);
+    assert_eq!(bbr.state(), BbrState::ProbeRtt);
+    assert_eq!(bbr.pacing_gain(), 100);
+
+    // ProbeRTT lasts 200ms, then resumes ProbeBW (pipe already filled).
+    feed_rounds(&mut bbr, &mut now, 200_000 / inflated as usize + 1, BW, inflated);
+    assert_eq!(bbr.state(), BbrState::ProbeBw);
+}
+
+#[test]
+fn test_ce_marks_reduce_pacing_rate() {
+    let mut clean = RateEstimator::new();
+    let mut marked = RateEstimator::new();
+    let mut now = 1_000_000;
+    let mut t = now;
+    reach_probe_bw(&mut clean, &mut now);
+    reach_probe_bw(&mut marked, &mut t);
+
+    // Probe-up phase (1.25x): One CE-marked ACK mid-round caps the gain.
+    let mid = now - RTT / 2;
+    clean.on_ack(BW, RTT, false, mid);
+    marked.on_ack(BW, RTT, true, mid);
+    assert_eq!(clean.pacing_gain(), 125);
+    assert_eq!(marked.pacing_gain(), 85);
+    assert!(marked.get_pacing_rate_bps(mid) < clean.get_pacing_rate_bps(mid));
+
+    // The next round restores the cycle.
+    feed_rounds(&mut marked, &mut now, 1, BW, RTT);
+    assert_eq!(marked.pacing_gain(), 75);
+}
+
+#[test]
+fn test_ce_mark_ends_startup() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    feed_rounds(&mut bbr, &mut now, 1, BW, RTT);
+    assert_eq!(bbr.state(), BbrState::Startup);
+
+    // Bandwidth still "growing", but the path is already queueing.
+    bbr.on_ack(2 * BW, RTT, true, now);
+    assert_eq!(bbr.state(), BbrState::Drain);
+    assert_eq!(bbr.pacing_gain(), 35);
+}
+
+#[test]
+fn test_inflight_cap_tracks_bdp() {
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    assert_eq!(bbr.bdp_bytes(now), None);
+    assert_eq!(bbr.inflight_cap_bytes(now), u64::MAX, "No model yet: Unbounded");
+
+    // 100 Mbps x 10ms = 125,000 bytes. Startup: 2.89x.
+    feed_rounds(&mut bbr, &mut now, 1, BW, RTT);
+    assert_eq!(bbr.bdp_bytes(now), Some(125_000));
+    assert_eq!(bbr.inflight_cap_bytes(now), 125_000 * 289 / 100);
+
+    // A shorter RTT sample lowers RTprop; a faster delivery raises BtlBw.
+    feed_rounds(&mut bbr, &mut now, 1, BW, RTT / 2);
+    assert_eq!(bbr.bdp_bytes(now), Some(62_500));
+    feed_rounds(&mut bbr, &mut now, 1, 2 * BW, RTT / 2);
+    assert_eq!(bbr.bdp_bytes(now), Some(125_000));
+
+    // Steady state: 2x BDP.
+    let mut bbr = RateEstimator::new();
+    let mut now = 1_000_000;
+    reach_probe_bw(&mut bbr, &mut now);
+    assert_eq!(bbr.inflight_cap_bytes(now), 2 * 125_000);
+}
@@ -5,7 +5,7 @@ fn test_bbr_logic() {
     let mut bbr = RateEstimator::new();
     
     // 1. Send ACK: 2Mbps
-    bbr.on_ack(2_000_000, 50_000, 1_000_000);
+    bbr.on_ack(2_000_000, 50_000, false, 1_000_000);
     
     // 2. Check Pacing Rate
     // Startup gain ~2.89 -> Expect ~5.78 Mbps
@@ -35,4 +35,71 @@ fn test_chaff_logic() {
     
     // Should NOT request chaff (Debt)
     assert!(!pacer.chaff_needed(1000), "Chaff should be suppressed when tokens consumed");
-}
\ No newline at end of file
+}
+#[test]
+fn test_chaff_tops_up_to_floor() {
+    // Floor: 8 Mbps (1,000 B/ms). No ACKs: BBR allows far more, so only the floor gates chaff.
+    let mut pacer = Pacer::new(8_000_000);
+    pacer.tick(1_000);
+    assert!(!pacer.chaff_due(1_000));
+
+    pacer.tick(2_000);
+    assert!(pacer.chaff_due(1_000), "One packet of floor accrued after 1ms");
+    pacer.consume(1_000);
+    assert!(!pacer.chaff_due(1_000));
+
+    // Real egress above the floor leaves nothing for chaff.
+    pacer.tick(3_000);
+    pacer.consume(5_000);
+    pacer.tick(4_000);
+    assert!(pacer.chaff_due(1_000), "Floor debt is not carried past zero");
+    assert!(!pacer.chaff_due(2_000));
+
+    // A long idle spell resumes at rate (bounded burst), not with a flood.
+    pacer.tick(10_000_000);
+    assert!(pacer.chaff_due(16 * 1024));
+    assert!(!pacer.chaff_due(16 * 1024 + 1));
+}
+
+#[test]
+fn test_gso_burst_fits_token_budget() {
+    use m13_flow::SEGMENT_OVERHEAD;
+    const SEG: usize = 1328;
+
+    // No ACKs: BBR startup (2.89 Gbps) refills ~361 B/us. 10us buys 3,612 bytes.
+    let mut pacer = Pacer::new(100_000);
+    pacer.tick(1_000);
+    let tokens = pacer.tick(1_010) as usize;
+    let per_segment = SEG + SEGMENT_OVERHEAD;
+    assert!(tokens >= 2 * per_segment && tokens < 3 * per_segment, "Budget {}", tokens);
+
+    // Tight budget: Exactly the segments the bucket can pay for, incl. per-datagram overhead.
+    assert_eq!(pacer.affordable_segments(SEG, 64), 2);
+    assert_eq!(pacer.affordable_segments(SEG, 1), 1, "Burst cap");
+
+    // The charge is the precise wire cost, and the remainder buys nothing more.
+    pacer.consume_segments(2 * SEG, 2);
+    assert_eq!(pacer.tick(1_010) as usize, tokens - 2 * per_segment);
+    assert_eq!(pacer.affordable_segments(SEG, 64), 0);
+}
+
+#[test]
+fn test_ready_at_predicts_refill() {
+    // Floor: 8 Mbps (1,000 B/ms).
+    let mut pacer = Pacer::new(8_000_000);
+    pacer.tick(1_000);
+    assert_eq!(pacer.chaff_due_at_us(1_000, 1_000), 2_000, "One floor packet accrues in 1ms");
+    pacer.tick(2_000);
+    assert_eq!(pacer.chaff_due_at_us(1_000, 2_000), 2_000, "Due now");
+
+    // Deep in debt: The predicted instant is exactly when the bucket covers the packet again.
+    let in_debt = || { let mut p = Pacer::new(8_000_000); p.tick(1_000); p.tick(2_000); p.consume(300_000); p };
+    let (mut early, mut on_time) = (in_debt(), in_debt());
+    let ready = on_time.ready_at_us(1_000, 2_000);
+    assert!(ready > 2_000);
+
+    early.tick(ready - 1);
+    assert!(!early.chaff_needed(1_000), "Refilled before the predicted time");
+    on_time.tick(ready);
+    assert!(on_time.chaff_needed(1_000), "Not refilled at the predicted time");
+}
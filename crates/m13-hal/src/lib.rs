@@ -1,7 +1,17 @@
 #![no_std]
 #![forbid(unsafe_code)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+pub mod loopback;
+
+use alloc::vec::Vec;
 use m13_core::{M13Error, M13Result};
+use m13_mem::FrameLease;
 
 /// Physical Link Metadata (Spec §4.2.1).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +29,11 @@ pub enum PeerAddr {
     None, // For Promiscuous/Sniffer modes
 }
 
+/// ECN codepoints (RFC 3168): The low two bits of IP TOS / IPv6 Traffic Class.
+pub const ECN_NOT_ECT: u8 = 0b00;
+pub const ECN_ECT0: u8 = 0b10;
+pub const ECN_CE: u8 = 0b11;
+
 // Helper to keep the interface cleaner in no_std
 #[derive(Debug, Clone, Copy, Default)]
 pub struct M13Endpoint;
@@ -79,6 +94,97 @@ pub trait PhysicalInterface: Send + Sync {
         }
         if count > 0 { Ok(count) } else { Err(nb::Error::WouldBlock) }
     }
+
+    // [TIER 1] TIMESTAMPED VECTOR RECEIVE
+    // Same as `recv_batch`, plus the kernel receive time per packet in `timestamps_us`
+    // (microseconds, platform epoch). A zero entry means "not available".
+    // Default implementation: No kernel timestamps on this platform.
+    fn recv_batch_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.recv_batch(buffers, meta)?;
+        for ts in timestamps_us.iter_mut().take(n) { *ts = 0; }
+        Ok(n)
+    }
+
+    // [TIER 1] ECN-AWARE VECTOR RECEIVE
+    // Same as `recv_batch`, plus the received ECN codepoint per packet in `ecn`.
+    // Default implementation: Path marks are invisible, report Not-ECT.
+    fn recv_batch_ecn(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.recv_batch(buffers, meta)?;
+        for e in ecn.iter_mut().take(n) { *e = ECN_NOT_ECT; }
+        Ok(n)
+    }
+
+    // [TIER 1] ECN-AWARE TIMESTAMPED VECTOR RECEIVE
+    // `recv_batch_ecn` and `recv_batch_ts` in one pass (the kernel RX path wants both).
+    // Default implementation: ECN only, no kernel timestamps.
+    fn recv_batch_ecn_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.recv_batch_ecn(buffers, meta, ecn)?;
+        for ts in timestamps_us.iter_mut().take(n) { *ts = 0; }
+        Ok(n)
+    }
+
+    // [TIER 1] ZERO-COPY VECTOR RECEIVE
+    // `recv_batch_ecn_ts` over slab frames. A link that posts frames of its own to the OS
+    // (io_uring) swaps each filled one into `leases[i]` and keeps the caller's in its place,
+    // so the datagram is never copied. Lengths are reported in `meta`; `len` is left to the caller.
+    // Default implementation: Receive into the caller's frames.
+    fn recv_leases(
+        &mut self,
+        leases: &mut [FrameLease],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let mut buffers: Vec<&mut [u8]> = leases.iter_mut().map(|lease| &mut lease.data[..]).collect();
+        self.recv_batch_ecn_ts(&mut buffers, meta, ecn, timestamps_us)
+    }
+
+    // The current time in the epoch of the RX timestamps above (microseconds).
+    // Callers subtract a timestamp from it to get the packet's queueing age.
+    // Default implementation: 0 = No kernel timestamps on this platform.
+    fn rx_clock_us(&self) -> u64 {
+        0
+    }
+
+    // [TIER 1] VECTOR TRANSMIT EXTENSION
+    // Returns the number of frames accepted (a prefix of `frames`).
+    // Default implementation falls back to scalar loop (for non-Linux support)
+    fn send_batch(
+        &mut self,
+        frames: &[&[u8]],
+        targets: &[Option<PeerAddr>]
+    ) -> nb::Result<usize, M13Error> {
+        if frames.is_empty() { return Ok(0); }
+        let mut count = 0;
+        for (i, frame) in frames.iter().enumerate() {
+            if i >= targets.len() { break; }
+            match self.send(frame, targets[i]) {
+                Ok(_) => count += 1,
+                Err(nb::Error::WouldBlock) => break,
+                Err(e) => {
+                    if count == 0 { return Err(e); }
+                    break;
+                }
+            }
+        }
+        if count > 0 { Ok(count) } else { Err(nb::Error::WouldBlock) }
+    }
 }
 
 /// The Security Module (Section 4.2.2).
@@ -86,6 +192,12 @@ pub trait SecurityModule: Send + Sync {
     fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()>;
     fn sign_digest(&mut self, digest: &[u8], signature: &mut [u8]) -> M13Result<usize>;
     fn panic_and_sanitize(&self) -> !;
+
+    // [ATTEST] SEC1 uncompressed P-256 public key matching `sign_digest` (the AIK).
+    // Default implementation: Not exposed, the caller supplies it.
+    fn attestation_key(&self) -> Option<[u8; 65]> {
+        None
+    }
 }
 
 /// The Wall Clock (Section 7.2.1).
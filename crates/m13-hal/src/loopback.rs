@@ -0,0 +1,178 @@
+//! In-memory `PhysicalInterface` pair for in-process integration tests.
+//!
+//! Two endpoints joined by one queue per direction. Loss and reordering draw from a
+//! seeded generator and delay runs on a shared `LoopbackClock`, so one seed and one
+//! schedule of `advance` calls always replay the same exchange.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+use m13_core::M13Error;
+
+use crate::{LinkProperties, PeerAddr, PhysicalInterface, PlatformClock};
+
+/// Manually advanced time, shared by the link and (as their `PlatformClock`) the kernels on it.
+#[derive(Clone, Default)]
+pub struct LoopbackClock(Arc<AtomicU64>);
+
+impl LoopbackClock {
+    pub fn new(start_us: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(start_us)))
+    }
+
+    pub fn advance(&self, us: u64) {
+        self.0.fetch_add(us, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, us: u64) {
+        self.0.store(us, Ordering::SeqCst);
+    }
+}
+
+impl PlatformClock for LoopbackClock {
+    fn now_us(&self) -> u64 { self.0.load(Ordering::SeqCst) }
+    fn ptp_ns(&self) -> Option<u64> { None }
+}
+
+/// Impairments applied to each direction independently.
+#[derive(Debug, Clone, Copy)]
+pub struct LinkConditions {
+    pub mtu: usize,
+    /// Probability (0..=1) that a frame is dropped.
+    pub loss: f64,
+    /// Probability (0..=1) that a frame overtakes the one queued before it.
+    pub reorder: f64,
+    /// One-way delay (us) on the `LoopbackClock`.
+    pub delay_us: u64,
+    pub seed: u64,
+}
+
+impl Default for LinkConditions {
+    /// A perfect link: No loss, no reordering, no delay.
+    fn default() -> Self {
+        Self { mtu: 1500, loss: 0.0, reorder: 0.0, delay_us: 0, seed: 0x4D13 }
+    }
+}
+
+struct Frame {
+    data: Vec<u8>,
+    src: PeerAddr,
+    deliver_at_us: u64,
+}
+
+// One direction of the link.
+struct Lane {
+    queue: VecDeque<Frame>,
+    rng: u64,
+    lost: u64,
+}
+
+impl Lane {
+    fn new(seed: u64) -> Self {
+        // xorshift64 must not start at zero.
+        Self { queue: VecDeque::new(), rng: seed | 1, lost: 0 }
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        if p <= 0.0 { return false; }
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        ((self.rng >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+}
+
+/// One end of a `LoopbackPair`.
+pub struct LoopbackPhy {
+    local: PeerAddr,
+    peer: PeerAddr,
+    conditions: LinkConditions,
+    clock: LoopbackClock,
+    tx: Arc<Mutex<Lane>>,
+    rx: Arc<Mutex<Lane>>,
+}
+
+impl LoopbackPhy {
+    pub fn local_addr(&self) -> PeerAddr { self.local }
+
+    /// Frames this end sent that the link dropped.
+    pub fn lost(&self) -> u64 {
+        self.tx.lock().map(|lane| lane.lost).unwrap_or(0)
+    }
+
+    /// Frames on their way to this end, delivered or not yet due.
+    pub fn pending(&self) -> usize {
+        self.rx.lock().map(|lane| lane.queue.len()).unwrap_or(0)
+    }
+}
+
+impl PhysicalInterface for LoopbackPhy {
+    fn properties(&self) -> LinkProperties {
+        LinkProperties { mtu: self.conditions.mtu, bandwidth_bps: 0, is_reliable: false }
+    }
+
+    /// A target other than the peer is a datagram into the void: Accepted, never delivered.
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        if frame.len() > self.conditions.mtu { return Err(nb::Error::Other(M13Error::HalError)); }
+        if target.is_some_and(|t| t != self.peer) { return Ok(frame.len()); }
+
+        let mut lane = self.tx.lock().map_err(|_| nb::Error::Other(M13Error::HalError))?;
+        if lane.chance(self.conditions.loss) {
+            lane.lost += 1;
+            return Ok(frame.len());
+        }
+        let entry = Frame {
+            data: frame.to_vec(),
+            src: self.local,
+            deliver_at_us: self.clock.now_us() + self.conditions.delay_us,
+        };
+        let overtake = !lane.queue.is_empty() && lane.chance(self.conditions.reorder);
+        if overtake {
+            let at = lane.queue.len() - 1;
+            lane.queue.insert(at, entry);
+        } else {
+            lane.queue.push_back(entry);
+        }
+        Ok(frame.len())
+    }
+
+    fn recv(&mut self, buffer: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        let mut lane = self.rx.lock().map_err(|_| nb::Error::Other(M13Error::HalError))?;
+        let now = self.clock.now_us();
+        match lane.queue.front() {
+            Some(frame) if frame.deliver_at_us <= now => {}
+            _ => return Err(nb::Error::WouldBlock),
+        }
+        let frame = lane.queue.pop_front().ok_or(nb::Error::WouldBlock)?;
+        // Truncated like a datagram read into a short buffer.
+        let n = frame.data.len().min(buffer.len());
+        buffer[..n].copy_from_slice(&frame.data[..n]);
+        Ok((n, frame.src))
+    }
+}
+
+/// Two `PhysicalInterface` endpoints joined in memory: `a` (at `addr_a`) and `b` (at `addr_b`).
+pub struct LoopbackPair {
+    pub a: LoopbackPhy,
+    pub b: LoopbackPhy,
+}
+
+impl LoopbackPair {
+    /// Both directions share `conditions`; the `b -> a` lane draws from a derived seed.
+    pub fn new(clock: &LoopbackClock, addr_a: PeerAddr, addr_b: PeerAddr, conditions: LinkConditions) -> Self {
+        let a_to_b = Arc::new(Mutex::new(Lane::new(conditions.seed)));
+        let b_to_a = Arc::new(Mutex::new(Lane::new(conditions.seed.rotate_left(32) ^ 0x9E37_79B9_7F4A_7C15)));
+        Self {
+            a: LoopbackPhy {
+                local: addr_a, peer: addr_b, conditions, clock: clock.clone(),
+                tx: a_to_b.clone(), rx: b_to_a.clone(),
+            },
+            b: LoopbackPhy {
+                local: addr_b, peer: addr_a, conditions, clock: clock.clone(),
+                tx: b_to_a, rx: a_to_b,
+            },
+        }
+    }
+}
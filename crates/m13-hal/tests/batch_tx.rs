@@ -0,0 +1,105 @@
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::M13Error;
+
+/// Scalar device: Only implements `send`, relies on the default `send_batch`.
+struct ScalarLoopback {
+    frames: usize,
+    syscalls: usize,
+}
+
+impl PhysicalInterface for ScalarLoopback {
+    fn properties(&self) -> LinkProperties {
+        LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true }
+    }
+    fn send(&mut self, frame: &[u8], _target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.syscalls += 1;
+        self.frames += 1;
+        Ok(frame.len())
+    }
+    fn recv(&mut self, _buffer: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        Err(nb::Error::WouldBlock)
+    }
+}
+
+/// Vector device: Accepts a whole burst per "syscall" (sendmmsg semantics).
+struct VectorLoopback {
+    frames: usize,
+    syscalls: usize,
+    max_per_call: usize,
+}
+
+impl PhysicalInterface for VectorLoopback {
+    fn properties(&self) -> LinkProperties {
+        LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true }
+    }
+    fn send(&mut self, frame: &[u8], _target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.syscalls += 1;
+        self.frames += 1;
+        Ok(frame.len())
+    }
+    fn recv(&mut self, _buffer: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        Err(nb::Error::WouldBlock)
+    }
+    fn send_batch(
+        &mut self,
+        frames: &[&[u8]],
+        targets: &[Option<PeerAddr>]
+    ) -> nb::Result<usize, M13Error> {
+        self.syscalls += 1;
+        let n = frames.len().min(targets.len()).min(self.max_per_call);
+        self.frames += n;
+        Ok(n)
+    }
+}
+
+fn burst(n: usize) -> (Vec<Vec<u8>>, Vec<Option<PeerAddr>>) {
+    let frames = (0..n).map(|i| vec![i as u8; 64]).collect();
+    let targets = vec![Some(PeerAddr::V4([127, 0, 0, 1], 443)); n];
+    (frames, targets)
+}
+
+#[test]
+fn test_default_send_batch_is_scalar() {
+    let mut dev = ScalarLoopback { frames: 0, syscalls: 0 };
+    let (frames, targets) = burst(16);
+    let refs: Vec<&[u8]> = frames.iter().map(|f| &f[..]).collect();
+
+    let obj: &mut dyn PhysicalInterface = &mut dev;
+    assert_eq!(obj.send_batch(&refs, &targets).unwrap(), 16);
+    assert_eq!(dev.frames, 16);
+    assert_eq!(dev.syscalls, 16);
+}
+
+#[test]
+fn test_vector_send_batch_single_syscall() {
+    let mut dev = VectorLoopback { frames: 0, syscalls: 0, max_per_call: 64 };
+    let (frames, targets) = burst(48);
+    let refs: Vec<&[u8]> = frames.iter().map(|f| &f[..]).collect();
+
+    let obj: &mut dyn PhysicalInterface = &mut dev;
+    assert_eq!(obj.send_batch(&refs, &targets).unwrap(), 48);
+    assert_eq!(dev.frames, 48);
+    assert_eq!(dev.syscalls, 1);
+}
+
+#[test]
+fn test_send_batch_partial_prefix() {
+    // A short write must report exactly how many frames left the host.
+    let mut dev = VectorLoopback { frames: 0, syscalls: 0, max_per_call: 10 };
+    let (frames, targets) = burst(25);
+    let refs: Vec<&[u8]> = frames.iter().map(|f| &f[..]).collect();
+
+    let mut offset = 0;
+    while offset < refs.len() {
+        offset += dev.send_batch(&refs[offset..], &targets[offset..]).unwrap();
+    }
+    assert_eq!(dev.frames, 25);
+    assert_eq!(dev.syscalls, 3);
+}
+
+#[test]
+fn test_send_batch_empty() {
+    let mut dev = ScalarLoopback { frames: 0, syscalls: 0 };
+    assert_eq!(dev.send_batch(&[], &[]).unwrap(), 0);
+    assert_eq!(dev.syscalls, 0);
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+/// `--config` file for `m13-node` / `m13-hub` (TOML). Every key is optional: An absent one
+/// keeps the binary's built-in default. Unknown keys are an error, so a typo never
+/// silently runs the default.
+///
+/// ```toml
+/// slab_small_frames = 1024      # KeepAlive / ACK frames
+/// slab_mtu_frames = 512         # Handshake fragments
+/// slab_large_frames = 8192      # Data frames
+/// symbol_size = 1024            # Raptor symbol (bytes)
+/// cbr_floor_bps = 10000000      # Pacing floor / chaff rate
+/// gso_segment_size = 1328       # Cap on the GSO segment (0: path MTU only)
+/// session_idle_timeout_us = 30000000
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    pub slab_small_frames: Option<usize>,
+    pub slab_mtu_frames: Option<usize>,
+    pub slab_large_frames: Option<usize>,
+    pub symbol_size: Option<usize>,
+    pub cbr_floor_bps: Option<u64>,
+    pub gso_segment_size: Option<u16>,
+    pub session_idle_timeout_us: Option<u64>,
+}
+
+impl RuntimeConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("in {}", path.display()))
+    }
+
+    /// Parses and checks what the kernel cannot: Every slab class needs frames.
+    /// Kernel tunables are checked by `KernelConfig::validate` once mapped.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        for (key, frames) in [
+            ("slab_small_frames", config.slab_small_frames),
+            ("slab_mtu_frames", config.slab_mtu_frames),
+            ("slab_large_frames", config.slab_large_frames),
+        ] {
+            if frames == Some(0) { anyhow::bail!("{key} must be at least 1"); }
+        }
+        Ok(config)
+    }
+}
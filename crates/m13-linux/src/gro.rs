@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+use m13_hal::PeerAddr;
+
+// One segment of a coalesced datagram that did not fit the caller's batch.
+struct GroSegment {
+    data: Vec<u8>,
+    src: PeerAddr,
+    tos: u8,
+    ts: u64,
+}
+
+/// [GRO] Splits UDP_GRO super-datagrams back into the sender's frames. The first segment
+/// stays in the slot the kernel wrote; the rest wait here and fill the next free slots.
+#[derive(Default)]
+pub(crate) struct GroBacklog(VecDeque<GroSegment>);
+
+impl GroBacklog {
+    /// Splits `buf` (one received datagram) at `seg`-byte boundaries and queues every segment
+    /// after the first. A truncated receive drops its partial tail segment: The Fountain layer
+    /// recovers it like any other loss. Returns the first segment's length.
+    pub fn split(&mut self, buf: &[u8], seg: usize, truncated: bool, src: PeerAddr, tos: u8, ts: u64) -> usize {
+        if seg == 0 || buf.len() <= seg { return buf.len(); }
+
+        let whole = if truncated { buf.len() / seg * seg } else { buf.len() };
+        for chunk in buf[seg..whole].chunks(seg) {
+            self.0.push_back(GroSegment { data: chunk.to_vec(), src, tos, ts });
+        }
+        seg
+    }
+
+    /// Moves queued segments into slots `from..to`. Returns the first slot left empty.
+    pub fn fill(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        mut ecn: Option<&mut [u8]>,
+        mut timestamps_us: Option<&mut [u64]>,
+        from: usize,
+        to: usize
+    ) -> usize {
+        let mut slot = from;
+        while slot < to {
+            let seg = match self.0.pop_front() {
+                Some(seg) => seg,
+                None => break,
+            };
+            let n = seg.data.len().min(buffers[slot].len());
+            buffers[slot][..n].copy_from_slice(&seg.data[..n]);
+            meta[slot] = (n, seg.src);
+            if let Some(e) = ecn.as_deref_mut() { e[slot] = seg.tos & 0b11; }
+            if let Some(t) = timestamps_us.as_deref_mut() { t[slot] = seg.ts; }
+            slot += 1;
+        }
+        slot
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -6,11 +6,11 @@ use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use std::process::Command;
 use tun::Device;
 use std::net::{SocketAddr, IpAddr};
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use socket2::{Socket, Domain, Type, Protocol, SockAddr};
 
 // [FIXED] Correct Import from HAL
-use m13_hal::{PhysicalInterface, LinkProperties, SecurityModule, PlatformClock, PeerAddr};
+use m13_hal::{PhysicalInterface, LinkProperties, SecurityModule, PlatformClock, PeerAddr, ECN_ECT0, ECN_NOT_ECT};
 use m13_core::{M13Error, M13Result};
 
 #[cfg(target_os = "macos")]
@@ -18,6 +18,15 @@ const BSD_AF_INET: [u8; 4] = [0, 0, 0, 2];
 
 #[cfg(target_os = "linux")]
 const MAX_BATCH: usize = 64;
+// Per-datagram cmsg space (u64 words): TOS + timespec + UDP_GRO.
+#[cfg(target_os = "linux")]
+const RX_CTRL_WORDS: usize = 12;
+
+// [PMTU] Conservative IP MTU until the kernel reports the path's real one.
+const DEFAULT_MTU: usize = 1400;
+// IPv6 minimum: Never shrink below what every path must carry.
+#[cfg(target_os = "linux")]
+const MIN_MTU: usize = 1280;
 
 fn to_peer_addr(addr: SocketAddr) -> PeerAddr {
     match addr {
@@ -59,9 +68,44 @@ impl TunDevice {
         let dev = tun::create(&config).map_err(|e| anyhow::anyhow!(e))?;
         let name = dev.name().to_string();
         
+        // The fd now belongs to the returned device; the tun handle must not close it.
+        let dev = std::mem::ManuallyDrop::new(dev);
         let raw_fd = dev.as_raw_fd();
+
+        Ok(Self::from_queue_fd(raw_fd, name, ip, dest))
+    }
+
+    /// [SHARD] One interface, `queues` fds (IFF_MULTI_QUEUE). The kernel spreads flows across
+    /// the queues, so each can be drained by its own worker, paired with a `new_sharded` socket.
+    #[cfg(target_os = "linux")]
+    pub fn new_multiqueue(name: &str, ip: &str, dest: &str, queues: usize) -> anyhow::Result<Vec<Self>> {
+        if queues == 0 { anyhow::bail!("queues must be at least 1"); }
+        let mut config = tun::Configuration::default();
+        config
+            .name(name)
+            .address(ip)
+            .destination(dest)
+            .netmask("255.255.255.0")
+            .mtu(1280)
+            .queues(queues)
+            .up();
+        config.platform(|c| { c.packet_information(false); });
+
+        // The queues now belong to the returned devices (see `new`).
+        let mut dev = std::mem::ManuallyDrop::new(tun::create(&config).map_err(|e| anyhow::anyhow!(e))?);
+        let name = dev.name().to_string();
+
+        let mut fds = Vec::with_capacity(queues);
+        for i in 0..queues {
+            let queue = dev.queue(i).ok_or_else(|| anyhow::anyhow!("TUN queue {} missing", i))?;
+            fds.push(queue.as_raw_fd());
+        }
+        Ok(fds.into_iter().map(|fd| Self::from_queue_fd(fd, name.clone(), ip, dest)).collect())
+    }
+
+    // Takes ownership of `raw_fd` and switches it to non-blocking.
+    fn from_queue_fd(raw_fd: RawFd, name: String, ip: &str, dest: &str) -> Self {
         let file = unsafe { File::from_raw_fd(raw_fd) };
-        std::mem::forget(dev); 
 
         unsafe {
             let mut flags = libc::fcntl(raw_fd, libc::F_GETFL, 0);
@@ -69,11 +113,11 @@ impl TunDevice {
             libc::fcntl(raw_fd, libc::F_SETFL, flags);
         }
 
-        Ok(Self { 
+        Self { 
             file, name, raw_fd,
             local_ip: ip.to_string(),
             peer_ip: dest.to_string(),
-        })
+        }
     }
 
     pub fn fd(&self) -> RawFd { self.raw_fd }
@@ -119,14 +163,77 @@ impl TunDevice {
     }
 }
 
+// TOS/TCLASS byte, SCM_TIMESTAMPNS (microseconds, 0 = absent) and the UDP_GRO segment
+// size (0 = not coalesced) from a received msghdr.
+#[cfg(target_os = "linux")]
+fn rx_cmsgs(hdr: &libc::msghdr) -> (u8, u64, usize) {
+    use libc::{timespec, CMSG_FIRSTHDR, CMSG_NXTHDR, CMSG_DATA};
+
+    let mut tos = ECN_NOT_ECT;
+    let mut ts = 0u64;
+    let mut gro = 0usize;
+    unsafe {
+        let mut cmsg = CMSG_FIRSTHDR(hdr);
+        while !cmsg.is_null() {
+            match ((*cmsg).cmsg_level, (*cmsg).cmsg_type) {
+                // IPv4 delivers a single byte; IPv6 a full int.
+                (libc::IPPROTO_IP, libc::IP_TOS) => tos = *CMSG_DATA(cmsg),
+                (libc::IPPROTO_IPV6, libc::IPV6_TCLASS) => {
+                    tos = std::ptr::read_unaligned(CMSG_DATA(cmsg) as *const libc::c_int) as u8;
+                }
+                (libc::SOL_SOCKET, libc::SCM_TIMESTAMPNS) => {
+                    let tv = std::ptr::read_unaligned(CMSG_DATA(cmsg) as *const timespec);
+                    ts = (tv.tv_sec as u64) * 1_000_000 + (tv.tv_nsec as u64) / 1_000;
+                }
+                (libc::SOL_UDP, libc::UDP_GRO) => {
+                    gro = std::ptr::read_unaligned(CMSG_DATA(cmsg) as *const libc::c_int).max(0) as usize;
+                }
+                _ => {}
+            }
+            cmsg = CMSG_NXTHDR(hdr, cmsg);
+        }
+    }
+    (tos, ts, gro)
+}
+
 pub struct LinuxUdp {
     socket: Socket,
     default_target: Option<PeerAddr>,
+    // [PHYSICS] Latched once the kernel/NIC rejects UDP_SEGMENT.
+    gso_disabled: bool,
+    // [PMTU] IP MTU toward the peer. Only shrinks (EMSGSIZE); no upward probing.
+    mtu: usize,
+    // [GRO] Segments of a coalesced receive still waiting for a slot.
+    #[cfg(target_os = "linux")]
+    gro: gro::GroBacklog,
+}
+
+impl AsRawFd for LinuxUdp {
+    fn as_raw_fd(&self) -> RawFd { self.socket.as_raw_fd() }
 }
 
 impl LinuxUdp {
     pub fn new(bind_addr: &str, target_addr: Option<&str>) -> anyhow::Result<Self> {
         let addr: SocketAddr = bind_addr.parse()?;
+        Self::open(addr, target_addr, false)
+    }
+
+    /// [SHARD] `num_shards` sockets on one address via SO_REUSEPORT. The kernel hashes each
+    /// flow's 4-tuple onto one socket, so N kernels (one per shard, one per core) split the load.
+    /// Binding port 0 picks one free port for the whole set.
+    pub fn new_sharded(bind_addr: &str, num_shards: usize) -> anyhow::Result<Vec<Self>> {
+        if num_shards == 0 { anyhow::bail!("num_shards must be at least 1"); }
+        let mut addr: SocketAddr = bind_addr.parse()?;
+        let mut shards = Vec::with_capacity(num_shards);
+        for _ in 0..num_shards {
+            let shard = Self::open(addr, None, true)?;
+            addr = shard.local_addr()?;
+            shards.push(shard);
+        }
+        Ok(shards)
+    }
+
+    fn open(addr: SocketAddr, target_addr: Option<&str>, reuse_port: bool) -> anyhow::Result<Self> {
         let domain = if addr.is_ipv4() { Domain::IPV4 } else { Domain::IPV6 };
         
         let socket = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))?;
@@ -137,7 +244,84 @@ impl LinuxUdp {
         let _ = socket.set_send_buffer_size(buf_size);
         
         socket.set_nonblocking(true)?;
+
+        // [PHYSICS] Kernel RX timestamps (consumed by recv_batch_ts).
+        #[cfg(target_os = "macos")]
+        unsafe {
+            let on: libc::c_int = 1;
+            libc::setsockopt(
+                socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_TIMESTAMP,
+                &on as *const _ as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            );
+        }
+        #[cfg(target_os = "linux")]
+        unsafe {
+            let on: libc::c_int = 1;
+            libc::setsockopt(
+                socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_TIMESTAMPNS,
+                &on as *const _ as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            );
+        }
         
+        // [ECN] Egress as ECT(0); ask for the received TOS/TCLASS (consumed by recv_batch_ecn).
+        #[cfg(target_os = "linux")]
+        unsafe {
+            let fd = socket.as_raw_fd();
+            let ect: libc::c_int = ECN_ECT0 as libc::c_int;
+            let on: libc::c_int = 1;
+            let len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+            let (level, tos, recv) = if addr.is_ipv4() {
+                (libc::IPPROTO_IP, libc::IP_TOS, libc::IP_RECVTOS)
+            } else {
+                (libc::IPPROTO_IPV6, libc::IPV6_TCLASS, libc::IPV6_RECVTCLASS)
+            };
+            libc::setsockopt(fd, level, tos, &ect as *const _ as *const libc::c_void, len);
+            libc::setsockopt(fd, level, recv, &on as *const _ as *const libc::c_void, len);
+        }
+
+        // [PMTU] Don't Fragment: An oversized datagram fails with EMSGSIZE instead of
+        // leaving as IP fragments that middleboxes drop.
+        #[cfg(target_os = "linux")]
+        unsafe {
+            let (level, opt, val) = if addr.is_ipv4() {
+                (libc::IPPROTO_IP, libc::IP_MTU_DISCOVER, libc::IP_PMTUDISC_DO)
+            } else {
+                (libc::IPPROTO_IPV6, libc::IPV6_MTU_DISCOVER, libc::IPV6_PMTUDISC_DO)
+            };
+            libc::setsockopt(
+                socket.as_raw_fd(), level, opt,
+                &val as *const _ as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            );
+        }
+
+        // [GRO] Accept coalesced super-datagrams (a GSO peer's burst arrives as one). recv_mmsg
+        // splits them on the UDP_GRO cmsg. Best effort: Older kernels just deliver segments.
+        #[cfg(target_os = "linux")]
+        unsafe {
+            let on: libc::c_int = 1;
+            libc::setsockopt(
+                socket.as_raw_fd(), libc::SOL_UDP, libc::UDP_GRO,
+                &on as *const _ as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            );
+        }
+
+        // [SHARD] Must be set on every socket in the group before bind().
+        if reuse_port {
+            let on: libc::c_int = 1;
+            let res = unsafe {
+                libc::setsockopt(
+                    socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_REUSEPORT,
+                    &on as *const _ as *const libc::c_void,
+                    std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+                )
+            };
+            if res < 0 { return Err(io::Error::last_os_error().into()); }
+        }
+
         let sa: SockAddr = addr.into();
         socket.bind(&sa)?;
         
@@ -148,13 +332,150 @@ impl LinuxUdp {
              None
         };
 
-        Ok(Self { socket, default_target })
+        Ok(Self {
+            socket, default_target, gso_disabled: false, mtu: DEFAULT_MTU,
+            #[cfg(target_os = "linux")]
+            gro: gro::GroBacklog::default(),
+        })
+    }
+
+    /// The bound local address (useful when binding to port 0).
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()?
+            .as_socket()
+            .ok_or_else(|| io::Error::other("non-IP socket"))
+    }
+
+    /// Whether the kernel accepted UDP_SEGMENT so far.
+    pub fn gso_active(&self) -> bool { !self.gso_disabled }
+
+    // [PMTU] EMSGSIZE: Adopt the kernel's path MTU toward `dest`. IP_MTU only answers on a
+    // connected socket, so ask a throwaway one routed the same way.
+    #[cfg(target_os = "linux")]
+    fn refresh_mtu(&mut self, dest: SocketAddr) {
+        let domain = if dest.is_ipv4() { Domain::IPV4 } else { Domain::IPV6 };
+        let probe = match Socket::new(domain, Type::DGRAM, Some(Protocol::UDP)) {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+        if probe.connect(&dest.into()).is_err() { return; }
+
+        let (level, opt) = if dest.is_ipv4() {
+            (libc::IPPROTO_IP, libc::IP_MTU)
+        } else {
+            (libc::IPPROTO_IPV6, libc::IPV6_MTU)
+        };
+        let mut mtu: libc::c_int = 0;
+        let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+        let res = unsafe {
+            libc::getsockopt(probe.as_raw_fd(), level, opt, &mut mtu as *mut _ as *mut libc::c_void, &mut len)
+        };
+        if res < 0 || mtu <= 0 { return; }
+
+        let mtu = (mtu as usize).max(MIN_MTU);
+        if mtu < self.mtu {
+            log::warn!("Path MTU to {} is {} (was {}).", dest, mtu, self.mtu);
+            self.mtu = mtu;
+        }
+    }
+
+    // Scalar GSO emulation (identical to the HAL default, which an override cannot call).
+    #[cfg(target_os = "linux")]
+    fn send_segmented(
+        &mut self,
+        data: &[u8],
+        target: Option<PeerAddr>,
+        segment_size: u16
+    ) -> nb::Result<usize, M13Error> {
+        let chunk_len = if segment_size == 0 { data.len().max(1) } else { segment_size as usize };
+        let mut sent_total = 0;
+        for chunk in data.chunks(chunk_len) {
+            sent_total += self.send(chunk, target)?;
+        }
+        Ok(sent_total)
+    }
+
+    // [PHYSICS] One recvmmsg() per burst. Harvests the TOS/TCLASS (ECN) and SCM_TIMESTAMPNS cmsgs.
+    // [GRO] Coalesced datagrams come back as one `meta` entry per segment; segments beyond
+    // the batch are delivered first on the next call.
+    #[cfg(target_os = "linux")]
+    fn recv_mmsg(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        mut ecn: Option<&mut [u8]>,
+        mut timestamps_us: Option<&mut [u64]>
+    ) -> nb::Result<usize, M13Error> {
+        use libc::{mmsghdr, iovec, sockaddr_storage, recvmmsg, MSG_DONTWAIT};
+        use std::mem;
+
+        let fd = self.socket.as_raw_fd();
+        let mut count = buffers.len().min(meta.len()).min(MAX_BATCH);
+        if let Some(e) = &ecn { count = count.min(e.len()); }
+        if let Some(t) = &timestamps_us { count = count.min(t.len()); }
+
+        let start = self.gro.fill(buffers, meta, ecn.as_deref_mut(), timestamps_us.as_deref_mut(), 0, count);
+        if start == count { return Ok(count); }
+        let slots = count - start;
+
+        let mut msg_vec: [mmsghdr; MAX_BATCH] = unsafe { mem::zeroed() };
+        let mut iov_vec: [iovec; MAX_BATCH] = unsafe { mem::zeroed() };
+        let mut addr_vec: [sockaddr_storage; MAX_BATCH] = unsafe { mem::zeroed() };
+        // u64 backing store keeps each cmsghdr aligned. Room for TOS + timespec + UDP_GRO.
+        let mut ctrl_vec = [[0u64; RX_CTRL_WORDS]; MAX_BATCH];
+
+        for i in 0..slots {
+            iov_vec[i].iov_base = buffers[start + i].as_mut_ptr() as *mut libc::c_void;
+            iov_vec[i].iov_len = buffers[start + i].len();
+
+            msg_vec[i].msg_hdr.msg_iov = &mut iov_vec[i];
+            msg_vec[i].msg_hdr.msg_iovlen = 1;
+            msg_vec[i].msg_hdr.msg_name = &mut addr_vec[i] as *mut _ as *mut libc::c_void;
+            msg_vec[i].msg_hdr.msg_namelen = mem::size_of::<sockaddr_storage>() as u32;
+            msg_vec[i].msg_hdr.msg_control = ctrl_vec[i].as_mut_ptr() as *mut libc::c_void;
+            msg_vec[i].msg_hdr.msg_controllen = mem::size_of_val(&ctrl_vec[i]);
+        }
+
+        let res = unsafe {
+            recvmmsg(fd, msg_vec.as_mut_ptr(), slots as u32, MSG_DONTWAIT, std::ptr::null_mut())
+        };
+
+        if res < 0 {
+            // Backlogged segments already went out: Report them, not the empty socket.
+            if start > 0 { return Ok(start); }
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::WouldBlock {
+                return Err(nb::Error::WouldBlock);
+            }
+            return Err(nb::Error::Other(M13Error::HalError));
+        }
+
+        let pkts = res as usize;
+        for i in 0..pkts {
+            let slot = start + i;
+            let addr = unsafe { 
+                socket2::SockAddr::new(addr_vec[i], msg_vec[i].msg_hdr.msg_namelen) 
+            };
+            let src = addr.as_socket().map(to_peer_addr).unwrap_or(PeerAddr::None);
+
+            let (tos, ts, seg) = rx_cmsgs(&msg_vec[i].msg_hdr);
+            let len = (msg_vec[i].msg_len as usize).min(buffers[slot].len());
+            let truncated = msg_vec[i].msg_hdr.msg_flags & libc::MSG_TRUNC != 0;
+            let len = self.gro.split(&buffers[slot][..len], seg, truncated, src, tos, ts);
+
+            meta[slot] = (len, src);
+            if let Some(e) = ecn.as_deref_mut() { e[slot] = tos & 0b11; }
+            if let Some(t) = timestamps_us.as_deref_mut() { t[slot] = ts; }
+        }
+
+        // Split segments take whatever slots recvmmsg left empty.
+        Ok(self.gro.fill(buffers, meta, ecn, timestamps_us, start + pkts, count))
     }
 }
 
 impl PhysicalInterface for LinuxUdp {
     fn properties(&self) -> LinkProperties {
-        LinkProperties { mtu: 1400, bandwidth_bps: 1_000_000_000, is_reliable: false }
+        LinkProperties { mtu: self.mtu, bandwidth_bps: 1_000_000_000, is_reliable: false }
     }
 
     fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
@@ -171,6 +492,11 @@ impl PhysicalInterface for LinuxUdp {
         match self.socket.send_to(frame, &addr) {
             Ok(n) => Ok(n),
             Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Err(nb::Error::WouldBlock),
+            #[cfg(target_os = "linux")]
+            Err(ref e) if e.raw_os_error() == Some(libc::EMSGSIZE) => {
+                self.refresh_mtu(dest_sock);
+                Err(nb::Error::Other(M13Error::HalError))
+            }
             Err(_) => Err(nb::Error::Other(M13Error::HalError)),
         }
     }
@@ -186,6 +512,10 @@ impl PhysicalInterface for LinuxUdp {
         use libc::{iovec, msghdr, sendmsg, cmsghdr, CMSG_FIRSTHDR, CMSG_DATA, SOL_UDP, UDP_SEGMENT};
         use std::mem;
 
+        if self.gso_disabled || segment_size == 0 {
+            return self.send_segmented(data, target, segment_size);
+        }
+
         // Resolve Target
         let final_target = target.or(self.default_target);
         let dest_peer = match final_target {
@@ -202,7 +532,8 @@ impl PhysicalInterface for LinuxUdp {
             iov_len: data.len(),
         };
 
-        let mut ctrl_buf = [0u8; 64]; 
+        // [FIX] u64 backing store keeps the cmsghdr correctly aligned.
+        let mut ctrl_buf = [0u64; 8]; 
         let mut msg = unsafe { mem::zeroed::<msghdr>() };
         
         msg.msg_name = socket_addr.as_ptr() as *mut _;
@@ -210,7 +541,7 @@ impl PhysicalInterface for LinuxUdp {
         msg.msg_iov = &mut iov;
         msg.msg_iovlen = 1;
         msg.msg_control = ctrl_buf.as_mut_ptr() as *mut _;
-        msg.msg_controllen = ctrl_buf.len() as usize;
+        msg.msg_controllen = mem::size_of_val(&ctrl_buf);
 
         let res = unsafe {
             let cmsg = CMSG_FIRSTHDR(&mut msg);
@@ -231,11 +562,30 @@ impl PhysicalInterface for LinuxUdp {
             if err.kind() == std::io::ErrorKind::WouldBlock {
                 return Err(nb::Error::WouldBlock);
             }
+            // [PHYSICS] No GSO on this path (old kernel, no csum offload): degrade once, permanently.
+            if matches!(err.raw_os_error(), Some(libc::EIO) | Some(libc::EOPNOTSUPP) | Some(libc::ENOPROTOOPT)) {
+                log::warn!("UDP_SEGMENT rejected ({}). Falling back to scalar egress.", err);
+                self.gso_disabled = true;
+                return self.send_segmented(data, target, segment_size);
+            }
+            // [PMTU] Segment larger than the path allows (EINVAL from the GSO size check).
+            if matches!(err.raw_os_error(), Some(libc::EMSGSIZE) | Some(libc::EINVAL)) {
+                self.refresh_mtu(dest_sock);
+            }
             return Err(nb::Error::Other(M13Error::HalError));
         }
         Ok(res as usize)
     }
 
+    // [GRO] Through recvmmsg, so a coalesced datagram still comes back one frame at a time.
+    #[cfg(target_os = "linux")]
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        let mut meta = [(0usize, PeerAddr::None)];
+        self.recv_mmsg(&mut [buf], &mut meta, None, None)?;
+        Ok(meta[0])
+    }
+
+    #[cfg(not(target_os = "linux"))]
     fn recv<'a>(&mut self, buf: &'a mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
         let buf_uninit = unsafe { 
             std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut std::mem::MaybeUninit<u8>, buf.len()) 
@@ -254,28 +604,175 @@ impl PhysicalInterface for LinuxUdp {
         buffers: &mut [&mut [u8]], 
         meta: &mut [(usize, PeerAddr)]
     ) -> nb::Result<usize, M13Error> {
-        use libc::{mmsghdr, iovec, sockaddr_storage, recvmmsg, MSG_DONTWAIT};
+        let mut ecn = [ECN_NOT_ECT; MAX_BATCH];
+        self.recv_batch_ecn(buffers, meta, &mut ecn)
+    }
+
+    // [ECN] recvmmsg() plus one IP_TOS / IPV6_TCLASS cmsg per datagram.
+    #[cfg(target_os = "linux")]
+    fn recv_batch_ecn(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8]
+    ) -> nb::Result<usize, M13Error> {
+        self.recv_mmsg(buffers, meta, Some(ecn), None)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn recv_batch_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        self.recv_mmsg(buffers, meta, None, Some(timestamps_us))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn recv_batch_ecn_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        self.recv_mmsg(buffers, meta, Some(ecn), Some(timestamps_us))
+    }
+
+    // SO_TIMESTAMPNS (Linux) and SO_TIMESTAMP (macOS) both stamp in CLOCK_REALTIME.
+    fn rx_clock_us(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_micros() as u64).unwrap_or(0)
+    }
+
+    // [PHYSICS] BSD has no recvmmsg: Loop recvmsg() and harvest SO_TIMESTAMP cmsgs.
+    #[cfg(target_os = "macos")]
+    fn recv_batch_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        use libc::{iovec, msghdr, recvmsg, sockaddr_storage, timeval, CMSG_FIRSTHDR, CMSG_NXTHDR, CMSG_DATA, SOL_SOCKET, SCM_TIMESTAMP, MSG_DONTWAIT};
+        use std::mem;
+
+        let fd = self.socket.as_raw_fd();
+        let count = buffers.len().min(meta.len()).min(timestamps_us.len());
+        let mut pkts = 0;
+
+        while pkts < count {
+            let buf = &mut buffers[pkts];
+            let mut iov = iovec {
+                iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+                iov_len: buf.len(),
+            };
+            let mut addr: sockaddr_storage = unsafe { mem::zeroed() };
+            let mut ctrl_buf = [0u64; 8];
+
+            let mut msg = unsafe { mem::zeroed::<msghdr>() };
+            msg.msg_name = &mut addr as *mut _ as *mut libc::c_void;
+            msg.msg_namelen = mem::size_of::<sockaddr_storage>() as libc::socklen_t;
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = ctrl_buf.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = mem::size_of_val(&ctrl_buf) as libc::socklen_t;
+
+            let res = unsafe { recvmsg(fd, &mut msg, MSG_DONTWAIT) };
+            if res < 0 {
+                let err = std::io::Error::last_os_error();
+                if pkts > 0 { break; }
+                if err.kind() == std::io::ErrorKind::WouldBlock {
+                    return Err(nb::Error::WouldBlock);
+                }
+                return Err(nb::Error::Other(M13Error::HalError));
+            }
+
+            let mut ts = 0u64;
+            unsafe {
+                let mut cmsg = CMSG_FIRSTHDR(&msg);
+                while !cmsg.is_null() {
+                    if (*cmsg).cmsg_level == SOL_SOCKET && (*cmsg).cmsg_type == SCM_TIMESTAMP {
+                        let tv = std::ptr::read_unaligned(CMSG_DATA(cmsg) as *const timeval);
+                        ts = (tv.tv_sec as u64) * 1_000_000 + (tv.tv_usec as u64);
+                    }
+                    cmsg = CMSG_NXTHDR(&msg, cmsg);
+                }
+            }
+
+            let sa = unsafe { SockAddr::new(addr, msg.msg_namelen) };
+            meta[pkts].0 = res as usize;
+            if let Some(sa) = sa.as_socket() {
+                meta[pkts].1 = to_peer_addr(sa);
+            }
+            timestamps_us[pkts] = ts;
+            pkts += 1;
+        }
+        Ok(pkts)
+    }
+
+    // No RECVTOS plumbing on BSD yet: Timestamps only, ECN reads as Not-ECT.
+    #[cfg(target_os = "macos")]
+    fn recv_batch_ecn_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.recv_batch_ts(buffers, meta, timestamps_us)?;
+        for e in ecn.iter_mut().take(n) { *e = ECN_NOT_ECT; }
+        Ok(n)
+    }
+
+    // [PHYSICS] Vector TX: One sendmmsg() per burst instead of N sendto().
+    #[cfg(target_os = "linux")]
+    fn send_batch(
+        &mut self,
+        frames: &[&[u8]],
+        targets: &[Option<PeerAddr>]
+    ) -> nb::Result<usize, M13Error> {
+        use libc::{mmsghdr, iovec, sendmmsg, MSG_DONTWAIT};
         use std::mem;
 
         let fd = self.socket.as_raw_fd();
-        let count = buffers.len().min(meta.len()).min(MAX_BATCH);
+        let count = frames.len().min(targets.len()).min(MAX_BATCH);
+        if count == 0 { return Ok(0); }
+
+        // Resolve destinations up-front; SockAddr storage must outlive the syscall.
+        let mut addrs: Vec<SockAddr> = Vec::with_capacity(count);
+        for target in targets.iter().take(count) {
+            let dest_peer = match target.or(self.default_target) {
+                Some(t) => t,
+                None => break,
+            };
+            match to_socket_addr(&dest_peer) {
+                Some(sa) => addrs.push(sa.into()),
+                None => break,
+            }
+        }
+
+        let n = addrs.len();
+        if n == 0 {
+            // Mirror scalar send(): an untargeted frame is silently consumed.
+            if targets[0].or(self.default_target).is_none() { return Ok(1); }
+            return Err(nb::Error::Other(M13Error::HalError));
+        }
 
         let mut msg_vec: [mmsghdr; MAX_BATCH] = unsafe { mem::zeroed() };
         let mut iov_vec: [iovec; MAX_BATCH] = unsafe { mem::zeroed() };
-        let mut addr_vec: [sockaddr_storage; MAX_BATCH] = unsafe { mem::zeroed() };
 
-        for i in 0..count {
-            iov_vec[i].iov_base = buffers[i].as_mut_ptr() as *mut libc::c_void;
-            iov_vec[i].iov_len = buffers[i].len();
+        for i in 0..n {
+            iov_vec[i].iov_base = frames[i].as_ptr() as *mut libc::c_void;
+            iov_vec[i].iov_len = frames[i].len();
 
             msg_vec[i].msg_hdr.msg_iov = &mut iov_vec[i];
             msg_vec[i].msg_hdr.msg_iovlen = 1;
-            msg_vec[i].msg_hdr.msg_name = &mut addr_vec[i] as *mut _ as *mut libc::c_void;
-            msg_vec[i].msg_hdr.msg_namelen = mem::size_of::<sockaddr_storage>() as u32;
+            msg_vec[i].msg_hdr.msg_name = addrs[i].as_ptr() as *mut libc::c_void;
+            msg_vec[i].msg_hdr.msg_namelen = addrs[i].len();
         }
 
         let res = unsafe {
-            recvmmsg(fd, msg_vec.as_mut_ptr(), count as u32, MSG_DONTWAIT, std::ptr::null_mut())
+            sendmmsg(fd, msg_vec.as_mut_ptr(), n as u32, MSG_DONTWAIT)
         };
 
         if res < 0 {
@@ -283,45 +780,112 @@ impl PhysicalInterface for LinuxUdp {
             if err.kind() == std::io::ErrorKind::WouldBlock {
                 return Err(nb::Error::WouldBlock);
             }
-            return Err(nb::Error::Other(M13Error::HalError));
-        }
-
-        let pkts = res as usize;
-        for i in 0..pkts {
-            meta[i].0 = msg_vec[i].msg_len as usize;
-            
-            let addr = unsafe { 
-                socket2::SockAddr::new(addr_vec[i], msg_vec[i].msg_hdr.msg_namelen) 
-            };
-            
-            if let Some(sa) = addr.as_socket() {
-                meta[i].1 = to_peer_addr(sa);
+            // [PMTU] The first frame was too big (later ones would have been a short count).
+            if err.raw_os_error() == Some(libc::EMSGSIZE) {
+                if let Some(dest) = addrs[0].as_socket() { self.refresh_mtu(dest); }
             }
+            return Err(nb::Error::Other(M13Error::HalError));
         }
-        Ok(pkts)
+        Ok(res as usize)
     }
 }
 
 pub type LinuxPhy = LinuxUdp; 
 
-pub struct LinuxHsm;
+/// OS RNG plus a P-256 AIK held in process memory (no TPM). `new()` makes an ephemeral AIK:
+/// A verifier pinning the key needs a provisioned one (`with_aik`).
+pub struct LinuxHsm {
+    aik: p256::ecdsa::SigningKey,
+}
+
+impl LinuxHsm {
+    pub fn new() -> Self {
+        Self { aik: p256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng) }
+    }
+
+    /// `secret`: 32-byte big-endian P-256 scalar.
+    pub fn with_aik(secret: &[u8]) -> anyhow::Result<Self> {
+        let aik = p256::ecdsa::SigningKey::from_slice(secret).map_err(|_| anyhow::anyhow!("invalid P-256 AIK"))?;
+        Ok(Self { aik })
+    }
+}
+
+impl Default for LinuxHsm {
+    fn default() -> Self { Self::new() }
+}
+
 impl SecurityModule for LinuxHsm {
     fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()> {
         use rand::RngCore;
         rand::thread_rng().fill_bytes(buf);
         Ok(())
     }
-    fn sign_digest(&mut self, _: &[u8], sig: &mut [u8]) -> M13Result<usize> {
-        sig.fill(0xAA); Ok(64)
+    // [ATTEST] ECDSA-P256-SHA256 over the digest, DER-encoded (at most 72 bytes).
+    fn sign_digest(&mut self, digest: &[u8], sig: &mut [u8]) -> M13Result<usize> {
+        use p256::ecdsa::{Signature, signature::Signer};
+        let signature: Signature = self.aik.try_sign(digest).map_err(|_| M13Error::CryptoFailure)?;
+        let der = signature.to_der();
+        let out = sig.get_mut(..der.len()).ok_or(M13Error::WireFormatError)?;
+        out.copy_from_slice(der.as_bytes());
+        Ok(der.len())
     }
     fn panic_and_sanitize(&self) -> ! { std::process::abort(); }
+    fn attestation_key(&self) -> Option<[u8; 65]> {
+        let point = p256::ecdsa::VerifyingKey::from(&self.aik).to_encoded_point(false);
+        point.as_bytes().try_into().ok()
+    }
 }
 
-pub struct LinuxClock(Instant);
-impl LinuxClock { pub fn new() -> Self { Self(Instant::now()) } }
+/// The PTP hardware clock read by `LinuxClock::new`.
+pub const PTP_DEVICE: &str = "/dev/ptp0";
+
+/// Monotonic `now_us`; `ptp_ns` reads a PTP hardware clock (kept on the grandmaster's time
+/// by ptp4l) through its dynamic POSIX clock id. No PHC: `ptp_ns` is None.
+pub struct LinuxClock {
+    origin: Instant,
+    phc: Option<File>,
+}
+impl LinuxClock {
+    pub fn new() -> Self { Self::with_phc(PTP_DEVICE) }
+
+    /// As `new`, with another PHC (e.g. `/dev/ptp1`, the NIC that faces the grandmaster).
+    pub fn with_phc(path: &str) -> Self {
+        Self { origin: Instant::now(), phc: File::open(path).ok() }
+    }
+}
+impl Default for LinuxClock {
+    fn default() -> Self { Self::new() }
+}
 impl PlatformClock for LinuxClock {
-    fn now_us(&self) -> u64 { self.0.elapsed().as_micros() as u64 }
+    fn now_us(&self) -> u64 { self.origin.elapsed().as_micros() as u64 }
+
+    #[cfg(target_os = "linux")]
+    fn ptp_ns(&self) -> Option<u64> {
+        let fd = self.phc.as_ref()?.as_raw_fd();
+        // FD_TO_CLOCKID (linux/posix-timers.h): CLOCKFD = 3.
+        let clock = ((!fd) << 3) | 3;
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        if unsafe { libc::clock_gettime(clock as libc::clockid_t, &mut ts) } != 0 { return None; }
+        Some((ts.tv_sec as u64) * 1_000_000_000 + ts.tv_nsec as u64)
+    }
+
+    #[cfg(not(target_os = "linux"))]
     fn ptp_ns(&self) -> Option<u64> { None }
 }
 
+#[cfg(target_os = "linux")]
+mod gro;
+#[cfg(target_os = "linux")]
+pub mod reactor;
+#[cfg(target_os = "linux")]
+pub use reactor::Reactor;
+pub mod config;
+pub use config::RuntimeConfig;
 pub mod setup;
+pub mod tcp;
+pub use tcp::LinuxTcp;
+
+#[cfg(all(target_os = "linux", feature = "uring"))]
+pub mod uring;
+#[cfg(all(target_os = "linux", feature = "uring"))]
+pub use uring::LinuxUring;
@@ -0,0 +1,61 @@
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+
+/// Longest single sleep. Bounds how late the loop sees a flag set from a signal handler
+/// (e.g. ctrlc), which does not interrupt the wait.
+pub const MAX_IDLE_WAIT_US: u64 = 100_000;
+
+const MAX_EVENTS: usize = 8;
+
+/// [IDLE] epoll for the runtime loop: Sleeps until the UDP socket or TUN is readable or the
+/// kernel's next timer (`M13Kernel::next_deadline_us`) is due, instead of spinning on `poll`.
+pub struct Reactor {
+    epfd: OwnedFd,
+    events: [libc::epoll_event; MAX_EVENTS],
+}
+
+impl Reactor {
+    pub fn new() -> anyhow::Result<Self> {
+        let fd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        if fd < 0 { return Err(io::Error::last_os_error().into()); }
+        Ok(Self {
+            epfd: unsafe { OwnedFd::from_raw_fd(fd) },
+            events: [libc::epoll_event { events: 0, u64: 0 }; MAX_EVENTS],
+        })
+    }
+
+    /// Wake when `fd` is readable. Level-triggered: Data the loop left unread wakes the next wait at once.
+    pub fn register(&mut self, fd: RawFd) -> anyhow::Result<()> {
+        let mut ev = libc::epoll_event { events: libc::EPOLLIN as u32, u64: fd as u64 };
+        if unsafe { libc::epoll_ctl(self.epfd.as_raw_fd(), libc::EPOLL_CTL_ADD, fd, &mut ev) } < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    /// Sleep until a registered fd is readable or `timeout_us` passes (None: No limit).
+    /// Returns the number of ready fds; 0 on timeout or signal.
+    pub fn wait(&mut self, timeout_us: Option<u64>) -> io::Result<usize> {
+        // Round up: Waking before the deadline would only spin until it.
+        let timeout_ms = match timeout_us {
+            Some(us) => us.div_ceil(1_000).min(i32::MAX as u64) as i32,
+            None => -1,
+        };
+        let n = unsafe {
+            libc::epoll_wait(self.epfd.as_raw_fd(), self.events.as_mut_ptr(), MAX_EVENTS as i32, timeout_ms)
+        };
+        if n < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted { return Ok(0); }
+            return Err(err);
+        }
+        Ok(n as usize)
+    }
+
+    /// The idle step of the runtime loop: Sleep toward `deadline_us` (kernel clock, as from
+    /// `next_deadline_us`), at most `MAX_IDLE_WAIT_US`. A past deadline returns at once.
+    pub fn wait_until(&mut self, deadline_us: Option<u64>, now_us: u64) -> io::Result<usize> {
+        let timeout = deadline_us.map_or(MAX_IDLE_WAIT_US, |d| d.saturating_sub(now_us).min(MAX_IDLE_WAIT_US));
+        self.wait(Some(timeout))
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::M13Error;
+
+use crate::to_peer_addr;
+
+/// Stream framing: [len u16 BE][frame].
+pub const TCP_FRAME_PREFIX: usize = 2;
+const READ_CHUNK: usize = 16 * 1024;
+
+/// [FALLBACK] M13 over one TCP stream, for networks that block or throttle UDP.
+/// The stream is point-to-point: `target` is ignored and every frame `recv`s from the stream peer.
+/// TCP retransmits underneath the Fountain layer. Redundant, but it gets through.
+pub struct LinuxTcp {
+    stream: TcpStream,
+    peer: PeerAddr,
+    // Bytes read but not yet delivered (partial frames included).
+    rx_buf: Vec<u8>,
+    // Tail of a frame the socket only partially accepted. Must drain before the next frame.
+    tx_pending: Vec<u8>,
+}
+
+impl LinuxTcp {
+    /// Node side: Connect (blocking), then switch to non-blocking.
+    pub fn connect(addr: &str) -> anyhow::Result<Self> {
+        let addr: SocketAddr = addr.parse()?;
+        Self::from_stream(TcpStream::connect(addr)?)
+    }
+
+    /// Hub side: Wrap a stream returned by `TcpListener::accept`.
+    pub fn from_stream(stream: TcpStream) -> anyhow::Result<Self> {
+        stream.set_nodelay(true)?;
+        stream.set_nonblocking(true)?;
+        let peer = to_peer_addr(stream.peer_addr()?);
+        Ok(Self { stream, peer, rx_buf: Vec::with_capacity(READ_CHUNK), tx_pending: Vec::new() })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.local_addr()
+    }
+
+    /// Push out a partially written frame. `WouldBlock` while bytes remain.
+    pub fn flush(&mut self) -> nb::Result<(), M13Error> {
+        while !self.tx_pending.is_empty() {
+            match self.stream.write(&self.tx_pending) {
+                Ok(0) => return Err(nb::Error::Other(M13Error::HalError)),
+                Ok(n) => { self.tx_pending.drain(..n); },
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Err(nb::Error::WouldBlock),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return Err(nb::Error::Other(M13Error::HalError)),
+            }
+        }
+        Ok(())
+    }
+
+    // Pops the first complete frame out of `rx_buf`. Oversized frames truncate, like a datagram.
+    fn take_frame(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.rx_buf.len() < TCP_FRAME_PREFIX { return None; }
+        let len = u16::from_be_bytes([self.rx_buf[0], self.rx_buf[1]]) as usize;
+        let end = TCP_FRAME_PREFIX + len;
+        if self.rx_buf.len() < end { return None; }
+
+        let n = len.min(buf.len());
+        buf[..n].copy_from_slice(&self.rx_buf[TCP_FRAME_PREFIX..TCP_FRAME_PREFIX + n]);
+        self.rx_buf.drain(..end);
+        Some(n)
+    }
+}
+
+impl PhysicalInterface for LinuxTcp {
+    fn properties(&self) -> LinkProperties {
+        LinkProperties { mtu: 1400, bandwidth_bps: 1_000_000_000, is_reliable: true }
+    }
+
+    fn send(&mut self, frame: &[u8], _target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        let len = u16::try_from(frame.len()).map_err(|_| nb::Error::Other(M13Error::HalError))?;
+
+        // Frames must never interleave: Finish the previous one first.
+        self.flush()?;
+
+        let mut wire = Vec::with_capacity(TCP_FRAME_PREFIX + frame.len());
+        wire.extend_from_slice(&len.to_be_bytes());
+        wire.extend_from_slice(frame);
+
+        let written = match self.stream.write(&wire) {
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+            Err(_) => return Err(nb::Error::Other(M13Error::HalError)),
+        };
+        if written == 0 { return Err(nb::Error::WouldBlock); }
+
+        // Accepted: The tail goes out on the next send/recv.
+        if written < wire.len() {
+            wire.drain(..written);
+            self.tx_pending = wire;
+        }
+        Ok(frame.len())
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        // recv runs every poll: Keep a stalled frame moving even when nothing new is sent.
+        match self.flush() {
+            Ok(()) | Err(nb::Error::WouldBlock) => {},
+            Err(e) => return Err(e),
+        }
+
+        if let Some(n) = self.take_frame(buf) { return Ok((n, self.peer)); }
+
+        let mut chunk = [0u8; READ_CHUNK];
+        loop {
+            match self.stream.read(&mut chunk) {
+                // EOF: The peer closed the stream.
+                Ok(0) => return Err(nb::Error::Other(M13Error::HalError)),
+                Ok(n) => {
+                    self.rx_buf.extend_from_slice(&chunk[..n]);
+                    if let Some(n) = self.take_frame(buf) { return Ok((n, self.peer)); }
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Err(nb::Error::WouldBlock),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return Err(nb::Error::Other(M13Error::HalError)),
+            }
+        }
+    }
+}
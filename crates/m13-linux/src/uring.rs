@@ -0,0 +1,291 @@
+use std::io;
+use std::mem;
+use std::net::SocketAddr;
+use std::os::unix::io::AsRawFd;
+use std::sync::Arc;
+
+use io_uring::{opcode, types, IoUring};
+use io_uring::types::CancelBuilder;
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::M13Error;
+use m13_mem::{FrameLease, SlabAllocator};
+
+use crate::{rx_cmsgs, to_peer_addr, LinuxUdp};
+
+// Receives kept posted, each into its own slab frame.
+const URING_DEPTH: usize = 256;
+// Room for TOS/TCLASS + timespec, as in `recv_mmsg` (u64 words).
+const URING_CTRL_WORDS: usize = 8;
+// Submission queue: Every slot can be re-posted at once.
+const URING_ENTRIES: u32 = URING_DEPTH as u32;
+// [PERF] Drained slots are re-posted in bulk: One io_uring_enter per REFILL_BATCH datagrams.
+const REFILL_BATCH: usize = URING_DEPTH / 4;
+
+// One posted receive: The frame the kernel writes the datagram into, and the msghdr it
+// reads the name/control buffers from. Boxed in `LinuxUring::slots`, so it never moves.
+struct RxSlot {
+    lease: FrameLease,
+    hdr: libc::msghdr,
+    iov: libc::iovec,
+    name: libc::sockaddr_storage,
+    // u64 backing store keeps every cmsghdr 8-byte aligned.
+    control: [u64; URING_CTRL_WORDS],
+}
+
+// SAFETY: The raw pointers in `hdr`/`iov` only ever point into the slot itself and its lease.
+unsafe impl Send for RxSlot {}
+unsafe impl Sync for RxSlot {}
+
+/// [PERF] UDP with an io_uring receive path: Every slot keeps a `recvmsg` posted straight
+/// into a `FrameLease` of the kernel's arena, so `recv_leases` drains completions from shared
+/// memory and swaps the filled frames out without copying them. Drained slots are re-posted
+/// with the caller's empty frames, one submit per `REFILL_BATCH`. Multishot recvmsg would
+/// put its `io_uring_recvmsg_out` header ahead of the payload, hence one receive per frame.
+/// The buffer-based `recv_batch*` copy once. Transmit, GSO and PMTU behave exactly as
+/// `LinuxUdp`; GRO is off. Needs Linux 6.0 (synchronous cancel on drop).
+pub struct LinuxUring {
+    // Field order matters: The ring must close before the memory the kernel writes into.
+    ring: IoUring,
+    udp: LinuxUdp,
+    slots: Box<[RxSlot]>,
+    // Slots drained but not yet posted again.
+    pending: Vec<usize>,
+    in_flight: usize,
+}
+
+impl LinuxUring {
+    /// Posts up to `URING_DEPTH` frames of `mem` (the kernel's arena) for receive.
+    pub fn new(bind_addr: &str, target_addr: Option<&str>, mem: &Arc<SlabAllocator>) -> anyhow::Result<Self> {
+        let udp = LinuxUdp::new(bind_addr, target_addr)?;
+        // [GRO] One datagram per frame: Have the kernel segment coalesced receives before queueing.
+        let off: libc::c_int = 0;
+        // SAFETY: `off` outlives the call and the length passed is its size.
+        let res = unsafe {
+            libc::setsockopt(
+                udp.socket.as_raw_fd(), libc::SOL_UDP, libc::UDP_GRO,
+                &off as *const _ as *const libc::c_void,
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if res < 0 { return Err(io::Error::last_os_error().into()); }
+        let ring = IoUring::new(URING_ENTRIES)?;
+
+        let mut leases = Vec::with_capacity(URING_DEPTH);
+        if mem.alloc_batch(URING_DEPTH, &mut leases) == 0 {
+            anyhow::bail!("no frames left in the arena for the receive ring");
+        }
+        let slots: Box<[RxSlot]> = leases.into_iter().map(|lease| RxSlot {
+            lease,
+            // SAFETY: All-zero is a valid msghdr, iovec and sockaddr_storage.
+            hdr: unsafe { mem::zeroed() },
+            iov: unsafe { mem::zeroed() },
+            name: unsafe { mem::zeroed() },
+            control: [0u64; URING_CTRL_WORDS],
+        }).collect();
+
+        let pending = (0..slots.len()).collect();
+        let mut this = Self { ring, udp, slots, pending, in_flight: 0 };
+        this.replenish().map_err(|_| anyhow::anyhow!("io_uring recvmsg could not be posted"))?;
+        Ok(this)
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.udp.local_addr()
+    }
+
+    // Re-posts drained slots. Skips the syscall while most of them are still with the kernel.
+    fn replenish(&mut self) -> nb::Result<(), M13Error> {
+        if self.pending.is_empty() { return Ok(()); }
+        if self.in_flight > 0 && self.pending.len() < REFILL_BATCH { return Ok(()); }
+
+        let fd = types::Fd(self.udp.socket.as_raw_fd());
+        {
+            let mut sq = self.ring.submission();
+            for idx in self.pending.drain(..) {
+                let slot = &mut self.slots[idx];
+                slot.iov.iov_base = slot.lease.data.as_mut_ptr() as *mut libc::c_void;
+                slot.iov.iov_len = slot.lease.data.len();
+                slot.hdr.msg_name = &mut slot.name as *mut _ as *mut libc::c_void;
+                slot.hdr.msg_namelen = mem::size_of::<libc::sockaddr_storage>() as u32;
+                slot.hdr.msg_iov = &mut slot.iov;
+                slot.hdr.msg_iovlen = 1;
+                slot.hdr.msg_control = slot.control.as_mut_ptr() as *mut libc::c_void;
+                slot.hdr.msg_controllen = mem::size_of_val(&slot.control);
+                slot.hdr.msg_flags = 0;
+
+                let recv = opcode::RecvMsg::new(fd, &mut slot.hdr).build().user_data(idx as u64);
+                // SAFETY: The slot is boxed and neither moves nor drops before its completion
+                // (or the cancel in `drop`); the lease stays in it until then.
+                unsafe { sq.push(&recv) }.map_err(|_| nb::Error::Other(M13Error::HalError))?;
+                self.in_flight += 1;
+            }
+        }
+        self.ring.submit().map_err(|_| nb::Error::Other(M13Error::HalError))?;
+        Ok(())
+    }
+
+    // Hands up to `max` completed datagrams to `deliver` as (index, frame, len, source, TOS,
+    // RX timestamp). The frame is the slot's: `deliver` may swap it for an empty one.
+    fn drain(
+        &mut self,
+        max: usize,
+        mut deliver: impl FnMut(usize, &mut FrameLease, usize, PeerAddr, u8, u64)
+    ) -> nb::Result<usize, M13Error> {
+        let mut got = 0;
+        let mut failed = false;
+        while got < max {
+            let cqe = match self.ring.completion().next() {
+                Some(cqe) => cqe,
+                None => break,
+            };
+            let idx = cqe.user_data() as usize;
+            self.in_flight -= 1;
+            self.pending.push(idx);
+            if cqe.result() < 0 {
+                log::warn!("io_uring recvmsg failed: {}", io::Error::from_raw_os_error(-cqe.result()));
+                failed = true;
+                continue;
+            }
+
+            let slot = &mut self.slots[idx];
+            // [PHYSICS] Larger than the frame: Only a prefix arrived. Drop it, never parse it.
+            if slot.hdr.msg_flags & libc::MSG_TRUNC != 0 {
+                log::debug!("Dropped truncated datagram ({} byte frame)", slot.lease.data.len());
+                continue;
+            }
+            // SAFETY: The kernel wrote a sockaddr of `msg_namelen` bytes into `name`.
+            let addr = unsafe { socket2::SockAddr::new(slot.name, slot.hdr.msg_namelen) };
+            let src = addr.as_socket().map(to_peer_addr).unwrap_or(PeerAddr::None);
+            let (tos, ts, _) = rx_cmsgs(&slot.hdr);
+            deliver(got, &mut slot.lease, cqe.result() as usize, src, tos & 0b11, ts);
+            got += 1;
+        }
+
+        self.replenish()?;
+        if got > 0 { return Ok(got); }
+        if failed { return Err(nb::Error::Other(M13Error::HalError)); }
+        Err(nb::Error::WouldBlock)
+    }
+
+    // Buffer-based receive: One copy out of the slot's frame.
+    fn drain_into(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        mut ecn: Option<&mut [u8]>,
+        mut timestamps_us: Option<&mut [u64]>
+    ) -> nb::Result<usize, M13Error> {
+        let mut count = buffers.len().min(meta.len());
+        if let Some(e) = &ecn { count = count.min(e.len()); }
+        if let Some(t) = &timestamps_us { count = count.min(t.len()); }
+
+        self.drain(count, |i, lease, len, src, tos, ts| {
+            let n = len.min(buffers[i].len());
+            buffers[i][..n].copy_from_slice(&lease.data[..n]);
+            meta[i] = (n, src);
+            if let Some(e) = ecn.as_deref_mut() { e[i] = tos; }
+            if let Some(t) = timestamps_us.as_deref_mut() { t[i] = ts; }
+        })
+    }
+}
+
+impl Drop for LinuxUring {
+    fn drop(&mut self) {
+        // Posted receives would still write into frames about to go back to the arena.
+        if self.in_flight > 0 {
+            if let Err(e) = self.ring.submitter().register_sync_cancel(None, CancelBuilder::any()) {
+                log::warn!("io_uring cancel on drop failed: {}", e);
+            }
+        }
+    }
+}
+
+impl PhysicalInterface for LinuxUring {
+    fn properties(&self) -> LinkProperties {
+        self.udp.properties()
+    }
+
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.udp.send(frame, target)
+    }
+
+    fn send_gso(
+        &mut self,
+        data: &[u8],
+        target: Option<PeerAddr>,
+        segment_size: u16
+    ) -> nb::Result<usize, M13Error> {
+        self.udp.send_gso(data, target, segment_size)
+    }
+
+    fn send_batch(
+        &mut self,
+        frames: &[&[u8]],
+        targets: &[Option<PeerAddr>]
+    ) -> nb::Result<usize, M13Error> {
+        self.udp.send_batch(frames, targets)
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        let mut meta = [(0usize, PeerAddr::None)];
+        self.drain_into(&mut [buf], &mut meta, None, None)?;
+        Ok(meta[0])
+    }
+
+    fn recv_batch(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)]
+    ) -> nb::Result<usize, M13Error> {
+        self.drain_into(buffers, meta, None, None)
+    }
+
+    fn recv_batch_ecn(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8]
+    ) -> nb::Result<usize, M13Error> {
+        self.drain_into(buffers, meta, Some(ecn), None)
+    }
+
+    fn recv_batch_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        self.drain_into(buffers, meta, None, Some(timestamps_us))
+    }
+
+    fn recv_batch_ecn_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        self.drain_into(buffers, meta, Some(ecn), Some(timestamps_us))
+    }
+
+    fn recv_leases(
+        &mut self,
+        leases: &mut [FrameLease],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let count = leases.len().min(meta.len()).min(ecn.len()).min(timestamps_us.len());
+        // [PERF] Zero-copy: The filled frame goes to the caller, the caller's frame gets posted.
+        self.drain(count, |i, lease, len, src, tos, ts| {
+            mem::swap(&mut leases[i], lease);
+            meta[i] = (len, src);
+            ecn[i] = tos;
+            timestamps_us[i] = ts;
+        })
+    }
+
+    fn rx_clock_us(&self) -> u64 {
+        self.udp.rx_clock_us()
+    }
+}
@@ -0,0 +1,41 @@
+use m13_attest::{generate_attestation, verify_epoch0, Epoch0Frame, PcrBank, PcrIndex};
+use m13_hal::SecurityModule;
+use m13_linux::LinuxHsm;
+use m13_pqc::DsaKeypair;
+use rand::rngs::OsRng;
+
+fn firmware() -> PcrBank {
+    let mut bank = PcrBank::new();
+    bank.extend(PcrIndex::Root, b"root-of-trust");
+    bank.extend(PcrIndex::Firmware, b"fw-1.0");
+    bank
+}
+
+#[test]
+fn test_linux_hsm_attestation_verifies() {
+    let mut hsm = LinuxHsm::new();
+    let identity = DsaKeypair::generate(&mut OsRng).unwrap();
+    let nonce = [0x61; 32];
+
+    let frame = generate_attestation(&nonce, &identity, firmware(), &mut hsm, &mut OsRng).unwrap();
+    assert_eq!(Some(frame.legacy_aik_pub), hsm.attestation_key(), "AIK filled in from the HSM");
+    assert!(verify_epoch0(&frame, &nonce, &firmware()).is_ok());
+
+    // Survives the wire, and the binding still pins the nonce.
+    let decoded = Epoch0Frame::from_bytes(&frame.to_bytes()).unwrap();
+    assert!(verify_epoch0(&decoded, &nonce, &firmware()).is_ok());
+    assert!(verify_epoch0(&frame, &[0x62; 32], &firmware()).is_err());
+
+    // Another HSM's AIK does not vouch for this signature.
+    let mut swapped = frame.clone();
+    swapped.legacy_aik_pub = LinuxHsm::new().attestation_key().unwrap();
+    assert!(verify_epoch0(&swapped, &nonce, &firmware()).is_err());
+}
+
+#[test]
+fn test_provisioned_aik_is_stable() {
+    let a = LinuxHsm::with_aik(&[0x42; 32]).unwrap();
+    let b = LinuxHsm::with_aik(&[0x42; 32]).unwrap();
+    assert_eq!(a.attestation_key(), b.attestation_key());
+    assert!(LinuxHsm::with_aik(&[0; 32]).is_err());
+}
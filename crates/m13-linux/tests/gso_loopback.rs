@@ -0,0 +1,32 @@
+#[cfg(target_os = "linux")]
+#[test]
+fn test_gso_four_segments() {
+    use m13_hal::PhysicalInterface;
+    use m13_linux::LinuxUdp;
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    const SEGMENT: usize = 1000;
+
+    let rx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    rx.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    let rx_addr = rx.local_addr().unwrap().to_string();
+
+    let mut tx = LinuxUdp::new("127.0.0.1:0", Some(&rx_addr)).unwrap();
+
+    let mut super_packet = Vec::with_capacity(4 * SEGMENT);
+    for i in 0..4u8 {
+        super_packet.extend(std::iter::repeat_n(i, SEGMENT));
+    }
+
+    let sent = tx.send_gso(&super_packet, None, SEGMENT as u16).unwrap();
+    assert_eq!(sent, super_packet.len());
+
+    // Whether segmented by the kernel or by the fallback, the wire sees 4 datagrams.
+    let mut buf = [0u8; 65536];
+    for i in 0..4u8 {
+        let n = rx.recv(&mut buf).unwrap();
+        assert_eq!(n, SEGMENT);
+        assert!(buf[..n].iter().all(|&b| b == i));
+    }
+}
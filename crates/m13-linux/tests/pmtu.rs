@@ -0,0 +1,43 @@
+#[cfg(target_os = "linux")]
+#[test]
+fn test_emsgsize_lowers_reported_mtu() {
+    use m13_hal::PhysicalInterface;
+    use m13_linux::LinuxUdp;
+    use std::net::UdpSocket;
+    use std::process::Command;
+    use std::time::Duration;
+
+    const PATH_MTU: usize = 1280;
+
+    // A private network namespace (this thread only) whose loopback has a small MTU.
+    let worker = std::thread::spawn(|| {
+        if unsafe { libc::unshare(libc::CLONE_NEWNET) } != 0 {
+            eprintln!("skipping: unshare(CLONE_NEWNET) needs CAP_SYS_ADMIN");
+            return;
+        }
+        let mtu = PATH_MTU.to_string();
+        let up = Command::new("ip").args(["link", "set", "lo", "mtu", &mtu, "up"]).status();
+        if !matches!(up, Ok(s) if s.success()) {
+            eprintln!("skipping: could not configure loopback");
+            return;
+        }
+
+        let rx = UdpSocket::bind("127.0.0.1:0").unwrap();
+        rx.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let rx_addr = rx.local_addr().unwrap().to_string();
+
+        let mut tx = LinuxUdp::new("127.0.0.1:0", Some(&rx_addr)).unwrap();
+        assert_eq!(tx.properties().mtu, 1400);
+
+        // DF is set: The kernel refuses rather than fragmenting.
+        assert!(tx.send(&[0xAB; 1400], None).is_err());
+        assert_eq!(tx.properties().mtu, PATH_MTU);
+
+        // A frame that fits the discovered MTU still gets through.
+        let fits = PATH_MTU - 28;
+        assert_eq!(tx.send(&vec![0xCD; fits], None).unwrap(), fits);
+        let mut buf = [0u8; 2048];
+        assert_eq!(rx.recv(&mut buf).unwrap(), fits);
+    });
+    worker.join().unwrap();
+}
@@ -0,0 +1,31 @@
+#[cfg(target_os = "linux")]
+#[test]
+fn test_ptp_ns_follows_phc_presence() {
+    use m13_hal::PlatformClock;
+    use m13_linux::{LinuxClock, PTP_DEVICE};
+
+    let clock = LinuxClock::new();
+    if std::fs::File::open(PTP_DEVICE).is_ok() {
+        let a = clock.ptp_ns().expect("PHC present but unreadable");
+        let b = clock.ptp_ns().unwrap();
+        assert!(a > 0 && b >= a);
+    } else {
+        assert_eq!(clock.ptp_ns(), None);
+    }
+
+    // The monotonic clock does not depend on PTP.
+    let t0 = clock.now_us();
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    assert!(clock.now_us() >= t0 + 2_000);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_missing_phc_falls_back_to_none() {
+    use m13_hal::PlatformClock;
+    use m13_linux::LinuxClock;
+
+    assert_eq!(LinuxClock::with_phc("/dev/m13-no-such-ptp").ptp_ns(), None);
+    // A regular file is not a clock: clock_gettime refuses it.
+    assert_eq!(LinuxClock::with_phc("/proc/self/status").ptp_ns(), None);
+}
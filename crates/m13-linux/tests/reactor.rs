@@ -0,0 +1,52 @@
+#[cfg(target_os = "linux")]
+#[test]
+fn test_reactor_sleeps_when_idle() {
+    use m13_linux::{LinuxUdp, Reactor};
+    use std::os::unix::io::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    let rx = LinuxUdp::new("127.0.0.1:0", None).unwrap();
+    let mut reactor = Reactor::new().unwrap();
+    reactor.register(rx.as_raw_fd()).unwrap();
+
+    let start = Instant::now();
+    assert_eq!(reactor.wait(Some(50_000)).unwrap(), 0, "Woke with nothing to read");
+    assert!(start.elapsed() >= Duration::from_millis(50), "Returned before the timeout");
+
+    // A deadline already passed never blocks.
+    let start = Instant::now();
+    assert_eq!(reactor.wait_until(Some(1_000), 2_000).unwrap(), 0);
+    assert!(start.elapsed() < Duration::from_millis(20));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_reactor_wakes_on_socket_readability() {
+    use m13_hal::PhysicalInterface;
+    use m13_linux::{LinuxUdp, Reactor};
+    use std::os::unix::io::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    let mut rx = LinuxUdp::new("127.0.0.1:0", None).unwrap();
+    let rx_addr = rx.local_addr().unwrap().to_string();
+    let mut reactor = Reactor::new().unwrap();
+    reactor.register(rx.as_raw_fd()).unwrap();
+
+    let sender = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        let mut tx = LinuxUdp::new("127.0.0.1:0", Some(&rx_addr)).unwrap();
+        tx.send(b"wake", None).unwrap();
+    });
+
+    // Unbounded wait: Only the datagram can end it.
+    let start = Instant::now();
+    assert_eq!(reactor.wait(None).unwrap(), 1);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    sender.join().unwrap();
+
+    let mut buf = [0u8; 64];
+    assert!(matches!(rx.recv(&mut buf), Ok((4, _))));
+
+    // Drained: Back to sleeping.
+    assert_eq!(reactor.wait(Some(20_000)).unwrap(), 0);
+}
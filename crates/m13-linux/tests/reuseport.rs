@@ -0,0 +1,33 @@
+#[cfg(target_os = "linux")]
+#[test]
+fn test_reuseport_shards_share_the_load() {
+    use m13_hal::PhysicalInterface;
+    use m13_linux::LinuxUdp;
+    use std::net::UdpSocket;
+    use std::time::{Duration, Instant};
+
+    let mut shards = LinuxUdp::new_sharded("127.0.0.1:0", 2).unwrap();
+    let addr = shards[0].local_addr().unwrap();
+    assert_eq!(shards[1].local_addr().unwrap(), addr, "Shards must share one address");
+
+    // Distinct source ports = distinct flows: The reuseport hash spreads them over both shards.
+    let senders: Vec<UdpSocket> = (0..32).map(|_| UdpSocket::bind("127.0.0.1:0").unwrap()).collect();
+    for (i, s) in senders.iter().enumerate() {
+        s.send_to(&[i as u8; 64], addr).unwrap();
+    }
+
+    let mut received = [0usize; 2];
+    let mut buf = [0u8; 2048];
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while received.iter().sum::<usize>() < senders.len() && Instant::now() < deadline {
+        for (i, shard) in shards.iter_mut().enumerate() {
+            while let Ok((n, _)) = shard.recv(&mut buf) {
+                assert_eq!(n, 64);
+                received[i] += 1;
+            }
+        }
+    }
+
+    assert_eq!(received.iter().sum::<usize>(), senders.len(), "Datagrams lost: {:?}", received);
+    assert!(received.iter().all(|&n| n > 0), "One shard took every flow: {:?}", received);
+}
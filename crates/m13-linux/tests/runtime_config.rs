@@ -0,0 +1,34 @@
+use m13_linux::RuntimeConfig;
+
+#[test]
+fn test_partial_file_keeps_defaults() {
+    let config = RuntimeConfig::parse("symbol_size = 512\ncbr_floor_bps = 2000000\nslab_large_frames = 16384\n").unwrap();
+    assert_eq!(config.symbol_size, Some(512));
+    assert_eq!(config.cbr_floor_bps, Some(2_000_000));
+    assert_eq!(config.slab_large_frames, Some(16_384));
+    assert_eq!(config.gso_segment_size, None);
+    assert_eq!(config.session_idle_timeout_us, None);
+
+    let empty = RuntimeConfig::parse("").unwrap();
+    assert_eq!(empty.symbol_size, None);
+}
+
+#[test]
+fn test_bad_files_are_rejected() {
+    // A typo must not silently run the default.
+    assert!(RuntimeConfig::parse("symbol_sise = 512\n").is_err());
+    assert!(RuntimeConfig::parse("symbol_size = \"big\"\n").is_err());
+    assert!(RuntimeConfig::parse("gso_segment_size = 70000\n").is_err(), "u16 overflow");
+    assert!(RuntimeConfig::parse("slab_mtu_frames = 0\n").is_err());
+}
+
+#[test]
+fn test_load_reads_the_file() {
+    let path = std::env::temp_dir().join(format!("m13-config-{}.toml", std::process::id()));
+    std::fs::write(&path, "session_idle_timeout_us = 60000000\n").unwrap();
+    let config = RuntimeConfig::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config.session_idle_timeout_us, Some(60_000_000));
+
+    assert!(RuntimeConfig::load(&path).is_err(), "Missing file");
+}
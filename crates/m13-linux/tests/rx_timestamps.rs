@@ -0,0 +1,77 @@
+#[cfg(target_os = "macos")]
+#[test]
+fn test_macos_rx_timestamps_monotonic() {
+    use m13_hal::{PhysicalInterface, PeerAddr};
+    use m13_linux::LinuxUdp;
+    use std::net::UdpSocket;
+    use std::time::{Duration, Instant};
+
+    const BURST: usize = 8;
+
+    let mut rx = LinuxUdp::new("127.0.0.1:0", None).unwrap();
+    let rx_addr = rx.local_addr().unwrap();
+
+    let tx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for i in 0..BURST {
+        tx.send_to(&[i as u8; 64], rx_addr).unwrap();
+    }
+
+    let mut storage = vec![[0u8; 2048]; BURST];
+    let mut meta = vec![(0usize, PeerAddr::None); BURST];
+    let mut stamps = vec![0u64; BURST];
+    let mut got = 0;
+    let deadline = Instant::now() + Duration::from_secs(2);
+
+    while got < BURST && Instant::now() < deadline {
+        let mut bufs: Vec<&mut [u8]> = storage[got..].iter_mut().map(|b| &mut b[..]).collect();
+        if let Ok(n) = rx.recv_batch_ts(&mut bufs, &mut meta[got..], &mut stamps[got..]) {
+            got += n;
+        }
+    }
+
+    assert_eq!(got, BURST);
+    assert!(stamps.iter().all(|&t| t > 0));
+    assert!(stamps.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_linux_rx_timestamps_monotonic() {
+    use m13_hal::{PhysicalInterface, PeerAddr};
+    use m13_linux::LinuxUdp;
+    use std::net::UdpSocket;
+    use std::time::{Duration, Instant};
+
+    const BURST: usize = 8;
+
+    let mut rx = LinuxUdp::new("127.0.0.1:0", None).unwrap();
+    let rx_addr = rx.local_addr().unwrap();
+
+    let tx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for i in 0..BURST {
+        tx.send_to(&[i as u8; 64], rx_addr).unwrap();
+        std::thread::sleep(Duration::from_micros(200));
+    }
+
+    let mut storage = vec![[0u8; 2048]; BURST];
+    let mut meta = vec![(0usize, PeerAddr::None); BURST];
+    let mut ecn = [0u8; BURST];
+    let mut stamps = vec![0u64; BURST];
+    let mut got = 0;
+    let deadline = Instant::now() + Duration::from_secs(2);
+
+    while got < BURST && Instant::now() < deadline {
+        let mut bufs: Vec<&mut [u8]> = storage[got..].iter_mut().map(|b| &mut b[..]).collect();
+        if let Ok(n) = rx.recv_batch_ecn_ts(&mut bufs, &mut meta[got..], &mut ecn[got..], &mut stamps[got..]) {
+            got += n;
+        }
+    }
+
+    assert_eq!(got, BURST);
+    assert!(stamps.iter().all(|&t| t > 0), "SO_TIMESTAMPNS missing: {:?}", stamps);
+    assert!(stamps.windows(2).all(|w| w[0] < w[1]), "Not increasing: {:?}", stamps);
+
+    // Same epoch as rx_clock_us: Ages are small and non-negative.
+    let clock = rx.rx_clock_us();
+    assert!(stamps.iter().all(|&t| t <= clock && clock - t < 2_000_000));
+}
@@ -0,0 +1,135 @@
+#[cfg(all(target_os = "linux", feature = "uring"))]
+#[test]
+fn test_uring_receives_a_burst() {
+    use m13_hal::{PhysicalInterface, PeerAddr};
+    use m13_linux::LinuxUring;
+    use m13_mem::SlabAllocator;
+    use std::net::UdpSocket;
+    use std::time::{Duration, Instant};
+
+    // More than the refill batch, so slots are re-posted at least once mid-burst.
+    const BURST: usize = 200;
+
+    let mem = SlabAllocator::new(512);
+    let mut rx = match LinuxUring::new("127.0.0.1:0", None, &mem) {
+        Ok(rx) => rx,
+        // Seccomp / io_uring_disabled sandboxes: Nothing to test here.
+        Err(e) => { eprintln!("io_uring unavailable, skipping: {e}"); return; }
+    };
+    let rx_addr = rx.local_addr().unwrap();
+
+    let tx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let tx_port = tx.local_addr().unwrap().port();
+    for i in 0..BURST {
+        tx.send_to(&[i as u8; 100], rx_addr).unwrap();
+    }
+
+    let mut storage = vec![[0u8; 2048]; 32];
+    let mut meta = vec![(0usize, PeerAddr::None); 32];
+    let mut ecn = [0u8; 32];
+    let mut seen = [false; BURST];
+    let mut got = 0;
+    let deadline = Instant::now() + Duration::from_secs(2);
+
+    while got < BURST && Instant::now() < deadline {
+        let mut bufs: Vec<&mut [u8]> = storage.iter_mut().map(|b| &mut b[..]).collect();
+        if let Ok(n) = rx.recv_batch_ecn(&mut bufs, &mut meta, &mut ecn) {
+            for i in 0..n {
+                assert_eq!(meta[i].0, 100);
+                assert_eq!(meta[i].1, PeerAddr::V4([127, 0, 0, 1], tx_port));
+                let tag = storage[i][0] as usize;
+                assert!(storage[i][..100].iter().all(|&b| b as usize == tag));
+                seen[tag] = true;
+            }
+            got += n;
+        }
+    }
+
+    assert_eq!(got, BURST);
+    assert!(seen.iter().all(|&s| s), "Every datagram of the burst arrives exactly once");
+}
+
+#[cfg(all(target_os = "linux", feature = "uring"))]
+#[test]
+fn test_uring_sends_like_udp() {
+    use m13_hal::PhysicalInterface;
+    use m13_linux::LinuxUring;
+    use m13_mem::SlabAllocator;
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    let target = peer.local_addr().unwrap().to_string();
+
+    let mem = SlabAllocator::new(512);
+    let mut tx = match LinuxUring::new("127.0.0.1:0", Some(&target), &mem) {
+        Ok(tx) => tx,
+        Err(e) => { eprintln!("io_uring unavailable, skipping: {e}"); return; }
+    };
+    assert!(matches!(tx.send(b"ping", None), Ok(4)));
+
+    let mut buf = [0u8; 64];
+    let (n, _) = peer.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"ping");
+}
+
+#[cfg(all(target_os = "linux", feature = "uring"))]
+#[test]
+fn test_uring_hands_out_arena_frames() {
+    use m13_hal::{PhysicalInterface, PeerAddr};
+    use m13_linux::LinuxUring;
+    use m13_mem::{SlabAllocator, SlabClass};
+    use std::net::UdpSocket;
+    use std::time::{Duration, Instant};
+
+    const FRAME: usize = 512;
+    const BURST: usize = 100;
+
+    let mem = SlabAllocator::with_classes(&[SlabClass::fixed(FRAME, 512)]);
+    let mut rx = match LinuxUring::new("127.0.0.1:0", None, &mem) {
+        Ok(rx) => rx,
+        Err(e) => { eprintln!("io_uring unavailable, skipping: {e}"); return; }
+    };
+    let rx_addr = rx.local_addr().unwrap();
+    let posted = mem.capacity() - mem.available();
+    assert!(posted > 0, "The ring posts frames of the arena");
+
+    // Every other datagram is larger than a frame: Those must not surface as a prefix.
+    let tx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for i in 0..BURST {
+        tx.send_to(&[i as u8; 100], rx_addr).unwrap();
+        tx.send_to(&[0xEE; FRAME + 100], rx_addr).unwrap();
+    }
+
+    let mut batch = Vec::new();
+    let mut seen = [false; BURST];
+    let mut got = 0;
+    let deadline = Instant::now() + Duration::from_secs(2);
+
+    while got < BURST && Instant::now() < deadline {
+        if batch.len() < 32 { mem.alloc_batch(32 - batch.len(), &mut batch); }
+        let mut meta = vec![(0usize, PeerAddr::None); batch.len()];
+        let mut ecn = vec![0u8; batch.len()];
+        let mut stamps = vec![0u64; batch.len()];
+        if let Ok(n) = rx.recv_leases(&mut batch, &mut meta, &mut ecn, &mut stamps) {
+            for (i, lease) in batch.drain(..n).enumerate() {
+                assert_eq!(meta[i].0, 100, "Truncated datagram delivered");
+                let tag = lease.data[0] as usize;
+                assert!(lease.data[..100].iter().all(|&b| b as usize == tag));
+                assert!(stamps[i] > 0);
+                seen[tag] = true;
+            }
+            got += n;
+        }
+    }
+
+    assert_eq!(got, BURST);
+    assert!(seen.iter().all(|&s| s), "Every datagram of the burst arrives exactly once");
+
+    // Swapped, not leaked: Frames out of the arena are the ring's plus the caller's spares.
+    assert_eq!(mem.capacity() - mem.available(), posted + batch.len());
+    drop(batch);
+    drop(rx);
+    assert_eq!(mem.available(), mem.capacity(), "Every posted frame returns to the arena");
+}
@@ -0,0 +1,40 @@
+use crate::GfSymbol;
+
+/// Constant-time GF(2^8) multiply via carryless multiply (no table lookups).
+/// Returns `None` when the CPU lacks PCLMULQDQ; callers keep their own fallback.
+#[inline]
+pub fn mul_pclmul(a: GfSymbol, b: GfSymbol) -> Option<GfSymbol> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if crate::cpu::has_pclmul() {
+            // SAFETY: PCLMULQDQ presence confirmed at runtime.
+            return Some(GfSymbol(unsafe { x86::mul_pclmul_raw(a.0, b.0) }));
+        }
+    }
+    let _ = (a, b);
+    None
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    /// The Rijndael Polynomial: x^8 + x^4 + x^3 + x + 1.
+    const POLY: i64 = 0x11B;
+    /// Barrett constant: floor(x^16 / POLY).
+    const MU: i64 = 0x11A;
+
+    #[target_feature(enable = "pclmulqdq")]
+    pub unsafe fn mul_pclmul_raw(a: u8, b: u8) -> u8 {
+        // 1. Product: Degree <= 14 (15 bits).
+        let prod = _mm_clmulepi64_si128(_mm_cvtsi64_si128(a as i64), _mm_cvtsi64_si128(b as i64), 0x00);
+
+        // 2. Barrett: q = ((p >> 8) * MU) >> 8
+        let hi = _mm_srli_epi64(prod, 8);
+        let q = _mm_srli_epi64(_mm_clmulepi64_si128(hi, _mm_cvtsi64_si128(MU), 0x00), 8);
+
+        // 3. r = p ^ (q * POLY) mod x^8
+        let qp = _mm_clmulepi64_si128(q, _mm_cvtsi64_si128(POLY), 0x00);
+        _mm_cvtsi128_si64(_mm_xor_si128(prod, qp)) as u8
+    }
+}
@@ -0,0 +1,187 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// SIMD backend for `row_add_scaled`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Engine {
+    Scalar = 1,
+    Avx2 = 2,
+    Avx512 = 3,
+    Neon = 4,
+    WasmSimd128 = 5,
+}
+
+impl Engine {
+    pub const ALL: [Engine; 5] = [Engine::Scalar, Engine::Avx2, Engine::Avx512, Engine::Neon, Engine::WasmSimd128];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Engine::Avx512 => "AVX-512BW (ZEN4/ICELAKE) [64B/CYCLE]",
+            Engine::Avx2 => "AVX2 (TITAN/MODERN) [32B/CYCLE]",
+            Engine::Neon => "NEON (APPLE/ARM) [16B/CYCLE]",
+            Engine::WasmSimd128 => "WASM-SIMD128 (BROWSER/EDGE) [16B/CYCLE]",
+            Engine::Scalar => "SCALAR (FALLBACK) [1B/CYCLE]",
+        }
+    }
+
+    /// True if the running CPU (not the build target) can execute this engine.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Engine::Scalar => true,
+            Engine::Avx2 => has_avx2(),
+            Engine::Avx512 => has_avx512bw(),
+            Engine::Neon => has_neon(),
+            Engine::WasmSimd128 => has_simd128(),
+        }
+    }
+
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Engine::Scalar),
+            2 => Some(Engine::Avx2),
+            3 => Some(Engine::Avx512),
+            4 => Some(Engine::Neon),
+            5 => Some(Engine::WasmSimd128),
+            _ => None,
+        }
+    }
+}
+
+// 0 = Not yet probed. Detection is idempotent, so a racing first call is benign.
+static ACTIVE: AtomicU8 = AtomicU8::new(0);
+
+/// Best engine for this CPU. Probed once, then served from the atomic.
+#[inline(always)]
+pub fn active_engine() -> Engine {
+    if let Some(e) = Engine::from_u8(ACTIVE.load(Ordering::Relaxed)) {
+        return e;
+    }
+    let e = detect();
+    ACTIVE.store(e as u8, Ordering::Relaxed);
+    e
+}
+
+fn detect() -> Engine {
+    if Engine::Avx512.is_supported() { return Engine::Avx512; }
+    if Engine::Avx2.is_supported() { return Engine::Avx2; }
+    if Engine::Neon.is_supported() { return Engine::Neon; }
+    if Engine::WasmSimd128.is_supported() { return Engine::WasmSimd128; }
+    Engine::Scalar
+}
+
+// 0 = Not yet probed, 1 = Absent, 2 = Present.
+static PCLMUL: AtomicU8 = AtomicU8::new(0);
+
+/// PCLMULQDQ availability (for `clmul::mul_pclmul`). Probed once.
+#[inline(always)]
+pub fn has_pclmul() -> bool {
+    match PCLMUL.load(Ordering::Relaxed) {
+        1 => false,
+        2 => true,
+        _ => {
+            let present = probe_pclmul();
+            PCLMUL.store(if present { 2 } else { 1 }, Ordering::Relaxed);
+            present
+        }
+    }
+}
+
+// --- x86_64 PROBES ---
+// Priority: std runtime detection > raw cpuid (no_std) > compile-time target features.
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn has_avx2() -> bool { std::is_x86_feature_detected!("avx2") }
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn has_avx512bw() -> bool {
+    std::is_x86_feature_detected!("avx512f") && std::is_x86_feature_detected!("avx512bw")
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), feature = "cpuid"))]
+fn has_avx2() -> bool { cpuid::probe().0 }
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), feature = "cpuid"))]
+fn has_avx512bw() -> bool { cpuid::probe().1 }
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), not(feature = "cpuid")))]
+fn has_avx2() -> bool { cfg!(target_feature = "avx2") }
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), not(feature = "cpuid")))]
+fn has_avx512bw() -> bool { cfg!(target_feature = "avx512f") && cfg!(target_feature = "avx512bw") }
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn probe_pclmul() -> bool { std::is_x86_feature_detected!("pclmulqdq") }
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), feature = "cpuid"))]
+fn probe_pclmul() -> bool { cpuid::has_pclmul() }
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), not(feature = "cpuid")))]
+fn probe_pclmul() -> bool { cfg!(target_feature = "pclmulqdq") }
+
+#[cfg(not(target_arch = "x86_64"))]
+fn probe_pclmul() -> bool { false }
+
+#[cfg(not(target_arch = "x86_64"))]
+fn has_avx2() -> bool { false }
+
+#[cfg(not(target_arch = "x86_64"))]
+fn has_avx512bw() -> bool { false }
+
+// --- aarch64 PROBES ---
+
+#[cfg(all(target_arch = "aarch64", feature = "std"))]
+fn has_neon() -> bool { std::arch::is_aarch64_feature_detected!("neon") }
+
+// NEON is baseline on ARMv8-A, but softfloat targets (aarch64-unknown-none-softfloat) drop it.
+#[cfg(all(target_arch = "aarch64", not(feature = "std")))]
+fn has_neon() -> bool { cfg!(target_feature = "neon") }
+
+#[cfg(not(target_arch = "aarch64"))]
+fn has_neon() -> bool { false }
+
+// --- wasm32 PROBES ---
+
+// WASM has no runtime feature detection: A module using v128 fails validation on an engine
+// without it, so simd128 is fixed at build time (`-C target-feature=+simd128`).
+fn has_simd128() -> bool { cfg!(all(target_arch = "wasm32", target_feature = "simd128")) }
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std"), feature = "cpuid"))]
+mod cpuid {
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+
+    const ECX_PCLMULQDQ: u32 = 1 << 1;
+    const ECX_OSXSAVE: u32 = 1 << 27;
+    const ECX_AVX: u32 = 1 << 28;
+    const EBX_AVX2: u32 = 1 << 5;
+    const EBX_AVX512F: u32 = 1 << 16;
+    const EBX_AVX512BW: u32 = 1 << 30;
+    // XCR0: SSE | AVX (YMM) state, plus opmask | ZMM_Hi256 | Hi16_ZMM for AVX-512.
+    const XCR0_YMM: u64 = 0b0000_0110;
+    const XCR0_ZMM: u64 = 0b1110_0110;
+
+    /// (avx2, avx512f+bw). The CPU must advertise the instructions AND the OS must save the registers.
+    #[allow(unused_unsafe)]
+    pub fn probe() -> (bool, bool) {
+        // SAFETY: CPUID is baseline on x86_64; XGETBV only runs once OSXSAVE is confirmed.
+        unsafe {
+            if __cpuid(0).eax < 7 { return (false, false); }
+            let leaf1 = __cpuid(1);
+            if leaf1.ecx & ECX_OSXSAVE == 0 || leaf1.ecx & ECX_AVX == 0 { return (false, false); }
+
+            let xcr0 = _xgetbv(0);
+            let leaf7 = __cpuid_count(7, 0);
+
+            let avx2 = xcr0 & XCR0_YMM == XCR0_YMM && leaf7.ebx & EBX_AVX2 != 0;
+            let avx512 = xcr0 & XCR0_ZMM == XCR0_ZMM
+                && leaf7.ebx & EBX_AVX512F != 0
+                && leaf7.ebx & EBX_AVX512BW != 0;
+            (avx2, avx512)
+        }
+    }
+
+    #[allow(unused_unsafe)]
+    pub fn has_pclmul() -> bool {
+        // SAFETY: CPUID is baseline on x86_64.
+        unsafe { __cpuid(1).ecx & ECX_PCLMULQDQ != 0 }
+    }
+}
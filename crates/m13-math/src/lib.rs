@@ -3,6 +3,8 @@
 #![allow(improper_ctypes_definitions)]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 // --- PRESERVED LEGACY MODULES ---
 pub mod tables; 
@@ -12,6 +14,9 @@ pub use tables::TABLES;
 
 // --- NEW SIMD ARCHITECTURE ---
 pub mod scalar;
+pub mod cpu;
+pub mod clmul;
+pub use cpu::Engine;
 
 #[cfg(target_arch = "x86_64")]
 mod avx2;
@@ -21,8 +26,26 @@ mod avx512;
 #[cfg(target_arch = "aarch64")]
 mod neon;
 
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm;
+
 use zeroize::Zeroize;
 
+/// [AUDIT] Log/exp table lookups made through `GfSymbol::mul`/`inv` since start-up.
+/// Lets tests prove a constant-time path never touches the tables.
+#[cfg(feature = "table-counter")]
+pub static TABLE_LOOKUPS: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+#[cfg(feature = "table-counter")]
+#[inline(always)]
+fn count_table_lookup() {
+    TABLE_LOOKUPS.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "table-counter"))]
+#[inline(always)]
+fn count_table_lookup() {}
+
 // --- GfSymbol Implementation ---
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Zeroize)]
 #[repr(transparent)]
@@ -39,12 +62,20 @@ impl GfSymbol {
     
     #[inline]
     pub fn mul(self, rhs: Self) -> Self {
+        count_table_lookup();
         if self.0 == 0 || rhs.0 == 0 { return Self::ZERO; }
         let idx = (TABLES.log[self.0 as usize] as usize) + (TABLES.log[rhs.0 as usize] as usize);
         Self(TABLES.exp[idx])
     }
 
+    /// Constant-time multiply: PCLMULQDQ when present, bitwise otherwise.
     pub fn mul_safe(self, rhs: Self) -> Self {
+        if let Some(p) = clmul::mul_pclmul(self, rhs) { return p; }
+        self.mul_bitwise(rhs)
+    }
+
+    /// Constant-time multiply: Branch-free shift-and-add, no tables.
+    pub fn mul_bitwise(self, rhs: Self) -> Self {
         let mut p = 0u8;
         let mut a = self.0;
         let mut b = rhs.0;
@@ -59,7 +90,21 @@ impl GfSymbol {
         Self(p)
     }
     
+    /// Constant-time inverse: a^254 over a fixed square-and-multiply chain (`mul_safe` only).
+    /// Zero maps to zero, like `inv`.
+    pub fn inv_safe(self) -> Self {
+        // 254 = 2 + 4 + ... + 128: Multiply in every square a^(2^k), k = 1..7.
+        let mut square = self;
+        let mut acc = Self::ONE;
+        for _ in 1..8 {
+            square = square.mul_safe(square);
+            acc = acc.mul_safe(square);
+        }
+        acc
+    }
+
     pub fn inv(self) -> Self {
+        count_table_lookup();
         if self.0 == 0 { return Self::ZERO; }
         let log_a = TABLES.log[self.0 as usize] as usize;
         let idx = 255 - log_a;
@@ -68,9 +113,16 @@ impl GfSymbol {
 }
 
 // --- THE SIMD DISPATCHER ---
+/// Runtime dispatch: The engine is probed once per process (see `cpu::active_engine`).
 #[inline(always)]
 pub fn row_add_scaled(dest: &mut [u8], src: &[u8], factor: GfSymbol) {
-    if factor.0 == 0 || dest.len() == 0 { return; }
+    row_add_scaled_with(cpu::active_engine(), dest, src, factor);
+}
+
+/// Forced dispatch. Unsupported engines degrade to scalar instead of faulting (SIGILL).
+#[inline(always)]
+pub fn row_add_scaled_with(engine: Engine, dest: &mut [u8], src: &[u8], factor: GfSymbol) {
+    if factor.0 == 0 || dest.is_empty() { return; }
     
     if factor.0 == 1 {
         let len = dest.len().min(src.len());
@@ -78,51 +130,45 @@ pub fn row_add_scaled(dest: &mut [u8], src: &[u8], factor: GfSymbol) {
         return;
     }
 
-    // 1. INTEL / AMD DISPATCH
-    #[cfg(target_arch = "x86_64")]
-    unsafe {
-        if cfg!(target_feature = "avx512f") && cfg!(target_feature = "avx512bw") {
-            avx512::row_add_scaled_avx512(dest, src, factor.0);
-            return;
-        }
-        if cfg!(target_feature = "avx2") {
-            avx2::row_add_scaled_avx2(dest, src, factor.0);
-            return;
-        }
+    if !engine.is_supported() {
+        scalar::row_add_scaled(dest, src, factor);
+        return;
     }
 
-    // 2. APPLE / ARM DISPATCH
-    #[cfg(target_arch = "aarch64")]
-    unsafe {
-        neon::row_add_scaled_neon(dest, src, factor.0);
-        return;
+    match engine {
+        // 1. INTEL / AMD DISPATCH
+        // SAFETY: `is_supported` confirmed the running CPU executes these instructions.
+        #[cfg(target_arch = "x86_64")]
+        Engine::Avx512 => unsafe { avx512::row_add_scaled_avx512(dest, src, factor.0) },
+        #[cfg(target_arch = "x86_64")]
+        Engine::Avx2 => unsafe { avx2::row_add_scaled_avx2(dest, src, factor.0) },
+
+        // 2. APPLE / ARM DISPATCH
+        #[cfg(target_arch = "aarch64")]
+        Engine::Neon => unsafe { neon::row_add_scaled_neon(dest, src, factor.0) },
+
+        // 3. BROWSER / EDGE WASM DISPATCH
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        Engine::WasmSimd128 => unsafe { wasm::row_add_scaled_wasm(dest, src, factor.0) },
+
+        // 4. FALLBACK
+        _ => scalar::row_add_scaled(dest, src, factor),
     }
+}
 
-    // 3. FALLBACK
-    #[cfg(not(target_arch = "aarch64"))]
-    scalar::row_add_scaled(dest, src, factor);
+/// `dest ^= src * factor` over symbol slices, routed through the SIMD dispatcher.
+#[inline(always)]
+pub fn symbols_add_scaled(dest: &mut [GfSymbol], src: &[GfSymbol], factor: GfSymbol) {
+    // SAFETY: GfSymbol is #[repr(transparent)] over u8, so the layouts are identical.
+    let dest_u8 = unsafe { core::slice::from_raw_parts_mut(dest.as_mut_ptr() as *mut u8, dest.len()) };
+    let src_u8 = unsafe { core::slice::from_raw_parts(src.as_ptr() as *const u8, src.len()) };
+    row_add_scaled(dest_u8, src_u8, factor);
 }
 
 // --- PHYSICS REPORTING (HONESTY PROTOCOL) ---
+/// Reports the engine the running CPU actually uses, not the build target's baseline.
 pub fn get_active_engine() -> &'static str {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if cfg!(target_feature = "avx512f") && cfg!(target_feature = "avx512bw") {
-            return "AVX-512BW (ZEN4/ICELAKE) [64B/CYCLE]";
-        }
-        if cfg!(target_feature = "avx2") {
-            return "AVX2 (TITAN/MODERN) [32B/CYCLE]";
-        }
-    }
-
-    #[cfg(target_arch = "aarch64")]
-    {
-        if cfg!(target_feature = "neon") {
-             return "NEON (APPLE/ARM) [16B/CYCLE]";
-        }
-    }
-
-    "SCALAR (FALLBACK) [1B/CYCLE]"
+    cpu::active_engine().label()
 }
 
 // Operator Overloads
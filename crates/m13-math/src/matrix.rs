@@ -32,6 +32,51 @@ impl GfMatrix {
         }
     }
 
+    /// Contiguous view of row `r`.
+    pub fn row(&self, r: usize) -> Option<&[GfSymbol]> {
+        if r >= self.rows { return None; }
+        Some(&self.data[r * self.cols..(r + 1) * self.cols])
+    }
+
+    /// Row elimination: `row[dest] -= factor * row[src]` (SIMD via `row_add_scaled`).
+    /// In GF(2^8) subtraction is XOR, so this is the same kernel as addition.
+    pub fn row_add_scaled_inplace(&mut self, dest_row: usize, src_row: usize, factor: GfSymbol) -> M13Result<()> {
+        if dest_row >= self.rows || src_row >= self.rows || dest_row == src_row {
+            return Err(M13Error::InvalidState);
+        }
+        if factor == GfSymbol::ZERO { return Ok(()); }
+
+        let cols = self.cols;
+        // Disjoint borrows of the two rows.
+        let (dest, src) = if dest_row < src_row {
+            let (lo, hi) = self.data.split_at_mut(src_row * cols);
+            (&mut lo[dest_row * cols..(dest_row + 1) * cols], &hi[..cols])
+        } else {
+            let (lo, hi) = self.data.split_at_mut(dest_row * cols);
+            (&mut hi[..cols], &lo[src_row * cols..(src_row + 1) * cols])
+        };
+        crate::symbols_add_scaled(dest, src, factor);
+        Ok(())
+    }
+
+    /// Matrix-Matrix Multiplication (C = A * B)
+    /// Row-oriented: C[i] = SUM_k A[i][k] * B[k], each term a SIMD row op.
+    pub fn mul(&self, rhs: &GfMatrix) -> M13Result<GfMatrix> {
+        if self.cols != rhs.rows {
+            return Err(M13Error::InvalidState); // Dimension mismatch
+        }
+
+        let mut out = GfMatrix::new(self.rows, rhs.cols);
+        if rhs.cols == 0 { return Ok(out); }
+
+        for (a_row, c_row) in self.data.chunks_exact(self.cols.max(1)).zip(out.data.chunks_exact_mut(rhs.cols)) {
+            for (&coeff, b_row) in a_row.iter().zip(rhs.data.chunks_exact(rhs.cols)) {
+                crate::symbols_add_scaled(c_row, b_row, coeff);
+            }
+        }
+        Ok(out)
+    }
+
     /// Matrix-Vector Multiplication (Y = A * X)
     /// No unwraps. Returns Error on mismatch.
     pub fn mul_vec(&self, x: &[GfSymbol]) -> M13Result<Vec<GfSymbol>> {
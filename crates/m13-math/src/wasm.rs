@@ -0,0 +1,52 @@
+#[cfg(target_arch = "wasm32")]
+use core::arch::wasm32::*;
+use crate::scalar;
+
+#[cfg(target_arch = "wasm32")]
+#[target_feature(enable = "simd128")]
+pub unsafe fn row_add_scaled_wasm(dest: &mut [u8], src: &[u8], factor: u8) {
+    let len = dest.len().min(src.len());
+    let mut i = 0;
+
+    // 1. PRE-COMPUTE SHUFFLE TABLES (16 Bytes)
+    let mut low_arr = [0u8; 16];
+    let mut high_arr = [0u8; 16];
+
+    for j in 0..16u8 {
+        low_arr[j as usize] = scalar::mul_gf8(j, factor);
+        high_arr[j as usize] = scalar::mul_gf8(j << 4, factor);
+    }
+
+    let tbl_lo = v128_load(low_arr.as_ptr() as *const v128);
+    let tbl_hi = v128_load(high_arr.as_ptr() as *const v128);
+    let mask = u8x16_splat(0x0F);
+
+    // 2. VECTOR LOOP (16 Bytes/Cycle)
+    while i + 16 <= len {
+        let s_ptr = src.as_ptr().add(i) as *const v128;
+        let d_ptr = dest.as_mut_ptr().add(i) as *mut v128;
+
+        // v128_load/store are unaligned: Slices carry no 16-byte alignment.
+        let v_src = v128_load(s_ptr);
+        let v_dest = v128_load(d_ptr);
+
+        let lo = v128_and(v_src, mask);
+        let hi = u8x16_shr(v_src, 4);
+
+        // Nibbles are 0..=15, so the swizzle never hits its out-of-range zeroing.
+        let res_lo = i8x16_swizzle(tbl_lo, lo);
+        let res_hi = i8x16_swizzle(tbl_hi, hi);
+
+        let product = v128_xor(res_lo, res_hi);
+        let result = v128_xor(v_dest, product);
+
+        v128_store(d_ptr, result);
+        i += 16;
+    }
+
+    // 3. SCALAR TAIL
+    if i < len {
+        let f_sym = crate::GfSymbol(factor);
+        scalar::row_add_scaled(&mut dest[i..], &src[i..], f_sym);
+    }
+}
@@ -0,0 +1,26 @@
+use m13_math::clmul::mul_pclmul;
+use m13_math::GfSymbol;
+
+#[test]
+fn test_pclmul_matches_mul_safe_exhaustive() {
+    if mul_pclmul(GfSymbol(1), GfSymbol(1)).is_none() {
+        println!("[SKIP] PCLMULQDQ unavailable on this CPU");
+        return;
+    }
+    for a in 0..=255u8 {
+        for b in 0..=255u8 {
+            let clmul = mul_pclmul(GfSymbol(a), GfSymbol(b)).unwrap();
+            let bitwise = GfSymbol(a).mul_bitwise(GfSymbol(b));
+            assert_eq!(clmul, bitwise, "{:#04x} * {:#04x}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_mul_safe_matches_table_mul_exhaustive() {
+    for a in 0..=255u8 {
+        for b in 0..=255u8 {
+            assert_eq!(GfSymbol(a).mul_safe(GfSymbol(b)), GfSymbol(a) * GfSymbol(b), "{:#04x} * {:#04x}", a, b);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use m13_math::{row_add_scaled, row_add_scaled_with, scalar, Engine, GfSymbol};
+
+/// Lengths straddle every vector width (16/32/64) plus ragged tails.
+const LENGTHS: [usize; 8] = [1, 15, 16, 31, 33, 64, 127, 1500];
+
+fn pattern(len: usize, mul: u32, add: u32) -> Vec<u8> {
+    (0..len as u32).map(|i| (i.wrapping_mul(mul).wrapping_add(add)) as u8).collect()
+}
+
+#[test]
+fn test_every_engine_matches_scalar() {
+    for engine in Engine::ALL {
+        for &len in &LENGTHS {
+            for factor in [2u8, 3, 0x1B, 0x53, 0x80, 0xFF] {
+                let src = pattern(len, 167, 13);
+                let mut reference = pattern(len, 31, 7);
+                let mut forced = reference.clone();
+
+                scalar::row_add_scaled(&mut reference, &src, GfSymbol(factor));
+                row_add_scaled_with(engine, &mut forced, &src, GfSymbol(factor));
+
+                assert_eq!(forced, reference, "{:?} diverged (len {}, factor {:#04x})", engine, len, factor);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_active_engine_is_supported() {
+    let engine = m13_math::cpu::active_engine();
+    assert!(engine.is_supported());
+    assert_eq!(m13_math::get_active_engine(), engine.label());
+
+    // Cached result is stable.
+    assert_eq!(m13_math::cpu::active_engine(), engine);
+
+    let src = pattern(257, 89, 1);
+    let mut a = pattern(257, 5, 3);
+    let mut b = a.clone();
+    row_add_scaled(&mut a, &src, GfSymbol(0x57));
+    scalar::row_add_scaled(&mut b, &src, GfSymbol(0x57));
+    assert_eq!(a, b);
+}
@@ -0,0 +1,83 @@
+use m13_math::{GfMatrix, GfSymbol};
+
+const N: usize = 256;
+const SYMBOL: usize = 256;
+
+/// Deterministic LCG fill (no rand dependency in m13-math).
+/// Not xorshift: its output is GF(2)-linear in the seed, which makes large matrices singular.
+fn fill(m: &mut GfMatrix, mut state: u32) {
+    for v in m.data.iter_mut() {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        *v = GfSymbol((state >> 24) as u8);
+    }
+}
+
+/// Gauss-Jordan over [A | B]. `simd` selects the row-op kernel.
+fn solve(mut a: GfMatrix, mut b: GfMatrix, simd: bool) -> Option<GfMatrix> {
+    let n = a.rows;
+    for col in 0..n {
+        let pivot = (col..n).find(|&r| a.get(r, col) != Some(GfSymbol::ZERO))?;
+        for c in 0..a.cols {
+            let t = a.get(col, c).unwrap();
+            a.set(col, c, a.get(pivot, c).unwrap());
+            a.set(pivot, c, t);
+        }
+        for c in 0..b.cols {
+            let t = b.get(col, c).unwrap();
+            b.set(col, c, b.get(pivot, c).unwrap());
+            b.set(pivot, c, t);
+        }
+
+        let inv = a.get(col, col).unwrap().inv();
+        for c in 0..a.cols { a.set(col, c, a.get(col, c).unwrap() * inv); }
+        for c in 0..b.cols { b.set(col, c, b.get(col, c).unwrap() * inv); }
+
+        for r in 0..n {
+            if r == col { continue; }
+            let factor = a.get(r, col).unwrap();
+            if factor == GfSymbol::ZERO { continue; }
+            if simd {
+                a.row_add_scaled_inplace(r, col, factor).unwrap();
+                b.row_add_scaled_inplace(r, col, factor).unwrap();
+            } else {
+                // Legacy scalar path.
+                for c in 0..a.cols {
+                    let v = a.get(r, c).unwrap() - (factor * a.get(col, c).unwrap());
+                    a.set(r, c, v);
+                }
+                for c in 0..b.cols {
+                    let v = b.get(r, c).unwrap() - (factor * b.get(col, c).unwrap());
+                    b.set(r, c, v);
+                }
+            }
+        }
+    }
+    Some(b)
+}
+
+#[test]
+fn test_simd_elimination_matches_scalar_256() {
+    let mut a = GfMatrix::new(N, N);
+    let mut b = GfMatrix::new(N, SYMBOL);
+    fill(&mut a, 0x1313_0001);
+    fill(&mut b, 0xDEAD_BEEF);
+
+    let scalar = solve(a.clone(), b.clone(), false).expect("singular test matrix");
+    let simd = solve(a, b, true).expect("singular test matrix");
+    assert_eq!(scalar.data, simd.data);
+}
+
+#[test]
+fn test_row_add_scaled_inplace_bounds() {
+    let mut m = GfMatrix::new(2, 4);
+    assert!(m.row_add_scaled_inplace(0, 0, GfSymbol(3)).is_err());
+    assert!(m.row_add_scaled_inplace(2, 0, GfSymbol(3)).is_err());
+
+    for c in 0..4 { m.set(1, c, GfSymbol(c as u8 + 1)); }
+    m.row_add_scaled_inplace(0, 1, GfSymbol::ONE).unwrap();
+    assert_eq!(m.row(0).unwrap(), m.row(1).unwrap());
+
+    // Eliminating a row against itself-equal row clears it.
+    m.row_add_scaled_inplace(0, 1, GfSymbol::ONE).unwrap();
+    assert!(m.row(0).unwrap().iter().all(|&s| s == GfSymbol::ZERO));
+}
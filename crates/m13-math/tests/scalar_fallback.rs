@@ -0,0 +1,47 @@
+use m13_math::{row_add_scaled_with, scalar, Engine, GfSymbol};
+
+/// Bitwise reference (no tables, no SIMD).
+fn reference(dest: &[u8], src: &[u8], factor: u8) -> Vec<u8> {
+    dest.iter().zip(src).map(|(&d, &s)| d ^ scalar::mul_gf8(s, factor)).collect()
+}
+
+fn inputs(len: usize) -> (Vec<u8>, Vec<u8>) {
+    let dest = (0..len).map(|i| (i * 13 + 5) as u8).collect();
+    let src = (0..len).map(|i| (i * 101 + 77) as u8).collect();
+    (dest, src)
+}
+
+#[test]
+fn test_scalar_engine_all_factors() {
+    let (dest, src) = inputs(67);
+    for factor in 0..=255u8 {
+        let mut out = dest.clone();
+        row_add_scaled_with(Engine::Scalar, &mut out, &src, GfSymbol(factor));
+        assert_eq!(out, reference(&dest, &src, factor), "factor {:#04x}", factor);
+    }
+}
+
+#[test]
+fn test_unsupported_engine_is_not_a_noop() {
+    // Every build target lacks at least one engine (x86 has no NEON, ARM no AVX).
+    let missing = Engine::ALL.into_iter().find(|e| !e.is_supported()).expect("no unsupported engine");
+    let (dest, src) = inputs(200);
+
+    let mut out = dest.clone();
+    row_add_scaled_with(missing, &mut out, &src, GfSymbol(0x8E));
+    assert_ne!(out, dest, "{:?} silently skipped the row op", missing);
+    assert_eq!(out, reference(&dest, &src, 0x8E));
+}
+
+/// Baseline x86-64 build with every target feature off (`.cargo/config.toml` defaults to native):
+/// `RUSTFLAGS="-C target-cpu=x86-64" cargo test -p m13-math --no-default-features --test scalar_fallback`.
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx2")))]
+#[test]
+fn test_baseline_x86_dispatch_correct() {
+    for len in [1usize, 31, 32, 63, 64, 65, 1500] {
+        let (dest, src) = inputs(len);
+        let mut out = dest.clone();
+        m13_math::row_add_scaled(&mut out, &src, GfSymbol(0x35));
+        assert_eq!(out, reference(&dest, &src, 0x35), "len {}", len);
+    }
+}
@@ -0,0 +1,44 @@
+use m13_math::{row_add_scaled_with, scalar, Engine, GfSymbol};
+
+// The v128 kernel only runs on a SIMD build, e.g.
+// `RUSTFLAGS="-C target-feature=+simd128" cargo test -p m13-math --target wasm32-wasip1 --test wasm_simd`
+// (with a WASI runner such as wasmtime). Elsewhere the engine degrades to scalar.
+
+/// Deterministic byte stream: Same inputs on every target.
+struct XorShift(u64);
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+#[test]
+fn test_wasm_engine_matches_scalar_on_random_inputs() {
+    let mut rng = XorShift(0x4D13_5EED);
+    for _ in 0..500 {
+        let len = (rng.next() % 1600) as usize + 1;
+        let factor = GfSymbol(rng.next() as u8);
+        let src = rng.bytes(len);
+        let mut reference = rng.bytes(len);
+        let mut forced = reference.clone();
+
+        scalar::row_add_scaled(&mut reference, &src, factor);
+        row_add_scaled_with(Engine::WasmSimd128, &mut forced, &src, factor);
+
+        assert_eq!(forced, reference, "len {}, factor {:#04x}", len, factor.0);
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#[test]
+fn test_wasm_simd_is_active() {
+    assert!(Engine::WasmSimd128.is_supported());
+    assert_eq!(m13_math::cpu::active_engine(), Engine::WasmSimd128);
+    assert!(m13_math::get_active_engine().starts_with("WASM-SIMD128"));
+}
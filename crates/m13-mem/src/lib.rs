@@ -8,79 +8,293 @@ use alloc::sync::Arc;
 use spin::Mutex;
 use zeroize::Zeroize;
 use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 // [PHYSICS] 10KB Frame covers Jumbo Frames + Headers
 pub const FRAME_SIZE: usize = 10240;
+// [CLASSES] Control traffic (header + KeepAlive/ACK) and one MTU-sized datagram.
+pub const SMALL_FRAME_SIZE: usize = 256;
+pub const MTU_FRAME_SIZE: usize = 1536;
 
 // [PHYSICS] Force 64-byte alignment to match CPU Cache Lines.
 // Prevents "Split Loads" where a header read spans two memory fetches.
 #[repr(C, align(64))]
 #[derive(Zeroize)]
 pub struct Frame {
-    pub data: [u8; FRAME_SIZE],
+    pub data: Box<[u8]>,
     pub len: usize,
 }
 
+impl Frame {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: alloc::vec![0u8; capacity].into_boxed_slice(),
+            len: 0,
+        }
+    }
+
+    /// Size class of this frame (bytes of `data`).
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+}
+
 impl Default for Frame {
     fn default() -> Self {
+        Self::with_capacity(FRAME_SIZE)
+    }
+}
+
+/// One size class of a `SlabAllocator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlabClass {
+    pub frame_size: usize,
+    pub initial: usize,
+    /// Hard ceiling (clamped to at least `initial`).
+    pub max: usize,
+    /// Frames added per growth step. 0 = Fixed pool.
+    pub growth: usize,
+}
+
+impl SlabClass {
+    pub fn fixed(frame_size: usize, capacity: usize) -> Self {
+        Self { frame_size, initial: capacity, max: capacity, growth: 0 }
+    }
+}
+
+struct ClassPool {
+    frame_size: usize,
+    free: Mutex<Vec<Frame>>,
+    // [ELASTIC] Frames owned (free + leased). Only mutated under the `free` lock.
+    total: AtomicUsize,
+    max: usize,
+    growth: usize,
+}
+
+impl ClassPool {
+    fn new(class: &SlabClass) -> Self {
+        let mut free = Vec::with_capacity(class.initial);
+        for _ in 0..class.initial {
+            free.push(Self::prefaulted_frame(class.frame_size));
+        }
         Self {
-            data: [0u8; FRAME_SIZE],
-            len: 0,
+            frame_size: class.frame_size,
+            free: Mutex::new(free),
+            total: AtomicUsize::new(class.initial),
+            max: core::cmp::max(class.initial, class.max),
+            growth: class.growth,
         }
     }
+
+    fn prefaulted_frame(frame_size: usize) -> Frame {
+        let mut frame = Frame::with_capacity(frame_size);
+        if frame_size == 0 { return frame; }
+        
+        // [PHYSICS] Pre-Faulting (Safe Mode)
+        // We read-modify-write the start and end of the frame to force 
+        // the OS MMU to assign physical RAM pages immediately (Dirty Bit).
+        // We use core::hint::black_box to prevent the compiler from 
+        // optimizing this away as "Dead Store", achieving the Physics 
+        // result without violating the Safety contract.
+        
+        let start_idx = 0;
+        let end_idx = frame_size - 1;
+
+        // Force Load -> Obfuscate -> Store
+        frame.data[start_idx] = core::hint::black_box(frame.data[start_idx]);
+        frame.data[end_idx] = core::hint::black_box(frame.data[end_idx]);
+
+        frame
+    }
+
+    fn take(&self) -> Option<Frame> {
+        let mut free = self.free.lock();
+        if free.is_empty() {
+            self.grow(&mut free);
+        }
+        free.pop()
+    }
+
+    /// Up to `n` frames under a single lock acquisition.
+    fn take_many(&self, n: usize, out: &mut Vec<Frame>) {
+        let mut free = self.free.lock();
+        while free.len() < n {
+            let before = free.len();
+            self.grow(&mut free);
+            if free.len() == before { break; }
+        }
+        let k = core::cmp::min(n, free.len());
+        let split = free.len() - k;
+        out.extend(free.drain(split..));
+    }
+
+    // [ELASTIC] Caller holds the `free` lock.
+    fn grow(&self, free: &mut Vec<Frame>) {
+        let total = self.total.load(Ordering::Relaxed);
+        let step = core::cmp::min(self.growth, self.max - total);
+        for _ in 0..step {
+            free.push(Self::prefaulted_frame(self.frame_size));
+        }
+        self.total.store(total + step, Ordering::Relaxed);
+    }
 }
 
 pub struct SlabAllocator {
-    pool: Mutex<Vec<Box<Frame>>>,
+    // Ascending `frame_size`.
+    classes: Vec<ClassPool>,
+    // [STATS] Lock-free counters for capacity planning.
+    allocated_total: AtomicU64,
+    in_use: AtomicUsize,
+    high_water_mark: AtomicUsize,
+    alloc_failures: AtomicU64,
+}
+
+/// Snapshot of pool utilization (see `SlabAllocator::stats`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SlabStats {
+    /// Successful `alloc()` calls since creation.
+    pub allocated_total: u64,
+    /// Frames currently leased out.
+    pub in_use_current: usize,
+    /// Peak of `in_use_current`.
+    pub high_water_mark: usize,
+    /// `alloc()` calls that found the pool empty (and, if elastic, at its ceiling).
+    pub alloc_failures: u64,
 }
 
 pub struct FrameLease {
-    frame: Option<Box<Frame>>,
+    frame: Option<Frame>,
     allocator: Arc<SlabAllocator>,
+    // false = Non-sensitive contents: Skip the drop-time zeroize (see `alloc_insecure`).
+    zeroize_on_drop: bool,
 }
 
 impl SlabAllocator {
+    /// Single class of `FRAME_SIZE` frames.
     pub fn new(capacity: usize) -> Arc<Self> {
-        let mut pool = Vec::with_capacity(capacity);
-        for _ in 0..capacity {
-            let mut frame = Box::new(Frame::default());
-            
-            // [PHYSICS] Pre-Faulting (Safe Mode)
-            // We read-modify-write the start and end of the frame to force 
-            // the OS MMU to assign physical RAM pages immediately (Dirty Bit).
-            // We use core::hint::black_box to prevent the compiler from 
-            // optimizing this away as "Dead Store", achieving the Physics 
-            // result without violating the Safety contract.
-            
-            let start_idx = 0;
-            let end_idx = FRAME_SIZE - 1;
-
-            // Force Load -> Obfuscate -> Store
-            frame.data[start_idx] = core::hint::black_box(frame.data[start_idx]);
-            frame.data[end_idx] = core::hint::black_box(frame.data[end_idx]);
-
-            pool.push(frame);
-        }
-        Arc::new(Self { pool: Mutex::new(pool) })
+        Self::new_elastic(capacity, capacity, 0)
     }
 
+    /// Elastic pool: Starts with `initial` frames and, when empty, grows by `growth`
+    /// (pre-faulted) frames per step, never beyond `max`. Frames are not returned to the OS.
+    pub fn new_elastic(initial: usize, max: usize, growth: usize) -> Arc<Self> {
+        Self::with_classes(&[SlabClass { frame_size: FRAME_SIZE, initial, max, growth }])
+    }
+
+    /// One pool per size class, e.g. `SMALL_FRAME_SIZE` / `MTU_FRAME_SIZE` / `FRAME_SIZE`.
+    pub fn with_classes(classes: &[SlabClass]) -> Arc<Self> {
+        let mut classes: Vec<ClassPool> = classes.iter().map(ClassPool::new).collect();
+        classes.sort_by_key(|c| c.frame_size);
+        Arc::new(Self {
+            classes,
+            allocated_total: AtomicU64::new(0),
+            in_use: AtomicUsize::new(0),
+            high_water_mark: AtomicUsize::new(0),
+            alloc_failures: AtomicU64::new(0),
+        })
+    }
+
+    /// Largest class (RX buffers, GSO, Fountain symbols).
     pub fn alloc(self: &Arc<Self>) -> Option<FrameLease> {
-        let mut pool = self.pool.lock();
-        if let Some(mut frame) = pool.pop() {
+        self.alloc_sized(self.max_frame_size())
+    }
+
+    /// Smallest class holding at least `min_len` bytes. If that class is exhausted,
+    /// the next larger one is used rather than failing.
+    pub fn alloc_sized(self: &Arc<Self>, min_len: usize) -> Option<FrameLease> {
+        let frame = self.classes.iter()
+            .filter(|c| c.frame_size >= min_len)
+            .find_map(|c| c.take());
+
+        if let Some(mut frame) = frame {
             frame.len = 0;
-            Some(FrameLease { frame: Some(frame), allocator: self.clone() })
+            self.record_alloc(1);
+            Some(FrameLease { frame: Some(frame), allocator: self.clone(), zeroize_on_drop: true })
         } else {
+            self.alloc_failures.fetch_add(1, Ordering::Relaxed);
             None
         }
     }
 
-    fn release(&self, frame: Box<Frame>) {
-        let mut pool = self.pool.lock();
-        pool.push(frame);
+    /// As `alloc`, but the frame is NOT zeroized on drop. Only for data that is not secret
+    /// (e.g. plaintext already bound for the TUN device). The next lease may see stale bytes.
+    pub fn alloc_insecure(self: &Arc<Self>) -> Option<FrameLease> {
+        let mut lease = self.alloc()?;
+        lease.zeroize_on_drop = false;
+        Some(lease)
+    }
+
+    /// Append up to `n` leases of the largest class to `out`, taking the pool lock once
+    /// (RX batch refill). Returns how many were appended; a short batch counts as one failure.
+    pub fn alloc_batch(self: &Arc<Self>, n: usize, out: &mut Vec<FrameLease>) -> usize {
+        let class = match self.classes.last() {
+            Some(c) => c,
+            None => return 0,
+        };
+        let mut frames = Vec::with_capacity(n);
+        class.take_many(n, &mut frames);
+
+        let got = frames.len();
+        if got < n {
+            self.alloc_failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.record_alloc(got);
+        out.reserve(got);
+        for mut frame in frames {
+            frame.len = 0;
+            out.push(FrameLease { frame: Some(frame), allocator: self.clone(), zeroize_on_drop: true });
+        }
+        got
+    }
+
+    fn record_alloc(&self, n: usize) {
+        if n == 0 { return; }
+        self.allocated_total.fetch_add(n as u64, Ordering::Relaxed);
+        let in_use = self.in_use.fetch_add(n, Ordering::Relaxed) + n;
+        self.high_water_mark.fetch_max(in_use, Ordering::Relaxed);
+    }
+
+    fn release(&self, frame: Frame) {
+        if let Some(class) = self.classes.iter().find(|c| c.frame_size == frame.capacity()) {
+            class.free.lock().push(frame);
+        }
+        self.in_use.fetch_sub(1, Ordering::Relaxed);
     }
     
     pub fn available(&self) -> usize {
-        self.pool.lock().len()
+        self.classes.iter().map(|c| c.free.lock().len()).sum()
+    }
+
+    /// Free frames of exactly `frame_size` bytes.
+    pub fn available_sized(&self, frame_size: usize) -> usize {
+        self.classes.iter()
+            .filter(|c| c.frame_size == frame_size)
+            .map(|c| c.free.lock().len())
+            .sum()
+    }
+
+    /// Frames currently owned by the pool (free + leased).
+    pub fn capacity(&self) -> usize {
+        self.classes.iter().map(|c| c.total.load(Ordering::Relaxed)).sum()
+    }
+
+    pub fn stats(&self) -> SlabStats {
+        SlabStats {
+            allocated_total: self.allocated_total.load(Ordering::Relaxed),
+            in_use_current: self.in_use.load(Ordering::Relaxed),
+            high_water_mark: self.high_water_mark.load(Ordering::Relaxed),
+            alloc_failures: self.alloc_failures.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Hard ceiling on `capacity`.
+    pub fn max_capacity(&self) -> usize {
+        self.classes.iter().map(|c| c.max).sum()
+    }
+
+    /// Size of the largest class (0 if there are none).
+    pub fn max_frame_size(&self) -> usize {
+        self.classes.last().map_or(0, |c| c.frame_size)
     }
 }
 
@@ -93,10 +307,19 @@ impl DerefMut for FrameLease {
     fn deref_mut(&mut self) -> &mut Self::Target { self.frame.as_mut().unwrap() }
 }
 
+impl FrameLease {
+    /// True if the frame is wiped when the lease is dropped.
+    pub fn zeroizes_on_drop(&self) -> bool {
+        self.zeroize_on_drop
+    }
+}
+
 impl Drop for FrameLease {
     fn drop(&mut self) {
         if let Some(mut frame) = self.frame.take() {
-            frame.zeroize();
+            if self.zeroize_on_drop {
+                frame.zeroize();
+            }
             self.allocator.release(frame);
         }
     }
@@ -1,4 +1,4 @@
-use m13_mem::SlabAllocator;
+use m13_mem::{SlabAllocator, SlabClass, SlabStats, FRAME_SIZE, MTU_FRAME_SIZE, SMALL_FRAME_SIZE};
 
 #[test]
 fn test_alloc_reuse() {
@@ -30,4 +30,143 @@ fn test_exhaustion() {
     
     // Pool empty
     assert!(slab.alloc().is_none());
-}
\ No newline at end of file
+}
+#[test]
+fn test_elastic_growth_caps_at_max() {
+    let slab = SlabAllocator::new_elastic(2, 7, 3);
+    assert_eq!(slab.capacity(), 2);
+
+    let mut leases = Vec::new();
+    leases.push(slab.alloc().unwrap());
+    leases.push(slab.alloc().unwrap());
+    assert_eq!(slab.available(), 0);
+
+    // Initial pool exhausted: Grow by 3.
+    leases.push(slab.alloc().unwrap());
+    assert_eq!(slab.capacity(), 5);
+    assert_eq!(slab.available(), 2);
+    leases.push(slab.alloc().unwrap());
+    leases.push(slab.alloc().unwrap());
+
+    // Final step is clamped to the ceiling (5 + 2 = 7).
+    leases.push(slab.alloc().unwrap());
+    leases.push(slab.alloc().unwrap());
+    assert_eq!(slab.capacity(), 7);
+    assert!(slab.alloc().is_none());
+    assert_eq!(slab.capacity(), 7);
+
+    // Released frames stay pooled; hygiene holds for grown frames too.
+    leases[6].data[0] = 0xFF;
+    leases.clear();
+    assert_eq!(slab.available(), 7);
+    assert_eq!(slab.alloc().unwrap().data[0], 0x00);
+}
+
+#[test]
+fn test_stats_high_water_mark() {
+    let slab = SlabAllocator::new(4);
+    assert_eq!(slab.stats(), SlabStats::default());
+
+    {
+        let _a = slab.alloc().unwrap();
+        let _b = slab.alloc().unwrap();
+        let _c = slab.alloc().unwrap();
+        assert_eq!(slab.stats().in_use_current, 3);
+    }
+    let l1 = slab.alloc().unwrap();
+    let l2 = slab.alloc().unwrap();
+    let stats = slab.stats();
+    assert_eq!(stats.allocated_total, 5);
+    assert_eq!(stats.in_use_current, 2);
+    assert_eq!(stats.high_water_mark, 3);
+    assert_eq!(stats.alloc_failures, 0);
+
+    // Exhaust: Peak rises to capacity, the overflow is counted.
+    let _rest: Vec<_> = (0..2).map(|_| slab.alloc().unwrap()).collect();
+    assert!(slab.alloc().is_none());
+    assert!(slab.alloc().is_none());
+    drop((l1, l2));
+    let stats = slab.stats();
+    assert_eq!(stats.allocated_total, 7);
+    assert_eq!(stats.in_use_current, 2);
+    assert_eq!(stats.high_water_mark, 4);
+    assert_eq!(stats.alloc_failures, 2);
+}
+
+#[test]
+fn test_size_classes() {
+    let slab = SlabAllocator::with_classes(&[
+        SlabClass::fixed(FRAME_SIZE, 2),
+        SlabClass::fixed(SMALL_FRAME_SIZE, 2),
+        SlabClass::fixed(MTU_FRAME_SIZE, 2),
+    ]);
+    assert_eq!(slab.capacity(), 6);
+
+    let control = slab.alloc_sized(32).unwrap();
+    assert_eq!(control.capacity(), SMALL_FRAME_SIZE);
+    let jumbo = slab.alloc_sized(9000).unwrap();
+    assert_eq!(jumbo.capacity(), FRAME_SIZE);
+    assert_eq!(slab.alloc_sized(1500).unwrap().capacity(), MTU_FRAME_SIZE);
+    // Plain alloc() is the largest class.
+    assert_eq!(slab.alloc().unwrap().capacity(), FRAME_SIZE);
+
+    // Small class exhausted: Spill into the next class up.
+    let _small = slab.alloc_sized(32).unwrap();
+    assert_eq!(slab.alloc_sized(32).unwrap().capacity(), MTU_FRAME_SIZE);
+    assert!(slab.alloc_sized(FRAME_SIZE + 1).is_none());
+
+    // Leases return to their own class.
+    drop((control, jumbo));
+    assert_eq!(slab.available_sized(SMALL_FRAME_SIZE), 1);
+    assert_eq!(slab.available_sized(FRAME_SIZE), 2);
+}
+
+#[test]
+fn test_alloc_batch_larger_than_pool() {
+    let slab = SlabAllocator::new(5);
+    let mut batch = Vec::new();
+
+    assert_eq!(slab.alloc_batch(3, &mut batch), 3);
+    assert_eq!(slab.alloc_batch(64, &mut batch), 2);
+    assert_eq!(batch.len(), 5);
+    assert_eq!(slab.available(), 0);
+    assert_eq!(slab.alloc_batch(8, &mut batch), 0);
+
+    let stats = slab.stats();
+    assert_eq!(stats.allocated_total, 5);
+    assert_eq!(stats.in_use_current, 5);
+    assert_eq!(stats.alloc_failures, 2);
+
+    batch.truncate(1);
+    assert_eq!(slab.available(), 4);
+
+    // Elastic pools grow to satisfy the batch, up to the ceiling.
+    let elastic = SlabAllocator::new_elastic(1, 10, 4);
+    let mut batch = Vec::new();
+    assert_eq!(elastic.alloc_batch(7, &mut batch), 7);
+    assert_eq!(elastic.capacity(), 9);
+    assert_eq!(elastic.alloc_batch(7, &mut batch), 3);
+    assert_eq!(elastic.capacity(), 10);
+}
+
+#[test]
+fn test_insecure_lease_skips_zeroize() {
+    let slab = SlabAllocator::new(1);
+
+    {
+        let mut lease = slab.alloc_insecure().unwrap();
+        assert!(!lease.zeroizes_on_drop());
+        lease.data[0] = 0xAB;
+        lease.data[FRAME_SIZE - 1] = 0xCD;
+    }
+    let mut lease = slab.alloc().unwrap();
+    assert!(lease.zeroizes_on_drop());
+    assert_eq!(lease.len, 0);
+    assert_eq!((lease.data[0], lease.data[FRAME_SIZE - 1]), (0xAB, 0xCD), "Insecure path paid for a wipe");
+
+    // Secure path wipes the stale bytes.
+    lease.data[1] = 0xEF;
+    drop(lease);
+    let lease = slab.alloc().unwrap();
+    assert!(lease.data.iter().all(|&b| b == 0), "Data Remanence Detected!");
+}
@@ -1,27 +1,142 @@
 #![no_std]
+extern crate alloc;
+use alloc::vec::Vec;
 
-use m13_core::{M13Error, M13Result};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use m13_core::{M13Error, M13Result, KYBER_CT_LEN_768, KYBER_CT_LEN_1024, KYBER_PK_LEN_768, KYBER_PK_LEN_1024};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 use rand_core::{RngCore, CryptoRng};
-use fips203::{ml_kem_1024, traits::{KeyGen, SerDes, Decaps, Encaps}};
+use fips203::{ml_kem_768, ml_kem_1024, traits::{KeyGen, SerDes, Decaps, Encaps}};
 use fips204::{ml_dsa_87, traits::{KeyGen as SignKeyGen, SerDes as SignSerDes, Signer, Verifier}};
+use hkdf::Hkdf;
+use sha2::Sha256;
 
 pub const KYBER_PUBLIC_KEY_SIZE: usize = ml_kem_1024::EK_LEN;
 pub const KYBER_CIPHERTEXT_SIZE: usize = ml_kem_1024::CT_LEN;
 pub const DILITHIUM_SIGNATURE_SIZE: usize = ml_dsa_87::SIG_LEN;
+pub const X25519_KEY_SIZE: usize = 32;
+
+/// HKDF domain separators for the hybrid session key, one per ML-KEM profile.
+const HYBRID_KDF_INFO_768: &[u8] = b"M13-HYBRID-X25519-MLKEM768-v1";
+const HYBRID_KDF_INFO_1024: &[u8] = b"M13-HYBRID-X25519-MLKEM1024-v1";
+
+// The wire lengths live in m13-core; they must match the parameter sets behind them.
+const _: () = assert!(KYBER_PK_LEN_768 == ml_kem_768::EK_LEN && KYBER_CT_LEN_768 == ml_kem_768::CT_LEN);
+const _: () = assert!(KYBER_PK_LEN_1024 == ml_kem_1024::EK_LEN && KYBER_CT_LEN_1024 == ml_kem_1024::CT_LEN);
 
 pub type KyberKeypair = KemKeypair;
 
+/// ML-KEM parameter set. 1024 is the default; 768 trims ~800 bytes off each handshake leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KemProfile {
+    MlKem768,
+    #[default]
+    MlKem1024,
+}
+
+impl KemProfile {
+    pub const fn public_key_len(self) -> usize {
+        match self {
+            KemProfile::MlKem768 => KYBER_PK_LEN_768,
+            KemProfile::MlKem1024 => KYBER_PK_LEN_1024,
+        }
+    }
+
+    pub const fn secret_key_len(self) -> usize {
+        match self {
+            KemProfile::MlKem768 => ml_kem_768::DK_LEN,
+            KemProfile::MlKem1024 => ml_kem_1024::DK_LEN,
+        }
+    }
+
+    pub const fn ciphertext_len(self) -> usize {
+        match self {
+            KemProfile::MlKem768 => KYBER_CT_LEN_768,
+            KemProfile::MlKem1024 => KYBER_CT_LEN_1024,
+        }
+    }
+
+    /// Identify the profile from an encapsulation key on the wire.
+    pub fn from_public_key_len(len: usize) -> Option<Self> {
+        match len {
+            KYBER_PK_LEN_768 => Some(KemProfile::MlKem768),
+            KYBER_PK_LEN_1024 => Some(KemProfile::MlKem1024),
+            _ => None,
+        }
+    }
+
+    /// HKDF info for the hybrid key: The two parameter sets never derive the same key.
+    const fn kdf_info(self) -> &'static [u8] {
+        match self {
+            KemProfile::MlKem768 => HYBRID_KDF_INFO_768,
+            KemProfile::MlKem1024 => HYBRID_KDF_INFO_1024,
+        }
+    }
+}
+
 #[derive(Debug, Zeroize, ZeroizeOnDrop)]
 pub struct KemKeypair {
-    pub public: [u8; ml_kem_1024::EK_LEN],
-    pub secret: [u8; ml_kem_1024::DK_LEN],
+    #[zeroize(skip)]
+    pub profile: KemProfile,
+    pub public: Vec<u8>,
+    pub secret: Vec<u8>,
 }
 
 impl KemKeypair {
+    /// Default profile (ML-KEM-1024).
     pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> M13Result<Self> {
-        let (ek, dk) = ml_kem_1024::KG::try_keygen_with_rng(rng).map_err(|_| M13Error::RngFailure)?;
-        Ok(Self { public: ek.into_bytes(), secret: dk.into_bytes() })
+        Self::generate_with_profile(KemProfile::default(), rng)
+    }
+
+    pub fn generate_with_profile<R: RngCore + CryptoRng>(profile: KemProfile, rng: &mut R) -> M13Result<Self> {
+        let (public, secret) = match profile {
+            KemProfile::MlKem768 => {
+                let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng(rng).map_err(|_| M13Error::RngFailure)?;
+                (ek.into_bytes().to_vec(), dk.into_bytes().to_vec())
+            }
+            KemProfile::MlKem1024 => {
+                let (ek, dk) = ml_kem_1024::KG::try_keygen_with_rng(rng).map_err(|_| M13Error::RngFailure)?;
+                (ek.into_bytes().to_vec(), dk.into_bytes().to_vec())
+            }
+        };
+        Ok(Self { profile, public, secret })
+    }
+
+    /// Serialized keypair: `public || secret`. Each profile has a distinct total length.
+    /// Holds the decapsulation key: Scrubbed on drop.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut out = Zeroizing::new(Vec::with_capacity(self.public.len() + self.secret.len()));
+        out.extend_from_slice(&self.public);
+        out.extend_from_slice(&self.secret);
+        out
+    }
+
+    /// Inverse of `to_bytes`. `WireFormatError` on a length no profile produces;
+    /// `CryptoFailure` if the public key does not belong to the secret (corruption).
+    pub fn from_bytes(bytes: &[u8]) -> M13Result<Self> {
+        let profile = [KemProfile::MlKem768, KemProfile::MlKem1024].into_iter()
+            .find(|p| p.public_key_len() + p.secret_key_len() == bytes.len())
+            .ok_or(M13Error::WireFormatError)?;
+        let (public, secret) = bytes.split_at(profile.public_key_len());
+
+        // The DK embeds its EK: Re-check the pair rather than trust storage.
+        let consistent = match profile {
+            KemProfile::MlKem768 => {
+                let ek: [u8; ml_kem_768::EK_LEN] = public.try_into().map_err(|_| M13Error::WireFormatError)?;
+                let mut dk: [u8; ml_kem_768::DK_LEN] = secret.try_into().map_err(|_| M13Error::WireFormatError)?;
+                let ok = ml_kem_768::KG::validate_keypair_vartime(&ek, &dk);
+                dk.zeroize();
+                ok
+            }
+            KemProfile::MlKem1024 => {
+                let ek: [u8; ml_kem_1024::EK_LEN] = public.try_into().map_err(|_| M13Error::WireFormatError)?;
+                let mut dk: [u8; ml_kem_1024::DK_LEN] = secret.try_into().map_err(|_| M13Error::WireFormatError)?;
+                let ok = ml_kem_1024::KG::validate_keypair_vartime(&ek, &dk);
+                dk.zeroize();
+                ok
+            }
+        };
+        if !consistent { return Err(M13Error::CryptoFailure); }
+        Ok(Self { profile, public: public.to_vec(), secret: secret.to_vec() })
     }
 }
 
@@ -29,20 +144,117 @@ pub fn kyber_keypair<R: RngCore + CryptoRng>(rng: &mut R) -> KemKeypair {
     KemKeypair::generate(rng).expect("RNG Fail")
 }
 
-pub fn kyber_encapsulate<R: RngCore + CryptoRng>(pk_bytes: &[u8], rng: &mut R) -> M13Result<([u8; ml_kem_1024::CT_LEN], [u8; 32])> {
-    let pk_array: [u8; ml_kem_1024::EK_LEN] = pk_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
-    let ek = ml_kem_1024::EncapsKey::try_from_bytes(pk_array).map_err(|_| M13Error::WireFormatError)?;
-    let (ss, ct) = ek.try_encaps_with_rng(rng).map_err(|_| M13Error::CryptoFailure)?;
-    Ok((ct.into_bytes(), ss.into_bytes()))
+/// Encapsulate to a peer EK. The profile is inferred from the key length.
+pub fn kyber_encapsulate<R: RngCore + CryptoRng>(pk_bytes: &[u8], rng: &mut R) -> M13Result<(Vec<u8>, [u8; 32])> {
+    match KemProfile::from_public_key_len(pk_bytes.len()) {
+        Some(KemProfile::MlKem768) => {
+            let pk_array: [u8; ml_kem_768::EK_LEN] = pk_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
+            let ek = ml_kem_768::EncapsKey::try_from_bytes(pk_array).map_err(|_| M13Error::WireFormatError)?;
+            let (ss, ct) = ek.try_encaps_with_rng(rng).map_err(|_| M13Error::CryptoFailure)?;
+            Ok((ct.into_bytes().to_vec(), ss.into_bytes()))
+        }
+        Some(KemProfile::MlKem1024) => {
+            let pk_array: [u8; ml_kem_1024::EK_LEN] = pk_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
+            let ek = ml_kem_1024::EncapsKey::try_from_bytes(pk_array).map_err(|_| M13Error::WireFormatError)?;
+            let (ss, ct) = ek.try_encaps_with_rng(rng).map_err(|_| M13Error::CryptoFailure)?;
+            Ok((ct.into_bytes().to_vec(), ss.into_bytes()))
+        }
+        None => Err(M13Error::WireFormatError),
+    }
 }
 
 pub fn kyber_decapsulate(keypair: &KemKeypair, ct_bytes: &[u8]) -> M13Result<[u8; 32]> {
-    let dk_array: [u8; ml_kem_1024::DK_LEN] = keypair.secret.try_into().map_err(|_| M13Error::WireFormatError)?;
-    let dk = ml_kem_1024::DecapsKey::try_from_bytes(dk_array).map_err(|_| M13Error::WireFormatError)?;
-    let ct_array: [u8; ml_kem_1024::CT_LEN] = ct_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
-    let ct = ml_kem_1024::CipherText::try_from_bytes(ct_array).map_err(|_| M13Error::WireFormatError)?;
-    let ss = dk.try_decaps(&ct).map_err(|_| M13Error::CryptoFailure)?;
-    Ok(ss.into_bytes())
+    match keypair.profile {
+        KemProfile::MlKem768 => {
+            let dk_array: [u8; ml_kem_768::DK_LEN] = keypair.secret[..].try_into().map_err(|_| M13Error::WireFormatError)?;
+            let dk = ml_kem_768::DecapsKey::try_from_bytes(dk_array).map_err(|_| M13Error::WireFormatError)?;
+            let ct_array: [u8; ml_kem_768::CT_LEN] = ct_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
+            let ct = ml_kem_768::CipherText::try_from_bytes(ct_array).map_err(|_| M13Error::WireFormatError)?;
+            let ss = dk.try_decaps(&ct).map_err(|_| M13Error::CryptoFailure)?;
+            Ok(ss.into_bytes())
+        }
+        KemProfile::MlKem1024 => {
+            let dk_array: [u8; ml_kem_1024::DK_LEN] = keypair.secret[..].try_into().map_err(|_| M13Error::WireFormatError)?;
+            let dk = ml_kem_1024::DecapsKey::try_from_bytes(dk_array).map_err(|_| M13Error::WireFormatError)?;
+            let ct_array: [u8; ml_kem_1024::CT_LEN] = ct_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
+            let ct = ml_kem_1024::CipherText::try_from_bytes(ct_array).map_err(|_| M13Error::WireFormatError)?;
+            let ss = dk.try_decaps(&ct).map_err(|_| M13Error::CryptoFailure)?;
+            Ok(ss.into_bytes())
+        }
+    }
+}
+
+/// Classical half of the hybrid exchange (Ephemeral X25519).
+#[derive(Debug, Zeroize, ZeroizeOnDrop)]
+pub struct X25519Keypair {
+    pub public: [u8; X25519_KEY_SIZE],
+    pub secret: [u8; X25519_KEY_SIZE],
+}
+
+impl X25519Keypair {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> M13Result<Self> {
+        let mut secret = [0u8; X25519_KEY_SIZE];
+        rng.try_fill_bytes(&mut secret).map_err(|_| M13Error::RngFailure)?;
+        let public = x25519_dalek::x25519(secret, x25519_dalek::X25519_BASEPOINT_BYTES);
+        Ok(Self { public, secret })
+    }
+
+    pub fn agree(&self, peer_public: &[u8]) -> M13Result<[u8; 32]> {
+        let peer: [u8; X25519_KEY_SIZE] = peer_public.try_into().map_err(|_| M13Error::WireFormatError)?;
+        let ss = x25519_dalek::x25519(self.secret, peer);
+        // Reject low-order points: an all-zero output means the peer contributed no entropy.
+        if ss.iter().fold(0u8, |acc, b| acc | b) == 0 {
+            return Err(M13Error::CryptoFailure);
+        }
+        Ok(ss)
+    }
+}
+
+/// Session Key = HKDF-SHA256(kyber_ss || x25519_ss), info naming the ML-KEM `profile`.
+/// Either primitive alone remaining unbroken keeps the output secret.
+pub fn hybrid_combine(profile: KemProfile, kyber_ss: &[u8; 32], x25519_ss: &[u8; 32]) -> [u8; 32] {
+    let mut ikm = [0u8; 64];
+    ikm[..32].copy_from_slice(kyber_ss);
+    ikm[32..].copy_from_slice(x25519_ss);
+
+    let hk = Hkdf::<Sha256>::new(None, &ikm);
+    let mut okm = [0u8; 32];
+    // 32 bytes is always a valid HKDF-SHA256 output length.
+    let _ = hk.expand(profile.kdf_info(), &mut okm);
+    ikm.zeroize();
+    okm
+}
+
+/// Responder side: Encapsulate to the Kyber EK and answer the X25519 share.
+/// Returns (kyber_ct, x25519_public, session_key).
+pub fn hybrid_encapsulate<R: RngCore + CryptoRng>(
+    kyber_pk: &[u8],
+    x25519_pk: &[u8],
+    rng: &mut R,
+) -> M13Result<(Vec<u8>, [u8; X25519_KEY_SIZE], [u8; 32])> {
+    let profile = KemProfile::from_public_key_len(kyber_pk.len()).ok_or(M13Error::WireFormatError)?;
+    let (ct, mut kyber_ss) = kyber_encapsulate(kyber_pk, rng)?;
+    let eph = X25519Keypair::generate(rng)?;
+    let mut x_ss = eph.agree(x25519_pk)?;
+    let key = hybrid_combine(profile, &kyber_ss, &x_ss);
+    kyber_ss.zeroize();
+    x_ss.zeroize();
+    Ok((ct, eph.public, key))
+}
+
+/// Initiator side: Recover both shared secrets and derive the same session key.
+pub fn hybrid_decapsulate(
+    kem: &KemKeypair,
+    x25519: &X25519Keypair,
+    ct_bytes: &[u8],
+    peer_x25519_pk: &[u8],
+) -> M13Result<[u8; 32]> {
+    let mut kyber_ss = kyber_decapsulate(kem, ct_bytes)?;
+    let mut x_ss = x25519.agree(peer_x25519_pk)?;
+    let key = hybrid_combine(kem.profile, &kyber_ss, &x_ss);
+    kyber_ss.zeroize();
+    x_ss.zeroize();
+    Ok(key)
 }
 
 #[derive(Debug, Zeroize, ZeroizeOnDrop)]
@@ -56,17 +268,61 @@ impl DsaKeypair {
         let (pk, sk) = ml_dsa_87::KG::try_keygen_with_rng(rng).map_err(|_| M13Error::RngFailure)?;
         Ok(Self { public: pk.into_bytes(), secret: sk.into_bytes() })
     }
+
+    /// Serialized identity: `public || secret` (fixed length). Scrubbed on drop.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut out = Zeroizing::new(Vec::with_capacity(ml_dsa_87::PK_LEN + ml_dsa_87::SK_LEN));
+        out.extend_from_slice(&self.public);
+        out.extend_from_slice(&self.secret);
+        out
+    }
+
+    /// Inverse of `to_bytes`. `WireFormatError` on a bad length; `CryptoFailure` if the
+    /// stored public key is not the one derived from the secret key.
+    pub fn from_bytes(bytes: &[u8]) -> M13Result<Self> {
+        if bytes.len() != ml_dsa_87::PK_LEN + ml_dsa_87::SK_LEN { return Err(M13Error::WireFormatError); }
+        let (public, secret) = bytes.split_at(ml_dsa_87::PK_LEN);
+        let public: [u8; ml_dsa_87::PK_LEN] = public.try_into().map_err(|_| M13Error::WireFormatError)?;
+        let secret: [u8; ml_dsa_87::SK_LEN] = secret.try_into().map_err(|_| M13Error::WireFormatError)?;
+
+        let sk = ml_dsa_87::PrivateKey::try_from_bytes(secret).map_err(|_| M13Error::WireFormatError)?;
+        let derived = sk.get_public_key().into_bytes();
+        if derived.iter().zip(public.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) != 0 {
+            return Err(M13Error::CryptoFailure);
+        }
+        Ok(Self { public, secret })
+    }
 }
 
-pub fn dsa_sign(msg: &[u8], sk_bytes: &[u8]) -> [u8; ml_dsa_87::SIG_LEN] {
-    let sk_array: [u8; ml_dsa_87::SK_LEN] = sk_bytes.try_into().unwrap();
-    let sk = ml_dsa_87::PrivateKey::try_from_bytes(sk_array).unwrap();
-    sk.try_sign_with_rng(&mut rand_core::OsRng, msg, b"").unwrap()
+/// ML-DSA context for the hub's HandshakeInit signature.
+pub const DSA_CTX_HANDSHAKE: &[u8] = b"M13-HANDSHAKE";
+/// ML-DSA context for the node's Epoch 0 attestation signature.
+pub const DSA_CTX_ATTEST: &[u8] = b"M13-ATTEST";
+
+/// ML-DSA signing is randomized (hedged): `rng` supplies the per-signature randomness,
+/// so a seeded RNG makes signatures reproducible.
+/// `ctx` (FIPS 204 context string, at most 255 bytes) domain-separates protocols: A
+/// signature only verifies under the context it was made with.
+pub fn dsa_sign<R: RngCore + CryptoRng>(msg: &[u8], sk_bytes: &[u8], ctx: &[u8], rng: &mut R) -> M13Result<[u8; ml_dsa_87::SIG_LEN]> {
+    let sk_array: [u8; ml_dsa_87::SK_LEN] = sk_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
+    let sk = ml_dsa_87::PrivateKey::try_from_bytes(sk_array).map_err(|_| M13Error::WireFormatError)?;
+    sk.try_sign_with_rng(rng, msg, ctx).map_err(|_| M13Error::CryptoFailure)
 }
 
-pub fn dsa_verify(pk_bytes: &[u8], sig_bytes: &[u8], msg: &[u8]) -> M13Result<()> {
+pub fn dsa_verify(pk_bytes: &[u8], sig_bytes: &[u8], msg: &[u8], ctx: &[u8]) -> M13Result<()> {
+    if ctx.len() > 255 { return Err(M13Error::WireFormatError); }
     let pk_array: [u8; ml_dsa_87::PK_LEN] = pk_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
     let pk = ml_dsa_87::PublicKey::try_from_bytes(pk_array).map_err(|_| M13Error::WireFormatError)?;
     let sig_array: [u8; ml_dsa_87::SIG_LEN] = sig_bytes.try_into().map_err(|_| M13Error::WireFormatError)?;
-    if pk.verify(msg, &sig_array, b"") { Ok(()) } else { Err(M13Error::CryptoFailure) }
+    if pk.verify(msg, &sig_array, ctx) { Ok(()) } else { Err(M13Error::CryptoFailure) }
+}
+
+/// One batch entry: (public key, signature, message).
+pub type DsaBatchItem<'a> = (&'a [u8], &'a [u8], &'a [u8]);
+
+/// Verifies every entry under `ctx`; `result[i]` is true iff `items[i]` verifies. Malformed keys or
+/// signatures are simply false. Entries are independent and order-preserving, so the
+/// loop can later share work or fan out across threads without changing callers.
+pub fn dsa_verify_batch(items: &[DsaBatchItem<'_>], ctx: &[u8]) -> Vec<bool> {
+    items.iter().map(|&(pk, sig, msg)| dsa_verify(pk, sig, msg, ctx).is_ok()).collect()
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+use m13_pqc::{DsaKeypair, KemKeypair, KemProfile, dsa_sign, dsa_verify, kyber_encapsulate, kyber_decapsulate, DSA_CTX_ATTEST};
+use m13_core::M13Error;
+use rand_core::OsRng;
+
+#[test]
+fn test_dsa_keypair_roundtrip() {
+    let id = DsaKeypair::generate(&mut OsRng).unwrap();
+    let stored = id.to_bytes();
+    let reloaded = DsaKeypair::from_bytes(&stored).unwrap();
+
+    assert_eq!(reloaded.public, id.public);
+    assert_eq!(reloaded.secret, id.secret);
+
+    // The reloaded identity still signs for the original public key.
+    let sig = dsa_sign(b"Epoch0", &reloaded.secret, DSA_CTX_ATTEST, &mut OsRng).unwrap();
+    dsa_verify(&id.public, &sig, b"Epoch0", DSA_CTX_ATTEST).unwrap();
+}
+
+#[test]
+fn test_kem_keypair_roundtrip_both_profiles() {
+    for profile in [KemProfile::MlKem768, KemProfile::MlKem1024] {
+        let kp = KemKeypair::generate_with_profile(profile, &mut OsRng).unwrap();
+        let reloaded = KemKeypair::from_bytes(&kp.to_bytes()).unwrap();
+
+        assert_eq!(reloaded.profile, profile);
+        assert_eq!(reloaded.public, kp.public);
+        assert_eq!(reloaded.secret, kp.secret);
+
+        let (ct, ss) = kyber_encapsulate(&kp.public, &mut OsRng).unwrap();
+        assert_eq!(kyber_decapsulate(&reloaded, &ct).unwrap(), ss);
+    }
+}
+
+#[test]
+fn test_truncated_keys_rejected() {
+    let id = DsaKeypair::generate(&mut OsRng).unwrap();
+    let stored = id.to_bytes();
+    assert!(matches!(DsaKeypair::from_bytes(&stored[..stored.len() - 1]), Err(M13Error::WireFormatError)));
+    assert!(matches!(DsaKeypair::from_bytes(&[]), Err(M13Error::WireFormatError)));
+
+    let kp = KemKeypair::generate(&mut OsRng).unwrap();
+    let stored = kp.to_bytes();
+    assert!(matches!(KemKeypair::from_bytes(&stored[..stored.len() - 1]), Err(M13Error::WireFormatError)));
+    assert!(matches!(KemKeypair::from_bytes(&kp.public), Err(M13Error::WireFormatError)));
+}
+
+#[test]
+fn test_mismatched_public_key_rejected() {
+    // A flipped bit in the stored public key is caught against the secret.
+    let id = DsaKeypair::generate(&mut OsRng).unwrap();
+    let mut stored = id.to_bytes();
+    stored[0] ^= 0x01;
+    assert!(matches!(DsaKeypair::from_bytes(&stored), Err(M13Error::CryptoFailure)));
+
+    let kp = KemKeypair::generate(&mut OsRng).unwrap();
+    let mut stored = kp.to_bytes();
+    stored[0] ^= 0x01;
+    assert!(matches!(KemKeypair::from_bytes(&stored), Err(M13Error::CryptoFailure)));
+}
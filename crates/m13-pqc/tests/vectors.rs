@@ -1,5 +1,10 @@
-use m13_pqc::{KemKeypair, encapsulate, decapsulate, DsaKeypair, verify};
-use rand_core::OsRng;
+use m13_pqc::{KemKeypair, kyber_encapsulate, kyber_decapsulate, DsaKeypair, dsa_sign, dsa_verify, dsa_verify_batch};
+use m13_pqc::{DSA_CTX_HANDSHAKE, DSA_CTX_ATTEST};
+use m13_pqc::{X25519Keypair, KemProfile, hybrid_combine, hybrid_encapsulate, hybrid_decapsulate};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{OsRng, SeedableRng};
+
+const CTX: &[u8] = DSA_CTX_HANDSHAKE;
 
 #[test]
 fn test_kem_exchange() {
@@ -7,21 +12,79 @@ fn test_kem_exchange() {
     let alice = KemKeypair::generate(&mut rng).unwrap();
     
     // Pass public key as slice (encapsulate handles conversion)
-    let (ct, ss_bob) = encapsulate(&alice.public, &mut rng).unwrap();
+    let (ct, ss_bob) = kyber_encapsulate(&alice.public, &mut rng).unwrap();
     
-    let ss_alice = decapsulate(&alice, &ct).unwrap();
+    let ss_alice = kyber_decapsulate(&alice, &ct).unwrap();
     assert_eq!(ss_bob, ss_alice);
 }
 
+#[test]
+fn test_kem_768_exchange() {
+    let mut rng = OsRng;
+    let alice = KemKeypair::generate_with_profile(KemProfile::MlKem768, &mut rng).unwrap();
+    assert_eq!(alice.public.len(), KemProfile::MlKem768.public_key_len());
+
+    let (ct, ss_bob) = kyber_encapsulate(&alice.public, &mut rng).unwrap();
+    assert_eq!(ct.len(), KemProfile::MlKem768.ciphertext_len());
+
+    let ss_alice = kyber_decapsulate(&alice, &ct).unwrap();
+    assert_eq!(ss_bob, ss_alice);
+
+    // A 1024 ciphertext must not be accepted by a 768 keypair.
+    let bob = KemKeypair::generate(&mut rng).unwrap();
+    let (ct_1024, _) = kyber_encapsulate(&bob.public, &mut rng).unwrap();
+    assert!(kyber_decapsulate(&alice, &ct_1024).is_err());
+}
+
+#[test]
+fn test_hybrid_exchange() {
+    let mut rng = OsRng;
+    let alice_kem = KemKeypair::generate(&mut rng).unwrap();
+    let alice_x = X25519Keypair::generate(&mut rng).unwrap();
+
+    let (ct, bob_x_pub, key_bob) = hybrid_encapsulate(&alice_kem.public, &alice_x.public, &mut rng).unwrap();
+    let key_alice = hybrid_decapsulate(&alice_kem, &alice_x, &ct, &bob_x_pub).unwrap();
+    assert_eq!(key_bob, key_alice);
+
+    // The hybrid key must not collapse to the bare Kyber secret.
+    let kyber_only = kyber_decapsulate(&alice_kem, &ct).unwrap();
+    assert_ne!(key_alice, kyber_only);
+}
+
+#[test]
+fn test_hybrid_key_binds_the_kem_profile() {
+    let mut rng = OsRng;
+    let alice_kem = KemKeypair::generate_with_profile(KemProfile::MlKem768, &mut rng).unwrap();
+    let alice_x = X25519Keypair::generate(&mut rng).unwrap();
+
+    let (ct, bob_x_pub, key_bob) = hybrid_encapsulate(&alice_kem.public, &alice_x.public, &mut rng).unwrap();
+    let key_alice = hybrid_decapsulate(&alice_kem, &alice_x, &ct, &bob_x_pub).unwrap();
+    assert_eq!(key_bob, key_alice);
+
+    // Same shared secrets, other profile: Another key.
+    let (kyber_ss, x_ss) = ([0x11; 32], [0x22; 32]);
+    assert_ne!(hybrid_combine(KemProfile::MlKem768, &kyber_ss, &x_ss), hybrid_combine(KemProfile::MlKem1024, &kyber_ss, &x_ss));
+}
+
 #[test]
 fn test_dsa_signing() {
     let mut rng = OsRng;
     let auth = DsaKeypair::generate(&mut rng).unwrap();
     let msg = b"Launch";
     
-    let sig = auth.sign(msg, &mut rng).unwrap();
+    let sig = dsa_sign(msg, &auth.secret, CTX, &mut rng).unwrap();
     
-    verify(&auth.public, msg, &sig).unwrap();
+    dsa_verify(&auth.public, &sig, msg, CTX).unwrap();
+}
+
+#[test]
+fn test_dsa_sign_truncated_key() {
+    let mut rng = OsRng;
+    let auth = DsaKeypair::generate(&mut rng).unwrap();
+
+    // Malformed key material must surface as an error, not a panic.
+    let truncated = &auth.secret[..auth.secret.len() - 1];
+    assert!(dsa_sign(b"Launch", truncated, CTX, &mut rng).is_err());
 }
 
 #[test]
@@ -45,4 +108,55 @@ fn test_header_serialization() {
     
     let recovered = M13Header::from_bytes(&buf).unwrap();
     assert_eq!(header, recovered);
-}
\ No newline at end of file
+}
+#[test]
+fn test_dsa_sign_seeded_rng_is_reproducible() {
+    let auth = DsaKeypair::generate(&mut OsRng).unwrap();
+    let msg = b"Launch";
+
+    // Hedged signing: Same key, message and RNG seed -> same signature.
+    let a = dsa_sign(msg, &auth.secret, CTX, &mut ChaCha20Rng::from_seed([7; 32])).unwrap();
+    let b = dsa_sign(msg, &auth.secret, CTX, &mut ChaCha20Rng::from_seed([7; 32])).unwrap();
+    assert_eq!(a, b);
+
+    // The randomness is real: Another seed signs differently, and both verify.
+    let c = dsa_sign(msg, &auth.secret, CTX, &mut ChaCha20Rng::from_seed([8; 32])).unwrap();
+    assert_ne!(a, c);
+    dsa_verify(&auth.public, &a, msg, CTX).unwrap();
+    dsa_verify(&auth.public, &c, msg, CTX).unwrap();
+}
+
+#[test]
+fn test_dsa_verify_batch_per_item() {
+    let nodes: Vec<DsaKeypair> = (0..4).map(|_| DsaKeypair::generate(&mut OsRng).unwrap()).collect();
+    let msgs: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 32]).collect();
+    let mut sigs: Vec<Vec<u8>> = nodes.iter().zip(&msgs)
+        .map(|(n, m)| dsa_sign(m, &n.secret, CTX, &mut OsRng).unwrap().to_vec())
+        .collect();
+
+    // Entry 2 is forged; entry 3 carries a truncated signature.
+    sigs[2][100] ^= 0x01;
+    sigs[3].pop();
+
+    let items: Vec<(&[u8], &[u8], &[u8])> = (0..4)
+        .map(|i| (&nodes[i].public[..], &sigs[i][..], &msgs[i][..]))
+        .collect();
+    assert_eq!(dsa_verify_batch(&items, CTX), vec![true, true, false, false]);
+    assert!(dsa_verify_batch(&[], CTX).is_empty());
+}
+
+#[test]
+fn test_dsa_context_separation() {
+    let auth = DsaKeypair::generate(&mut OsRng).unwrap();
+    let msg = b"ciphertext || challenge";
+
+    // A handshake signature must not pass as an attestation signature, or vice versa.
+    let sig = dsa_sign(msg, &auth.secret, DSA_CTX_HANDSHAKE, &mut OsRng).unwrap();
+    dsa_verify(&auth.public, &sig, msg, DSA_CTX_HANDSHAKE).unwrap();
+    assert!(dsa_verify(&auth.public, &sig, msg, DSA_CTX_ATTEST).is_err());
+    assert!(dsa_verify(&auth.public, &sig, msg, b"").is_err());
+
+    // FIPS 204 caps the context at 255 bytes.
+    assert!(dsa_sign(msg, &auth.secret, &[0u8; 256], &mut OsRng).is_err());
+    assert!(dsa_verify(&auth.public, &sig, msg, &[0u8; 256]).is_err());
+}
@@ -0,0 +1,187 @@
+#![forbid(unsafe_code)]
+
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use m13_core::{M13Error, M13Header, M13Result};
+
+use crate::decoder::FountainDecoder;
+use crate::encoder::{FountainEncoder, LEN_PREFIX, MAX_BLOCK_SYMBOLS};
+
+/// Object tag prepended to every symbol of a blocked object.
+/// [block_idx u16][block_count u16][block_k u16][object_len u32] (Big Endian)
+/// `M13Header::reserved` saturates at 255, so K=256 travels here instead.
+pub const BLOCK_TAG_LEN: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockTag {
+    pub block_idx: u16,
+    pub block_count: u16,
+    pub block_k: u16,
+    pub object_len: u32,
+}
+
+impl BlockTag {
+    pub fn to_bytes(&self, buf: &mut [u8]) -> M13Result<()> {
+        if buf.len() < BLOCK_TAG_LEN { return Err(M13Error::WireFormatError); }
+        buf[0..2].copy_from_slice(&self.block_idx.to_be_bytes());
+        buf[2..4].copy_from_slice(&self.block_count.to_be_bytes());
+        buf[4..6].copy_from_slice(&self.block_k.to_be_bytes());
+        buf[6..10].copy_from_slice(&self.object_len.to_be_bytes());
+        Ok(())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> M13Result<Self> {
+        if buf.len() < BLOCK_TAG_LEN { return Err(M13Error::WireFormatError); }
+        let tag = Self {
+            block_idx: u16::from_be_bytes([buf[0], buf[1]]),
+            block_count: u16::from_be_bytes([buf[2], buf[3]]),
+            block_k: u16::from_be_bytes([buf[4], buf[5]]),
+            object_len: u32::from_be_bytes([buf[6], buf[7], buf[8], buf[9]]),
+        };
+        if tag.block_count == 0 || tag.block_idx >= tag.block_count { return Err(M13Error::WireFormatError); }
+        if tag.block_k == 0 || tag.block_k as usize > MAX_BLOCK_SYMBOLS { return Err(M13Error::WireFormatError); }
+        Ok(tag)
+    }
+}
+
+/// Partitions objects above `MAX_BLOCK_SYMBOLS` into independent Fountain generations.
+/// Block i is coded under `gen_id = base_gen_id + i`.
+pub struct BlockedEncoder {
+    blocks: Vec<FountainEncoder>,
+    object_len: u32,
+    cursor: usize, // Round-robin: Interleaves blocks so a loss burst spreads out.
+}
+
+impl BlockedEncoder {
+    pub fn new(data: &[u8], symbol_size: usize, base_gen_id: u16) -> M13Result<Self> {
+        if symbol_size == 0 || data.is_empty() { return Err(M13Error::InvalidState); }
+        let object_len = u32::try_from(data.len()).map_err(|_| M13Error::InvalidState)?;
+
+        // Each generation spends LEN_PREFIX bytes of symbol 0 on its exact length.
+        let block_bytes = (MAX_BLOCK_SYMBOLS * symbol_size).checked_sub(LEN_PREFIX)
+            .filter(|&b| b > 0)
+            .ok_or(M13Error::InvalidState)?;
+        let block_count = data.len().div_ceil(block_bytes);
+        if block_count > u16::MAX as usize { return Err(M13Error::InvalidState); }
+
+        let mut blocks = Vec::with_capacity(block_count);
+        for (i, chunk) in data.chunks(block_bytes).enumerate() {
+            blocks.push(FountainEncoder::new(chunk, symbol_size, base_gen_id.wrapping_add(i as u16))?);
+        }
+
+        Ok(Self { blocks, object_len, cursor: 0 })
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Next packet, round-robin across blocks. Payload = `BlockTag || symbol`.
+    pub fn next_packet(&mut self) -> (M13Header, Vec<u8>) {
+        let idx = self.cursor;
+        self.cursor = (self.cursor + 1) % self.blocks.len();
+        self.next_packet_for(idx)
+    }
+
+    /// Next packet for one block (e.g. targeted repair).
+    pub fn next_packet_for(&mut self, block_idx: usize) -> (M13Header, Vec<u8>) {
+        let block_count = self.blocks.len();
+        let block_idx = block_idx % block_count;
+        let enc = &mut self.blocks[block_idx];
+        let tag = BlockTag {
+            block_idx: block_idx as u16,
+            block_count: block_count as u16,
+            block_k: enc.num_source_symbols() as u16,
+            object_len: self.object_len,
+        };
+
+        let (mut header, symbol) = enc.next_packet();
+        let mut payload = alloc::vec![0u8; BLOCK_TAG_LEN + symbol.len()];
+        // Infallible: Buffer sized above.
+        let _ = tag.to_bytes(&mut payload[..BLOCK_TAG_LEN]);
+        payload[BLOCK_TAG_LEN..].copy_from_slice(&symbol);
+
+        header.payload_len = payload.len() as u16;
+        (header, payload)
+    }
+}
+
+/// Reassembles a blocked object. Decoders are created lazily per `gen_id`.
+pub struct BlockedDecoder {
+    symbol_size: usize,
+    decoders: BTreeMap<u16, FountainDecoder>,
+    blocks: Vec<Option<Vec<u8>>>,
+    remaining: usize,
+    object_len: u32,
+    is_complete: bool,
+}
+
+impl BlockedDecoder {
+    pub fn new(symbol_size: usize) -> Self {
+        Self {
+            symbol_size,
+            decoders: BTreeMap::new(),
+            blocks: Vec::new(),
+            remaining: 0,
+            object_len: 0,
+            is_complete: false,
+        }
+    }
+
+    /// Absorb one packet. Returns the whole object once every block decoded.
+    pub fn receive(&mut self, header: &M13Header, payload: &[u8]) -> M13Result<Option<Vec<u8>>> {
+        if self.is_complete { return Ok(None); }
+
+        let tag = BlockTag::from_bytes(payload)?;
+        let symbol = &payload[BLOCK_TAG_LEN..];
+        if symbol.len() != self.symbol_size { return Err(M13Error::WireFormatError); }
+
+        // 1. First packet fixes the object shape; later packets must agree.
+        if self.blocks.is_empty() {
+            self.blocks = alloc::vec![None; tag.block_count as usize];
+            self.remaining = tag.block_count as usize;
+            self.object_len = tag.object_len;
+        } else if self.blocks.len() != tag.block_count as usize || self.object_len != tag.object_len {
+            return Err(M13Error::WireFormatError);
+        }
+
+        let idx = tag.block_idx as usize;
+        if self.blocks[idx].is_some() { return Ok(None); }
+
+        // 2. Per-block Fountain decode
+        // Copy out of the packed header before borrowing.
+        let gen_id = header.gen_id;
+        let symbol_id = header.symbol_id;
+        let symbol_size = self.symbol_size;
+        let decoder = self.decoders
+            .entry(gen_id)
+            .or_insert_with(|| FountainDecoder::new(tag.block_k as usize, symbol_size, gen_id));
+
+        if let Some(data) = decoder.receive_symbol(symbol_id, symbol)? {
+            self.decoders.remove(&gen_id);
+            self.blocks[idx] = Some(data);
+            self.remaining -= 1;
+        }
+
+        // 3. Reassemble
+        if self.remaining > 0 { return Ok(None); }
+        self.is_complete = true;
+
+        let mut object = Vec::with_capacity(self.object_len as usize);
+        for block in self.blocks.iter_mut() {
+            if let Some(data) = block.take() { object.extend_from_slice(&data); }
+        }
+        if object.len() != self.object_len as usize { return Err(M13Error::WireFormatError); }
+        Ok(Some(object))
+    }
+
+    /// Blocks still awaiting decode (0 before the first packet).
+    pub fn blocks_remaining(&self) -> usize {
+        self.remaining
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.is_complete
+    }
+}
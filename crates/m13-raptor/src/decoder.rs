@@ -3,44 +3,56 @@
 extern crate alloc;
 use alloc::vec::Vec;
 use m13_core::{M13Error, M13Result};
-use m13_math::{GfMatrix, GfSymbol};
-use m13_cipher::generate_coefficients;
+use m13_math::{symbols_add_scaled, GfMatrix, GfSymbol};
+use m13_cipher::{expand_coefficients, generate_coefficients};
+use crate::encoder::LEN_PREFIX;
 
-const LDPC_OVERHEAD_S: usize = 16; 
+/// LDPC constraint symbols: L = K + S intermediates.
+pub const LDPC_OVERHEAD_S: usize = 16;
 
 /// The Fountain Decoder.
+/// On-the-fly Gaussian Elimination: Every equation is reduced against the existing
+/// pivots as it arrives, so `decode` is only a back-substitution.
 pub struct FountainDecoder {
     block_size_k: usize,
     extended_size_l: usize,
     symbol_size: usize,
     gen_id: u16,
-    
-    // The Equation Matrix acting on Intermediate Symbols (L)
+
+    // The Equation Matrix acting on Intermediate Symbols (L x L, Upper Triangular).
+    // Row p holds the equation whose leading 1 sits in column p.
     matrix: GfMatrix,
     // The Symbols Vector (RHS)
-    symbols: GfMatrix, 
-    
+    symbols: GfMatrix,
+    pivots: Vec<bool>,
+
+    rank: usize,
     count: usize,
+    // Fresh symbols that reduced to zero: No new information.
+    dependent: usize,
     seen_symbols: Vec<u32>,
     is_solved: bool,
+    coeff_scratch: Vec<u8>, // Reused per coded symbol (L bytes)
 }
 
 impl FountainDecoder {
     pub fn new(block_size_k: usize, symbol_size: usize, gen_id: u16) -> Self {
         let extended_size_l = block_size_k + LDPC_OVERHEAD_S;
-        // Capacity: L + 8 overhead
-        let capacity = extended_size_l + 8;
-        
+
         let mut decoder = Self {
             block_size_k,
             extended_size_l,
             symbol_size,
             gen_id,
-            matrix: GfMatrix::new(capacity, extended_size_l),
-            symbols: GfMatrix::new(capacity, symbol_size),
+            matrix: GfMatrix::new(extended_size_l, extended_size_l),
+            symbols: GfMatrix::new(extended_size_l, symbol_size),
+            pivots: alloc::vec![false; extended_size_l],
+            rank: 0,
             count: 0,
+            dependent: 0,
             seen_symbols: Vec::new(),
             is_solved: false,
+            coeff_scratch: alloc::vec![0u8; extended_size_l],
         };
 
         // [AUDIT FIX] Initialize LDPC Constraints
@@ -50,22 +62,22 @@ impl FountainDecoder {
             let parity_idx = block_size_k + i;
             let seed = (gen_id as u32) << 16 | (parity_idx as u32);
             let neighbors = generate_coefficients(seed, gen_id, block_size_k);
-            
-            let row = decoder.count;
-            
+
+            let mut row = alloc::vec![GfSymbol::ZERO; extended_size_l];
+
             // 1. Set Parity Coeff (Identity)
-            decoder.matrix.set(row, parity_idx, GfSymbol::ONE);
-            
+            row[parity_idx] = GfSymbol::ONE;
+
             // 2. Set Neighbor Coeffs (XOR sum -> coeff 1)
-            for j in 0..block_size_k {
-                if neighbors[j] > 128 {
-                    decoder.matrix.set(row, j, GfSymbol::ONE);
+            for (j, &n) in neighbors.iter().enumerate().take(block_size_k) {
+                if n > 128 {
+                    row[j] = GfSymbol::ONE;
                 }
             }
-            
+
             // 3. RHS is 0 (Constraint)
-            // symbols matrix initialized to 0, so no action needed.
-            
+            let rhs = alloc::vec![GfSymbol::ZERO; symbol_size];
+            decoder.eliminate(row, rhs);
             decoder.count += 1;
         }
 
@@ -74,14 +86,29 @@ impl FountainDecoder {
 
     pub fn receive_symbol(&mut self, symbol_id: u32, payload: &[u8]) -> M13Result<Option<Vec<u8>>> {
         self.absorb(symbol_id, self.gen_id, payload)?;
+        self.try_complete()
+    }
+
+    /// Absorbs an equation given directly as coefficients over the L intermediate symbols
+    /// (e.g. a combination recoded in the network). No symbol_id, so no duplicate check:
+    /// A repeated combination is caught as dependent instead.
+    pub fn receive_combination(&mut self, coefficients: &[u8], payload: &[u8]) -> M13Result<Option<Vec<u8>>> {
+        if coefficients.len() != self.extended_size_l { return Err(M13Error::WireFormatError); }
+        if !self.is_decodable() {
+            let row = coefficients.iter().map(|&b| GfSymbol(b)).collect();
+            self.absorb_row(row, payload);
+        }
+        self.try_complete()
+    }
 
+    fn try_complete(&mut self) -> M13Result<Option<Vec<u8>>> {
         if self.is_decodable() && !self.is_solved {
             match self.decode() {
                 Ok(data) => {
                     self.is_solved = true;
                     Ok(Some(data))
                 },
-                Err(M13Error::CryptoFailure) => Ok(None), 
+                Err(M13Error::CryptoFailure) => Ok(None),
                 Err(e) => Err(e),
             }
         } else {
@@ -91,8 +118,8 @@ impl FountainDecoder {
 
     fn absorb(&mut self, symbol_id: u32, gen_id: u16, payload: &[u8]) -> M13Result<()> {
         if gen_id != self.gen_id { return Err(M13Error::WireFormatError); }
-        if self.seen_symbols.contains(&symbol_id) { return Ok(()); } 
-        if self.count >= self.matrix.rows { return Ok(()); } 
+        if self.seen_symbols.contains(&symbol_id) { return Ok(()); }
+        if self.is_decodable() { return Ok(()); }
 
         // 1. Construct Equation Row for Intermediate Symbols
         let row_coeffs = if (symbol_id as usize) < self.block_size_k {
@@ -102,102 +129,118 @@ impl FountainDecoder {
             r
         } else {
             // Coded: Generated from L intermediate symbols
-            let raw = generate_coefficients(symbol_id, self.gen_id, self.extended_size_l);
-            raw.iter().map(|&b| GfSymbol(b)).collect()
+            expand_coefficients(symbol_id, self.gen_id, self.extended_size_l, &mut self.coeff_scratch)?;
+            self.coeff_scratch.iter().map(|&b| GfSymbol(b)).collect()
         };
 
-        // 2. Insert into Matrix
-        let slot = self.count;
-        for c in 0..self.extended_size_l {
-            self.matrix.set(slot, c, row_coeffs[c]);
-        }
-        for c in 0..self.symbol_size {
-            let val = if c < payload.len() { payload[c] } else { 0 };
-            self.symbols.set(slot, c, GfSymbol(val));
-        }
-
-        self.count += 1;
+        // 2. Reduce against existing pivots and store
+        self.absorb_row(row_coeffs, payload);
         self.seen_symbols.push(symbol_id);
         Ok(())
     }
 
-    pub fn is_decodable(&self) -> bool {
-        // We need L independent equations (including the S static constraints)
-        self.count >= self.extended_size_l
+    fn absorb_row(&mut self, row: Vec<GfSymbol>, payload: &[u8]) {
+        let mut rhs = alloc::vec![GfSymbol::ZERO; self.symbol_size];
+        for (dst, &b) in rhs.iter_mut().zip(payload) { *dst = GfSymbol(b); }
+
+        if !self.eliminate(row, rhs) { self.dependent += 1; }
+        self.count += 1;
     }
 
-    pub fn decode(&self) -> M13Result<Vec<u8>> {
-        if !self.is_decodable() { return Err(M13Error::InvalidState); }
+    /// Forward elimination of one equation. Returns true if it was innovative.
+    fn eliminate(&mut self, mut row: Vec<GfSymbol>, mut rhs: Vec<GfSymbol>) -> bool {
+        for col in 0..self.extended_size_l {
+            let factor = row[col];
+            if factor == GfSymbol::ZERO { continue; }
+
+            if self.pivots[col] {
+                // Stored row `col` is zero left of `col`, so earlier columns stay clean.
+                if let (Some(p_row), Some(p_rhs)) = (self.matrix.row(col), self.symbols.row(col)) {
+                    symbols_add_scaled(&mut row, p_row, factor);
+                    symbols_add_scaled(&mut rhs, p_rhs, factor);
+                }
+            } else {
+                // New pivot: Normalize and claim slot `col`.
+                let inv = factor.inv();
+                for (c, v) in row.iter().enumerate().skip(col) { self.matrix.set(col, c, *v * inv); }
+                for (c, v) in rhs.iter().enumerate() { self.symbols.set(col, c, *v * inv); }
+                self.pivots[col] = true;
+                self.rank += 1;
+                return true;
+            }
+        }
+        false // Linear Dependence
+    }
 
-        let rows = self.count;
-        let cols = self.extended_size_l; 
-        
-        let mut a = self.matrix.clone();
-        let mut b = self.symbols.clone();
+    pub fn symbol_size(&self) -> usize {
+        self.symbol_size
+    }
 
-        let mut pivot_row = 0;
-        
-        // Gaussian Elimination solving for Intermediate Symbols
-        for col_idx in 0..cols {
-            if pivot_row >= rows { break; }
+    /// L: Source symbols plus LDPC intermediates. The rank `decode` needs, and the
+    /// coefficient count of `receive_combination`.
+    pub fn extended_size(&self) -> usize {
+        self.extended_size_l
+    }
 
-            let mut curr = pivot_row;
-            while curr < rows && a.get(curr, col_idx) == Some(GfSymbol::ZERO) {
-                curr += 1;
-            }
-            
-            if curr == rows { 
-                return Err(M13Error::CryptoFailure); 
-            }
+    /// True rank of the reduced system (LDPC constraints included, duplicates excluded).
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
 
-            if curr != pivot_row {
-                for c in 0..cols {
-                    let temp = a.get(pivot_row, c).unwrap();
-                    a.set(pivot_row, c, a.get(curr, c).unwrap());
-                    a.set(curr, c, temp);
-                }
-                for c in 0..self.symbol_size {
-                    let temp = b.get(pivot_row, c).unwrap();
-                    b.set(pivot_row, c, b.get(curr, c).unwrap());
-                    b.set(curr, c, temp);
-                }
-            }
+    /// Independent equations still missing before `decode` can succeed.
+    pub fn needed(&self) -> usize {
+        self.extended_size_l.saturating_sub(self.rank)
+    }
 
-            let p_val = a.get(pivot_row, col_idx).unwrap();
-            let inv = p_val.inv();
-            
-            for c in col_idx..cols {
-                a.set(pivot_row, c, a.get(pivot_row, c).unwrap() * inv);
-            }
-            for c in 0..self.symbol_size {
-                b.set(pivot_row, c, b.get(pivot_row, c).unwrap() * inv);
-            }
+    /// Distinct symbols absorbed, innovative or not (LDPC constraints excluded).
+    pub fn received(&self) -> usize {
+        self.count - LDPC_OVERHEAD_S
+    }
+
+    /// Distinct symbols that added no rank. Over GF(256) a healthy stream almost never
+    /// produces one, so a growing count means the symbols arriving cannot complete the system.
+    pub fn dependent(&self) -> usize {
+        self.dependent
+    }
 
-            for r in 0..rows {
-                if r != pivot_row {
-                    let factor = a.get(r, col_idx).unwrap();
-                    if factor != GfSymbol::ZERO {
-                        for c in col_idx..cols {
-                            let val = a.get(r, c).unwrap() - (factor * a.get(pivot_row, c).unwrap());
-                            a.set(r, c, val);
-                        }
-                        for c in 0..self.symbol_size {
-                            let val = b.get(r, c).unwrap() - (factor * b.get(pivot_row, c).unwrap());
-                            b.set(r, c, val);
-                        }
-                    }
+    pub fn is_decodable(&self) -> bool {
+        // We need L independent equations (including the S static constraints)
+        self.rank >= self.extended_size_l
+    }
+
+    pub fn decode(&self) -> M13Result<Vec<u8>> {
+        if !self.is_decodable() {
+            // Enough equations but rank deficient: Singular system.
+            if self.count >= self.extended_size_l { return Err(M13Error::CryptoFailure); }
+            return Err(M13Error::InvalidState);
+        }
+
+        let l = self.extended_size_l;
+        let mut b = self.symbols.clone();
+
+        // Back Substitution (Clear Upper Triangle)
+        for col in (0..l).rev() {
+            for r in 0..col {
+                let factor = self.matrix.get(r, col).ok_or(M13Error::InvalidState)?;
+                if factor != GfSymbol::ZERO {
+                    b.row_add_scaled_inplace(r, col, factor)?;
                 }
             }
-            pivot_row += 1;
         }
 
         // Extract Source Symbols (0..K) from Intermediate Symbols (0..L)
         let mut result = Vec::with_capacity(self.block_size_k * self.symbol_size);
         for r in 0..self.block_size_k {
-            for c in 0..self.symbol_size {
-                result.push(b.get(r, c).unwrap().0);
-            }
+            let row = b.row(r).ok_or(M13Error::InvalidState)?;
+            result.extend(row.iter().map(|s| s.0));
         }
+
+        // Strip Framing: [len u32 BE][payload][zero pad]
+        if result.len() < LEN_PREFIX { return Err(M13Error::WireFormatError); }
+        let len = u32::from_be_bytes([result[0], result[1], result[2], result[3]]) as usize;
+        if len > result.len() - LEN_PREFIX { return Err(M13Error::WireFormatError); }
+        result.drain(..LEN_PREFIX);
+        result.truncate(len);
         Ok(result)
     }
-}
\ No newline at end of file
+}
@@ -2,15 +2,18 @@
 
 extern crate alloc;
 use alloc::vec::Vec;
-use m13_core::{M13Error, M13Result, M13Header, PacketType, M13_MAGIC};
+use m13_core::{M13Error, M13Result, M13Header, PacketType};
 use m13_math::{GfSymbol};
-use m13_cipher::generate_coefficients;
+use m13_cipher::{expand_coefficients, generate_coefficients};
 
 /// Appendix D.1: Cap block size to prevent CPU exhaustion.
 pub const MAX_BLOCK_SYMBOLS: usize = 256; 
 /// [AUDIT FIX] RFC 6330 Pre-coding Overhead (Systematic LDPC)
 /// We define L = K + S, where S is the number of constraint symbols.
 const LDPC_OVERHEAD_S: usize = 16; 
+/// Exact payload length (u32 BE) carried in-band at the head of symbol 0.
+/// Lets the decoder strip the zero padding of the final source symbol.
+pub const LEN_PREFIX: usize = 4;
 
 /// The Fountain Encoder.
 /// "Pours" symbols into the channel.
@@ -26,14 +29,21 @@ pub struct FountainEncoder {
     
     gen_id: u16,
     cursor: u32, // The current Symbol ID being generated
+    coeff_scratch: Vec<u8>, // Reused per repair symbol (L bytes)
 }
 
 impl FountainEncoder {
-    pub fn new(data: &[u8], symbol_size: usize, gen_id: u16) -> M13Result<Self> {
+    pub fn new(payload: &[u8], symbol_size: usize, gen_id: u16) -> M13Result<Self> {
         if symbol_size == 0 { return Err(M13Error::InvalidState); }
+        let payload_len = u32::try_from(payload.len()).map_err(|_| M13Error::InvalidState)?;
+
+        // Frame: [len u32 BE][payload]
+        let mut data = Vec::with_capacity(LEN_PREFIX + payload.len());
+        data.extend_from_slice(&payload_len.to_be_bytes());
+        data.extend_from_slice(payload);
         
         // Calculate K (Round up)
-        let block_size_k = (data.len() + symbol_size - 1) / symbol_size;
+        let block_size_k = data.len().div_ceil(symbol_size);
         
         if block_size_k > MAX_BLOCK_SYMBOLS {
              return Err(M13Error::InvalidState); 
@@ -90,6 +100,7 @@ impl FountainEncoder {
             extended_size_l,
             gen_id,
             cursor: 0,
+            coeff_scratch: alloc::vec![0u8; extended_size_l],
         })
     }
 
@@ -107,7 +118,9 @@ impl FountainEncoder {
         } else {
             // REPAIR PHASE: Random Linear Combination of INTERMEDIATE Symbols (L)
             // Note: We mix both Source and Parity symbols now.
-            let coeffs_raw = generate_coefficients(sym_id, self.gen_id, self.extended_size_l);
+            // Infallible: Scratch is exactly L long.
+            let _ = expand_coefficients(sym_id, self.gen_id, self.extended_size_l, &mut self.coeff_scratch);
+            let coeffs_raw = &self.coeff_scratch;
             
             let mut result = alloc::vec![GfSymbol::ZERO; self.symbol_size];
 
@@ -126,16 +139,11 @@ impl FountainEncoder {
             result.iter().map(|s| s.0).collect()
         };
 
+        let packet_type = if (sym_id as usize) < self.block_size_k { PacketType::Data } else { PacketType::Coded };
         let header = M13Header {
-            magic: M13_MAGIC,
-            version: 1,
-            packet_type: if (sym_id as usize) < self.block_size_k { PacketType::Data } else { PacketType::Coded },
-            gen_id: self.gen_id,
-            symbol_id: sym_id,
             payload_len: payload.len() as u16,
-            recoder_rank: 0,
-            reserved: k_to_reserved(self.block_size_k), 
-            auth_tag: [0u8; 16],
+            reserved: k_to_reserved(self.block_size_k),
+            ..M13Header::new(packet_type, self.gen_id, sym_id)
         };
 
         (header, payload)
@@ -144,6 +152,10 @@ impl FountainEncoder {
     pub fn num_source_symbols(&self) -> usize {
         self.block_size_k
     }
+
+    pub fn gen_id(&self) -> u16 {
+        self.gen_id
+    }
 }
 
 fn k_to_reserved(k: usize) -> u8 {
@@ -3,10 +3,12 @@ extern crate alloc;
 
 pub mod encoder;
 pub mod decoder;
+pub mod blocked;
 
 // Export Logic
 pub use encoder::FountainEncoder;
-pub use decoder::FountainDecoder;
+pub use decoder::{FountainDecoder, LDPC_OVERHEAD_S};
+pub use blocked::{BlockedEncoder, BlockedDecoder, BlockTag};
 
 #[derive(Debug)]
 pub enum RaptorError {
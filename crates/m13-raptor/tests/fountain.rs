@@ -7,34 +7,169 @@ fn test_fountain_recovery() {
     let gen_id = 1;
 
     let mut enc = FountainEncoder::new(data, symbol_size, gen_id).unwrap();
-    // K = ceil(66 / 4) = 17 packets needed.
+    // K = ceil((4 + 66) / 4) = 18 packets needed (4-byte length prefix).
+    assert_eq!(enc.num_source_symbols(), 18);
 
     // Allow Decoder to buffer K+5 packets
-    let mut dec = FountainDecoder::new(17, symbol_size, gen_id);
+    let mut dec = FountainDecoder::new(18, symbol_size, gen_id);
 
     // Simulate Loss: Drop Systematic Packets 0, 2, 5
     // We will supply 3 Repair packets to compensate.
     
     // 1. Send all Systematic except dropped
-    // We iterate up to K (0..17)
-    for i in 0..17 {
+    // We iterate up to K (0..18)
+    for i in 0..18 {
         let (header, payload) = enc.next_packet(); // this advances internal cursor
         
         // Simulating Loss: If index is 0, 2, or 5, we DROP it (don't absorb).
         if i == 0 || i == 2 || i == 5 { continue; }
         
-        dec.absorb(&header, &payload).unwrap();
+        dec.receive_symbol(header.symbol_id, &payload).unwrap();
     }
     
     // 2. Send 5 Repair Packets (plus extras to test robustness)
     for _ in 0..5 {
         let (header, payload) = enc.next_packet();
-        dec.absorb(&header, &payload).unwrap();
+        dec.receive_symbol(header.symbol_id, &payload).unwrap();
     }
     
     // 3. Decode
     let recovered = dec.decode().expect("Decoder failed (Singular Matrix?)");
     
-    // Trim padding and verify
-    assert_eq!(&recovered[0..data.len()], data);
-}
\ No newline at end of file
+    // Padding already stripped
+    assert_eq!(recovered, data);
+}
+#[test]
+fn test_exact_payload_length() {
+    let data: Vec<u8> = (0..1500u32).map(|i| (i % 251) as u8 + 1).collect();
+    let symbol_size = 1024;
+
+    let mut enc = FountainEncoder::new(&data, symbol_size, 7).unwrap();
+    assert_eq!(enc.num_source_symbols(), 2);
+
+    let mut dec = FountainDecoder::new(enc.num_source_symbols(), symbol_size, 7);
+    let mut recovered = None;
+    for _ in 0..8 {
+        let (header, payload) = enc.next_packet();
+        if let Some(out) = dec.receive_symbol(header.symbol_id, &payload).unwrap() {
+            recovered = Some(out);
+            break;
+        }
+    }
+
+    let recovered = recovered.expect("Generation never decoded");
+    assert_eq!(recovered.len(), 1500, "Zero padding leaked into the payload");
+    assert_eq!(recovered, data);
+}
+
+#[test]
+fn test_incremental_decode_on_full_rank() {
+    // K = (4 + 92) / 8 = 12
+    let data: Vec<u8> = (0..92u8).collect();
+    let symbol_size = 8;
+
+    // 1. In-order systematic: Decodable exactly on the K-th symbol.
+    let mut enc = FountainEncoder::new(&data, symbol_size, 3).unwrap();
+    let k = enc.num_source_symbols();
+    assert_eq!(k, 12);
+    let mut dec = FountainDecoder::new(k, symbol_size, 3);
+
+    for i in 0..k {
+        assert!(!dec.is_decodable());
+        assert!(dec.decode().is_err(), "Decoded before full rank");
+
+        let (header, payload) = enc.next_packet();
+        let out = dec.receive_symbol(header.symbol_id, &payload).unwrap();
+        if i + 1 < k {
+            assert!(out.is_none(), "Decoded early at symbol {}", i);
+        } else {
+            assert_eq!(out.unwrap(), data);
+        }
+    }
+    assert!(dec.is_decodable());
+
+    // 2. Losses healed by repair symbols: Output appears the moment rank is full.
+    let mut enc = FountainEncoder::new(&data, symbol_size, 4).unwrap();
+    let mut dec = FountainDecoder::new(k, symbol_size, 4);
+    let mut recovered = None;
+    for sym in 0..40 {
+        let (header, payload) = enc.next_packet();
+        if sym == 3 || sym == 7 { continue; }
+
+        let was_decodable = dec.is_decodable();
+        let out = dec.receive_symbol(header.symbol_id, &payload).unwrap();
+        assert_eq!(out.is_some(), !was_decodable && dec.is_decodable());
+        if out.is_some() { recovered = out; break; }
+    }
+    assert_eq!(recovered.expect("Repair symbols never completed the rank"), data);
+}
+
+#[test]
+fn test_duplicates_do_not_raise_rank() {
+    let data: Vec<u8> = (0..92u8).collect();
+    let mut enc = FountainEncoder::new(&data, 8, 5).unwrap();
+    let k = enc.num_source_symbols();
+    let mut dec = FountainDecoder::new(k, 8, 5);
+
+    // LDPC constraints alone: 16 independent rows.
+    assert_eq!(dec.rank(), 16);
+    assert_eq!(dec.needed(), k);
+
+    let (h0, p0) = enc.next_packet();
+    dec.receive_symbol(h0.symbol_id, &p0).unwrap();
+    assert_eq!(dec.rank(), 17);
+
+    for _ in 0..3 {
+        dec.receive_symbol(h0.symbol_id, &p0).unwrap();
+        assert_eq!(dec.rank(), 17, "Duplicate symbol counted toward rank");
+    }
+    assert_eq!(dec.rank() + dec.needed(), k + 16);
+}
+
+#[test]
+fn test_dependent_symbols_report_not_decodable() {
+    use m13_core::M13Error;
+    use m13_math::GfSymbol;
+
+    let k = 12;
+    let mut dec = FountainDecoder::new(k, 8, 6);
+    let l = dec.extended_size();
+    assert_eq!(l, k + 16);
+
+    // L equations spanning only two directions: Enough symbols, never enough rank.
+    let c1: Vec<u8> = (0..l).map(|i| (i * 37 + 1) as u8).collect();
+    let c2: Vec<u8> = (0..l).map(|i| (i * 91 + 5) as u8).collect();
+    for n in 0..l {
+        let (a, b) = (GfSymbol((n + 1) as u8), GfSymbol((n * 3 + 2) as u8));
+        let row: Vec<u8> = c1.iter().zip(&c2).map(|(&x, &y)| (a * GfSymbol(x) + b * GfSymbol(y)).0).collect();
+        assert!(dec.receive_combination(&row, &[0u8; 8]).unwrap().is_none());
+    }
+
+    assert_eq!(dec.received(), l);
+    assert_eq!(dec.rank(), 16 + 2);
+    assert_eq!(dec.dependent(), l - 2);
+    assert!(!dec.is_decodable());
+    assert!(matches!(dec.decode(), Err(M13Error::CryptoFailure)), "Singular system not reported");
+
+    assert!(matches!(dec.receive_combination(&c1[..l - 1], &[0u8; 8]), Err(M13Error::WireFormatError)));
+}
+
+#[test]
+fn test_healthy_stream_has_no_dependent_symbols() {
+    let data: Vec<u8> = (0..92u8).collect();
+    let mut enc = FountainEncoder::new(&data, 8, 8).unwrap();
+    let k = enc.num_source_symbols();
+    let mut dec = FountainDecoder::new(k, 8, 8);
+
+    // Every other symbol lost: Repair fills the holes without a single wasted symbol.
+    let mut recovered = None;
+    for sym in 0..200 {
+        let (header, payload) = enc.next_packet();
+        if sym % 2 == 0 { continue; }
+        recovered = dec.receive_symbol(header.symbol_id, &payload).unwrap();
+        if recovered.is_some() { break; }
+    }
+    assert_eq!(recovered.expect("Never decoded"), data);
+    assert_eq!(dec.dependent(), 0);
+    assert_eq!(dec.received(), k);
+}
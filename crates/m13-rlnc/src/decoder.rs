@@ -3,7 +3,8 @@
 extern crate alloc;
 use alloc::vec::Vec;
 use m13_core::{M13Error, M13Result};
-use m13_math::{GfMatrix, GfSymbol};
+use m13_math::{symbols_add_scaled, GfMatrix, GfSymbol};
+use crate::packet::expand_seeded;
 
 pub struct RlncDecoder {
     gen_id: u16,
@@ -16,6 +17,8 @@ pub struct RlncDecoder {
     data: GfMatrix,
     
     rank: usize,
+    // [GC] Last `touch`. The manager owns the clock.
+    last_active_us: u64,
 }
 
 impl RlncDecoder {
@@ -27,9 +30,28 @@ impl RlncDecoder {
             matrix: GfMatrix::new(k, k),
             data: GfMatrix::new(k, payload_size),
             rank: 0,
+            last_active_us: 0,
         }
     }
 
+    /// Record activity at `now_us` (call alongside `absorb`).
+    pub fn touch(&mut self, now_us: u64) {
+        self.last_active_us = now_us;
+    }
+
+    /// No activity for `timeout_us`: The manager should evict this generation.
+    pub fn is_stale(&self, now_us: u64, timeout_us: u64) -> bool {
+        now_us.saturating_sub(self.last_active_us) >= timeout_us
+    }
+
+    /// Clear all state and start over on `gen_id` (same K and payload size).
+    pub fn reset(&mut self, gen_id: u16) {
+        self.gen_id = gen_id;
+        self.matrix = GfMatrix::new(self.k, self.k);
+        self.data = GfMatrix::new(self.k, self.payload_size);
+        self.rank = 0;
+    }
+
     /// Returns the Generation ID managed by this Decoder.
     pub fn gen_id(&self) -> u16 {
         self.gen_id
@@ -51,15 +73,11 @@ impl RlncDecoder {
             if self.matrix.get(r, r) == Some(GfSymbol::ONE) {
                 let factor = row_gev[r];
                 if factor != GfSymbol::ZERO {
-                    // Eliminate
-                    for c in r..self.k {
-                        let val = row_gev[c] - (factor * self.matrix.get(r, c).unwrap());
-                        row_gev[c] = val;
-                    }
-                    for c in 0..self.payload_size {
-                        let val = row_data[c] - (factor * self.data.get(r, c).unwrap());
-                        row_data[c] = val;
-                    }
+                    // Eliminate (stored row r is zero left of its pivot)
+                    let pivot_gev = self.matrix.row(r).ok_or(M13Error::InvalidState)?;
+                    let pivot_data = self.data.row(r).ok_or(M13Error::InvalidState)?;
+                    symbols_add_scaled(&mut row_gev, pivot_gev, factor);
+                    symbols_add_scaled(&mut row_data, pivot_data, factor);
                 }
             } else {
                 // Pivot empty! We assume we can claim it.
@@ -68,12 +86,12 @@ impl RlncDecoder {
 
                 // Normalize
                 let inv = row_gev[r].inv();
-                for c in r..self.k { row_gev[c] = row_gev[c] * inv; }
-                for c in 0..self.payload_size { row_data[c] = row_data[c] * inv; }
+                for g in &mut row_gev[r..] { *g = *g * inv; }
+                for d in &mut row_data { *d = *d * inv; }
 
                 // Store
-                for c in 0..self.k { self.matrix.set(r, c, row_gev[c]); }
-                for c in 0..self.payload_size { self.data.set(r, c, row_data[c]); }
+                for (c, &g) in row_gev.iter().enumerate() { self.matrix.set(r, c, g); }
+                for (c, &d) in row_data.iter().enumerate() { self.data.set(r, c, d); }
                 
                 self.rank += 1;
                 return Ok(true);
@@ -82,10 +100,42 @@ impl RlncDecoder {
         Ok(false) // Linear Dependence
     }
 
+    /// Process a seeded packet ([ SEED | Payload ]). Returns true if innovative.
+    pub fn absorb_seeded(&mut self, packet_bytes: &[u8]) -> M13Result<bool> {
+        let full = expand_seeded(packet_bytes, self.gen_id, self.k).ok_or(M13Error::WireFormatError)?;
+        self.absorb(&full)
+    }
+
+    /// Innovative packets absorbed so far.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Innovative packets still missing before `decode` can succeed.
+    pub fn needed(&self) -> usize {
+        self.k - self.rank
+    }
+
+    /// Whether to keep pulling from a relay advertising `advertised_rank`
+    /// (`M13Header::advertised_rank`). A relay whose basis is larger than ours must hold
+    /// something we lack; an equal or smaller one may only repeat what we have.
+    pub fn should_pull(&self, advertised_rank: u8) -> bool {
+        !self.is_complete() && advertised_rank as usize > self.rank
+    }
+
     pub fn is_complete(&self) -> bool {
         self.rank == self.k
     }
 
+    /// Completion hand-off: Decode, then reset (same gen_id) so the matrices can be reused.
+    /// On error (incomplete) the state is left untouched.
+    pub fn take_decoded(&mut self) -> M13Result<Vec<Vec<u8>>> {
+        let packets = self.decode()?;
+        let gen_id = self.gen_id;
+        self.reset(gen_id);
+        Ok(packets)
+    }
+
     /// Extract original packets.
     pub fn decode(&mut self) -> M13Result<Vec<Vec<u8>>> {
         if !self.is_complete() { return Err(M13Error::InvalidState); }
@@ -95,10 +145,7 @@ impl RlncDecoder {
             for row_above in 0..r {
                 let factor = self.matrix.get(row_above, r).unwrap();
                 if factor != GfSymbol::ZERO {
-                    for c in 0..self.payload_size {
-                        let val = self.data.get(row_above, c).unwrap() - (factor * self.data.get(r, c).unwrap());
-                        self.data.set(row_above, c, val);
-                    }
+                    self.data.row_add_scaled_inplace(row_above, r, factor)?;
                 }
             }
         }
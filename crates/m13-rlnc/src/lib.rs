@@ -4,6 +4,6 @@ mod packet;
 mod recoder;
 mod decoder;
 
-pub use packet::RlncPacket;
-pub use recoder::Recoder;
+pub use packet::{RlncPacket, seeded_gev, SEED_LEN};
+pub use recoder::{Recoder, MAX_RLNC_GENERATION};
 pub use decoder::RlncDecoder;
\ No newline at end of file
@@ -1,9 +1,27 @@
 #![forbid(unsafe_code)]
 extern crate alloc;
 use alloc::vec::Vec;
+use m13_cipher::generate_coefficients;
 use m13_math::GfSymbol;
 use zeroize::Zeroize;
 
+/// Seeded wire format: [ SEED u32 BE | Payload ]. The GEV is regenerated from the seed.
+pub const SEED_LEN: usize = 4;
+
+/// Effective GEV of a seeded packet (same PRF as the Fountain repair symbols).
+pub fn seeded_gev(seed: u32, gen_id: u16, k: usize) -> Vec<u8> {
+    generate_coefficients(seed, gen_id, k)
+}
+
+/// Expand a seeded packet into the explicit [ GEV | Payload ] form.
+pub(crate) fn expand_seeded(packet: &[u8], gen_id: u16, k: usize) -> Option<Vec<u8>> {
+    if packet.len() <= SEED_LEN { return None; }
+    let seed = u32::from_be_bytes([packet[0], packet[1], packet[2], packet[3]]);
+    let mut full = seeded_gev(seed, gen_id, k);
+    full.extend_from_slice(&packet[SEED_LEN..]);
+    Some(full)
+}
+
 /// The RLNC Packet Structure (In-Memory).
 /// Maps to wire format: [ GEV | Payload ]
 #[derive(Clone, Debug, Zeroize)]
@@ -2,12 +2,15 @@
 
 extern crate alloc;
 use alloc::vec::Vec;
-use m13_core::{M13Error, M13Result};
-use m13_math::{GfSymbol};
+use m13_core::{M13Error, M13Header, M13Result, PacketType, RECODER_RANK_MASK};
+use m13_math::{symbols_add_scaled, GfSymbol};
+use crate::packet::{expand_seeded, seeded_gev, SEED_LEN};
 use rand_core::{RngCore, CryptoRng};
 
 /// Limits generation size to control complexity.
 pub const MAX_RLNC_GENERATION: usize = 32;
+// Every basis size must fit the rank bits of `recoder_rank` unsaturated.
+const _: () = assert!(MAX_RLNC_GENERATION <= RECODER_RANK_MASK as usize);
 
 /// A stored packet in the basis.
 #[derive(Clone)]
@@ -22,6 +25,11 @@ pub struct Recoder {
     gen_id: u16,
     generation_size_k: usize,
     basis: Vec<BasisSlot>, // Kept in triangular form
+    forwarded: usize, // [SYSTEMATIC] Basis rows already sent un-mixed
+    // [SEEDED] Source packets, solved once the basis reaches full rank.
+    sources: Option<Vec<Vec<GfSymbol>>>,
+    // [GC] Last `touch`. The manager owns the clock.
+    last_active_us: u64,
 }
 
 impl Recoder {
@@ -31,9 +39,30 @@ impl Recoder {
             gen_id,
             generation_size_k: k,
             basis: Vec::with_capacity(k),
+            forwarded: 0,
+            sources: None,
+            last_active_us: 0,
         })
     }
 
+    /// Record activity at `now_us` (call alongside `absorb`).
+    pub fn touch(&mut self, now_us: u64) {
+        self.last_active_us = now_us;
+    }
+
+    /// No activity for `timeout_us`: The manager should evict this generation.
+    pub fn is_stale(&self, now_us: u64, timeout_us: u64) -> bool {
+        now_us.saturating_sub(self.last_active_us) >= timeout_us
+    }
+
+    /// Drop the basis and start over on `gen_id` (same K).
+    pub fn reset(&mut self, gen_id: u16) {
+        self.gen_id = gen_id;
+        self.basis.clear();
+        self.forwarded = 0;
+        self.sources = None;
+    }
+
     /// Returns the Generation ID managed by this Recoder.
     pub fn gen_id(&self) -> u16 {
         self.gen_id
@@ -61,11 +90,11 @@ impl Recoder {
                 if factor != GfSymbol::ZERO {
                     // Eliminate
                     // candidate -= factor * slot
-                    for i in pivot_idx..k {
-                         candidate_gev[i] = candidate_gev[i] - (factor * slot.gev[i]);
+                    for (g, &s) in candidate_gev[pivot_idx..k].iter_mut().zip(&slot.gev[pivot_idx..k]) {
+                         *g = *g - (factor * s);
                     }
-                    for i in 0..candidate_data.len() {
-                         candidate_data[i] = candidate_data[i] - (factor * slot.data[i]);
+                    for (d, &s) in candidate_data.iter_mut().zip(&slot.data) {
+                         *d = *d - (factor * s);
                     }
                 }
             }
@@ -92,44 +121,150 @@ impl Recoder {
         }
     }
 
+    /// Ingest a seeded packet ([ SEED | Payload ]).
+    pub fn absorb_seeded(&mut self, data: &[u8]) -> M13Result<bool> {
+        let full = expand_seeded(data, self.gen_id, self.generation_size_k).ok_or(M13Error::WireFormatError)?;
+        self.absorb(&full)
+    }
+
+    /// Generate a seeded packet: [ SEED | sum( PRF(seed)_j * Source_j ) ].
+    /// The GEV is fixed by the seed, so the basis must span the whole generation
+    /// (full rank); a partial basis cannot hit an arbitrary GEV.
+    pub fn recode_seeded(&mut self, seed: u32) -> M13Result<Vec<u8>> {
+        let k = self.generation_size_k;
+        if self.basis.len() < k { return Err(M13Error::InvalidState); }
+        if self.sources.is_none() {
+            self.sources = Some(self.solve_sources()?);
+        }
+        let sources = self.sources.as_ref().ok_or(M13Error::InvalidState)?;
+
+        let coeffs = seeded_gev(seed, self.gen_id, k);
+        let mut out_data = alloc::vec![GfSymbol::ZERO; sources[0].len()];
+        for (src, &c) in sources.iter().zip(&coeffs) {
+            if c != 0 { symbols_add_scaled(&mut out_data, src, GfSymbol(c)); }
+        }
+
+        let mut output = Vec::with_capacity(SEED_LEN + out_data.len());
+        output.extend_from_slice(&seed.to_be_bytes());
+        for s in out_data { output.push(s.0); }
+        Ok(output)
+    }
+
+    /// Gauss-Jordan on a copy of the basis: Row j becomes Source_j (GEV = e_j).
+    fn solve_sources(&self) -> M13Result<Vec<Vec<GfSymbol>>> {
+        let k = self.generation_size_k;
+        let mut rows = self.basis.clone();
+        for col in 0..k {
+            let pivot = (col..rows.len())
+                .find(|&r| rows[r].gev[col] != GfSymbol::ZERO)
+                .ok_or(M13Error::InvalidState)?;
+            rows.swap(col, pivot);
+
+            let inv = rows[col].gev[col].inv();
+            for x in rows[col].gev.iter_mut() { *x = *x * inv; }
+            for x in rows[col].data.iter_mut() { *x = *x * inv; }
+
+            let pivot_row = rows[col].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                let factor = row.gev[col];
+                if r == col || factor == GfSymbol::ZERO { continue; }
+                symbols_add_scaled(&mut row.gev, &pivot_row.gev, factor);
+                symbols_add_scaled(&mut row.data, &pivot_row.data, factor);
+            }
+        }
+        Ok(rows.into_iter().take(k).map(|slot| slot.data).collect())
+    }
+
     /// Generate a mixed packet.
     /// P_out = sum( rand_i * Basis_i )
     pub fn recode<R: RngCore + CryptoRng>(&self, rng: &mut R) -> M13Result<Vec<u8>> {
         if self.basis.is_empty() { return Err(M13Error::InvalidState); }
 
-        let data_len = self.basis[0].data.len();
-        let k = self.generation_size_k;
-
         // 1. Generate Local Coefficients
         let mut local_coeffs = alloc::vec![0u8; self.basis.len()];
         rng.fill_bytes(&mut local_coeffs);
 
         // 2. Mix
+        Ok(self.mix(&local_coeffs))
+    }
+
+    /// Generate a sparse mixed packet: Each basis row joins with probability `density`
+    /// (clamped to 0..=1) under a non-zero coefficient. At least one row always joins.
+    /// Fewer non-zero terms = cheaper recoding and a sparser row for the receiver.
+    pub fn recode_sparse<R: RngCore + CryptoRng>(&self, rng: &mut R, density: f32) -> M13Result<Vec<u8>> {
+        if self.basis.is_empty() { return Err(M13Error::InvalidState); }
+
+        let threshold = (density.clamp(0.0, 1.0) * u32::MAX as f32) as u32;
+        let mut local_coeffs = alloc::vec![0u8; self.basis.len()];
+        for c in local_coeffs.iter_mut() {
+            if rng.next_u32() <= threshold {
+                *c = Self::nonzero_coeff(rng);
+            }
+        }
+        if local_coeffs.iter().all(|&c| c == 0) {
+            let i = rng.next_u32() as usize % local_coeffs.len();
+            local_coeffs[i] = Self::nonzero_coeff(rng);
+        }
+
+        Ok(self.mix(&local_coeffs))
+    }
+
+    /// Systematic-then-repair (as the Fountain encoder): Forward each basis row un-mixed
+    /// once, in arrival order, then fall back to `recode_sparse`.
+    pub fn recode_systematic<R: RngCore + CryptoRng>(&mut self, rng: &mut R, density: f32) -> M13Result<Vec<u8>> {
+        if self.forwarded < self.basis.len() {
+            let mut unit = alloc::vec![0u8; self.basis.len()];
+            unit[self.forwarded] = 1;
+            self.forwarded += 1;
+            return Ok(self.mix(&unit));
+        }
+        self.recode_sparse(rng, density)
+    }
+
+    /// `recode_systematic` framed for the wire: A Coded header for this generation that
+    /// advertises `current_rank` in `recoder_rank`, and the [ GEV | Payload ] body.
+    /// `symbol_id` is the caller's (nonce) counter; sealing is left to the caller too.
+    pub fn recode_packet<R: RngCore + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        density: f32,
+        symbol_id: u32,
+    ) -> M13Result<(M13Header, Vec<u8>)> {
+        let body = self.recode_systematic(rng, density)?;
+        let payload_len = u16::try_from(body.len()).map_err(|_| M13Error::WireFormatError)?;
+        let mut header = M13Header { payload_len, ..M13Header::new(PacketType::Coded, self.gen_id, symbol_id) };
+        header.set_advertised_rank(self.current_rank());
+        Ok((header, body))
+    }
+
+    fn nonzero_coeff<R: RngCore>(rng: &mut R) -> u8 {
+        1 + (rng.next_u32() % 255) as u8
+    }
+
+    /// P_out = sum( coeffs_i * Basis_i ), serialized as [ GEV | Payload ].
+    fn mix(&self, coeffs: &[u8]) -> Vec<u8> {
+        let data_len = self.basis[0].data.len();
+        let k = self.generation_size_k;
+
         let mut out_gev = alloc::vec![GfSymbol::ZERO; k];
         let mut out_data = alloc::vec![GfSymbol::ZERO; data_len];
 
-        for (i, slot) in self.basis.iter().enumerate() {
-            let alpha = GfSymbol(local_coeffs[i]);
+        for (slot, &c) in self.basis.iter().zip(coeffs) {
+            let alpha = GfSymbol(c);
             if alpha == GfSymbol::ZERO { continue; }
 
-            // Mix GEV
-            for j in 0..k {
-                out_gev[j] = out_gev[j] + (alpha * slot.gev[j]);
-            }
-            // Mix Data
-            for j in 0..data_len {
-                out_data[j] = out_data[j] + (alpha * slot.data[j]);
-            }
+            symbols_add_scaled(&mut out_gev, &slot.gev, alpha);
+            symbols_add_scaled(&mut out_data, &slot.data, alpha);
         }
 
         // 3. Serialize
         let mut output = Vec::with_capacity(k + data_len);
         for s in out_gev { output.push(s.0); }
         for s in out_data { output.push(s.0); }
-        
-        Ok(output)
+        output
     }
 
+    /// Basis size: What a relay advertises in `recoder_rank`.
     pub fn current_rank(&self) -> usize {
         self.basis.len()
     }
@@ -1,4 +1,5 @@
-use m13_rlnc::{Recoder, RlncDecoder};
+use m13_rlnc::{seeded_gev, Recoder, RlncDecoder, SEED_LEN};
+use m13_core::{M13Header, M13HeaderRef, PacketType};
 use rand_core::OsRng;
 
 #[test]
@@ -13,7 +14,7 @@ fn test_rank_check_filter() {
 
     // Packet B: [1, 0 | AA] (Duplicate)
     // Should be rejected as linearly dependent
-    assert_eq!(recoder.absorb(&p_a).unwrap(), false);
+    assert!(!recoder.absorb(&p_a).unwrap());
     assert_eq!(recoder.current_rank(), 1);
 
     // Packet C: [0, 1 | BB] (Innovative)
@@ -54,4 +55,194 @@ fn test_mesh_mixing_recovery() {
     // Verify
     let data = rx.decode().unwrap();
     assert_eq!(data[0], vec![10,10,10,10]);
-}
\ No newline at end of file
+}
+#[test]
+fn test_decoder_progress_ignores_duplicates() {
+    let mut rx = RlncDecoder::new(1, 3, 2);
+    assert_eq!((rx.rank(), rx.needed()), (0, 3));
+
+    let p1 = vec![1, 0, 0, 10, 10];
+    assert!(rx.absorb(&p1).unwrap());
+    assert!(!rx.absorb(&p1).unwrap());
+    assert_eq!((rx.rank(), rx.needed()), (1, 2));
+
+    // Linear combination of what we have: Not innovative either.
+    let p1_scaled = vec![2, 0, 0, 20, 20];
+    assert!(!rx.absorb(&p1_scaled).unwrap());
+    assert_eq!(rx.rank(), 1);
+}
+
+/// K source packets with unit GEVs: Packet i = [e_i | i repeated `size` times].
+fn source_generation(k: usize, size: usize) -> Vec<Vec<u8>> {
+    (0..k).map(|i| {
+        let mut p = vec![0u8; k + size];
+        p[i] = 1;
+        p[k..].fill(i as u8 + 1);
+        p
+    }).collect()
+}
+
+#[test]
+fn test_sparse_recode_k16_decodes() {
+    let (k, size) = (16, 8);
+    let mut rng = OsRng;
+    let mut relay = Recoder::new(7, k).unwrap();
+    for p in source_generation(k, size) { relay.absorb(&p).unwrap(); }
+
+    let mut rx = RlncDecoder::new(7, k, size);
+    let mut sent = 0;
+    let mut nonzero = 0;
+    while !rx.is_complete() && sent < 200 {
+        let pkt = relay.recode_sparse(&mut rng, 0.5).unwrap();
+        nonzero += pkt[..k].iter().filter(|&&c| c != 0).count();
+        rx.absorb(&pkt).unwrap();
+        sent += 1;
+    }
+    assert!(rx.is_complete(), "Sparse recoding never reached full rank");
+    let data = rx.decode().unwrap();
+    for (i, row) in data.iter().enumerate() {
+        assert_eq!(row, &vec![i as u8 + 1; size]);
+    }
+    // Roughly half the GEV is populated (dense recoding fills ~255/256 of it).
+    assert!(nonzero * 4 < sent * k * 3, "Rows not sparse: {} non-zero over {} packets", nonzero, sent);
+
+    // Density 0 still emits a useful (single-row) combination.
+    let pkt = relay.recode_sparse(&mut rng, 0.0).unwrap();
+    assert_eq!(pkt[..k].iter().filter(|&&c| c != 0).count(), 1);
+}
+
+#[test]
+fn test_systematic_recode_forwards_basis_first() {
+    let (k, size) = (4, 3);
+    let mut rng = OsRng;
+    let source = source_generation(k, size);
+    let mut relay = Recoder::new(1, k).unwrap();
+    for p in &source { relay.absorb(p).unwrap(); }
+
+    // Un-mixed rows, in arrival order: The receiver decodes with zero repair.
+    let mut rx = RlncDecoder::new(1, k, size);
+    for p in &source {
+        assert_eq!(&relay.recode_systematic(&mut rng, 0.5).unwrap(), p);
+    }
+    for p in &source { assert!(rx.absorb(p).unwrap()); }
+    assert!(rx.is_complete());
+
+    // Then repair.
+    let repair = relay.recode_systematic(&mut rng, 1.0).unwrap();
+    assert!(repair[..k].iter().all(|&c| c != 0));
+}
+
+#[test]
+fn test_seeded_coefficients_roundtrip() {
+    let (k, size) = (8, 16);
+    let mut rng = OsRng;
+    let source = source_generation(k, size);
+
+    // Relay learns the generation from dense mixes (its basis is not the identity).
+    let mut upstream = Recoder::new(9, k).unwrap();
+    for p in &source { upstream.absorb(p).unwrap(); }
+    let mut relay = Recoder::new(9, k).unwrap();
+    assert!(relay.recode_seeded(1).is_err(), "Empty basis cannot hit a seeded GEV");
+    while relay.current_rank() < k {
+        relay.absorb(&upstream.recode(&mut rng).unwrap()).unwrap();
+    }
+
+    // Wire: 4-byte seed instead of a K-byte GEV.
+    let mut rx = RlncDecoder::new(9, k, size);
+    let mut seed = 0u32;
+    while !rx.is_complete() && seed < 100 {
+        seed += 1;
+        let pkt = relay.recode_seeded(seed).unwrap();
+        assert_eq!(pkt.len(), SEED_LEN + size);
+        rx.absorb_seeded(&pkt).unwrap();
+    }
+    assert!(rx.is_complete());
+    let data = rx.decode().unwrap();
+    for (i, row) in data.iter().enumerate() {
+        assert_eq!(row, &vec![i as u8 + 1; size]);
+    }
+
+    // The expanded form is an ordinary [GEV | Payload] packet.
+    let pkt = relay.recode_seeded(1234).unwrap();
+    let mut explicit = seeded_gev(1234, 9, k);
+    explicit.extend_from_slice(&pkt[SEED_LEN..]);
+    let mut check = Recoder::new(9, k).unwrap();
+    assert!(check.absorb_seeded(&pkt).unwrap());
+    assert!(!check.absorb(&explicit).unwrap());
+}
+
+#[test]
+fn test_take_decoded_resets_for_reuse() {
+    let (k, size) = (4, 3);
+    let source = source_generation(k, size);
+    let mut rx = RlncDecoder::new(5, k, size);
+    assert!(rx.take_decoded().is_err());
+
+    rx.touch(1_000);
+    for p in &source { rx.absorb(p).unwrap(); }
+    let data = rx.take_decoded().unwrap();
+    assert_eq!(data.len(), k);
+    assert_eq!(data[2], vec![3; size]);
+
+    // Empty and reusable.
+    assert_eq!((rx.rank(), rx.needed(), rx.gen_id()), (0, k, 5));
+    assert!(!rx.is_complete());
+    rx.reset(6);
+    assert!(rx.absorb(&source[0]).unwrap());
+    assert_eq!((rx.rank(), rx.gen_id()), (1, 6));
+
+    // Staleness is relative to the last touch.
+    assert!(!rx.is_stale(1_500, 1_000));
+    assert!(rx.is_stale(2_000, 1_000));
+    rx.touch(2_000);
+    assert!(!rx.is_stale(2_500, 1_000));
+
+    let mut relay = Recoder::new(5, k).unwrap();
+    relay.absorb(&source[0]).unwrap();
+    relay.reset(7);
+    assert_eq!((relay.current_rank(), relay.gen_id()), (0, 7));
+    assert!(relay.is_stale(10, 10));
+}
+
+#[test]
+fn test_recoded_header_advertises_relay_rank() {
+    let (k, size) = (5, 6);
+    let mut rng = OsRng;
+    let source = source_generation(k, size);
+    let mut relay = Recoder::new(3, k).unwrap();
+    for p in &source[..3] { relay.absorb(p).unwrap(); }
+
+    let (header, body) = relay.recode_packet(&mut rng, 0.5, 77).unwrap();
+    assert_eq!(header.packet_type, PacketType::Coded);
+    assert_eq!((header.gen_id, header.symbol_id), (3, 77));
+    assert_eq!(header.payload_len as usize, body.len());
+    assert_eq!(header.advertised_rank() as usize, relay.current_rank());
+    assert_eq!(header.advertised_rank(), 3);
+
+    // Survives the wire, and the sender's flag bits never disturb it.
+    let mut flagged = header;
+    flagged.recoder_rank |= 0xC0;
+    let mut wire = [0u8; M13Header::SIZE];
+    flagged.to_bytes(&mut wire).unwrap();
+    assert_eq!(M13HeaderRef::parse(&wire).unwrap().advertised_rank(), 3);
+    assert_eq!(M13Header::from_bytes(&wire).unwrap().advertised_rank(), 3);
+
+    // Receiver pulls while the relay holds more than it does.
+    let mut rx = RlncDecoder::new(3, k, size);
+    assert!(rx.should_pull(header.advertised_rank()));
+    for _ in 0..2 {
+        let (h, b) = relay.recode_packet(&mut rng, 0.5, 78).unwrap();
+        assert_eq!(h.advertised_rank(), 3);
+        rx.absorb(&b).unwrap();
+    }
+    rx.absorb(&body).unwrap();
+    assert_eq!(rx.rank(), 3);
+    assert!(!rx.should_pull(3), "Relay has nothing left for us");
+
+    // The relay learns more; its next packet says so.
+    relay.absorb(&source[3]).unwrap();
+    let (h, b) = relay.recode_packet(&mut rng, 0.5, 79).unwrap();
+    assert_eq!(h.advertised_rank(), 4);
+    assert!(rx.should_pull(h.advertised_rank()));
+    assert!(rx.absorb(&b).unwrap());
+}
@@ -1,7 +1,7 @@
 #![no_std]
 #![forbid(unsafe_code)]
 
-use m13_core::{M13Result};
+use m13_core::{M13Error, M13Result};
 use m13_hal::{SecurityModule, PlatformClock};
 use m13_time::PhaseMonitor;
 
@@ -10,20 +10,103 @@ use m13_time::PhaseMonitor;
 const WATCHDOG_TIMEOUT_US: u64 = 20_000; // 20ms (Missed 2 cycles)
 const MAX_TEMP_CELSIUS: f32 = 85.0;      // Silicon damage risk
 const MAX_BUFFER_DEPTH_US: u64 = 100_000;// >100ms Latency is unsafe for control
+const JITTER_STRIKES: u8 = 3;
+const THERMAL_STRIKES: u8 = 3;           // Reject single-sample ADC/EMI glitches
+const THERMAL_HYSTERESIS_C: f32 = 5.0;   // Must cool to 80°C before clearing
+
+/// Per-deployment thresholds. `default()` is the 100Hz / 85°C profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SafetyLimits {
+    /// Max gap between ticks before the scheduler is considered hung.
+    pub watchdog_us: u64,
+    /// SoC temperature ceiling.
+    pub max_temp_c: f32,
+    /// Max jitter buffer depth the control loop tolerates.
+    pub max_buffer_us: u64,
+    /// Consecutive jitter violations before tripping.
+    pub jitter_strikes: u8,
+    /// Consecutive over-temperature samples before tripping.
+    pub thermal_strikes: u8,
+    /// Degrees below `max_temp_c` required to clear a pending thermal violation.
+    pub thermal_hysteresis_c: f32,
+}
+
+impl Default for SafetyLimits {
+    fn default() -> Self {
+        Self {
+            watchdog_us: WATCHDOG_TIMEOUT_US,
+            max_temp_c: MAX_TEMP_CELSIUS,
+            max_buffer_us: MAX_BUFFER_DEPTH_US,
+            jitter_strikes: JITTER_STRIKES,
+            thermal_strikes: THERMAL_STRIKES,
+            thermal_hysteresis_c: THERMAL_HYSTERESIS_C,
+        }
+    }
+}
+
+impl SafetyLimits {
+    /// Reject limits that would disable a check or trip unconditionally.
+    pub fn validate(&self) -> M13Result<()> {
+        if self.watchdog_us == 0 || self.max_buffer_us == 0 || self.jitter_strikes == 0 || self.thermal_strikes == 0 {
+            return Err(M13Error::InvalidState);
+        }
+        if !self.max_temp_c.is_finite() || !self.thermal_hysteresis_c.is_finite() || self.thermal_hysteresis_c < 0.0 {
+            return Err(M13Error::InvalidState);
+        }
+        Ok(())
+    }
+}
+
+/// Why the monitor demanded Safe Torque Off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoReason {
+    /// Scheduler missed its deadline (Software Hung).
+    Watchdog,
+    /// SoC above the thermal limit.
+    Thermal,
+    /// Link jitter requires more buffering than the control loop tolerates.
+    Jitter,
+}
+
+/// Outcome of a single safety evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafetyVerdict {
+    /// All checks passed. `pin` is the heartbeat level to drive.
+    Healthy { pin: bool },
+    /// A check failed. The runtime must bring the system to a safe state.
+    Trip(StoReason),
+}
+
+// [JITTER] RTT window for the stability check: 2x the default, so one burst cannot trip it.
+const SAFETY_RTT_WINDOW: usize = 32;
 
 pub struct SafetyMonitor {
     last_tick_us: u64,
-    phase_mon: PhaseMonitor,
+    phase_mon: PhaseMonitor<SAFETY_RTT_WINDOW>,
     consecutive_violations: u8,
+    thermal_violations: u8,
+    limits: SafetyLimits,
 }
 
 impl SafetyMonitor {
-    pub fn new(clock: &dyn PlatformClock) -> Self {
-        Self {
+    pub fn new(clock: &dyn PlatformClock, limits: SafetyLimits) -> M13Result<Self> {
+        limits.validate()?;
+        Ok(Self {
             last_tick_us: clock.now_us(),
-            phase_mon: PhaseMonitor::new(),
+            phase_mon: PhaseMonitor::with_window(),
             consecutive_violations: 0,
-        }
+            thermal_violations: 0,
+            limits,
+        })
+    }
+
+    /// Consecutive over-temperature samples seen (0 once cooled past hysteresis).
+    pub fn thermal_violations(&self) -> u8 {
+        self.thermal_violations
+    }
+
+    pub fn limits(&self) -> &SafetyLimits {
+        &self.limits
     }
 
     /// Update Link Physics Stats (Called by RX Thread).
@@ -31,54 +114,45 @@ impl SafetyMonitor {
         self.phase_mon.add_sample(rtt_us);
     }
 
-    /// The "Heartbeat" function.
-    /// Must be called at the end of every scheduler loop.
-    ///
-    /// # Arguments
-    /// * `temp_c` - Current SoC temperature.
-    /// * `hal` - Interface to trigger hardware STO if needed.
-    /// * `clock` - Time source.
-    ///
-    /// # Returns
-    /// * `Ok(bool)` - State of the Safety Pin (High/Low).
-    ///    Caller (Runtime) must write this bool to the GPIO.
-    pub fn tick(
-        &mut self,
-        temp_c: f32,
-        hal: &mut dyn SecurityModule,
-        clock: &dyn PlatformClock
-    ) -> M13Result<bool> {
+    /// Run all checks without acting on the result.
+    /// The heartbeat only advances on `Healthy`, so a tripped watchdog stays tripped.
+    pub fn evaluate(&mut self, temp_c: f32, clock: &dyn PlatformClock) -> SafetyVerdict {
         let now = clock.now_us();
         let delta = now.saturating_sub(self.last_tick_us);
 
         // 1. WATCHDOG CHECK (Livelock/Hang)
-        // If we haven't been kicked in >20ms, software is hanging.
-        if delta > WATCHDOG_TIMEOUT_US {
-            // "Software Hung" -> STO
-            // Invariant V: Fail-Safe.
-            hal.panic_and_sanitize();
+        // If we haven't been kicked within the watchdog window, software is hanging.
+        if delta > self.limits.watchdog_us {
+            return SafetyVerdict::Trip(StoReason::Watchdog);
         }
 
-        // 2. THERMAL CHECK
-        if temp_c > MAX_TEMP_CELSIUS {
-             hal.panic_and_sanitize();
+        // 2. THERMAL CHECK (Debounced, with Hysteresis)
+        // Over the limit: strike. Below (limit - hysteresis): clear.
+        // In the band between: hold the count (no flapping around the threshold).
+        if temp_c > self.limits.max_temp_c || temp_c.is_nan() {
+            self.thermal_violations = self.thermal_violations.saturating_add(1);
+        } else if temp_c <= self.limits.max_temp_c - self.limits.thermal_hysteresis_c {
+            self.thermal_violations = 0;
+        }
+
+        if self.thermal_violations >= self.limits.thermal_strikes {
+            return SafetyVerdict::Trip(StoReason::Thermal);
         }
 
         // 3. JITTER CHECK (Phase Stability)
         // We calculate the required buffer depth based on variance (4-Sigma).
-        // If the network requires >100ms buffering, it is too unstable for the robot.
+        // If the network requires more buffering than allowed, it is too unstable for the robot.
         let optimal_depth = self.phase_mon.calculate_depth();
         
-        if optimal_depth > MAX_BUFFER_DEPTH_US {
-            self.consecutive_violations += 1;
+        if optimal_depth > self.limits.max_buffer_us {
+            self.consecutive_violations = self.consecutive_violations.saturating_add(1);
         } else {
             self.consecutive_violations = 0;
         }
 
-        // 3 Strikes Rule for Jitter (Debounce)
-        if self.consecutive_violations >= 3 {
-             // "Link Unstable" -> STO
-             hal.panic_and_sanitize();
+        // N Strikes Rule for Jitter (Debounce)
+        if self.consecutive_violations >= self.limits.jitter_strikes {
+            return SafetyVerdict::Trip(StoReason::Jitter);
         }
 
         // 4. GENERATE PULSE (100 Hz Square Wave)
@@ -88,8 +162,49 @@ impl SafetyMonitor {
         // 100Hz = 10ms Period. High for 5ms, Low for 5ms.
         // (now / 5000) % 2 == 0 -> High
         let cycle_5ms = now / 5_000;
-        let pin_state = (cycle_5ms % 2) == 0;
-        
-        Ok(pin_state)
+        SafetyVerdict::Healthy { pin: cycle_5ms.is_multiple_of(2) }
+    }
+
+    /// The "Heartbeat" function.
+    /// Must be called at the end of every scheduler loop.
+    ///
+    /// # Arguments
+    /// * `temp_c` - Current SoC temperature.
+    /// * `hal` - Interface to trigger hardware STO if needed.
+    /// * `clock` - Time source.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - State of the Safety Pin (High/Low).
+    ///   Caller (Runtime) must write this bool to the GPIO.
+    pub fn tick(
+        &mut self,
+        temp_c: f32,
+        hal: &mut dyn SecurityModule,
+        clock: &dyn PlatformClock
+    ) -> M13Result<bool> {
+        // Invariant V: Fail-Safe. No handler -> hardware STO.
+        self.tick_with(temp_c, hal, clock, |_| false)
     }
-}
\ No newline at end of file
+
+    /// Like `tick`, but offers a trip to `on_sto` first (flush logs, drive GPIO low, ...).
+    /// If the handler returns `true` it has taken the system to a safe state and the
+    /// pin is reported Low. Otherwise the hardware abort fires within this same tick.
+    pub fn tick_with<F: FnMut(StoReason) -> bool>(
+        &mut self,
+        temp_c: f32,
+        hal: &mut dyn SecurityModule,
+        clock: &dyn PlatformClock,
+        mut on_sto: F
+    ) -> M13Result<bool> {
+        match self.evaluate(temp_c, clock) {
+            SafetyVerdict::Healthy { pin } => Ok(pin),
+            SafetyVerdict::Trip(reason) => {
+                if on_sto(reason) {
+                    Ok(false)
+                } else {
+                    hal.panic_and_sanitize()
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use m13_safety::{SafetyMonitor};
+use m13_safety::{SafetyMonitor, SafetyLimits, SafetyVerdict, StoReason};
 use m13_hal::{PlatformClock, SecurityModule};
 use m13_core::M13Result;
 // FIX: Use AtomicU64 instead of Cell for thread safety (Sync)
@@ -40,21 +40,21 @@ impl SecurityModule for MockHal {
 fn test_heartbeat_square_wave() {
     let clock = MockClock { time_us: AtomicU64::new(1_000_000) }; // Start at 1s
     let mut hal = MockHal;
-    let mut monitor = SafetyMonitor::new(&clock);
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
 
     // t=0ms (relative): High (0/5000 % 2 == 0)
     let s1 = monitor.tick(40.0, &mut hal, &clock).unwrap();
-    assert_eq!(s1, true);
+    assert!(s1);
 
     // t=6ms (relative): Low (crossed 5ms boundary)
     clock.advance(6_000);
     let s2 = monitor.tick(40.0, &mut hal, &clock).unwrap();
-    assert_eq!(s2, false);
+    assert!(!s2);
     
     // t=11ms (relative): High (crossed 10ms boundary)
     clock.advance(5_000);
     let s3 = monitor.tick(40.0, &mut hal, &clock).unwrap();
-    assert_eq!(s3, true);
+    assert!(s3);
 }
 
 #[test]
@@ -62,7 +62,7 @@ fn test_heartbeat_square_wave() {
 fn test_watchdog_timeout() {
     let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
     let mut hal = MockHal;
-    let mut monitor = SafetyMonitor::new(&clock);
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
 
     // Healthy tick
     monitor.tick(40.0, &mut hal, &clock).unwrap();
@@ -79,7 +79,7 @@ fn test_watchdog_timeout() {
 fn test_jitter_instability() {
     let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
     let mut hal = MockHal;
-    let mut monitor = SafetyMonitor::new(&clock);
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
 
     // Feed terrible RTT samples (>1s variance)
     // This will cause calculated buffer depth to explode > 100ms
@@ -92,4 +92,110 @@ fn test_jitter_instability() {
     let _ = monitor.tick(40.0, &mut hal, &clock);
     let _ = monitor.tick(40.0, &mut hal, &clock);
     let _ = monitor.tick(40.0, &mut hal, &clock); // BOOM
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_handler_consumes_watchdog() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let mut hal = MockHal;
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
+
+    monitor.tick(40.0, &mut hal, &clock).unwrap();
+    clock.advance(30_000);
+
+    let mut seen = None;
+    let pin = monitor.tick_with(40.0, &mut hal, &clock, |reason| { seen = Some(reason); true }).unwrap();
+    assert!(!pin);
+    assert_eq!(seen, Some(StoReason::Watchdog));
+
+    // Latched: The watchdog stays tripped until the runtime recovers.
+    assert_eq!(monitor.evaluate(40.0, &clock), SafetyVerdict::Trip(StoReason::Watchdog));
+}
+
+#[test]
+#[should_panic(expected = "STO_TRIGGERED")]
+fn test_handler_declines_falls_back_to_abort() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let mut hal = MockHal;
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
+
+    // Sustained over-temperature (3 strikes).
+    for _ in 0..3 {
+        let _ = monitor.tick_with(90.0, &mut hal, &clock, |reason| {
+            assert_eq!(reason, StoReason::Thermal);
+            false
+        });
+    }
+}
+
+#[test]
+fn test_reject_zero_watchdog() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let limits = SafetyLimits { watchdog_us: 0, ..SafetyLimits::default() };
+    assert!(SafetyMonitor::new(&clock, limits).is_err());
+}
+
+#[test]
+fn test_50hz_loop_no_false_watchdog() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let mut hal = MockHal;
+    // 50Hz = 20ms period. Watchdog = 2 missed cycles.
+    let limits = SafetyLimits { watchdog_us: 40_000, ..SafetyLimits::default() };
+    let mut monitor = SafetyMonitor::new(&clock, limits).unwrap();
+
+    for _ in 0..10 {
+        clock.advance(15_000);
+        monitor.tick(40.0, &mut hal, &clock).unwrap();
+    }
+
+    // A 35ms stall would trip the 100Hz default, but is legal here.
+    clock.advance(35_000);
+    monitor.tick(40.0, &mut hal, &clock).unwrap();
+}
+
+#[test]
+fn test_thermal_single_spike_ignored() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let mut hal = MockHal;
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
+
+    // EMI blip on the ADC.
+    monitor.tick(120.0, &mut hal, &clock).unwrap();
+    assert_eq!(monitor.thermal_violations(), 1);
+    clock.advance(5_000);
+    monitor.tick(40.0, &mut hal, &clock).unwrap();
+    assert_eq!(monitor.thermal_violations(), 0);
+}
+
+#[test]
+fn test_thermal_sustained_trips() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
+
+    assert!(matches!(monitor.evaluate(90.0, &clock), SafetyVerdict::Healthy { .. }));
+    assert!(matches!(monitor.evaluate(90.0, &clock), SafetyVerdict::Healthy { .. }));
+    assert_eq!(monitor.evaluate(90.0, &clock), SafetyVerdict::Trip(StoReason::Thermal));
+}
+
+#[test]
+fn test_thermal_hysteresis_reset() {
+    let clock = MockClock { time_us: AtomicU64::new(1_000_000) };
+    let mut hal = MockHal;
+    let mut monitor = SafetyMonitor::new(&clock, SafetyLimits::default()).unwrap();
+
+    monitor.tick(86.0, &mut hal, &clock).unwrap();
+    monitor.tick(86.0, &mut hal, &clock).unwrap();
+    assert_eq!(monitor.thermal_violations(), 2);
+
+    // 83°C is under the limit but inside the hysteresis band: count holds.
+    monitor.tick(83.0, &mut hal, &clock).unwrap();
+    assert_eq!(monitor.thermal_violations(), 2);
+
+    // Cooling to 80°C clears the pending violation.
+    monitor.tick(80.0, &mut hal, &clock).unwrap();
+    assert_eq!(monitor.thermal_violations(), 0);
+
+    // Two more hot samples must not trip (count restarted).
+    monitor.tick(86.0, &mut hal, &clock).unwrap();
+    monitor.tick(86.0, &mut hal, &clock).unwrap();
+}
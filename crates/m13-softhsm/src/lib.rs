@@ -0,0 +1,80 @@
+#![no_std]
+#![forbid(unsafe_code)]
+
+use m13_core::{M13Error, M13Result};
+use m13_hal::SecurityModule;
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey, signature::Signer};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use zeroize::Zeroize;
+
+/// SEC1 uncompressed P-256 point (`Epoch0Frame::legacy_aik_pub`).
+pub const AIK_PUBLIC_LEN: usize = 65;
+/// Raw r || s.
+pub const AIK_SIGNATURE_LEN: usize = 64;
+
+/// Pure-Rust `SecurityModule` for targets without a TPM/HSM (bare-metal `no_std`, CI).
+/// Randomness: ChaCha20 seeded once from the platform's entropy source.
+/// Signing: A P-256 AIK in RAM, ECDSA over SHA-256 of the digest, which is exactly
+/// what `verify_epoch0` checks. Offers no key isolation: Prefer a real TPM where one exists.
+pub struct SoftwareSecurityModule {
+    rng: ChaCha20Rng,
+    aik: SigningKey,
+}
+
+impl SoftwareSecurityModule {
+    /// Deterministic: The same seed yields the same random stream and the same AIK.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let aik = SigningKey::random(&mut rng);
+        Self { rng, aik }
+    }
+
+    /// Seeds from `source` (a TRNG peripheral, `getrandom`, ...). It is read once, here.
+    pub fn from_entropy<R: RngCore + CryptoRng>(source: &mut R) -> M13Result<Self> {
+        let mut seed = [0u8; 32];
+        source.try_fill_bytes(&mut seed).map_err(|_| M13Error::RngFailure)?;
+        let hsm = Self::from_seed(seed);
+        seed.zeroize();
+        Ok(hsm)
+    }
+
+    /// Replaces the generated AIK with a provisioned one (32-byte big-endian scalar), so
+    /// the verifier's pinned AIK survives reboots.
+    pub fn with_aik(mut self, secret: &[u8]) -> M13Result<Self> {
+        self.aik = SigningKey::from_slice(secret).map_err(|_| M13Error::CryptoFailure)?;
+        Ok(self)
+    }
+
+    /// The AIK public key to pin on the verifier.
+    pub fn aik_public(&self) -> [u8; AIK_PUBLIC_LEN] {
+        let point = VerifyingKey::from(&self.aik).to_encoded_point(false);
+        let mut out = [0u8; AIK_PUBLIC_LEN];
+        out.copy_from_slice(point.as_bytes());
+        out
+    }
+}
+
+impl SecurityModule for SoftwareSecurityModule {
+    fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()> {
+        self.rng.try_fill_bytes(buf).map_err(|_| M13Error::RngFailure)
+    }
+
+    // RFC 6979 nonces: No RNG on the signing path, so a weak stream cannot leak the AIK.
+    fn sign_digest(&mut self, digest: &[u8], signature: &mut [u8]) -> M13Result<usize> {
+        if signature.len() < AIK_SIGNATURE_LEN { return Err(M13Error::WireFormatError); }
+        let sig: Signature = self.aik.try_sign(digest).map_err(|_| M13Error::CryptoFailure)?;
+        signature[..AIK_SIGNATURE_LEN].copy_from_slice(&sig.to_bytes());
+        Ok(AIK_SIGNATURE_LEN)
+    }
+
+    // `&self` cannot wipe the keys: The platform's panic handler must reset the SoC
+    // (clearing RAM), as on every other M13 target.
+    fn panic_and_sanitize(&self) -> ! {
+        panic!("M13 security panic: software HSM halted");
+    }
+
+    fn attestation_key(&self) -> Option<[u8; AIK_PUBLIC_LEN]> {
+        Some(self.aik_public())
+    }
+}
@@ -0,0 +1,66 @@
+use m13_core::M13Error;
+use m13_hal::SecurityModule;
+use m13_softhsm::{SoftwareSecurityModule, AIK_SIGNATURE_LEN};
+use p256::ecdsa::{Signature, VerifyingKey, signature::Verifier};
+use rand_core::OsRng;
+
+#[test]
+fn test_sign_digest_verifies_against_aik_public() {
+    let mut hsm = SoftwareSecurityModule::from_entropy(&mut OsRng).unwrap();
+    let vk = VerifyingKey::from_sec1_bytes(&hsm.aik_public()).unwrap();
+
+    let digest = [0x5C; 32];
+    let mut sig = [0u8; 256];
+    let len = hsm.sign_digest(&digest, &mut sig).unwrap();
+    assert_eq!(len, AIK_SIGNATURE_LEN);
+
+    let parsed = Signature::from_slice(&sig[..len]).unwrap();
+    assert!(vk.verify(&digest, &parsed).is_ok());
+
+    // Bound to the digest.
+    assert!(vk.verify(&[0x5D; 32], &parsed).is_err());
+}
+
+#[test]
+fn test_seed_determines_stream_and_aik() {
+    let mut a = SoftwareSecurityModule::from_seed([7; 32]);
+    let mut b = SoftwareSecurityModule::from_seed([7; 32]);
+    let mut c = SoftwareSecurityModule::from_seed([8; 32]);
+    assert_eq!(a.aik_public(), b.aik_public());
+    assert_ne!(a.aik_public(), c.aik_public());
+
+    let (mut ra, mut rb, mut rc) = ([0u8; 64], [0u8; 64], [0u8; 64]);
+    a.get_random_bytes(&mut ra).unwrap();
+    b.get_random_bytes(&mut rb).unwrap();
+    c.get_random_bytes(&mut rc).unwrap();
+    assert_eq!(ra, rb);
+    assert_ne!(ra, rc);
+    assert_ne!(ra, [0u8; 64]);
+
+    // The stream advances.
+    a.get_random_bytes(&mut rb).unwrap();
+    assert_ne!(ra, rb);
+}
+
+#[test]
+fn test_provisioned_aik() {
+    let secret = [0x42; 32];
+    let mut a = SoftwareSecurityModule::from_seed([1; 32]).with_aik(&secret).unwrap();
+    let b = SoftwareSecurityModule::from_seed([2; 32]).with_aik(&secret).unwrap();
+    assert_eq!(a.aik_public(), b.aik_public());
+
+    let mut sig = [0u8; 64];
+    a.sign_digest(&[0x11; 32], &mut sig).unwrap();
+    let vk = VerifyingKey::from_sec1_bytes(&b.aik_public()).unwrap();
+    assert!(vk.verify(&[0x11; 32], &Signature::from_slice(&sig).unwrap()).is_ok());
+
+    // Zero is not a valid scalar.
+    assert!(matches!(SoftwareSecurityModule::from_seed([1; 32]).with_aik(&[0; 32]), Err(M13Error::CryptoFailure)));
+}
+
+#[test]
+fn test_short_signature_buffer_rejected() {
+    let mut hsm = SoftwareSecurityModule::from_seed([3; 32]);
+    let mut sig = [0u8; 63];
+    assert!(matches!(hsm.sign_digest(&[0; 32], &mut sig), Err(M13Error::WireFormatError)));
+}
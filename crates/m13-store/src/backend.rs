@@ -18,4 +18,7 @@ pub trait StorageBackend: Send + Sync {
     
     /// Verify if ID exists.
     fn exists(&self, id: u32) -> bool;
+
+    /// Enumerate stored IDs (ascending).
+    fn list(&self) -> M13Result<Vec<u32>>;
 }
\ No newline at end of file
@@ -70,4 +70,20 @@ impl StorageBackend for FileSystemBackend {
     fn exists(&self, id: u32) -> bool {
         self.get_path(id).exists()
     }
+
+    fn list(&self) -> M13Result<Vec<u32>> {
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&self.root).map_err(|_| M13Error::HalError)? {
+            let entry = entry.map_err(|_| M13Error::HalError)?;
+            // Only committed bundles: `bundle_<id>.bin` (in-flight `.tmp` files are skipped).
+            let name = entry.file_name();
+            let id = name.to_str()
+                .and_then(|n| n.strip_prefix("bundle_"))
+                .and_then(|n| n.strip_suffix(".bin"))
+                .and_then(|n| n.parse::<u32>().ok());
+            if let Some(id) = id { ids.push(id); }
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
 }
\ No newline at end of file
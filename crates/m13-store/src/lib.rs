@@ -5,15 +5,29 @@ extern crate std;
 pub mod backend;
 #[cfg(feature = "std")]
 pub mod fs_backend;
+pub mod mem_backend;
+pub mod shamir;
 
 use alloc::vec::Vec;
 use alloc::boxed::Box;
-// FIX: Removed unused M13Error import
-use m13_core::{M13Result};
+use m13_core::{M13Error, M13Result};
 use m13_aont::{AontTransform, PrivacyMode};
 use backend::StorageBackend;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 use rand_core::{RngCore, CryptoRng};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Each share backend holds its anchor share under this ID.
+pub const ANCHOR_SHARE_ID: u32 = 0xE000_0000;
+// Serialized anchor: [seed u32 BE][mac_key 32]
+const ANCHOR_LEN: usize = 36;
+
+/// Integrity tag prepended to the plaintext INSIDE the AONT transform.
+/// A new seed destroys it together with the payload.
+pub const BUNDLE_TAG_LEN: usize = 32;
 
 extern crate alloc;
 
@@ -22,6 +36,23 @@ extern crate alloc;
 #[derive(Zeroize, ZeroizeOnDrop)]
 struct VolatileAnchor {
     seed: u32,
+    mac_key: [u8; 32],
+}
+
+impl VolatileAnchor {
+    fn to_bytes(&self) -> [u8; ANCHOR_LEN] {
+        let mut out = [0u8; ANCHOR_LEN];
+        out[..4].copy_from_slice(&self.seed.to_be_bytes());
+        out[4..].copy_from_slice(&self.mac_key);
+        out
+    }
+
+    fn from_bytes(buf: &[u8]) -> M13Result<Self> {
+        if buf.len() != ANCHOR_LEN { return Err(M13Error::InvalidState); }
+        let mut mac_key = [0u8; 32];
+        mac_key.copy_from_slice(&buf[4..]);
+        Ok(Self { seed: u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]), mac_key })
+    }
 }
 
 pub struct BundleStore<R> {
@@ -36,50 +67,138 @@ impl<R: RngCore + CryptoRng> BundleStore<R> {
     /// WARNING: Any data on disk from previous boots is now logically erased.
     pub fn new(backend: Box<dyn StorageBackend>, mut rng: R) -> Self {
         let seed = rng.next_u32();
+        let mut mac_key = [0u8; 32];
+        rng.fill_bytes(&mut mac_key);
         Self {
             backend,
-            anchor: VolatileAnchor { seed },
+            anchor: VolatileAnchor { seed, mac_key },
             rng,
         }
     }
 
+    /// Survivable mode: As `new`, then Shamir-split the anchor across `share_backends`
+    /// (one share each, any `threshold` reconstruct). Wiping shares below the
+    /// threshold restores passive zeroization.
+    pub fn new_with_shares(
+        backend: Box<dyn StorageBackend>,
+        rng: R,
+        share_backends: &mut [&mut dyn StorageBackend],
+        threshold: u8,
+    ) -> M13Result<Self> {
+        let count = u8::try_from(share_backends.len()).map_err(|_| M13Error::InvalidState)?;
+        let mut store = Self::new(backend, rng);
+
+        let mut secret = store.anchor.to_bytes();
+        let shares = shamir::split(&secret, threshold, count, &mut store.rng);
+        secret.zeroize();
+        for (target, share) in share_backends.iter_mut().zip(shares?.iter()) {
+            let mut bytes = share.to_bytes();
+            let written = target.write(ANCHOR_SHARE_ID, &bytes);
+            bytes.zeroize();
+            written?;
+        }
+        Ok(store)
+    }
+
+    /// Boot in survivable mode: Rebuild the anchor from whichever share backends still hold a share.
+    /// `InvalidState` if fewer than the threshold survive.
+    pub fn recover_from_shares(
+        backend: Box<dyn StorageBackend>,
+        rng: R,
+        share_backends: &[&dyn StorageBackend],
+    ) -> M13Result<Self> {
+        let mut shares = Vec::new();
+        for source in share_backends {
+            if let Ok(mut bytes) = source.read(ANCHOR_SHARE_ID) {
+                if let Ok(share) = shamir::Share::from_bytes(&bytes) { shares.push(share); }
+                bytes.zeroize();
+            }
+        }
+
+        let mut secret = shamir::combine(&shares)?;
+        let anchor = VolatileAnchor::from_bytes(&secret);
+        secret.zeroize();
+        Ok(Self { backend, anchor: anchor?, rng })
+    }
+
+    /// Tag = HMAC-SHA256(mac_key, id || payload). Binding `id` rejects swapped slots.
+    fn tag(&self, id: u32, payload: &[u8]) -> M13Result<HmacSha256> {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.anchor.mac_key)
+            .map_err(|_| M13Error::CryptoFailure)?;
+        mac.update(&id.to_be_bytes());
+        mac.update(payload);
+        Ok(mac)
+    }
+
     /// Commit a Bundle (Spec §8.2.1).
-    /// Applies Passive Zeroization (AONT Mode A) before writing.
+    /// Applies Passive Zeroization (AONT Mode A) over `tag || payload` before writing.
     pub fn commit(&mut self, id: u32, payload: &[u8]) -> M13Result<()> {
+        let mut framed = Vec::with_capacity(BUNDLE_TAG_LEN + payload.len());
+        framed.extend_from_slice(&self.tag(id, payload)?.finalize().into_bytes());
+        framed.extend_from_slice(payload);
+
         // 1. Transform (Passive Zeroization)
         let transformed = AontTransform::transform(
-            payload, 
+            &framed, 
             self.anchor.seed, 
             PrivacyMode::ModeA, 
             &mut self.rng
-        )?;
+        );
+        framed.zeroize();
+        let transformed = transformed?;
 
         // 2. Atomic Write
         self.backend.write(id, &transformed)
     }
 
     /// Retrieve a Bundle.
+    /// `AuthFail` if the tag does not verify: Seed changed (reboot) or the media rotted.
     pub fn retrieve(&self, id: u32) -> M13Result<Vec<u8>> {
         // 1. Read from Disk
         let transformed = self.backend.read(id)?;
 
         // 2. Recover
         // If power was lost, 'self.anchor.seed' is new.
-        // Recovery will produce algebraic noise.
-        let plaintext = AontTransform::recover(
+        // Recovery will produce algebraic noise, and the tag will not verify.
+        let mut framed = AontTransform::recover(
             &transformed, 
             self.anchor.seed, 
             PrivacyMode::ModeA
         )?;
+        if framed.len() < BUNDLE_TAG_LEN {
+            framed.zeroize();
+            return Err(M13Error::AuthFail);
+        }
 
-        Ok(plaintext)
+        // 3. Verify (Constant Time)
+        let (tag, payload) = framed.split_at(BUNDLE_TAG_LEN);
+        if self.tag(id, payload)?.verify_slice(tag).is_err() {
+            framed.zeroize();
+            return Err(M13Error::AuthFail);
+        }
+        framed.drain(..BUNDLE_TAG_LEN);
+        Ok(framed)
     }
 
     pub fn release(&mut self, id: u32) -> M13Result<()> {
         self.backend.delete(id)
     }
+
+    /// IDs of every stored bundle (ascending).
+    pub fn list(&self) -> M13Result<Vec<u32>> {
+        self.backend.list()
+    }
+
+    /// Power down: The anchor is zeroized, the media survives.
+    pub fn into_backend(self) -> Box<dyn StorageBackend> {
+        let Self { backend, .. } = self;
+        backend
+    }
 }
 
+/// Log entries live in the high ID range: `LOG_ID_BASE | counter`.
+const LOG_ID_BASE: u32 = 0xF000_0000;
+
 /// Forensic Logger (Spec §7.3.2).
 /// Wraps the BundleStore to secure logs.
 pub struct ForensicLogger<R> {
@@ -94,9 +213,23 @@ impl<R: RngCore + CryptoRng> ForensicLogger<R> {
 
     pub fn log(&mut self, msg: &[u8]) -> M13Result<()> {
         // Use High IDs (0xF000_0000+) for logs
-        let id = 0xF000_0000 | self.log_counter;
+        let id = LOG_ID_BASE | self.log_counter;
         self.store.commit(id, msg)?;
         self.log_counter += 1;
         Ok(())
     }
+
+    /// Recovered log entries in counter order.
+    /// Entries from a previous boot yield `AuthFail` (the anchor that sealed them is gone).
+    pub fn replay(&self) -> impl Iterator<Item = M13Result<Vec<u8>>> + '_ {
+        let (ids, err) = match self.store.list() {
+            Ok(mut ids) => {
+                ids.retain(|id| id & LOG_ID_BASE == LOG_ID_BASE);
+                (ids, None)
+            }
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        err.map(Err).into_iter()
+            .chain(ids.into_iter().map(move |id| self.store.retrieve(id)))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use crate::backend::StorageBackend;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use m13_core::{M13Error, M13Result};
+use zeroize::Zeroize;
+
+/// RAM-only NVM (Tests, diskless targets).
+/// Contents die with the process, like the Volatile Anchor itself.
+#[derive(Default)]
+pub struct MemoryBackend {
+    slots: BTreeMap<u32, Vec<u8>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self { slots: BTreeMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn write(&mut self, id: u32, data: &[u8]) -> M13Result<()> {
+        // Replaced slots are scrubbed, not just freed.
+        if let Some(mut old) = self.slots.insert(id, data.to_vec()) {
+            old.zeroize();
+        }
+        Ok(())
+    }
+
+    fn read(&self, id: u32) -> M13Result<Vec<u8>> {
+        self.slots.get(&id).cloned().ok_or(M13Error::InvalidState)
+    }
+
+    fn delete(&mut self, id: u32) -> M13Result<()> {
+        if let Some(mut old) = self.slots.remove(&id) {
+            old.zeroize();
+        }
+        Ok(())
+    }
+
+    fn exists(&self, id: u32) -> bool {
+        self.slots.contains_key(&id)
+    }
+
+    fn list(&self) -> M13Result<Vec<u32>> {
+        Ok(self.slots.keys().copied().collect())
+    }
+}
+
+impl Drop for MemoryBackend {
+    fn drop(&mut self) {
+        for data in self.slots.values_mut() {
+            data.zeroize();
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+use m13_core::{M13Error, M13Result};
+use m13_math::GfSymbol;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Wire layout: [threshold u8][x u8][y ...]
+pub const SHARE_HEADER_LEN: usize = 2;
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Share {
+    pub threshold: u8,
+    pub x: u8,
+    pub y: Vec<u8>,
+}
+
+impl Share {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(SHARE_HEADER_LEN + self.y.len());
+        out.push(self.threshold);
+        out.push(self.x);
+        out.extend_from_slice(&self.y);
+        out
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> M13Result<Self> {
+        if buf.len() <= SHARE_HEADER_LEN { return Err(M13Error::WireFormatError); }
+        let (threshold, x) = (buf[0], buf[1]);
+        if threshold == 0 || x == 0 { return Err(M13Error::WireFormatError); }
+        Ok(Self { threshold, x, y: buf[SHARE_HEADER_LEN..].to_vec() })
+    }
+}
+
+/// Shamir split over GF(2^8): Any `threshold` of the `shares` shares reconstruct `secret`.
+/// Each secret byte is the constant term of its own random degree T-1 polynomial;
+/// share i holds every polynomial evaluated at x = i (1..=N).
+pub fn split<R: RngCore + CryptoRng>(secret: &[u8], threshold: u8, shares: u8, rng: &mut R) -> M13Result<Vec<Share>> {
+    if secret.is_empty() || threshold == 0 || shares < threshold { return Err(M13Error::InvalidState); }
+
+    let mut out: Vec<Share> = (1..=shares)
+        .map(|x| Share { threshold, x, y: alloc::vec![0u8; secret.len()] })
+        .collect();
+
+    // coeffs[0] = secret byte, coeffs[1..T] = random.
+    let mut coeffs = alloc::vec![0u8; threshold as usize];
+    for (b, &s) in secret.iter().enumerate() {
+        coeffs[0] = s;
+        rng.fill_bytes(&mut coeffs[1..]);
+
+        for share in out.iter_mut() {
+            // Horner (Constant Time: Coefficients are secret).
+            let x = GfSymbol(share.x);
+            let mut acc = GfSymbol::ZERO;
+            for &c in coeffs.iter().rev() {
+                acc = acc.mul_safe(x) + GfSymbol(c);
+            }
+            share.y[b] = acc.0;
+        }
+    }
+    coeffs.zeroize();
+    Ok(out)
+}
+
+/// Reconstruct the secret by Lagrange interpolation at x = 0.
+/// `InvalidState` below the threshold or on inconsistent shares.
+pub fn combine(shares: &[Share]) -> M13Result<Vec<u8>> {
+    let first = shares.first().ok_or(M13Error::InvalidState)?;
+    let threshold = first.threshold as usize;
+    let len = first.y.len();
+    if shares.len() < threshold { return Err(M13Error::InvalidState); }
+
+    let used = &shares[..threshold];
+    for (i, s) in used.iter().enumerate() {
+        if s.threshold != first.threshold || s.y.len() != len || s.x == 0 {
+            return Err(M13Error::InvalidState);
+        }
+        if used[..i].iter().any(|o| o.x == s.x) { return Err(M13Error::InvalidState); }
+    }
+
+    // L_i(0) = PROD_{j != i} x_j / (x_j - x_i). The x's are public: Table math is fine.
+    let mut secret = alloc::vec![0u8; len];
+    for (i, s) in used.iter().enumerate() {
+        let mut basis = GfSymbol::ONE;
+        for (j, o) in used.iter().enumerate() {
+            if i == j { continue; }
+            basis = basis * GfSymbol(o.x) * (GfSymbol(o.x) - GfSymbol(s.x)).inv();
+        }
+        for (out, &y) in secret.iter_mut().zip(&s.y) {
+            *out ^= GfSymbol(y).mul_safe(basis).0;
+        }
+    }
+    Ok(secret)
+}
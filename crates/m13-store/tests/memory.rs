@@ -0,0 +1,97 @@
+use m13_core::{M13Error, M13Result};
+use std::sync::{Arc, Mutex};
+use m13_store::backend::StorageBackend;
+use m13_store::mem_backend::MemoryBackend;
+use m13_store::{BundleStore, ForensicLogger};
+use rand_core::OsRng;
+
+#[test]
+fn test_memory_backend_semantics() {
+    let mut backend = MemoryBackend::new();
+    assert!(!backend.exists(7));
+    assert!(matches!(backend.read(7), Err(M13Error::InvalidState)));
+
+    backend.write(7, b"first").unwrap();
+    backend.write(7, b"second").unwrap();
+    assert!(backend.exists(7));
+    assert_eq!(backend.read(7).unwrap(), b"second");
+
+    backend.delete(7).unwrap();
+    backend.delete(7).unwrap(); // Idempotent, like the filesystem backend
+    assert!(!backend.exists(7));
+    assert!(matches!(backend.read(7), Err(M13Error::InvalidState)));
+}
+
+#[test]
+fn test_passive_zeroization_on_reboot_in_memory() {
+    let id = 99;
+    let payload = b"Classified Coordinates";
+
+    // Session 1: Commit and read back under the same anchor.
+    let mut store = BundleStore::new(Box::new(MemoryBackend::new()), OsRng);
+    store.commit(id, payload).unwrap();
+    assert_eq!(store.retrieve(id).unwrap(), payload);
+
+    // Power cut: The anchor dies, the media survives.
+    let backend = store.into_backend();
+    let raw = backend.read(id).unwrap();
+    assert!(raw.windows(payload.len()).all(|w| w != payload), "Data stored in plaintext!");
+
+    // Session 2: New seed, same media.
+    let store = BundleStore::new(backend, OsRng);
+    assert!(matches!(store.retrieve(id), Err(M13Error::AuthFail)), "Data survived power loss! Zeroization failed.");
+}
+
+/// Media the test can tamper with while the store (and its anchor) stays alive.
+#[derive(Clone, Default)]
+struct SharedBackend(Arc<Mutex<MemoryBackend>>);
+impl StorageBackend for SharedBackend {
+    fn write(&mut self, id: u32, data: &[u8]) -> M13Result<()> { self.0.lock().unwrap().write(id, data) }
+    fn read(&self, id: u32) -> M13Result<Vec<u8>> { self.0.lock().unwrap().read(id) }
+    fn delete(&mut self, id: u32) -> M13Result<()> { self.0.lock().unwrap().delete(id) }
+    fn exists(&self, id: u32) -> bool { self.0.lock().unwrap().exists(id) }
+    fn list(&self) -> M13Result<Vec<u32>> { self.0.lock().unwrap().list() }
+}
+
+#[test]
+fn test_tag_binds_bundle_id() {
+    let mut media = SharedBackend::default();
+    let mut store = BundleStore::new(Box::new(media.clone()), OsRng);
+    store.commit(1, b"alpha").unwrap();
+    store.commit(2, b"bravo").unwrap();
+    assert_eq!(store.retrieve(1).unwrap(), b"alpha");
+    assert_eq!(store.retrieve(2).unwrap(), b"bravo");
+
+    // Swap slots on the media: Same anchor, wrong id.
+    let one = media.read(1).unwrap();
+    let two = media.read(2).unwrap();
+    media.write(1, &two).unwrap();
+    media.write(2, &one).unwrap();
+    assert!(matches!(store.retrieve(1), Err(M13Error::AuthFail)));
+    assert!(matches!(store.retrieve(2), Err(M13Error::AuthFail)));
+}
+
+#[test]
+fn test_forensic_logger_without_filesystem() {
+    let store = BundleStore::new(Box::new(MemoryBackend::new()), OsRng);
+    let mut logger = ForensicLogger::new(store);
+    logger.log(b"boot").unwrap();
+    logger.log(b"fault: overcurrent").unwrap();
+    logger.log(b"sto engaged").unwrap();
+
+    let entries: Vec<Vec<u8>> = logger.replay().map(|e| e.unwrap()).collect();
+    assert_eq!(entries, [&b"boot"[..], b"fault: overcurrent", b"sto engaged"]);
+}
+
+#[test]
+fn test_replay_skips_non_log_bundles() {
+    let media = SharedBackend::default();
+    let mut store = BundleStore::new(Box::new(media.clone()), OsRng);
+    store.commit(5, b"mission bundle").unwrap();
+    let mut logger = ForensicLogger::new(store);
+    logger.log(b"only entry").unwrap();
+
+    assert_eq!(media.list().unwrap(), [5, 0xF000_0000]);
+    let entries: Vec<Vec<u8>> = logger.replay().map(|e| e.unwrap()).collect();
+    assert_eq!(entries, [b"only entry"]);
+}
@@ -0,0 +1,73 @@
+use m13_core::M13Error;
+use m13_store::backend::StorageBackend;
+use m13_store::mem_backend::MemoryBackend;
+use m13_store::shamir::{combine, split, Share};
+use m13_store::{BundleStore, ANCHOR_SHARE_ID};
+use rand_core::OsRng;
+
+#[test]
+fn test_split_5_any_3_reconstruct() {
+    let secret = 0xDEAD_BEEFu32.to_be_bytes();
+    let shares = split(&secret, 3, 5, &mut OsRng).unwrap();
+    assert_eq!(shares.len(), 5);
+
+    // Every 3-subset of the 5 shares.
+    for a in 0..5 {
+        for b in a + 1..5 {
+            for c in b + 1..5 {
+                let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                assert_eq!(combine(&subset).unwrap(), secret, "Subset {:?}", (a, b, c));
+            }
+        }
+    }
+
+    // Two shares are below the threshold.
+    let pair = [shares[0].clone(), shares[3].clone()];
+    assert!(matches!(combine(&pair), Err(M13Error::InvalidState)));
+}
+
+#[test]
+fn test_share_encoding_and_bad_input() {
+    let shares = split(b"anchor", 2, 3, &mut OsRng).unwrap();
+    let decoded: Vec<Share> = shares.iter().map(|s| Share::from_bytes(&s.to_bytes()).unwrap()).collect();
+    assert_eq!(combine(&decoded[1..]).unwrap(), b"anchor");
+
+    // Duplicate x is not two shares.
+    let dup = [shares[0].clone(), shares[0].clone()];
+    assert!(matches!(combine(&dup), Err(M13Error::InvalidState)));
+    assert!(split(b"x", 4, 3, &mut OsRng).is_err());
+    assert!(split(b"x", 0, 3, &mut OsRng).is_err());
+    assert!(Share::from_bytes(&[2, 0, 7]).is_err());
+}
+
+#[test]
+fn test_bundle_survives_controlled_reboot() {
+    let payload = b"Mission Plan";
+    let mut shares: Vec<MemoryBackend> = (0..5).map(|_| MemoryBackend::new()).collect();
+
+    // Boot 1: Commit under a shared anchor, then power down.
+    let nvm = {
+        let mut targets: Vec<&mut dyn StorageBackend> = shares.iter_mut().map(|s| s as &mut dyn StorageBackend).collect();
+        let mut store = BundleStore::new_with_shares(Box::new(MemoryBackend::new()), OsRng, &mut targets, 3).unwrap();
+        store.commit(1, payload).unwrap();
+        store.into_backend()
+    };
+
+    // Boot 2: Two share holders lost; the remaining three rebuild the anchor.
+    shares[0].delete(ANCHOR_SHARE_ID).unwrap();
+    shares[4].delete(ANCHOR_SHARE_ID).unwrap();
+    let nvm = {
+        let sources: Vec<&dyn StorageBackend> = shares.iter().map(|s| s as &dyn StorageBackend).collect();
+        let store = BundleStore::recover_from_shares(nvm, OsRng, &sources).unwrap();
+        assert_eq!(store.retrieve(1).unwrap(), payload);
+        store.into_backend()
+    };
+
+    // Tamper: One more share wiped. Below the threshold, the bundle is gone for good.
+    shares[2].delete(ANCHOR_SHARE_ID).unwrap();
+    let sources: Vec<&dyn StorageBackend> = shares.iter().map(|s| s as &dyn StorageBackend).collect();
+    assert!(matches!(
+        BundleStore::recover_from_shares(nvm, OsRng, &sources),
+        Err(M13Error::InvalidState)
+    ));
+}
@@ -24,7 +24,7 @@ impl Eq for OrderedPacket {}
 // Logic: If Self < Other (Time), we return Greater, so Self floats to top.
 impl PartialOrd for OrderedPacket {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(other.release_time_us.cmp(&self.release_time_us))
+        Some(self.cmp(other))
     }
 }
 impl Ord for OrderedPacket {
@@ -33,6 +33,15 @@ impl Ord for OrderedPacket {
     }
 }
 
+/// What `push` sacrifices when the buffer already holds `max_packets`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Evict whichever packet (queued or incoming) releases last. Keeps the near-term playout intact.
+    DropFurthest,
+    /// Refuse the incoming packet. Keeps what is already scheduled.
+    RejectIncoming,
+}
+
 pub struct JitterBuffer {
     /// Fixed Playout Delay (Target Latency).
     /// Calculated as Avg_RTT + 4 * StdDev_RTT.
@@ -40,17 +49,31 @@ pub struct JitterBuffer {
     
     /// The Priority Queue (Earliest Deadline First).
     queue: BinaryHeap<OrderedPacket>,
+
+    /// Capacity cap (packets). A stuck queue is itself a safety issue.
+    max_packets: usize,
+    policy: OverflowPolicy,
     
     /// Stats
     pub drop_late_count: u64,
+    pub drop_overflow_count: u64,
 }
 
 impl JitterBuffer {
+    /// Unbounded buffer.
     pub fn new(buffer_depth_us: u64) -> Self {
+        Self::with_capacity(buffer_depth_us, usize::MAX, OverflowPolicy::DropFurthest)
+    }
+
+    /// Buffer holding at most `max_packets` (min 1); `policy` picks the victim when full.
+    pub fn with_capacity(buffer_depth_us: u64, max_packets: usize, policy: OverflowPolicy) -> Self {
         Self {
             buffer_depth_us,
             queue: BinaryHeap::new(),
+            max_packets: core::cmp::max(max_packets, 1),
+            policy,
             drop_late_count: 0,
+            drop_overflow_count: 0,
         }
     }
 
@@ -75,6 +98,23 @@ impl JitterBuffer {
             return; 
         }
 
+        if self.queue.len() >= self.max_packets {
+            self.drop_overflow_count += 1;
+            match self.policy {
+                OverflowPolicy::RejectIncoming => return,
+                OverflowPolicy::DropFurthest => {
+                    // O(n), but only on overflow, and n is capped.
+                    let furthest = self.queue.iter().map(|p| p.release_time_us).max().unwrap_or(0);
+                    if release_time >= furthest { return; }
+                    let mut packets = core::mem::take(&mut self.queue).into_vec();
+                    if let Some(i) = packets.iter().position(|p| p.release_time_us == furthest) {
+                        packets.swap_remove(i);
+                    }
+                    self.queue = BinaryHeap::from(packets);
+                }
+            }
+        }
+
         self.queue.push(OrderedPacket {
             header,
             payload,
@@ -96,7 +136,45 @@ impl JitterBuffer {
         None
     }
 
+    /// Retune the playout delay. Queued packets are re-stamped by the same delta, so
+    /// the adaptive loop takes effect immediately instead of one depth later.
+    /// A shrink never drops: A deadline pulled into the past just releases on the next `pop`.
+    pub fn set_depth(&mut self, buffer_depth_us: u64) {
+        let old = self.buffer_depth_us;
+        if buffer_depth_us == old { return; }
+        self.buffer_depth_us = buffer_depth_us;
+        if self.queue.is_empty() { return; }
+
+        // A uniform shift preserves relative order (ties aside), so the rebuild is O(n).
+        let mut packets = core::mem::take(&mut self.queue).into_vec();
+        for p in packets.iter_mut() {
+            p.release_time_us = if buffer_depth_us > old {
+                p.release_time_us.saturating_add(buffer_depth_us - old)
+            } else {
+                p.release_time_us.saturating_sub(old - buffer_depth_us)
+            };
+        }
+        self.queue = BinaryHeap::from(packets);
+    }
+
+    /// Release time (us) of the earliest queued packet. None when empty.
+    pub fn next_release_us(&self) -> Option<u64> {
+        self.queue.peek().map(|p| p.release_time_us)
+    }
+
+    pub fn depth(&self) -> u64 {
+        self.buffer_depth_us
+    }
+
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.max_packets
+    }
 }
\ No newline at end of file
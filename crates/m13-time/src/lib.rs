@@ -1,29 +1,56 @@
 #![no_std]
 
 mod jitter;
-pub use jitter::JitterBuffer;
+pub use jitter::{JitterBuffer, OverflowPolicy};
+
+/// RTT samples are clamped to this (10 s): Nothing real takes longer, and one garbage
+/// sample must not swamp the window's mean and variance.
+pub const MAX_RTT_SAMPLE_US: u64 = 10_000_000;
 
 /// Calculates safety margins for Control Loops.
 /// Continuously samples RTT to determine the optimal buffer depth.
-pub struct PhaseMonitor {
-    rtt_samples: [u64; 16],
+/// `N` = RTT window (samples). Longer windows characterize slow jitter; shorter ones react faster.
+pub struct PhaseMonitor<const N: usize = 16> {
+    rtt_samples: [u64; N],
     idx: usize,
     count: usize,
 }
 
 impl PhaseMonitor {
     pub fn new() -> Self {
+        Self::with_window()
+    }
+}
+
+impl Default for PhaseMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> PhaseMonitor<N> {
+    const NON_EMPTY: () = assert!(N > 0, "PhaseMonitor window must hold at least one sample");
+
+    /// Monitor with an `N`-sample window, e.g. `PhaseMonitor::<64>::with_window()`.
+    pub fn with_window() -> Self {
+        let () = Self::NON_EMPTY;
         Self {
-            rtt_samples: [0; 16],
+            rtt_samples: [0; N],
             idx: 0,
             count: 0,
         }
     }
 
+    /// Window size `N`.
+    pub const fn window(&self) -> usize {
+        N
+    }
+
+    /// Record one RTT, clamped to `MAX_RTT_SAMPLE_US`.
     pub fn add_sample(&mut self, rtt_us: u64) {
-        self.rtt_samples[self.idx] = rtt_us;
-        self.idx = (self.idx + 1) % 16;
-        if self.count < 16 { self.count += 1; }
+        self.rtt_samples[self.idx] = rtt_us.min(MAX_RTT_SAMPLE_US);
+        self.idx = (self.idx + 1) % N;
+        if self.count < N { self.count += 1; }
     }
 
     /// Calculates the optimal Buffer Depth (D_buf).
@@ -32,29 +59,45 @@ impl PhaseMonitor {
     pub fn calculate_depth(&self) -> u64 {
         if self.count == 0 { return 100_000; } // Default 100ms safe start
         
-        // 1. Mean
-        let sum: u64 = self.rtt_samples.iter().take(self.count).sum();
-        let mean = sum / self.count as u64;
+        // 1. Mean (u128: Any window size, no wrap)
+        let sum: u128 = self.rtt_samples.iter().take(self.count).map(|&s| s as u128).sum();
+        let mean = (sum / self.count as u128) as u64;
 
         // 2. Variance -> StdDev
-        let mut var_sum = 0;
+        // Squares accumulate in u128: Even u64::MAX diffs cannot wrap, only saturate.
+        let mut var_sum: u128 = 0;
         for &s in self.rtt_samples.iter().take(self.count) {
-             let diff = if s > mean { s - mean } else { mean - s };
-             var_sum += diff * diff;
+             let diff = s.abs_diff(mean) as u128;
+             var_sum = var_sum.saturating_add(diff * diff);
         }
-        let variance = var_sum / self.count as u64;
+        let variance = u64::try_from(var_sum / self.count as u128).unwrap_or(u64::MAX);
         
         // Integer Sqrt approximation (no_std)
         let std_dev = int_sqrt(variance);
 
         // 3. Safety Margin (4 Sigma)
         // Spec §7.2.1
-        let safety_margin = 4 * std_dev;
+        let safety_margin = std_dev.saturating_mul(4);
         
         // 4. Proc Offset (Fixed Crypto overhead ~50us)
         let proc_offset = 50;
 
-        mean + safety_margin + proc_offset
+        mean.saturating_add(safety_margin).saturating_add(proc_offset)
+    }
+
+    /// Robust alternative to `calculate_depth`: D = RTT_p + Delta_Proc.
+    /// `p` in 0.0..=1.0 (e.g. 0.99). Lower-rank percentile over the window, so with
+    /// 16 samples p99 is the second-largest: A single outlier cannot set the depth.
+    pub fn calculate_depth_percentile(&self, p: f32) -> u64 {
+        if self.count == 0 { return 100_000; } // Default 100ms safe start
+
+        let mut window = self.rtt_samples;
+        let window = &mut window[..self.count];
+        window.sort_unstable();
+
+        let rank = (p.clamp(0.0, 1.0) * (self.count - 1) as f32) as usize;
+        let proc_offset = 50;
+        window[rank] + proc_offset
     }
 }
 
@@ -62,7 +105,7 @@ impl PhaseMonitor {
 fn int_sqrt(n: u64) -> u64 {
     if n < 2 { return n; }
     let mut x = n;
-    let mut y = (x + 1) / 2;
+    let mut y = x / 2 + x % 2; // ceil(n / 2) without overflowing at u64::MAX
     while y < x {
         x = y;
         y = (n / x + x) / 2;
@@ -1,4 +1,4 @@
-use m13_time::{JitterBuffer, PhaseMonitor};
+use m13_time::{JitterBuffer, OverflowPolicy, PhaseMonitor, MAX_RTT_SAMPLE_US};
 use m13_core::{M13Header, PacketType, M13_MAGIC};
 
 fn mock_header() -> M13Header {
@@ -49,7 +49,7 @@ fn test_phase_calc() {
     
     let d = pm.calculate_depth();
     // Mean 10000 + 0 Var + 50 Proc = 10050
-    assert!(d >= 10_050 && d < 10_100);
+    assert!((10_050..10_100).contains(&d));
     
     // Jittery RTT (10ms, 20ms alternating)
     let mut pm2 = PhaseMonitor::new();
@@ -60,4 +60,121 @@ fn test_phase_calc() {
     // Target = 15000 + (4*5000) + 50 = 35050
     let d2 = pm2.calculate_depth();
     assert!(d2 > 35_000);
-}
\ No newline at end of file
+}
+#[test]
+fn test_percentile_ignores_outlier() {
+    let mut pm = PhaseMonitor::new();
+    assert_eq!(pm.calculate_depth_percentile(0.99), 100_000);
+
+    // 15 samples around 10ms, then one 1-second spike.
+    for i in 0..15 { pm.add_sample(10_000 + i * 100); }
+    pm.add_sample(1_000_000);
+
+    let sigma = pm.calculate_depth();
+    let p99 = pm.calculate_depth_percentile(0.99);
+    assert!(sigma > 1_000_000, "4-sigma estimate should explode: {}", sigma);
+    assert_eq!(p99, 11_400 + 50);
+    assert_eq!(pm.calculate_depth_percentile(0.5), 10_700 + 50);
+    // p100 is the maximum, spike included.
+    assert_eq!(pm.calculate_depth_percentile(1.0), 1_000_050);
+}
+
+/// Fill with `slow` samples, then `fast` ones; return (depth, window) once the fast run has been seen.
+fn depth_after_shift<const N: usize>(slow: u64, fast: u64, fast_count: usize) -> (u64, usize) {
+    let mut pm = PhaseMonitor::<N>::with_window();
+    for _ in 0..64 { pm.add_sample(slow); }
+    for _ in 0..fast_count { pm.add_sample(fast); }
+    (pm.calculate_depth(), pm.window())
+}
+
+#[test]
+fn test_window_size_is_configurable() {
+    assert_eq!(PhaseMonitor::new().window(), 16);
+
+    // 8 fast samples after a slow run: An 8-sample window has fully forgotten
+    // the slow regime, a 32-sample window still mixes both.
+    let (short, n_short) = depth_after_shift::<8>(50_000, 10_000, 8);
+    let (long, n_long) = depth_after_shift::<32>(50_000, 10_000, 8);
+    assert_eq!((n_short, n_long), (8, 32));
+    assert_eq!(short, 10_050);
+    assert!(long > 50_000, "Long window forgot the slow regime: {}", long);
+
+    // Partially filled windows only average what they hold.
+    let mut pm = PhaseMonitor::<4>::with_window();
+    pm.add_sample(20_000);
+    assert_eq!(pm.calculate_depth(), 20_050);
+}
+
+#[test]
+fn test_capacity_bound_holds() {
+    let depth = 10_000;
+    let now = 1_000_000;
+
+    // Drop-furthest: Far-future floods cannot displace the near-term schedule.
+    let mut jb = JitterBuffer::with_capacity(depth, 4, OverflowPolicy::DropFurthest);
+    for i in 0..4 { jb.push(mock_header(), vec![i], now + 1_000 * i as u64, now); }
+    jb.push(mock_header(), vec![0xFF], now + 1_000_000, now); // Furthest: Itself dropped
+    jb.push(mock_header(), vec![9], now + 500, now);          // Evicts release +3000
+    assert_eq!(jb.len(), 4);
+    assert_eq!(jb.drop_overflow_count, 2);
+    assert_eq!(jb.drop_late_count, 0);
+
+    let mut order = Vec::new();
+    while let Some((_, p)) = jb.pop(u64::MAX) { order.push(p[0]); }
+    assert_eq!(order, vec![0, 9, 1, 2]);
+
+    // Reject-incoming: The queue keeps what it already scheduled.
+    let mut jb = JitterBuffer::with_capacity(depth, 4, OverflowPolicy::RejectIncoming);
+    for i in 0..100u64 { jb.push(mock_header(), vec![i as u8], now + i, now); }
+    assert_eq!(jb.len(), 4);
+    assert_eq!(jb.capacity(), 4);
+    assert_eq!(jb.drop_overflow_count, 96);
+    assert_eq!(jb.pop(u64::MAX).unwrap().1, vec![0]);
+}
+
+#[test]
+fn test_set_depth_restamps_queue() {
+    let now = 1_000_000;
+    let mut jb = JitterBuffer::new(50_000);
+    for i in 0..3u64 { jb.push(mock_header(), vec![i as u8], now + i * 1_000, now); }
+
+    // Shrink 50ms -> 10ms: The queue releases 40ms earlier.
+    jb.set_depth(10_000);
+    assert!(jb.pop(now + 9_999).is_none());
+    assert_eq!(jb.pop(now + 10_000).unwrap().1, vec![0]);
+
+    // Shrink past the remaining deadlines: Released now, not dropped as late.
+    jb.set_depth(0);
+    let t = now + 10_000;
+    assert_eq!(jb.pop(t).unwrap().1, vec![1]);
+    assert_eq!(jb.pop(t).unwrap().1, vec![2]);
+    assert_eq!(jb.drop_late_count, 0);
+
+    // Grow: Queued packets are held back by the delta.
+    jb.push(mock_header(), vec![3], t, t);
+    jb.set_depth(20_000);
+    assert!(jb.pop(t + 19_999).is_none());
+    assert_eq!(jb.pop(t + 20_000).unwrap().1, vec![3]);
+}
+
+#[test]
+fn test_extreme_rtt_samples_stay_bounded() {
+    // Garbage alternating with a sane path: Clamped on the way in, no overflow on the way out.
+    let mut pm = PhaseMonitor::new();
+    for i in 0..16 { pm.add_sample(if i % 2 == 0 { u64::MAX } else { 10_000 }); }
+    let d = pm.calculate_depth();
+    // Mean ~5s, sigma ~5s: Bounded by mean + 4 * (clamp / 2) + proc.
+    let bound = MAX_RTT_SAMPLE_US / 2 + 4 * (MAX_RTT_SAMPLE_US / 2) + 50;
+    assert!(d > MAX_RTT_SAMPLE_US && d <= bound, "depth {}", d);
+    assert_eq!(pm.calculate_depth_percentile(1.0), MAX_RTT_SAMPLE_US + 50);
+
+    // Slow satellite link (50s RTTs) saturates at the clamp.
+    let mut sat = PhaseMonitor::new();
+    for _ in 0..16 { sat.add_sample(50_000_000); }
+    assert_eq!(sat.calculate_depth(), MAX_RTT_SAMPLE_US + 50);
+
+    // A huge window of maximal samples.
+    let mut wide = PhaseMonitor::<4096>::with_window();
+    for _ in 0..4096 { wide.add_sample(u64::MAX); }
+    assert_eq!(wide.calculate_depth(), MAX_RTT_SAMPLE_US + 50);
+}
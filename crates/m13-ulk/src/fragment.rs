@@ -1,45 +1,235 @@
 extern crate alloc; // [FIX] Removed #![no_std]
 use alloc::vec::Vec;
-use m13_core::{M13Result, M13Error};
+use m13_core::{M13Result, M13Error, M13Header, PacketType};
+use m13_hal::{PhysicalInterface, PeerAddr};
+use m13_time::PhaseMonitor;
 
+/// Control-message fragment body. Payload = [total_len u16 BE][offset u16 BE][chunk].
+pub const FRAGMENT_CHUNK_SIZE: usize = 1000;
+const FRAGMENT_PREFIX: usize = 4;
+/// [ARQ] Fragment ACK payload: The acknowledged fragment's prefix, echoed.
+pub const FRAGMENT_ACK_LEN: usize = FRAGMENT_PREFIX;
+/// [DOS] Default ceiling on a reassembled control message.
+pub const DEFAULT_MAX_REASSEMBLY_LEN: usize = 10240;
+// [ARQ] RTO bounds. The floor keeps a LAN's microsecond RTT from retransmitting into its own ACKs.
+const ARQ_RTO_MIN_US: u64 = 50_000;
+const ARQ_RTO_MAX_US: u64 = 3_200_000;
+// [ARQ] Transmissions per fragment before the message is abandoned.
+const ARQ_MAX_ATTEMPTS: u32 = 6;
+
+/// Reassembles one fragmented control message at a time.
+/// Completion requires every byte of `total_len` to be covered, in any arrival order.
 pub struct FragmentAssembler {
     buffer: Vec<u8>,
     expected_len: usize,
+    // One bit per byte of `buffer`: Set once that byte has arrived.
+    covered: Vec<u64>,
+    covered_bytes: usize,
+    // Local arrival of the first fragment of the message in progress.
+    started_us: u64,
+    max_len: usize,
 }
 
 impl FragmentAssembler {
     pub fn new() -> Self {
-        Self { buffer: Vec::new(), expected_len: 0 }
+        Self::with_max_len(DEFAULT_MAX_REASSEMBLY_LEN)
+    }
+
+    /// Assembler refusing messages over `max_len` bytes (capped at the u16 wire limit).
+    /// Small targets lower it to bound reassembly memory.
+    pub fn with_max_len(max_len: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            expected_len: 0,
+            covered: Vec::new(),
+            covered_bytes: 0,
+            started_us: 0,
+            max_len: max_len.min(u16::MAX as usize),
+        }
+    }
+
+    /// Abandon the message in progress, if any.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.covered.clear();
+        self.expected_len = 0;
+        self.covered_bytes = 0;
     }
 
-    pub fn ingest(&mut self, payload: &[u8]) -> M13Result<Option<Vec<u8>>> {
-        if payload.len() < 4 { return Err(M13Error::WireFormatError); }
-        
-        let total_len = u16::from_be_bytes(payload[0..2].try_into().unwrap()) as usize;
-        let offset = u16::from_be_bytes(payload[2..4].try_into().unwrap()) as usize;
-        let data = &payload[4..];
+    /// True if a partial message has been pending for `timeout_us` or longer.
+    pub fn is_expired(&self, now: u64, timeout_us: u64) -> bool {
+        self.expected_len != 0 && now.saturating_sub(self.started_us) >= timeout_us
+    }
+
+    fn is_covered(&self, i: usize) -> bool {
+        self.covered[i / 64] & (1 << (i % 64)) != 0
+    }
 
-        if self.buffer.is_empty() {
+    /// Absorb one fragment that arrived at `now`. Returns the message once every byte is in.
+    pub fn ingest(&mut self, payload: &[u8], now: u64) -> M13Result<Option<Vec<u8>>> {
+        if payload.len() < FRAGMENT_PREFIX { return Err(M13Error::WireFormatError); }
+
+        let total_len = u16::from_be_bytes([payload[0], payload[1]]) as usize;
+        let offset = u16::from_be_bytes([payload[2], payload[3]]) as usize;
+        let data = &payload[FRAGMENT_PREFIX..];
+
+        if self.expected_len == 0 {
+            if total_len == 0 || total_len > self.max_len { return Err(M13Error::WireFormatError); }
             self.expected_len = total_len;
-            if total_len > 10240 { return Err(M13Error::WireFormatError); }
+            self.started_us = now;
             self.buffer.resize(total_len, 0);
+            self.covered.resize(total_len.div_ceil(64), 0);
         }
 
-        if total_len != self.expected_len { 
-            self.buffer.clear();
-            return Err(M13Error::InvalidState); 
+        // A different message started mid-reassembly: Abandon the old one entirely.
+        if total_len != self.expected_len {
+            self.reset();
+            return Err(M13Error::InvalidState);
         }
         if offset + data.len() > self.expected_len { return Err(M13Error::WireFormatError); }
 
-        self.buffer[offset..offset+data.len()].copy_from_slice(data);
+        // Overlaps must agree with what already arrived. Duplicates are then no-ops.
+        for (i, &b) in data.iter().enumerate() {
+            let pos = offset + i;
+            if self.is_covered(pos) && self.buffer[pos] != b {
+                self.reset();
+                return Err(M13Error::InvalidState);
+            }
+        }
+        for (i, &b) in data.iter().enumerate() {
+            let pos = offset + i;
+            if !self.is_covered(pos) {
+                self.buffer[pos] = b;
+                self.covered[pos / 64] |= 1 << (pos % 64);
+                self.covered_bytes += 1;
+            }
+        }
 
-        if offset + data.len() == self.expected_len {
-             let res = self.buffer.clone();
-             self.buffer.clear();
-             self.expected_len = 0;
-             return Ok(Some(res));
+        if self.covered_bytes == self.expected_len {
+            let res = core::mem::take(&mut self.buffer);
+            self.reset();
+            return Ok(Some(res));
         }
 
         Ok(None)
     }
 }
+
+impl Default for FragmentAssembler {
+    fn default() -> Self { Self::new() }
+}
+
+struct OutstandingFragment {
+    offset: u16,
+    frame: Vec<u8>,
+    acked: bool,
+    attempts: u32,
+    next_tx_us: u64,
+}
+
+/// [ARQ] Reliable delivery for small control messages, outside the Fountain path.
+/// Fragments are framed exactly as `send_fragmented` frames them (so `FragmentAssembler`
+/// reassembles them unchanged), with `gen_id` carrying the (non-zero) message id. The
+/// receiver answers each one with `ack_fragment`; unACKed fragments go out again after the
+/// RTO, backing off exponentially per fragment.
+pub struct ReliableFragmentSender {
+    msg_id: u16,
+    target: Option<PeerAddr>,
+    fragments: Vec<OutstandingFragment>,
+    rto_us: u64,
+}
+
+impl ReliableFragmentSender {
+    pub fn new(ptype: PacketType, msg_id: u16, payload: &[u8], target: Option<PeerAddr>) -> M13Result<Self> {
+        if msg_id == 0 || payload.is_empty() || payload.len() > u16::MAX as usize { return Err(M13Error::WireFormatError); }
+        let total_len = payload.len() as u16;
+
+        let mut fragments = Vec::new();
+        for (i, chunk) in payload.chunks(FRAGMENT_CHUNK_SIZE).enumerate() {
+            let offset = (i * FRAGMENT_CHUNK_SIZE) as u16;
+            let body_len = FRAGMENT_PREFIX + chunk.len();
+            let header = M13Header { payload_len: body_len as u16, ..M13Header::new(ptype, msg_id, offset as u32) };
+            let mut frame = alloc::vec![0u8; 32 + body_len];
+            header.to_bytes(&mut frame)?;
+            frame[32..34].copy_from_slice(&total_len.to_be_bytes());
+            frame[34..36].copy_from_slice(&offset.to_be_bytes());
+            frame[36..].copy_from_slice(chunk);
+            fragments.push(OutstandingFragment { offset, frame, acked: false, attempts: 0, next_tx_us: 0 });
+        }
+
+        Ok(Self { msg_id, target, fragments, rto_us: PhaseMonitor::new().calculate_depth() })
+    }
+
+    /// Derive the RTO from measured RTT: `PhaseMonitor`'s mean + 4 sigma is the classic
+    /// SRTT + 4 RTTVAR. Unsampled monitors give 100ms.
+    pub fn update_rto<const N: usize>(&mut self, phase: &PhaseMonitor<N>) {
+        self.rto_us = phase.calculate_depth().clamp(ARQ_RTO_MIN_US, ARQ_RTO_MAX_US);
+    }
+
+    pub fn rto_us(&self) -> u64 {
+        self.rto_us
+    }
+
+    /// Send every fragment that is new or past its RTO; returns how many went out.
+    /// `Err(InvalidState)` once a fragment has exhausted its attempts: The peer is
+    /// unreachable and the message is abandoned.
+    pub fn poll(&mut self, phy: &mut dyn PhysicalInterface, now: u64) -> M13Result<usize> {
+        let mut sent = 0;
+        for frag in self.fragments.iter_mut().filter(|f| !f.acked && now >= f.next_tx_us) {
+            if frag.attempts >= ARQ_MAX_ATTEMPTS { return Err(M13Error::InvalidState); }
+            match phy.send(&frag.frame, self.target) {
+                Ok(_) => {},
+                // Socket full: Retry next poll without spending an attempt.
+                Err(nb::Error::WouldBlock) => return Ok(sent),
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            let backoff = self.rto_us << frag.attempts.min(6);
+            frag.attempts += 1;
+            frag.next_tx_us = now.saturating_add(backoff.min(ARQ_RTO_MAX_US));
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// When the next fragment falls due. None once every fragment is ACKed.
+    pub fn next_tx_us(&self) -> Option<u64> {
+        self.fragments.iter().filter(|f| !f.acked).map(|f| f.next_tx_us).min()
+    }
+
+    /// Consume a fragment ACK. True if it acknowledged an outstanding fragment of this message.
+    pub fn on_ack(&mut self, header: &M13Header) -> bool {
+        if header.packet_type != PacketType::Ack || header.payload_len as usize != FRAGMENT_ACK_LEN || header.gen_id != self.msg_id {
+            return false;
+        }
+        match self.fragments.iter_mut().find(|f| f.offset as u32 == header.symbol_id && !f.acked) {
+            Some(frag) => { frag.acked = true; true },
+            None => false,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fragments.iter().all(|f| f.acked)
+    }
+
+    /// Fragments still awaiting an ACK.
+    pub fn outstanding(&self) -> usize {
+        self.fragments.iter().filter(|f| !f.acked).count()
+    }
+}
+
+/// [ARQ] Receiver: Acknowledge one reliable fragment (`header` + its payload). The ACK is a
+/// `PacketType::Ack` echoing the message id (`gen_id`), fragment offset (`symbol_id`) and
+/// fragment prefix (payload). Unsealed: The handshake it serves has no keys yet, so a forger
+/// must guess the random message id, and gains only a stalled retransmission.
+pub fn ack_fragment(phy: &mut dyn PhysicalInterface, header: &M13Header, payload: &[u8], target: Option<PeerAddr>) -> M13Result<()> {
+    if header.gen_id == 0 || payload.len() < FRAGMENT_PREFIX { return Err(M13Error::WireFormatError); }
+    let offset = u16::from_be_bytes([payload[2], payload[3]]);
+    let ack = M13Header { payload_len: FRAGMENT_ACK_LEN as u16, ..M13Header::new(PacketType::Ack, header.gen_id, offset as u32) };
+    let mut frame = [0u8; 32 + FRAGMENT_ACK_LEN];
+    ack.to_bytes(&mut frame)?;
+    frame[32..].copy_from_slice(&payload[..FRAGMENT_PREFIX]);
+    match phy.send(&frame, target) {
+        Ok(_) | Err(nb::Error::WouldBlock) => Ok(()),
+        Err(nb::Error::Other(e)) => Err(e),
+    }
+}
@@ -5,29 +5,118 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 use alloc::collections::{VecDeque, BTreeMap};
 
-use log::{info, warn};
+use log::{debug, info, warn};
 
-use m13_core::{M13Result, M13Header, PacketType, M13_MAGIC, M13Error};
-use m13_core::KYBER_PK_LEN_1024;
-use m13_core::KYBER_CT_LEN_1024;
+use m13_core::{M13Result, M13Header, M13HeaderRef, PacketType, M13Error};
 
-use m13_hal::{PhysicalInterface, SecurityModule, PlatformClock, PeerAddr};
+use m13_hal::{PhysicalInterface, SecurityModule, PlatformClock, PeerAddr, ECN_CE, ECN_NOT_ECT};
 use m13_mem::{SlabAllocator, FrameLease};
-use m13_cipher::{M13Cipher, SessionKey};
-use m13_pqc::{KyberKeypair, kyber_encapsulate, kyber_decapsulate, dsa_sign, DsaKeypair};
-use m13_raptor::{FountainEncoder, FountainDecoder};
-use m13_flow::Pacer;
+use m13_cipher::{M13Cipher, DirectionalKeys, cookie_tag, cookie_verify, derive_session_key};
+use m13_cipher::{expand_coefficients, mesh_seal, mesh_open, MESH_SALT_LEN};
+use m13_pqc::{KyberKeypair, KemProfile, kyber_encapsulate, kyber_decapsulate, dsa_sign, DsaKeypair, DSA_CTX_HANDSHAKE};
+use m13_pqc::{X25519Keypair, hybrid_encapsulate, hybrid_decapsulate, X25519_KEY_SIZE, DILITHIUM_SIGNATURE_SIZE};
+use m13_attest::{PcrBank, Epoch0Frame, generate_attestation, verify_epoch0, bind_nonce};
+use m13_raptor::{FountainEncoder, FountainDecoder, LDPC_OVERHEAD_S};
+use m13_rlnc::{Recoder, MAX_RLNC_GENERATION};
+use m13_flow::{generate_chaff, Pacer, CHAFF_MARKER, SEGMENT_OVERHEAD};
+use m13_time::{JitterBuffer, OverflowPolicy, PhaseMonitor};
 
-use rand_core::SeedableRng;
+use rand_core::{RngCore, SeedableRng};
 use rand_chacha::ChaCha20Rng;
+use zeroize::Zeroizing;
 
 pub mod fragment;
 pub mod session;
+pub mod stats;
 use session::Session;
+use stats::MeteredPhy;
+pub use stats::KernelStats;
+pub use fragment::{ReliableFragmentSender, ack_fragment};
 
 // VECTOR BATCH SIZE
 const BATCH_SIZE: usize = 64;
-const RAPTOR_SYMBOL_SIZE: usize = 1024;
+/// [FEC] Raptor symbol size (bytes). 1024 + 32-byte header fits any IPv6 path.
+pub const DEFAULT_SYMBOL_SIZE: usize = 1024;
+// [FEC] Symbol size bounds. The upper one needs jumbo frames end to end.
+pub const MIN_SYMBOL_SIZE: usize = 64;
+pub const MAX_SYMBOL_SIZE: usize = 8192;
+/// [BBR] CBR floor (bits/sec) per peer: No pacer drops below it, and chaff tops each peer up to it.
+pub const DEFAULT_CBR_FLOOR_BPS: u64 = 10_000_000;
+// [BBR] ACK payload: Receiver timestamp (u64 BE) + [ECN] CE-marked symbols (u32 BE) +
+// the acked gen_id (u16 BE) and highest symbol_id (u32 BE).
+const ACK_PAYLOAD_LEN: usize = 18;
+// [BBR] Bound on in-flight generations tracked for RTT.
+const MAX_TX_GEN_LOG: usize = 256;
+// [BBR] An unACKed generation stops counting as in flight after this long (lost, or
+// undecodable). Floor for paths whose RTprop is tiny.
+const INFLIGHT_LOSS_FLOOR_US: u64 = 200_000;
+// [PMTU] GSO: Segment payload = link MTU minus IPv6 (40) + UDP (8) + 24 bytes of headroom.
+const GSO_SEGMENT_OVERHEAD: usize = 72;
+const GSO_MIN_SEGMENT: usize = 512;
+// [PHYSICS] GSO: Burst cap under the 64KB super-packet limit.
+const GSO_MAX_BYTES: usize = 65_000;
+// [JITTER] Per-session playout cap. A peer cannot park unbounded payloads in the future.
+const JITTER_MAX_PACKETS: usize = 1024;
+// [JITTER] A kernel RX timestamp older than this is a clock step, not queueing. Ignored.
+const RX_TS_MAX_AGE_US: u64 = 1_000_000;
+/// [JITTER] `recoder_rank` flag on every symbol of a generation whose plaintext opens with
+/// the sender's PTP origin time (u64 BE ns). Raptor generations are never recoded, so the
+/// byte is free; it is AAD-bound like the rest of the header.
+pub const ORIGIN_STAMPED: u8 = 0x80;
+const ORIGIN_STAMP_LEN: usize = 8;
+/// [FEC] `recoder_rank` flag on the final symbol of every burst for a generation (the first
+/// one and each repair). A receiver still short when it arrives NACKs instead of waiting.
+pub const LAST_SYMBOL: u8 = 0x40;
+// [FEC] NACK payload: gen_id (u16 BE) + independent symbols still missing (u16 BE).
+const NACK_PAYLOAD_LEN: usize = 4;
+// [FEC] Repair bursts per generation. The sender then forgets it; the receiver abandons it
+// at the next end marker instead of waiting on a sender that has given up.
+const MAX_REPAIR_ROUNDS: u8 = 3;
+// [FEC] Sent, unACKed generations kept for repair (oldest dropped first).
+const MAX_REPAIR_HISTORY: usize = 64;
+// [FEC] Decoded (or, on a relay, delivered onward) (peer, gen_id)s remembered, so a late
+// repair symbol or end marker cannot reopen them.
+const MAX_COMPLETED_GENS: usize = 256;
+// [JITTER] A PTP origin further back than this is a clock fault, not transit. Ignored.
+const PTP_MAX_AGE_US: u64 = 10_000_000;
+/// [REKEY] Generations per key epoch. Also the ceiling: The remaining 4096 gen_ids
+/// of headroom cover in-flight stragglers before the 16-bit space wraps.
+pub const DEFAULT_REKEY_INTERVAL_GENS: u16 = 0xF000;
+/// [DOS] Hub: Established sessions silent for this long are evicted.
+pub const DEFAULT_SESSION_IDLE_TIMEOUT_US: u64 = 30_000_000;
+/// [NAT] Send a KeepAlive after this long without TX to a session.
+pub const DEFAULT_KEEPALIVE_INTERVAL_US: u64 = 15_000_000;
+// [NAT] Control nonce space (KeepAlive, Goodbye, chaff, ACK, NACK): gen_id 0, symbol_id with
+// the top bit set, counted by tx_sequence. Data symbol_ids never approach 2^31, so
+// (gen_id, symbol_id) never collides.
+const KEEPALIVE_SYMBOL_BASE: u32 = 0x8000_0000;
+// [HANDSHAKE] ClientHello retransmission: RTO doubles per attempt up to the cap.
+const HANDSHAKE_RTO_INITIAL_US: u64 = 200_000;
+const HANDSHAKE_RTO_MAX_US: u64 = 3_200_000;
+const HANDSHAKE_MAX_ATTEMPTS: u32 = 8;
+// [DOS] Bound on partially decoded generations (each holds L x symbol_size matrices).
+const MAX_DATA_DECODERS: usize = 64;
+// [FEC] A generation whose fresh symbols keep reducing to zero can never reach full rank.
+// Past this many it is abandoned instead of pinning a decoder until LRU eviction.
+const DECODE_STALL_DEPENDENT: usize = 8;
+// [DOS] Relay: Bound on generations being recoded (each holds up to L rows of a symbol).
+const MAX_RELAY_GENS: usize = 64;
+// [MESH] Relay repairs mix the whole basis: Innovative at any receiver still short, w.h.p.
+const RELAY_REPAIR_DENSITY: f32 = 1.0;
+// [DOS] Hub: Half-open sessions (no cipher yet) get far less rope against handshake floods.
+const HALF_OPEN_TIMEOUT_US: u64 = 5_000_000;
+// [DOS] Hub: Sweep cadence. Eviction is coarse by design.
+const SESSION_SWEEP_INTERVAL_US: u64 = 1_000_000;
+// [DOS] Partial control-message reassembly is dropped after this long. Fragments go out
+// back to back, so a message still incomplete this late is never finishing.
+const FRAGMENT_REASSEMBLY_TIMEOUT_US: u64 = 1_000_000;
+// [DOS] Hub: Source addresses one peer may claim. Bounds the routing table per session.
+const MAX_VIPS_PER_SESSION: usize = 8;
+// [DOS] Hub: Retry cookies rotate this often. The current and previous window are accepted.
+const COOKIE_WINDOW_US: u64 = 30_000_000;
+const COOKIE_LEN: usize = 16;
+// [ATTEST] Hub challenge carried in the server hello, ahead of the signature.
+const ATTEST_CHALLENGE_LEN: usize = 32;
 
 fn is_allowed(addr: &PeerAddr) -> bool {
     match addr {
@@ -36,23 +125,289 @@ fn is_allowed(addr: &PeerAddr) -> bool {
     }
 }
 
-fn parse_ipv4_headers(packet: &[u8]) -> Option<(u32, u32)> {
-    if packet.len() < 20 { return None; }
-    if packet[0] >> 4 != 4 { return None; }
-    let src = u32::from_be_bytes(packet[12..16].try_into().ok()?);
-    let dst = u32::from_be_bytes(packet[16..20].try_into().ok()?);
-    Some((src, dst))
+// [ARQ] Random and non-zero (zero marks a fire-and-forget fragment): An unsealed fragment
+// ACK is only believed if it echoes this.
+fn fragment_msg_id(rng: &mut ChaCha20Rng) -> u16 {
+    (rng.next_u32() as u16).max(1)
+}
+
+// [FEC] The first symbol sizes its generation's decoder (the sender picks the symbol size);
+// a symbol of any other size cannot join it.
+fn symbol_fits(decoders: &BTreeMap<(PeerAddr, u16), PendingGen>, key: (PeerAddr, u16), symbol_len: usize) -> bool {
+    match decoders.get(&key) {
+        Some(pending) => pending.decoder.symbol_size() == symbol_len,
+        None => (MIN_SYMBOL_SIZE..=MAX_SYMBOL_SIZE).contains(&symbol_len),
+    }
+}
+
+// [MESH] A coded frame as (gen_id seeding its rows, explicit GEV over L, symbol).
+type CodedParts<'p> = (u16, Option<&'p [u8]>, &'p [u8]);
+
+// Raptor frames carry no GEV: The symbol_id implies the row. None: A malformed Recoded frame.
+fn split_coded<'p>(header: &M13HeaderRef<'_>, payload: &'p [u8]) -> Option<CodedParts<'p>> {
+    if header.packet_type() != PacketType::Recoded {
+        return Some((header.gen_id(), None, payload));
+    }
+    let l = header.reserved() as usize + LDPC_OVERHEAD_S;
+    if header.reserved() == 0 || payload.len() <= 2 + l { return None; }
+    let (code_gen, rest) = payload.split_at(2);
+    let (gev, symbol) = rest.split_at(l);
+    Some((u16::from_be_bytes([code_gen[0], code_gen[1]]), Some(gev), symbol))
+}
+
+// [MESH] Relay input row [ GEV over L | symbol ]. A Raptor symbol's GEV is rebuilt as its
+// decoder would: e_id for a source symbol, the seeded PRF row for a repair symbol.
+fn relay_row(header: &M13HeaderRef<'_>, code_gen: u16, gev: Option<&[u8]>, symbol: &[u8]) -> Option<Vec<u8>> {
+    let k = core::cmp::max(1, header.reserved() as usize);
+    let l = k + LDPC_OVERHEAD_S;
+    let mut row = alloc::vec![0u8; l + symbol.len()];
+    match gev {
+        Some(gev) => row[..l].copy_from_slice(gev),
+        None if (header.symbol_id() as usize) < k => row[header.symbol_id() as usize] = 1,
+        None => expand_coefficients(header.symbol_id(), code_gen, l, &mut row).ok()?,
+    }
+    row[l..].copy_from_slice(symbol);
+    Some(row)
+}
+
+// [PMTU] Largest GSO segment that leaves unfragmented at this MTU.
+fn gso_segment_size(mtu: usize) -> u16 {
+    mtu.saturating_sub(GSO_SEGMENT_OVERHEAD).clamp(GSO_MIN_SEGMENT, u16::MAX as usize) as u16
+}
+
+// [JITTER] Map a kernel RX timestamp onto the platform clock via its queueing age.
+// `rx_clock` is the PHY's clock read after the batch landed. 0 on either side = unavailable.
+// [BBR] The session whose pacer and in-flight log egress to `target` runs on.
+// None: The node's hub session, or the unbound stand-in before there is one.
+fn egress_session<'a>(
+    sessions: &'a mut BTreeMap<PeerAddr, Session>,
+    unbound: &'a mut Session,
+    target: Option<PeerAddr>,
+) -> Option<&'a mut Session> {
+    match target {
+        Some(t) => sessions.get_mut(&t),
+        None => Some(sessions.values_mut().next().unwrap_or(unbound)),
+    }
+}
+
+// [BBR] A generation unacknowledged for this long no longer counts as in flight.
+fn inflight_horizon_us(pacer: &Pacer, now: u64) -> u64 {
+    core::cmp::max(4 * pacer.rt_prop_us(now), INFLIGHT_LOSS_FLOOR_US)
+}
+
+// [BBR] Wire bytes of the session's generations sent but not yet ACKed (lost ones age out).
+fn inflight_of(session: &Session, now: u64) -> u64 {
+    let horizon = inflight_horizon_us(&session.pacer, now);
+    session.tx_gen_log.values()
+        .filter(|(tx_us, _)| now.saturating_sub(*tx_us) < horizon)
+        .map(|(_, bytes)| bytes)
+        .sum()
+}
+
+fn local_rx_us(now: u64, rx_clock: u64, stamp: u64) -> u64 {
+    if rx_clock == 0 || stamp == 0 { return now; }
+    let age = rx_clock.saturating_sub(stamp);
+    if age > RX_TS_MAX_AGE_US { return now; }
+    now.saturating_sub(age)
+}
+
+// [JITTER] Map the sender's PTP origin onto the platform clock via its age on the local PHC.
+// None without a local PTP clock, or when the two clocks disagree beyond PTP_MAX_AGE_US.
+fn ptp_origin_us(now: u64, local_ptp_ns: Option<u64>, origin_ns: u64) -> Option<u64> {
+    let age = local_ptp_ns?.saturating_sub(origin_ns) / 1_000;
+    if age > PTP_MAX_AGE_US { return None; }
+    Some(now.saturating_sub(age))
+}
+
+/// Hub routing key: A tunnelled packet's IP address (source when learning, destination on egress).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IpDest {
+    V4(u32),
+    V6([u8; 16]),
+}
+
+/// (source, destination) of an IPv4 or IPv6 packet.
+fn parse_ip_headers(packet: &[u8]) -> Option<(IpDest, IpDest)> {
+    match packet.first()? >> 4 {
+        4 if packet.len() >= 20 => {
+            let src = u32::from_be_bytes(packet[12..16].try_into().ok()?);
+            let dst = u32::from_be_bytes(packet[16..20].try_into().ok()?);
+            Some((IpDest::V4(src), IpDest::V4(dst)))
+        }
+        6 if packet.len() >= 40 => {
+            let src: [u8; 16] = packet[8..24].try_into().ok()?;
+            let dst: [u8; 16] = packet[24..40].try_into().ok()?;
+            Some((IpDest::V6(src), IpDest::V6(dst)))
+        }
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct KernelConfig {
     pub is_hub: bool,
+    /// false: [DEBUG] Plaintext mode for protocol debugging and crypto-overhead baselines.
+    /// No handshake; Coded/Data/ACK frames go out untagged and are accepted unopened.
+    /// Framing is unchanged. Both ends must agree. Never in production.
     pub enable_encryption: bool,
+    /// Node: Offer X25519 alongside ML-KEM-1024 in the ClientHello.
+    /// Hub: Ignored (hybrid is detected from the ClientHello length).
+    pub hybrid_kex: bool,
+    /// Node: ML-KEM parameter set for the ClientHello.
+    /// Hub: Ignored (the profile is inferred from the offered EK length).
+    pub kem_profile: KemProfile,
+    /// Ratchet the session key after this many generations.
+    /// Clamped to `1..=DEFAULT_REKEY_INTERVAL_GENS`.
+    pub rekey_interval_gens: u16,
+    /// Hub: Evict established sessions with no authenticated RX for this long.
+    /// Half-open sessions expire after `min(5s, this)`.
+    pub session_idle_timeout_us: u64,
+    /// Hold decoded payloads in a per-session `JitterBuffer` for deterministic playout
+    /// (control loops). Off for bulk VPN traffic, which wants minimum latency.
+    pub jitter_buffer: bool,
+    /// Emit an authenticated KeepAlive to any session idle (no TX) for this long,
+    /// holding NAT bindings open for hub-initiated delivery. 0 disables.
+    pub keepalive_interval_us: u64,
+    /// Fill idle time on established sessions with encrypted cover frames, holding egress
+    /// to each peer at its pacer's CBR floor. Costs the floor rate per peer even when silent.
+    pub chaff: bool,
+    /// [FEC] Raptor symbol size for our generations. The receiver sizes each decoder from
+    /// the generation's first symbol, so the two ends need not agree.
+    pub symbol_size: usize,
+    /// [BBR] Pacing floor (bits/sec). Also the chaff rate.
+    pub cbr_floor_bps: u64,
+    /// [PMTU] Cap on the GSO segment (bytes). 0: Derive from the path MTU alone.
+    pub gso_segment_size: u16,
+    /// [MESH] Relay: A node toward its upstream (the PHY default peer) that also accepts
+    /// nodes of its own. Their coded generations are recoded onward, never decoded: Pair
+    /// with `set_mesh_key` at the two ends and the relay only handles ciphertext.
+    /// Uplink only; traffic from the upstream is delivered locally. Needs encryption.
+    pub relay: bool,
+    /// [ARQ] Send handshake replies (HandshakeInit, HandshakeAuth) through a
+    /// `ReliableFragmentSender`: Each fragment is ACKed, and only lost ones are resent, on an
+    /// RTO from the measured RTT. Off: A lost fragment costs the node a whole ClientHello
+    /// round. The receiving end ACKs either way.
+    pub reliable_fragments: bool,
+}
+
+impl Default for KernelConfig {
+    /// An encrypted node: Every tunable at its `DEFAULT_*`, every optional mode off.
+    fn default() -> Self {
+        Self {
+            is_hub: false,
+            enable_encryption: true,
+            hybrid_kex: false,
+            kem_profile: KemProfile::default(),
+            rekey_interval_gens: DEFAULT_REKEY_INTERVAL_GENS,
+            session_idle_timeout_us: DEFAULT_SESSION_IDLE_TIMEOUT_US,
+            jitter_buffer: false,
+            keepalive_interval_us: DEFAULT_KEEPALIVE_INTERVAL_US,
+            chaff: false,
+            symbol_size: DEFAULT_SYMBOL_SIZE,
+            cbr_floor_bps: DEFAULT_CBR_FLOOR_BPS,
+            gso_segment_size: 0,
+            relay: false,
+            reliable_fragments: false,
+        }
+    }
+}
+
+impl KernelConfig {
+    /// Rejects tunables the kernel cannot run with. Call before `M13Kernel::new`.
+    pub fn validate(&self) -> M13Result<()> {
+        if !(MIN_SYMBOL_SIZE..=MAX_SYMBOL_SIZE).contains(&self.symbol_size) {
+            return Err(M13Error::InvalidState);
+        }
+        if self.gso_segment_size != 0 && (self.gso_segment_size as usize) < GSO_MIN_SEGMENT {
+            return Err(M13Error::InvalidState);
+        }
+        // Every session would be evicted on the first sweep.
+        if self.session_idle_timeout_us == 0 {
+            return Err(M13Error::InvalidState);
+        }
+        // A relay has an upstream to forward to, and tells it from its nodes by session.
+        if self.relay && (self.is_hub || !self.enable_encryption) {
+            return Err(M13Error::InvalidState);
+        }
+        Ok(())
+    }
+}
+
+/// Node: ClientHello in flight, awaiting the server hello.
+struct HandshakeAttempt {
+    hello: Vec<u8>, // Resent verbatim: The hub answers a repeat with its cached reply.
+    attempts: u32,
+    next_tx_us: u64,
+}
+
+impl HandshakeAttempt {
+    fn rto_us(&self) -> u64 {
+        let shift = self.attempts.saturating_sub(1).min(16);
+        core::cmp::min(HANDSHAKE_RTO_INITIAL_US << shift, HANDSHAKE_RTO_MAX_US)
+    }
+}
+
+/// A generation still being decoded.
+struct PendingGen {
+    decoder: FountainDecoder,
+    // [JITTER] Origin proxy: Local (kernel-stamped when available) arrival of the first
+    // symbol. Needs no clock sync and trusts nothing the sender claims.
+    first_rx_us: u64,
+    // [DOS] LRU key.
+    last_rx_us: u64,
+    // [ECN] Symbols that arrived Congestion Experienced. Echoed in the ACK.
+    ce_marks: u32,
+    // [FEC] NACKs sent (one per end marker that found us short).
+    nacks: u8,
+}
+
+/// [FEC] One generation on the sending side: Pumped while `sent < budget`, then kept for
+/// repair until ACKed.
+struct TxGen {
+    enc: FountainEncoder,
+    // Symbols sent in the current burst, and the burst's size.
+    sent: u32,
+    budget: u32,
+    target: Option<PeerAddr>,
+    // [JITTER] The plaintext opens with the origin stamp.
+    stamped: bool,
+    repairs: u8,
+}
+
+/// [MESH] Relay: One downstream generation being recoded upstream.
+struct RelayGen {
+    // None: L exceeds the RLNC generation cap. Its rows are forwarded as they come.
+    recoder: Option<Recoder>,
+    // Upstream gen_id, drawn with our own: (gen_id, symbol_id) stays unique under the upstream key.
+    out_gen: u16,
+    // The source's gen_id: Seeds the Raptor rows and LDPC constraints the sink decodes with.
+    code_gen: u16,
+    k: u8,
+    // [ GEV | symbol ] length: The first row sizes the generation, like a decoder's first symbol.
+    row_len: usize,
+    stamped: bool,
+    // Rows absorbed (forward mode), and combinations sent upstream (the symbol_id counter).
+    received: usize,
+    sent: u32,
+    // [DOS] LRU key.
+    last_rx_us: u64,
+    // The source was ACKed once we held K rows; NACKs sent to it; repairs granted upstream.
+    acked: bool,
+    nacks: u8,
+    repairs: u8,
+}
+
+/// Decode progress of one in-flight generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeProgress {
+    pub peer: PeerAddr,
+    pub gen_id: u16,
+    pub rank: usize,
+    pub needed: usize,
 }
 
 pub struct M13Kernel {
-    phy: Box<dyn PhysicalInterface>,
-    #[allow(dead_code)]
+    phy: MeteredPhy,
     sec: Box<dyn SecurityModule>,
     clock: Box<dyn PlatformClock>,
     mem: Arc<SlabAllocator>,
@@ -62,10 +417,16 @@ pub struct M13Kernel {
     identity: DsaKeypair,
 
     sessions: BTreeMap<PeerAddr, Session>,
-    routes: BTreeMap<u32, PeerAddr>,
+    routes: BTreeMap<IpDest, PeerAddr>,
 
     node_target: Option<PeerAddr>,
     pending_kyber: Option<KyberKeypair>,
+    pending_x25519: Option<X25519Keypair>,
+
+    // [ATTEST] Hub: Admit only nodes quoting these PCRs.
+    golden_pcrs: Option<PcrBank>,
+    // [ATTEST] Node: Evidence for a hub challenge (PCRs, legacy AIK public key).
+    attestation: Option<(PcrBank, [u8; 65])>,
 
     // [PHYSICS] Zero-Copy Batch Cache (Scalar 'rx_queue' Removed)
     rx_batch_cache: Vec<FrameLease>, 
@@ -73,13 +434,44 @@ pub struct M13Kernel {
     pub tun_tx_queue: VecDeque<Vec<u8>>, 
     pub tun_rx_queue: VecDeque<Vec<u8>>,
     
-    last_handshake_tx: u64,
+    handshake: Option<HandshakeAttempt>,
+    handshake_holdoff_until: u64,
+    // [DOS] Hub: Key for stateless retry cookies. Never leaves the hub.
+    cookie_secret: [u8; 32],
+    // [DOS] Node: Last cookie the hub issued us, echoed in every ClientHello fragment.
+    hub_cookie: Option<[u8; COOKIE_LEN]>,
+    handshake_failures: u64,
+    last_session_sweep: u64,
 
     // LIQUID VECTOR STATE
-    pacer: Pacer,
-    data_encoder: Option<(FountainEncoder, u32, Option<PeerAddr>)>, 
-    data_decoders: BTreeMap<u16, FountainDecoder>,
+    // One generation in progress per egress target (None: The PHY default peer).
+    data_encoders: BTreeMap<Option<PeerAddr>, TxGen>,
+    // [DEBUG] Plaintext node before the hub's first reply: Paces egress to the PHY default
+    // peer, then hands its pacer over to the session.
+    unbound_egress: Session,
+    // [FEC] Sent generations awaiting their ACK, and those a NACK asked to repair.
+    tx_history: BTreeMap<u16, TxGen>,
+    repair_queue: VecDeque<u16>,
+    repair_bursts: u64,
+    // Keyed by (sender, its gen_id): Peers draw gen_ids independently. LRU-capped at MAX_DATA_DECODERS.
+    data_decoders: BTreeMap<(PeerAddr, u16), PendingGen>,
+    // [MESH] Relay: Keyed by (downstream peer, its gen_id). LRU-capped at MAX_RELAY_GENS.
+    relay_gens: BTreeMap<(PeerAddr, u16), RelayGen>,
+    // [MESH] Seals our generations end to end, and opens the ones we decode.
+    mesh_key: Option<Zeroizing<[u8; 32]>>,
+    completed_gens: VecDeque<(PeerAddr, u16)>,
+    decode_failures: u64,
+    decode_completions: u64,
+    handshakes_completed: u64,
+    unroutable_drops: u64,
+    // [CHAFF] Authenticated cover frames received and discarded.
+    chaff_received: u64,
     next_data_gen_id: u16,
+    // [JITTER] RTT statistics -> playout depth.
+    phase: PhaseMonitor,
+    last_rtt_us: Option<u64>,
+    // External RTT consumer (e.g. SafetyMonitor::record_rtt).
+    rtt_hook: Option<Box<dyn FnMut(u64)>>,
 }
 
 impl M13Kernel {
@@ -93,7 +485,24 @@ impl M13Kernel {
     ) -> Self {
         let mut seed = [0u8; 32];
         let _ = sec.get_random_bytes(&mut seed);
-        let rng = ChaCha20Rng::from_seed(seed);
+        Self::with_seed(phy, sec, clock, mem, config, identity, seed)
+    }
+
+    /// Like `new`, but the kernel RNG (ephemeral keys, signatures, challenges, cookie
+    /// secret, chaff) is seeded from `seed` instead of the security module.
+    /// For reproducible tests only: Two kernels with one seed emit identical handshakes.
+    pub fn with_seed(
+        phy: Box<dyn PhysicalInterface>,
+        sec: Box<dyn SecurityModule>,
+        clock: Box<dyn PlatformClock>,
+        mem: Arc<SlabAllocator>,
+        config: KernelConfig,
+        identity: DsaKeypair,
+        seed: [u8; 32],
+    ) -> Self {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let mut cookie_secret = [0u8; 32];
+        rng.fill_bytes(&mut cookie_secret);
 
         // [COSMETIC UPDATE] v0.3.0 Identity
         info!(">>> [KERNEL] v0.3.0: System Physics & Egress Offload <<<");
@@ -101,23 +510,48 @@ impl M13Kernel {
         // [PHYSICS CHECK] Query the math engine for truth
         let math_engine = m13_math::get_active_engine();
         info!(">>> [PHYSICS] MATH ACCELERATOR: {} <<<", math_engine);
+        if !config.enable_encryption {
+            warn!(">>> [INSECURE] ENCRYPTION DISABLED: No handshake, data and ACKs travel in plaintext, unauthenticated. Debug only. <<<");
+        }
 
         Self {
-            phy, sec, clock, mem, config, identity,
+            phy: MeteredPhy::new(phy), sec, clock, mem, config, identity,
             rng,
             sessions: BTreeMap::new(),
             routes: BTreeMap::new(),
             node_target: None,
             pending_kyber: None,
+            pending_x25519: None,
+            golden_pcrs: None,
+            attestation: None,
             rx_batch_cache: Vec::with_capacity(BATCH_SIZE),
             tun_tx_queue: VecDeque::new(),
             tun_rx_queue: VecDeque::new(),
-            last_handshake_tx: 0,
+            handshake: None,
+            handshake_holdoff_until: 0,
+            cookie_secret,
+            hub_cookie: None,
+            handshake_failures: 0,
+            last_session_sweep: 0,
             
-            pacer: Pacer::new(10_000_000), 
-            data_encoder: None,
+            data_encoders: BTreeMap::new(),
+            unbound_egress: Session::new(0, config.cbr_floor_bps),
+            tx_history: BTreeMap::new(),
+            repair_queue: VecDeque::new(),
+            repair_bursts: 0,
             data_decoders: BTreeMap::new(),
+            relay_gens: BTreeMap::new(),
+            mesh_key: None,
+            completed_gens: VecDeque::new(),
+            decode_failures: 0,
+            decode_completions: 0,
+            handshakes_completed: 0,
+            unroutable_drops: 0,
+            chaff_received: 0,
             next_data_gen_id: 1,
+            phase: PhaseMonitor::new(),
+            last_rtt_us: None,
+            rtt_hook: None,
         }
     }
 
@@ -134,53 +568,364 @@ impl M13Kernel {
         self.tun_rx_queue.pop_front()
     }
 
+    /// Current egress pacing rate (bits/sec), summed over every peer's pacer.
+    pub fn pacing_rate_bps(&self) -> u64 {
+        let now = self.clock.now_us();
+        self.pacers().map(|p| p.pacing_rate_bps(now)).sum()
+    }
+
+    /// Measured bottleneck bandwidth (bits/sec), summed over peers. 0 until ACK feedback arrives.
+    pub fn bandwidth_estimate_bps(&self) -> u64 {
+        let now = self.clock.now_us();
+        self.pacers().map(|p| p.bandwidth_estimate_bps(now)).sum()
+    }
+
+    /// Pacing rate (bits/sec) of egress to `peer` alone.
+    pub fn peer_pacing_rate_bps(&self, peer: &PeerAddr) -> Option<u64> {
+        self.sessions.get(peer).map(|s| s.pacer.pacing_rate_bps(self.clock.now_us()))
+    }
+
+    /// Wire bytes sent to `peer` but not yet ACKed.
+    pub fn peer_bytes_in_flight(&self, peer: &PeerAddr) -> Option<u64> {
+        self.sessions.get(peer).map(|s| inflight_of(s, self.clock.now_us()))
+    }
+
+    /// BBR in-flight cap of the path to `peer`.
+    pub fn peer_inflight_cap_bytes(&self, peer: &PeerAddr) -> Option<u64> {
+        self.sessions.get(peer).map(|s| s.pacer.inflight_cap_bytes(self.clock.now_us()))
+    }
+
+    pub fn has_session(&self, peer: &PeerAddr) -> bool {
+        self.sessions.contains_key(peer)
+    }
+
+    /// Hub: Peer currently routed for IPv4 virtual IP `vip`.
+    pub fn route(&self, vip: u32) -> Option<PeerAddr> {
+        self.route_to(IpDest::V4(vip))
+    }
+
+    /// Hub: Peer currently routed for `dest` (IPv4 or IPv6).
+    pub fn route_to(&self, dest: IpDest) -> Option<PeerAddr> {
+        self.routes.get(&dest).cloned()
+    }
+
+    /// Egress payloads dropped for want of a route (hub) or a hub session (node).
+    pub fn unroutable_drops(&self) -> u64 {
+        self.unroutable_drops
+    }
+
+    /// Key epoch of the session with `peer` (0 = handshake key).
+    pub fn session_key_epoch(&self, peer: &PeerAddr) -> Option<u32> {
+        self.sessions.get(peer).filter(|s| s.tx_cipher.is_some()).map(|s| s.key_epoch)
+    }
+
+    /// Progress of every generation still being decoded (by sender, then ascending gen_id).
+    pub fn decode_progress(&self) -> Vec<DecodeProgress> {
+        self.data_decoders.iter()
+            .map(|(&(peer, gen_id), p)| DecodeProgress { peer, gen_id, rank: p.decoder.rank(), needed: p.decoder.needed() })
+            .collect()
+    }
+
+    /// Wire bytes of generations sent but not yet ACKed (lost ones age out), over all peers.
+    pub fn bytes_in_flight(&self) -> u64 {
+        let now = self.clock.now_us();
+        self.egress_sessions().map(|s| inflight_of(s, now)).sum()
+    }
+
+    /// BBR in-flight caps, summed over peers: Each peer's new generations wait while its
+    /// own in-flight bytes exceed its own cap (see `peer_inflight_cap_bytes`).
+    pub fn inflight_cap_bytes(&self) -> u64 {
+        let now = self.clock.now_us();
+        self.pacers().fold(0u64, |sum, p| sum.saturating_add(p.inflight_cap_bytes(now)))
+    }
+
+    // Every session egress runs on: The peers', or the unbound stand-in before there are any.
+    fn egress_sessions(&self) -> impl Iterator<Item = &Session> {
+        let unbound = self.sessions.is_empty().then_some(&self.unbound_egress);
+        self.sessions.values().chain(unbound)
+    }
+
+    fn pacers(&self) -> impl Iterator<Item = &Pacer> {
+        self.egress_sessions().map(|s| &s.pacer)
+    }
+
+    // Read-only `egress_session`.
+    fn egress_session_ref(&self, target: Option<PeerAddr>) -> Option<&Session> {
+        match target {
+            Some(t) => self.sessions.get(&t),
+            None => Some(self.sessions.values().next().unwrap_or(&self.unbound_egress)),
+        }
+    }
+
+    // Where a TUN packet goes (Some(None): The PHY's default peer). None: Unroutable.
+    fn egress_target(&self, payload: &[u8]) -> Option<Option<PeerAddr>> {
+        if self.config.is_hub {
+            parse_ip_headers(payload).and_then(|(_, dest)| self.routes.get(&dest).cloned()).map(Some)
+        } else if self.node_target.is_some() || !self.config.enable_encryption {
+            // [DEBUG] Plaintext node: Nothing to wait for, the hub is the default target.
+            Some(self.node_target)
+        } else {
+            None
+        }
+    }
+
+    // Node: A keyed session with the hub. A relay asks it of its upstream alone: Its nodes'
+    // sessions say nothing about the path onward.
+    fn upstream_established(&self) -> bool {
+        if self.config.relay {
+            self.node_target.and_then(|t| self.sessions.get(&t)).is_some_and(|s| s.tx_cipher.is_some())
+        } else {
+            self.sessions.values().any(|s| s.tx_cipher.is_some())
+        }
+    }
+
+    // Hub and relay: Admit nodes (ClientHellos, retry cookies, the idle sweep).
+    fn serves_nodes(&self) -> bool {
+        self.config.is_hub || self.config.relay
+    }
+
+    // Egress runs for the hub, a node with a session, or a plaintext node.
+    fn egress_open(&self) -> bool {
+        self.config.is_hub || !self.sessions.is_empty() || !self.config.enable_encryption
+    }
+
+    // [PMTU] Largest GSO segment for the current path MTU, under the configured cap.
+    fn segment_size(&self) -> u16 {
+        let derived = gso_segment_size(self.phy.properties().mtu);
+        match self.config.gso_segment_size {
+            0 => derived,
+            cap => core::cmp::min(derived, cap),
+        }
+    }
+
+    /// Most recent RTT measured from an ACK.
+    pub fn last_rtt_us(&self) -> Option<u64> {
+        self.last_rtt_us
+    }
+
+    /// Current playout depth derived from RTT statistics.
+    pub fn playout_depth_us(&self) -> u64 {
+        self.phase.calculate_depth()
+    }
+
+    /// Forward every RTT sample to `hook` as well (e.g. the SafetyMonitor jitter check).
+    pub fn set_rtt_hook(&mut self, hook: Box<dyn FnMut(u64)>) {
+        self.rtt_hook = Some(hook);
+    }
+
+    /// RTT plumbing point: One sample per ACK (or injected by the runtime).
+    pub fn on_rtt_sample(&mut self, rtt_us: u64) {
+        self.last_rtt_us = Some(rtt_us);
+        self.phase.add_sample(rtt_us);
+        if let Some(hook) = &mut self.rtt_hook {
+            hook(rtt_us);
+        }
+    }
+
+    /// Hub: Challenge every ClientHello and install the session key only once the node's
+    /// Epoch0Frame verifies against `golden_pcrs`. Nodes that fail are dropped.
+    pub fn require_attestation(&mut self, golden_pcrs: PcrBank) {
+        self.golden_pcrs = Some(golden_pcrs);
+    }
+
+    /// Node: Answer a hub challenge with these PCRs. The legacy binding is signed by the
+    /// SecurityModule; `aik_pub` is the matching P-256 public key (SEC1).
+    pub fn set_attestation(&mut self, pcrs: PcrBank, aik_pub: [u8; 65]) {
+        self.attestation = Some((pcrs, aik_pub));
+    }
+
+    /// [MESH] Seal every generation we send under `key` end to end, and open every one we
+    /// decode with it. Relays in between then mix and forward ciphertext only. The sources
+    /// and sinks of a path share the key; relays need none.
+    pub fn set_mesh_key(&mut self, key: [u8; 32]) {
+        self.mesh_key = Some(Zeroizing::new(key));
+    }
+
+    /// Handshakes abandoned after `HANDSHAKE_MAX_ATTEMPTS` unanswered ClientHellos.
+    pub fn handshake_failures(&self) -> u64 {
+        self.handshake_failures
+    }
+
+    /// Generations abandoned before decoding (evicted by the decoder cap, or stalled).
+    pub fn decode_failures(&self) -> u64 {
+        self.decode_failures
+    }
+
+    /// Authenticated chaff frames received (and discarded) from peers.
+    pub fn chaff_received(&self) -> u64 {
+        self.chaff_received
+    }
+
+    /// Snapshot of traffic, decode and resource counters. Cheap: No locks beyond the slab's atomics.
+    pub fn stats(&self) -> KernelStats {
+        let slab = self.mem.stats();
+        KernelStats {
+            packets_sent: self.phy.packets_sent,
+            bytes_sent: self.phy.bytes_sent,
+            packets_received: self.phy.packets_received,
+            bytes_received: self.phy.bytes_received,
+            decode_completions: self.decode_completions,
+            decode_failures: self.decode_failures,
+            repair_bursts: self.repair_bursts,
+            handshakes_completed: self.handshakes_completed,
+            unroutable_drops: self.unroutable_drops,
+            sessions_active: self.sessions.len(),
+            pacing_rate_bps: self.pacing_rate_bps(),
+            frames_in_use: slab.in_use_current,
+            frames_high_water: slab.high_water_mark,
+        }
+    }
+
+    /// The kernel clock (us): The time base of `next_deadline_us`.
+    pub fn now_us(&self) -> u64 {
+        self.clock.now_us()
+    }
+
+    /// [IDLE] Earliest kernel-clock time (us) at which `poll` has timed work: A handshake
+    /// retransmit, the session sweep, a KeepAlive, a playout release or paced egress.
+    /// None: Only inbound traffic or `send_payload` can create work, so the runtime may
+    /// block on its fds. A deadline at or before `now_us()` means poll again at once.
+    pub fn next_deadline_us(&self) -> Option<u64> {
+        let now = self.clock.now_us();
+        let mut next: Option<u64> = None;
+        let mut at = |t: u64| next = Some(next.map_or(t, |n| core::cmp::min(n, t)));
+
+        if !self.config.is_hub && self.config.enable_encryption && !self.upstream_established() {
+            at(self.handshake.as_ref().map_or(self.handshake_holdoff_until, |hs| hs.next_tx_us));
+        }
+        if self.serves_nodes() && !self.sessions.is_empty() {
+            at(self.last_session_sweep + SESSION_SWEEP_INTERVAL_US);
+        }
+        if self.config.keepalive_interval_us > 0 {
+            for session in self.sessions.values().filter(|s| s.tx_cipher.is_some()) {
+                at(session.last_tx_us + self.config.keepalive_interval_us);
+            }
+        }
+        if self.config.jitter_buffer {
+            for jb in self.sessions.values().filter_map(|s| s.jitter.as_ref()) {
+                if let Some(release) = jb.next_release_us() { at(release); }
+            }
+        }
+        for tx in self.sessions.values().filter_map(|s| s.fragment_tx.as_ref()) {
+            if let Some(due) = tx.next_tx_us() { at(due); }
+        }
+
+        if self.egress_open() {
+            let packet_cost = self.config.symbol_size + 64;
+            // Generations in progress or awaiting repair: Their pacer's next quantum.
+            let mut engaged: Vec<Option<PeerAddr>> = self.data_encoders.keys().copied().collect();
+            engaged.extend(self.repair_queue.iter().filter_map(|id| self.tx_history.get(id)).map(|g| g.target));
+            for &target in &engaged {
+                at(self.egress_session_ref(target).map_or(now, |s| s.pacer.ready_at_us(packet_cost, now)));
+            }
+
+            // Queued payloads: Their target's refill, or its pipe draining. Unroutable ones are
+            // dropped on the next poll.
+            let segment = self.segment_size() as usize;
+            let mut queued: Vec<Option<PeerAddr>> = Vec::new();
+            for payload in &self.tun_tx_queue {
+                let target = match self.egress_target(payload) {
+                    Some(t) => t,
+                    None => { at(now); continue; }
+                };
+                if engaged.contains(&target) || queued.contains(&target) { continue; }
+                queued.push(target);
+                let session = match self.egress_session_ref(target) {
+                    Some(s) => s,
+                    None => { at(now); continue; }
+                };
+                let ready = session.pacer.ready_at_us(segment + SEGMENT_OVERHEAD, now);
+                // [BBR] Pipe full: An ACK (socket readable) or the oldest generation ageing
+                // out of the in-flight count reopens it.
+                let drained = if inflight_of(session, now) < session.pacer.inflight_cap_bytes(now) {
+                    now
+                } else {
+                    let horizon = inflight_horizon_us(&session.pacer, now);
+                    session.tx_gen_log.values()
+                        .map(|(tx_us, _)| tx_us + horizon)
+                        .filter(|&expiry| expiry > now)
+                        .min()
+                        .unwrap_or(now)
+                };
+                at(core::cmp::max(ready, drained));
+            }
+
+            if self.config.chaff {
+                for (peer, session) in self.sessions.iter().filter(|(_, s)| s.tx_cipher.is_some()) {
+                    let target = Some(*peer);
+                    if engaged.contains(&target) || queued.contains(&target) { continue; }
+                    at(session.pacer.chaff_due_at_us(packet_cost, now));
+                }
+            }
+        }
+
+        next
+    }
+
     pub fn poll(&mut self) -> bool {
         let now = self.clock.now_us();
         let mut work_done = false;
 
-        // Session Liveness Check
-        if !self.config.is_hub {
-            let mut session_alive = false;
-            for (_, session) in self.sessions.iter() {
-                if session.cipher.is_some() {
-                    session_alive = true;
-                }
+        // Session Liveness Check ([DEBUG] Plaintext: No handshake to drive)
+        if !self.config.is_hub && self.config.enable_encryption {
+            if self.upstream_established() {
+                self.handshake = None;
+            } else {
+                work_done |= self.drive_handshake(now);
             }
-            if !session_alive {
-                if now.saturating_sub(self.last_handshake_tx) > 2_000_000 {
-                    info!("Client: Initiating Handshake (Cold Start)...");
-                    self.initiate_handshake(None); 
-                    self.last_handshake_tx = now;
+        }
+
+        // [DOS] Hub and relay: Reap idle and half-open sessions.
+        if self.serves_nodes() && now.saturating_sub(self.last_session_sweep) >= SESSION_SWEEP_INTERVAL_US {
+            self.evict_idle_sessions(now);
+            self.last_session_sweep = now;
+        }
+
+        // [NAT] Heartbeat idle sessions.
+        if self.config.keepalive_interval_us > 0 {
+            let interval = self.config.keepalive_interval_us;
+            for (peer, session) in self.sessions.iter_mut() {
+                if session.tx_cipher.is_some() && now.saturating_sub(session.last_tx_us) >= interval {
+                    Self::send_control(&self.mem, &mut self.phy, session, PacketType::KeepAlive, *peer, now);
                     work_done = true;
                 }
             }
         }
 
+        // [REKEY] Ratchet before the current key could see a gen_id twice.
+        let next_gen_id = self.next_data_gen_id;
+        let interval = self.config.rekey_interval_gens.clamp(1, DEFAULT_REKEY_INTERVAL_GENS);
+        for (peer, session) in self.sessions.iter_mut() {
+            if session.needs_rekey(next_gen_id, interval) {
+                session.rekey(next_gen_id);
+                info!("Rekeyed {:?} (epoch {})", peer, session.key_epoch);
+            }
+        }
+
         // [PHYSICS] ZERO-COPY BATCH RX
         let mut batch = core::mem::take(&mut self.rx_batch_cache);
 
-        while batch.len() < BATCH_SIZE {
-            if let Some(lease) = self.mem.alloc() { batch.push(lease); }
-            else { break; }
+        if batch.len() < BATCH_SIZE {
+            self.mem.alloc_batch(BATCH_SIZE - batch.len(), &mut batch);
         }
 
         if !batch.is_empty() {
-            let mut ptrs: Vec<&mut [u8]> = batch.iter_mut()
-                .map(|lease| &mut lease.data[..])
-                .collect();
-            
-            let mut meta = alloc::vec![(0, PeerAddr::None); ptrs.len()];
+            let mut meta = alloc::vec![(0, PeerAddr::None); batch.len()];
+            let mut ecn = alloc::vec![ECN_NOT_ECT; batch.len()];
+            let mut stamps = alloc::vec![0u64; batch.len()];
 
-            if let Ok(n) = self.phy.recv_batch(&mut ptrs, &mut meta) {
+            if let Ok(n) = self.phy.recv_leases(&mut batch, &mut meta, &mut ecn, &mut stamps) {
                 if n > 0 {
                     work_done = true;
+                    let rx_clock = self.phy.rx_clock_us();
                     for (i, mut lease) in batch.drain(0..n).enumerate() {
                         let (len, src) = meta[i];
                         lease.len = len;
                         if self.config.is_hub && !is_allowed(&src) {
                              warn!("Blocked unauthorized peer: {:?}", src);
                         } else {
-                             self.handle_packet(lease, src, now); 
+                             let rx_us = local_rx_us(now, rx_clock, stamps[i]);
+                             self.handle_packet(lease, src, ecn[i], rx_us, now); 
                         }
                     }
                 }
@@ -189,239 +934,1096 @@ impl M13Kernel {
 
         self.rx_batch_cache = batch;
 
-        // PACER TICK
-        self.pacer.tick(now);
+        // [ARQ] Reliable control messages: First transmission, and resends past the RTO.
+        for (peer, session) in self.sessions.iter_mut() {
+            if let Some(tx) = &mut session.fragment_tx {
+                tx.update_rto(&self.phase);
+                match tx.poll(&mut self.phy, now) {
+                    Ok(sent) => work_done |= sent > 0,
+                    Err(e) => {
+                        warn!("Control message to {:?} abandoned: {:?}", peer, e);
+                        session.fragment_tx = None;
+                    }
+                }
+            }
+        }
 
-        // LIQUID EGRESS (GSO Enabled)
-        if self.config.is_hub || !self.sessions.is_empty() {
-            if self.data_encoder.is_some() {
-                self.pump_liquid_data();
-                work_done = true;
-            } 
-            else {
-                // [PHYSICS] GSO AGGREGATION
-                let segment_size = 1328u16; 
-                let mut gso_buffer = Vec::with_capacity(64000);
-                let mut current_target: Option<PeerAddr> = None;
-                
-                // Drain up to 64 packets
-                let mut count = 0;
-                while count < 64 {
-                    // [AUDIT FIX] Pacer Check for GSO
-                    // We must check if we have tokens BEFORE popping to avoid dropping packets.
-                    // Assuming MTU cost + overhead
-                    if let Some(next_payload) = self.tun_tx_queue.front() {
-                        let cost = next_payload.len() + 64;
-                        if !self.pacer.chaff_needed(cost) {
-                            // Pacer exhausted: Yield to allow token refill
-                            break;
-                        }
-                    } else {
-                        break; // Queue empty
+        // [JITTER] Playout: Release everything whose deadline has arrived.
+        if self.config.jitter_buffer {
+            let depth = self.phase.calculate_depth();
+            for session in self.sessions.values_mut() {
+                if let Some(jb) = &mut session.jitter {
+                    jb.set_depth(depth);
+                    while let Some((_, payload)) = jb.pop(now) {
+                        self.tun_rx_queue.push_back(payload);
+                        work_done = true;
                     }
+                }
+            }
+        }
 
-                    if let Some(payload) = self.tun_tx_queue.pop_front() {
-                        // Consume Tokens
-                        let cost = payload.len() + 64;
-                        self.pacer.consume(cost);
+        // PACER TICK: Every peer refills its own bucket.
+        for session in self.sessions.values_mut() {
+            session.pacer.tick(now);
+        }
+        self.unbound_egress.pacer.tick(now);
 
-                        // 1. Determine Target
-                        let target_peer = if self.config.is_hub {
-                             if let Some((_, dest_vip)) = parse_ipv4_headers(&payload) {
-                                self.routes.get(&dest_vip).cloned()
-                             } else { None }
-                        } else {
-                             self.node_target
-                        };
+        // LIQUID EGRESS (GSO Enabled)
+        // [BBR] Peers are served in turn, each within its own budget: At most one burst per
+        // target per poll, so a peer whose pipe is full never holds up the others.
+        if self.egress_open() {
+            let mut served: Vec<Option<PeerAddr>> = self.data_encoders.keys().copied().collect();
+            for &target in &served {
+                self.pump_liquid_data(target);
+            }
+            work_done |= !served.is_empty();
 
-                        if let Some(target) = target_peer {
-                            // 2. Flush on Target Mismatch
-                            if let Some(curr) = current_target {
-                                if curr != target {
-                                    // Flush current buffer
-                                    self.phy.send_gso(&gso_buffer, Some(curr), segment_size).ok();
-                                    gso_buffer.clear();
-                                    current_target = Some(target);
-                                }
-                            } else {
-                                current_target = Some(target);
-                            }
-                            
-                            // 3. Encrypt & Append
-                            // (Fountain Encoder Logic - Swaps Mode if Enabled)
-                            if let Ok(enc) = FountainEncoder::new(&payload, RAPTOR_SYMBOL_SIZE, self.next_data_gen_id) {
-                                 self.data_encoder = Some((enc, 0, Some(target)));
-                                 self.next_data_gen_id = self.next_data_gen_id.wrapping_add(1);
-                                 // Flush whatever we have in GSO
-                                 if !gso_buffer.is_empty() {
-                                     if let Some(curr) = current_target {
-                                         self.phy.send_gso(&gso_buffer, Some(curr), segment_size).ok();
-                                     }
-                                 }
-                                 self.pump_liquid_data();
-                                 work_done = true;
-                                 break; 
-                            }
-                            
-                            // 4. Standard Encryption (Non-Fountain)
-                             gso_buffer.extend_from_slice(&payload);
-                        }
-                    } else {
-                        break;
-                    }
-                    count += 1;
+            // [FEC] Repairs go first: The receiver is holding a partial generation for them.
+            work_done |= self.start_repairs(&mut served);
+            work_done |= self.pump_tun_tx(now, &mut served);
+
+            // [CHAFF] Nothing real to send to a peer: Hold its egress at the CBR floor.
+            if self.config.chaff {
+                work_done |= self.pump_chaff(now, &served);
+            }
+        }
+        
+        work_done
+    }
+
+    fn evict_idle_sessions(&mut self, now: u64) {
+        let idle_timeout = self.config.session_idle_timeout_us;
+        let half_open_timeout = core::cmp::min(HALF_OPEN_TIMEOUT_US, idle_timeout);
+        let plaintext = !self.config.enable_encryption;
+
+        let mut evicted = Vec::new();
+        self.sessions.retain(|peer, session| {
+            let established = session.tx_cipher.is_some() || plaintext;
+            let timeout = if established { idle_timeout } else { half_open_timeout };
+            let alive = now.saturating_sub(session.last_valid_rx_us) < timeout;
+            if !alive { evicted.push(*peer); }
+            alive
+        });
+        if evicted.is_empty() { return; }
+
+        self.purge_peers(&evicted);
+        for peer in &evicted {
+            info!("Evicted idle session {:?}", peer);
+        }
+    }
+
+    /// Drop everything still keyed to sessions that are gone.
+    fn purge_peers(&mut self, gone: &[PeerAddr]) {
+        self.routes.retain(|_, peer| !gone.contains(peer));
+        self.data_decoders.retain(|(peer, _), _| !gone.contains(peer));
+        // Never keep pumping a generation whose key is gone (it would leave unencrypted).
+        self.data_encoders.retain(|target, _| !target.is_some_and(|t| gone.contains(&t)));
+        self.tx_history.retain(|_, gen| !gen.target.is_some_and(|t| gone.contains(&t)));
+        self.completed_gens.retain(|(peer, _)| !gone.contains(peer));
+        self.relay_gens.retain(|(peer, _), _| !gone.contains(peer));
+        // Relay: With the upstream gone, nothing relayed can arrive; the next handshake rebinds it.
+        if self.node_target.is_some_and(|t| gone.contains(&t)) {
+            self.node_target = None;
+            if self.config.relay { self.relay_gens.clear(); }
+        }
+    }
+
+    /// Graceful teardown: Tell every established peer we are leaving, then forget them.
+    /// The peer drops the session at once instead of waiting out the idle timeout.
+    pub fn shutdown(&mut self) {
+        let now = self.clock.now_us();
+        let mut gone = Vec::with_capacity(self.sessions.len());
+        for (peer, session) in self.sessions.iter_mut() {
+            Self::send_control(&self.mem, &mut self.phy, session, PacketType::Goodbye, *peer, now);
+            gone.push(*peer);
+        }
+        self.sessions.clear();
+        self.purge_peers(&gone);
+        self.tun_tx_queue.clear();
+        info!("Shutdown: Goodbye sent to {} peer(s)", gone.len());
+    }
+
+    /// New generations from the TUN queue, in arrival order. A target already `served`
+    /// this poll, over its in-flight cap or short of budget keeps its packets queued (in
+    /// order) while the rest of the queue moves past them. Held targets join `served`.
+    fn pump_tun_tx(&mut self, now: u64, served: &mut Vec<Option<PeerAddr>>) -> bool {
+        // The PHY tracks the path MTU: Re-derive the segment every poll.
+        let segment_size = self.segment_size();
+        let max_segments = GSO_MAX_BYTES / segment_size as usize;
+        let mut held = VecDeque::new();
+        let mut work_done = false;
+
+        while let Some(payload) = self.tun_tx_queue.pop_front() {
+            // 1. Determine Target (Some(None): The PHY's default peer)
+            let target = match self.egress_target(&payload) {
+                Some(t) => t,
+                None => {
+                    // Unroutable: Dropped, but never silently.
+                    self.unroutable_drops += 1;
+                    continue;
                 }
-                
-                // Final Flush
-                if !gso_buffer.is_empty() {
-                    if let Some(curr) = current_target {
-                        self.phy.send_gso(&gso_buffer, Some(curr), segment_size).ok();
-                        work_done = true;
+            };
+            if served.contains(&target) {
+                held.push_back(payload);
+                continue;
+            }
+            let session = match egress_session(&mut self.sessions, &mut self.unbound_egress, target) {
+                Some(s) => s,
+                None => {
+                    self.unroutable_drops += 1;
+                    continue;
+                }
+            };
+
+            // 2. [BBR] Pipe full or bucket empty: Wait for this peer's ACKs or refill.
+            // The pacer sizes the burst up front: Nothing is popped that the bucket cannot pay for
+            // (a packet alone larger than a burst still leaves once the bucket holds a segment).
+            if inflight_of(session, now) >= session.pacer.inflight_cap_bytes(now)
+                || session.pacer.affordable_segments(segment_size as usize, max_segments) == 0 {
+                served.push(target);
+                held.push_back(payload);
+                continue;
+            }
+            served.push(target);
+            work_done = true;
+
+            // 3. Encrypt & Send
+            // (Fountain Encoder Logic - Swaps Mode if Enabled)
+            // [JITTER] Control-loop traffic carries its PTP origin: The receiver's playout
+            // deadline then runs from when it was sent, not from when it arrived.
+            let origin_ns = if self.config.jitter_buffer { self.clock.ptp_ns() } else { None };
+            let stamped = origin_ns.map(|ns| [&ns.to_be_bytes()[..], &payload].concat());
+            let plaintext = stamped.as_deref().unwrap_or(&payload);
+            // [MESH] Sealed end to end: Relays on the way recode ciphertext only.
+            let sealed = match &self.mesh_key {
+                Some(key) => {
+                    let mut salt = [0u8; MESH_SALT_LEN];
+                    self.rng.fill_bytes(&mut salt);
+                    match mesh_seal(key, &salt, plaintext) {
+                        Ok(sealed) => Some(sealed),
+                        Err(_) => continue,
                     }
                 }
+                None => None,
+            };
+            let plaintext = sealed.as_deref().unwrap_or(plaintext);
+            if let Ok(enc) = FountainEncoder::new(plaintext, self.config.symbol_size, self.next_data_gen_id) {
+                let k = enc.num_source_symbols() as u32;
+                self.data_encoders.insert(target, TxGen {
+                    enc, sent: 0, budget: k + core::cmp::max(1, k / 10), target,
+                    stamped: origin_ns.is_some(), repairs: 0,
+                });
+                self.next_data_gen_id = self.next_data_gen_id.wrapping_add(1);
+                // Symbols are charged as they leave.
+                self.pump_liquid_data(target);
+                continue;
             }
+
+            // 4. Standard Encryption (Non-Fountain)
+            let segments = core::cmp::max(1, payload.len().div_ceil(segment_size as usize));
+            let mut gso_buffer = payload;
+            self.flush_gso(&mut gso_buffer, segments, target, segment_size);
         }
-        
+
+        self.tun_tx_queue = held;
         work_done
     }
 
-    fn pump_liquid_data(&mut self) {
-        if let Some((enc, sent_count, target_peer)) = &mut self.data_encoder {
-            let k = enc.num_source_symbols();
-            let overhead = core::cmp::max(1, (k * 10) / 100);
-            let target = (k + overhead) as u32;
-            let packet_cost = RAPTOR_SYMBOL_SIZE + 64; 
+    /// Send one super-packet and charge the target's pacer its exact wire cost.
+    fn flush_gso(&mut self, buf: &mut Vec<u8>, segments: usize, target: Option<PeerAddr>, segment_size: u16) {
+        if buf.is_empty() { return; }
+        self.phy.send_gso(buf, target, segment_size).ok();
+        if let Some(session) = egress_session(&mut self.sessions, &mut self.unbound_egress, target) {
+            session.pacer.consume_segments(buf.len(), segments);
+        }
+        buf.clear();
+    }
 
+    /// [CHAFF] Spend each established peer's floor budget, skipping peers with real data.
+    fn pump_chaff(&mut self, now: u64, served: &[Option<PeerAddr>]) -> bool {
+        let packet_cost = self.config.symbol_size + 64;
+        let mut work_done = false;
+        for (peer, session) in self.sessions.iter_mut() {
+            if session.tx_cipher.is_none() || served.contains(&Some(*peer)) { continue; }
             let mut burst = 0;
-            while *sent_count < target && burst < BATCH_SIZE {
-                if !self.pacer.chaff_needed(packet_cost) { break; }
-                
-                let (mut header, mut payload) = enc.next_packet();
-                header.packet_type = PacketType::Coded; 
-                header.reserved = k as u8;
-
-                if let Some(mut lease) = self.mem.alloc() {
-                    let cipher_ref = if let Some(t) = target_peer {
-                         self.sessions.get(t).and_then(|s| s.cipher.as_ref())
-                    } else if !self.config.is_hub {
-                         self.sessions.values().next().and_then(|s| s.cipher.as_ref())
-                    } else {
-                         None 
-                    };
-
-                    if let Some(cipher) = cipher_ref {
-                        if let Ok(tag) = cipher.encrypt_detached(&header, &mut payload) {
-                             header.auth_tag = tag;
-                        }
+            while burst < BATCH_SIZE && session.pacer.chaff_due(packet_cost) {
+                Self::send_chaff(&self.mem, &mut self.phy, &mut self.rng, self.config.symbol_size, session, *peer, now);
+                session.pacer.consume(packet_cost);
+                burst += 1;
+            }
+            work_done |= burst > 0;
+        }
+        work_done
+    }
+
+    fn pump_liquid_data(&mut self, target: Option<PeerAddr>) {
+        let now = self.clock.now_us();
+        let gen = match self.data_encoders.get_mut(&target) {
+            Some(g) => g,
+            None => return,
+        };
+        let session = match egress_session(&mut self.sessions, &mut self.unbound_egress, target) {
+            Some(s) => s,
+            None => {
+                // Session gone mid-generation: Never send it unencrypted.
+                self.data_encoders.remove(&target);
+                return;
+            }
+        };
+        let k = gen.enc.num_source_symbols();
+        let enc = &mut gen.enc;
+        let packet_cost = self.config.symbol_size + 64;
+
+        // [BBR] Stamp the generation on its first symbol.
+        if gen.sent == 0 && gen.repairs == 0 {
+            if session.tx_gen_log.len() >= MAX_TX_GEN_LOG {
+                session.tx_gen_log.pop_first();
+            }
+            session.tx_gen_log.insert(enc.gen_id(), (now, 0));
+        }
+
+        // [BBR] A full bucket must not become a micro-burst: Cap each poll at the send quantum.
+        let max_burst = (session.pacer.send_quantum(now) / packet_cost).clamp(1, BATCH_SIZE);
+
+        // [PHYSICS] Vector TX: Accumulate the burst, flush with one syscall.
+        let mut tx_batch: Vec<(FrameLease, usize)> = Vec::with_capacity(max_burst);
+
+        let mut burst = 0;
+        while gen.sent < gen.budget && burst < max_burst {
+            if !session.pacer.chaff_needed(packet_cost) { break; }
+
+            let (mut header, mut payload) = enc.next_packet();
+            header.packet_type = PacketType::Coded; 
+            header.reserved = k as u8;
+            if gen.stamped { header.recoder_rank |= ORIGIN_STAMPED; }
+            if gen.sent + 1 == gen.budget { header.recoder_rank |= LAST_SYMBOL; }
+
+            if let Some(mut lease) = self.mem.alloc() {
+                if let Some(cipher) = &session.tx_cipher {
+                    if let Ok(tag) = cipher.encrypt_detached(&header, &mut payload) {
+                         header.auth_tag = tag;
                     }
+                }
 
-                    header.to_bytes(&mut lease.data).ok();
-                    lease.data[32..32+payload.len()].copy_from_slice(&payload);
-                    
-                    self.phy.send(&lease.data[..32+payload.len()], *target_peer).ok();
-                    
-                    self.pacer.consume(packet_cost);
-                    *sent_count += 1;
-                    burst += 1;
-                } else {
-                    break; 
+                header.to_bytes(&mut lease.data).ok();
+                lease.data[32..32+payload.len()].copy_from_slice(&payload);
+                
+                tx_batch.push((lease, 32 + payload.len()));
+                if let Some((_, bytes)) = session.tx_gen_log.get_mut(&enc.gen_id()) {
+                    *bytes += (32 + payload.len()) as u64;
                 }
+                
+                session.pacer.consume(packet_cost);
+                gen.sent += 1;
+                burst += 1;
+            } else {
+                break; 
+            }
+        }
+
+        if !tx_batch.is_empty() {
+            let frames: Vec<&[u8]> = tx_batch.iter().map(|(l, len)| &l.data[..*len]).collect();
+            let targets = alloc::vec![target; frames.len()];
+            let mut offset = 0;
+            while offset < frames.len() {
+                match self.phy.send_batch(&frames[offset..], &targets[offset..]) {
+                    Ok(n) if n > 0 => offset += n,
+                    _ => break, // Fountain overhead absorbs the tail loss
+                }
+            }
+
+            // [NAT] Data counts as a heartbeat.
+            session.last_tx_us = now;
+        }
+        
+        // [FEC] Burst done: Keep the encoder until the ACK, in case a NACK asks for more.
+        if gen.sent >= gen.budget {
+            if let Some(done) = self.data_encoders.remove(&target) {
+                if self.tx_history.len() >= MAX_REPAIR_HISTORY { self.tx_history.pop_first(); }
+                self.tx_history.insert(done.enc.gen_id(), done);
+            }
+        }
+    }
+
+    /// [FEC] Put the generations a NACK asked for back in flight, each once its target is
+    /// free. Targets repaired this poll join `served`.
+    fn start_repairs(&mut self, served: &mut Vec<Option<PeerAddr>>) -> bool {
+        let mut waiting = VecDeque::new();
+        let mut started = false;
+        while let Some(gen_id) = self.repair_queue.pop_front() {
+            let target = match self.tx_history.get(&gen_id) {
+                Some(g) => g.target,
+                None => continue, // ACKed or aged out meanwhile
+            };
+            if served.contains(&target) {
+                waiting.push_back(gen_id);
+                continue;
+            }
+            if let Some(gen) = self.tx_history.remove(&gen_id) {
+                // [BBR] The ACK would time the repair round too: No RTT sample from this generation.
+                if let Some(session) = egress_session(&mut self.sessions, &mut self.unbound_egress, target) {
+                    session.tx_gen_log.remove(&gen_id);
+                }
+                self.repair_bursts += 1;
+                self.data_encoders.insert(target, gen);
+                self.pump_liquid_data(target);
+                served.push(target);
+                started = true;
             }
-            
-            if *sent_count >= target { self.data_encoder = None; }
         }
+        self.repair_queue = waiting;
+        started
+    }
+
+    /// [FEC] The receiver reached the end marker of `gen_id` still `needed` symbols short.
+    fn process_nack(&mut self, gen_id: u16, needed: u16, peer: PeerAddr) {
+        let gen = match self.tx_history.get_mut(&gen_id) {
+            Some(g) => g,
+            None => {
+                // ACKed, aged out, or given up. Or a relay's: Repaired from its basis.
+                self.repair_relayed(gen_id, needed, peer);
+                return;
+            }
+        };
+        // Only the peer the generation went to may ask for it again.
+        if gen.target.is_some_and(|t| t != peer) { return; }
+        if gen.repairs >= MAX_REPAIR_ROUNDS {
+            self.tx_history.remove(&gen_id);
+            return;
+        }
+        // Never more than the generation took in the first place.
+        let k = gen.enc.num_source_symbols() as u32;
+        let needed = (needed as u32).clamp(1, k);
+        gen.budget = needed + core::cmp::max(1, needed / 10);
+        gen.sent = 0;
+        gen.repairs += 1;
+        if !self.repair_queue.contains(&gen_id) { self.repair_queue.push_back(gen_id); }
+    }
+
+    /// [MESH] Relay: Absorb one row ([ GEV over L | symbol ]) of `peer`'s generation into its
+    /// recoder and send a fresh combination upstream. The symbol is never decoded: Under a
+    /// mesh key it is ciphertext to us. Once we hold K rows the source gets its ACK (the hop is
+    /// done); an end marker that finds us short NACKs it instead.
+    fn relay_symbol(&mut self, peer: PeerAddr, header: M13Header, code_gen: u16, row: Vec<u8>, now: u64) {
+        if !self.upstream_established() { return; }
+        let key = (peer, header.gen_id);
+        let k = core::cmp::max(1, header.reserved as usize);
+        let l = k + LDPC_OVERHEAD_S;
+
+        if !self.relay_gens.contains_key(&key) {
+            // [DOS] Make room by dropping the least recently touched generation.
+            if self.relay_gens.len() >= MAX_RELAY_GENS {
+                let stalest = self.relay_gens.iter().min_by_key(|(_, g)| g.last_rx_us).map(|(&key, _)| key);
+                if let Some(key) = stalest { self.relay_gens.remove(&key); }
+            }
+            let out_gen = self.next_data_gen_id;
+            self.next_data_gen_id = self.next_data_gen_id.wrapping_add(1);
+            self.relay_gens.insert(key, RelayGen {
+                recoder: (l <= MAX_RLNC_GENERATION).then(|| Recoder::new(out_gen, l).ok()).flatten(),
+                out_gen, code_gen, k: k as u8, row_len: row.len(),
+                stamped: header.recoder_rank & ORIGIN_STAMPED != 0,
+                received: 0, sent: 0, last_rx_us: now,
+                acked: false, nacks: 0, repairs: 0,
+            });
+        }
+        let gen = match self.relay_gens.get_mut(&key) {
+            Some(g) if g.k as usize == k && g.code_gen == code_gen && g.row_len == row.len() => g,
+            _ => return,
+        };
+        gen.last_rx_us = now;
+        let forward = match &mut gen.recoder {
+            Some(recoder) => {
+                if recoder.absorb(&row).is_err() { return; }
+                None
+            }
+            None => {
+                gen.received += 1;
+                Some(row)
+            }
+        };
+        let rank = gen.recoder.as_ref().map_or(gen.received, |r| r.current_rank());
+        let last = header.recoder_rank & LAST_SYMBOL != 0;
+
+        // Hop feedback to the source, on its own session.
+        let (ack, nack) = if rank >= k {
+            (!core::mem::replace(&mut gen.acked, true), false)
+        } else if last && gen.nacks < MAX_REPAIR_ROUNDS {
+            gen.nacks += 1;
+            (false, true)
+        } else {
+            (false, false)
+        };
+        if let Some(session) = self.sessions.get_mut(&peer) {
+            let cipher = session.tx_cipher.as_ref();
+            if ack {
+                Self::send_ack(&self.mem, &mut self.phy, cipher, session.tx_sequence, header.gen_id, header.symbol_id, 0, now, peer);
+                session.tx_sequence = session.tx_sequence.wrapping_add(1);
+                session.last_tx_us = now;
+            } else if nack {
+                Self::send_nack(&self.mem, &mut self.phy, cipher, session.tx_sequence, header.gen_id, k - rank, peer);
+                session.tx_sequence = session.tx_sequence.wrapping_add(1);
+                session.last_tx_us = now;
+            }
+        }
+
+        self.relay_emit(key, forward, 1, last, now);
+    }
+
+    /// [MESH] Relay: The upstream NACKed `out_gen`. Recode `needed` fresh combinations (plus
+    /// the usual tenth) from whatever basis we hold. Forwarded generations have none to mix.
+    fn repair_relayed(&mut self, out_gen: u16, needed: u16, peer: PeerAddr) {
+        if Some(peer) != self.node_target { return; }
+        let key = match self.relay_gens.iter().find(|(_, g)| g.out_gen == out_gen && g.recoder.is_some()) {
+            Some((&key, _)) => key,
+            None => return,
+        };
+        let gen = match self.relay_gens.get_mut(&key) {
+            Some(g) if g.repairs < MAX_REPAIR_ROUNDS => g,
+            _ => return,
+        };
+        gen.repairs += 1;
+        let needed = (needed as usize).clamp(1, gen.k as usize);
+        // [BBR] The ACK then times the repair round alone.
+        if let Some(session) = self.sessions.get_mut(&peer) {
+            session.tx_gen_log.remove(&out_gen);
+        }
+        self.repair_bursts += 1;
+        self.relay_emit(key, None, needed + core::cmp::max(1, needed / 10), true, self.clock.now_us());
+    }
+
+    /// [MESH] Relay: Send `count` Recoded frames of a relayed generation upstream (the last one
+    /// end-marked if `last`): Recoder output, or the `forward` row as is when there is no
+    /// recoder. Charged to the upstream's pacer and logged for its ACK like our own generations.
+    fn relay_emit(&mut self, key: (PeerAddr, u16), forward: Option<Vec<u8>>, count: usize, last: bool, now: u64) {
+        let upstream = match self.node_target {
+            Some(t) => t,
+            None => return,
+        };
+        let (session, gen) = match (self.sessions.get_mut(&upstream), self.relay_gens.get_mut(&key)) {
+            (Some(s), Some(g)) => (s, g),
+            _ => return,
+        };
+        let cipher = match &session.tx_cipher {
+            Some(c) => c,
+            None => return,
+        };
+        let mut forward = forward;
+
+        for i in 0..count {
+            // Header: Recoded, gen_id = out_gen, symbol_id = our counter, rank advertised.
+            let (mut header, row) = match (&mut gen.recoder, forward.take()) {
+                (Some(recoder), _) => match recoder.recode_packet(&mut self.rng, RELAY_REPAIR_DENSITY, gen.sent) {
+                    Ok(packet) => packet,
+                    Err(_) => break,
+                },
+                (None, Some(row)) => (M13Header::new(PacketType::Coded, gen.out_gen, gen.sent), row),
+                (None, None) => break,
+            };
+            let mut payload = Vec::with_capacity(2 + row.len());
+            payload.extend_from_slice(&gen.code_gen.to_be_bytes());
+            payload.extend_from_slice(&row);
+            header.packet_type = PacketType::Recoded;
+            header.payload_len = match u16::try_from(payload.len()) {
+                Ok(len) => len,
+                Err(_) => break,
+            };
+            header.reserved = gen.k;
+            if gen.stamped { header.recoder_rank |= ORIGIN_STAMPED; }
+            if last && i + 1 == count { header.recoder_rank |= LAST_SYMBOL; }
+            match cipher.encrypt_detached(&header, &mut payload) {
+                Ok(tag) => header.auth_tag = tag,
+                Err(_) => break,
+            }
+
+            let len = M13Header::SIZE + payload.len();
+            if let Some(mut lease) = self.mem.alloc_sized(len) {
+                if header.to_bytes(&mut lease.data).is_ok() {
+                    lease.data[M13Header::SIZE..len].copy_from_slice(&payload);
+                    self.phy.send(&lease.data[..len], Some(upstream)).ok();
+                }
+            }
+            // [BBR] The upstream's ACK of out_gen times this hop, as for our own generations.
+            if !session.tx_gen_log.contains_key(&gen.out_gen) && session.tx_gen_log.len() >= MAX_TX_GEN_LOG {
+                session.tx_gen_log.pop_first();
+            }
+            session.tx_gen_log.entry(gen.out_gen).or_insert((now, 0)).1 += len as u64;
+            session.pacer.consume(len);
+            gen.sent += 1;
+        }
+        session.last_tx_us = now;
     }
 
     // ... (rest of handle_packet and others unchanged) ...
-    fn handle_packet(&mut self, mut frame: FrameLease, peer: PeerAddr, now: u64) {
-        if let Ok(header) = M13Header::from_bytes(&frame.data[0..32]) {
-            let payload_len = header.payload_len as usize;
-            if frame.len < 32 + payload_len { return; }
-            let payload = &mut frame.data[32..32+payload_len];
-
-            if !self.sessions.contains_key(&peer) {
-                if self.config.is_hub && header.packet_type == PacketType::ClientHello {
-                    info!("New Peer Detected: {:?}", peer);
-                    self.sessions.insert(peer, Session::new(now));
-                } else if !self.config.is_hub {
-                    if self.sessions.is_empty() {
-                        self.sessions.insert(peer, Session::new(now));
-                        self.node_target = Some(peer);
+    fn handle_packet(&mut self, mut frame: FrameLease, peer: PeerAddr, ecn: u8, rx_us: u64, now: u64) {
+        // [PERF] Borrowed header: Fields and AAD are read in place, never copied out.
+        let frame_len = frame.len;
+        let (head, body) = frame.data.split_at_mut(M13Header::SIZE);
+        // Rejects are silent on the wire; the reason is only worth a debug line.
+        let header = match M13HeaderRef::parse(head) {
+            Ok(h) => h,
+            Err(e) => {
+                debug!("Dropped frame from {:?}: {:?}", peer, e);
+                return;
+            }
+        };
+        let payload_len = header.payload_len() as usize;
+        if frame_len < 32 + payload_len { return; }
+        let payload = &mut body[..payload_len];
+
+        if !self.sessions.contains_key(&peer) {
+            if self.serves_nodes() && header.packet_type() == PacketType::ClientHello {
+                // [DOS] Stateless retry: No session, KEM or signature until the peer
+                // proves it receives at this address. The cookie rides in the tag field.
+                if !self.cookie_valid(&peer, header.auth_tag(), now) {
+                    self.send_cookie(peer, now);
+                    return;
+                }
+                info!("New Peer Detected: {:?}", peer);
+                self.sessions.insert(peer, Session::new(now, self.config.cbr_floor_bps));
+            } else if self.config.is_hub && !self.config.enable_encryption
+                && matches!(header.packet_type(), PacketType::Coded | PacketType::Data) {
+                // [DEBUG] Plaintext: Any sender of data is a peer.
+                warn!("New plaintext peer {:?} (INSECURE: unauthenticated)", peer);
+                self.sessions.insert(peer, Session::new(now, self.config.cbr_floor_bps));
+            } else if !self.config.is_hub {
+                // Relay: Its nodes hold sessions too. Only a reply to our own hello binds the upstream.
+                let upstream = if self.config.relay {
+                    self.node_target.is_none() && self.handshake.is_some()
+                        && matches!(header.packet_type(), PacketType::Cookie | PacketType::HandshakeInit)
+                } else {
+                    self.sessions.is_empty()
+                };
+                if upstream {
+                    let mut session = Session::new(now, self.config.cbr_floor_bps);
+                    // [DEBUG] Plaintext egress so far ran on the stand-in: The session takes it over.
+                    core::mem::swap(&mut session.pacer, &mut self.unbound_egress.pacer);
+                    core::mem::swap(&mut session.tx_gen_log, &mut self.unbound_egress.tx_gen_log);
+                    self.sessions.insert(peer, session);
+                    self.node_target = Some(peer);
+                } else if self.config.relay {
+                    return;
+                }
+            } else { return; }
+        }
+
+        let session = self.sessions.get_mut(&peer).unwrap();
+        let had_cipher = session.tx_cipher.is_some();
+        let rng = &mut self.rng;
+        let identity = &self.identity;
+        let mem = &self.mem;
+        let phy = &mut self.phy;
+        let pending_kyber = &mut self.pending_kyber;
+        let pending_x25519 = &mut self.pending_x25519;
+        let routes = &mut self.routes;
+        let is_hub = self.config.is_hub;
+        let serves_nodes = is_hub || self.config.relay;
+        let reliable = self.config.reliable_fragments;
+        // Relay: Handshake replies count only from the upstream, never from our own nodes.
+        let from_upstream = !self.config.relay || Some(peer) == self.node_target;
+        let next_gen_id = self.next_data_gen_id;
+        // [DEBUG] Plaintext: Data and ACKs are taken as they come; no AEAD to open.
+        let plaintext = !self.config.enable_encryption;
+        let mut acked: Option<(u16, u32, u32)> = None;
+        let mut nacked: Option<(u16, u16)> = None;
+        let mut relayed: Option<(M13Header, u16, Vec<u8>)> = None;
+        let mut drop_session = false;
+
+        // [DOS] A reassembly whose fragments stopped arriving must not pin the assembler.
+        if session.assembler.is_expired(now, FRAGMENT_REASSEMBLY_TIMEOUT_US) {
+            session.assembler.reset();
+        }
+
+        match header.packet_type() {
+            PacketType::ClientHello if serves_nodes => {
+                if let Ok(Some(full_data)) = session.assembler.ingest(payload, now) {
+                    session.last_valid_rx_us = now;
+                    let attest = self.golden_pcrs.is_some();
+                    if let Err(e) = Self::process_client_hello(rng, identity, mem, phy, session, &full_data, peer, next_gen_id, attest, reliable) {
+                        warn!("Handshake with {:?} failed: {:?}", peer, e);
                     }
-                } else { return; }
-            }
-
-            let session = self.sessions.get_mut(&peer).unwrap();
-            let rng = &mut self.rng;
-            let identity = &self.identity;
-            let mem = &self.mem;
-            let phy = &mut *self.phy;
-            let pending_kyber = &mut self.pending_kyber;
-            let routes = &mut self.routes;
-            let is_hub = self.config.is_hub;
-
-            match header.packet_type {
-                PacketType::ClientHello => {
-                    if is_hub {
-                        if let Ok(Some(full_data)) = session.assembler.ingest(payload) {
-                            session.last_valid_rx_us = now;
-                            Self::process_client_hello(rng, identity, mem, phy, session, &full_data, peer);
+                }
+            },
+            PacketType::HandshakeInit if !is_hub && from_upstream => {
+                if header.gen_id() != 0 {
+                    ack_fragment(phy, &header.to_header(), payload, Some(peer)).ok();
+                }
+                if let Ok(Some(full_data)) = session.assembler.ingest(payload, now) {
+                    session.last_valid_rx_us = now;
+                    let nonce = Self::process_server_hello(session, &full_data, pending_kyber, pending_x25519, next_gen_id);
+                    // [ATTEST] The hub challenged: Prove our state, bound to this key exchange.
+                    if let Some(nonce) = nonce {
+                        match &self.attestation {
+                            Some((pcrs, aik_pub)) => {
+                                match generate_attestation(&nonce, identity, pcrs.clone(), &mut *self.sec, rng) {
+                                    Ok(mut frame) => {
+                                        frame.legacy_aik_pub = *aik_pub;
+                                        let arq = reliable.then(|| (&mut session.fragment_tx, fragment_msg_id(rng)));
+                                        Self::send_fragmented(mem, phy, PacketType::HandshakeAuth, &frame.to_bytes(), Some(peer), arq);
+                                    }
+                                    Err(e) => warn!("Attestation failed: {:?}", e),
+                                }
+                            }
+                            None => warn!("Hub requires attestation, but no evidence is configured"),
                         }
                     }
-                },
-                PacketType::HandshakeInit => {
-                    if !is_hub {
-                        if let Ok(Some(full_data)) = session.assembler.ingest(payload) {
-                            session.last_valid_rx_us = now;
-                            Self::process_server_hello(session, &full_data, pending_kyber);
+                }
+            },
+            PacketType::HandshakeAuth => {
+                let awaiting = is_hub && session.pending_attestation.is_some();
+                if awaiting {
+                    if header.gen_id() != 0 {
+                        ack_fragment(phy, &header.to_header(), payload, Some(peer)).ok();
+                    }
+                    if let Ok(Some(full_data)) = session.assembler.ingest(payload, now) {
+                        let verdict = match (&session.pending_attestation, &self.golden_pcrs) {
+                            (Some((nonce, _, _)), Some(golden)) => Epoch0Frame::from_bytes(&full_data)
+                                .and_then(|frame| verify_epoch0(&frame, nonce, golden)),
+                            _ => Err(M13Error::InvalidState),
+                        };
+                        match verdict {
+                            Ok(()) => {
+                                if let Some((_, tx, rx)) = session.pending_attestation.take() {
+                                    session.last_valid_rx_us = now;
+                                    session.install_ciphers(tx, rx, next_gen_id);
+                                    info!("Attestation verified: Session Established with {:?}", peer);
+                                }
+                            }
+                            Err(e) => {
+                                warn!("Attestation of {:?} rejected: {:?}", peer, e);
+                                drop_session = true;
+                            }
                         }
                     }
-                },
-                PacketType::Coded | PacketType::Data => {
-                    if let Some(cipher) = &session.cipher {
-                        if cipher.decrypt_detached(&header, payload).is_ok() {
+                }
+            },
+            PacketType::Coded | PacketType::Data | PacketType::Recoded => {
+                // [REKEY] Trial-decrypt across epochs; may advance the session key.
+                let opened = plaintext || session.open(&header, payload, next_gen_id).is_ok();
+                if opened {
+                    let coded = split_coded(&header, payload);
+                    // [MESH] Relay: Coded traffic from our own nodes goes upstream, undecoded.
+                    let relaying = self.config.relay && Some(peer) != self.node_target
+                        && header.packet_type() != PacketType::Data && session.tx_cipher.is_some();
+                    // [CHAFF] The marker is AAD-bound: Only the peer could have set it.
+                    if header.packet_type() == PacketType::Data && header.reserved() == CHAFF_MARKER {
+                        session.last_valid_rx_us = now;
+                        self.chaff_received += 1;
+                    } else if self.completed_gens.contains(&(peer, header.gen_id())) {
+                        // [FEC] Late symbol of a delivered generation.
+                        session.last_valid_rx_us = now;
+                    } else if relaying {
+                        if let Some((code_gen, gev, symbol)) = coded {
                             session.last_valid_rx_us = now;
-                            
-                            let gen_id = header.gen_id;
-                            let k = if header.reserved > 0 { header.reserved as usize } else { 1 };
-                            
-                            let decoder = self.data_decoders.entry(gen_id).or_insert_with(|| {
-                                FountainDecoder::new(k, RAPTOR_SYMBOL_SIZE, gen_id)
-                            });
-                            
-                            if let Ok(Some(decoded_data)) = decoder.receive_symbol(header.symbol_id, payload) {
+                            relayed = relay_row(&header, code_gen, gev, symbol).map(|row| (header.to_header(), code_gen, row));
+                        }
+                    } else if let Some((code_gen, gev, symbol)) = coded.filter(|&(_, _, symbol)| {
+                        (plaintext || session.tx_cipher.is_some()) && symbol_fits(&self.data_decoders, (peer, header.gen_id()), symbol.len())
+                    }) {
+                        session.last_valid_rx_us = now;
+                        let cipher = session.tx_cipher.as_ref();
+                        
+                        let gen_id = header.gen_id();
+                        let key = (peer, gen_id);
+                        let k = if header.reserved() > 0 { header.reserved() as usize } else { 1 };
+                        
+                        // [DOS] Make room by dropping the least recently touched generation.
+                        if !self.data_decoders.contains_key(&key) && self.data_decoders.len() >= MAX_DATA_DECODERS {
+                            let stalest = self.data_decoders.iter()
+                                .min_by_key(|(_, p)| p.last_rx_us)
+                                .map(|(&g, _)| g);
+                            if let Some(g) = stalest {
+                                self.data_decoders.remove(&g);
+                                self.decode_failures += 1;
+                            }
+                        }
+
+                        // [MESH] Recoded rows are over the source's intermediates: Its gen_id seeds them.
+                        let pending = self.data_decoders.entry(key).or_insert_with(|| PendingGen {
+                            decoder: FountainDecoder::new(k, symbol.len(), code_gen),
+                            first_rx_us: rx_us,
+                            last_rx_us: now,
+                            ce_marks: 0,
+                            nacks: 0,
+                        });
+                        pending.last_rx_us = now;
+                        if ecn == ECN_CE { pending.ce_marks = pending.ce_marks.saturating_add(1); }
+                        let first_rx_us = pending.first_rx_us;
+                        let ce_marks = pending.ce_marks;
+                        
+                        let received = match gev {
+                            Some(gev) => pending.decoder.receive_combination(gev, symbol),
+                            None => pending.decoder.receive_symbol(header.symbol_id(), symbol),
+                        };
+                        if let Ok(Some(decoded_data)) = received {
+                            self.decode_completions += 1;
+                            // [MESH] Sealed by the source end to end: Any relay on the way held ciphertext only.
+                            let delivered = match &self.mesh_key {
+                                Some(key) => mesh_open(key, &decoded_data).ok(),
+                                None => Some(decoded_data),
+                            };
+                            if let Some(mut decoded_data) = delivered {
+                                // [JITTER] Strip the sender's PTP origin stamp.
+                                let mut origin_ns = None;
+                                if header.recoder_rank() & ORIGIN_STAMPED != 0 && decoded_data.len() >= ORIGIN_STAMP_LEN {
+                                    let mut stamp = [0u8; ORIGIN_STAMP_LEN];
+                                    stamp.copy_from_slice(&decoded_data[..ORIGIN_STAMP_LEN]);
+                                    origin_ns = Some(u64::from_be_bytes(stamp));
+                                    decoded_data.drain(..ORIGIN_STAMP_LEN);
+                                }
                                 if is_hub {
-                                    if let Some((src_vip, _)) = parse_ipv4_headers(&decoded_data) {
-                                        routes.insert(src_vip, peer);
+                                    if let Some((src, _)) = parse_ip_headers(&decoded_data) {
+                                        if !session.assigned_vips.contains(&src) && session.assigned_vips.len() < MAX_VIPS_PER_SESSION {
+                                            session.assigned_vips.push(src);
+                                        }
+                                        // [DOS] Past the cap, unknown sources are delivered but never routed.
+                                        if session.assigned_vips.contains(&src) {
+                                            routes.insert(src, peer);
+                                        }
                                     }
                                 }
-                                self.tun_rx_queue.push_back(decoded_data);
-                                self.data_decoders.remove(&gen_id); 
+                                // [JITTER] Release at origin + depth, in arrival order, or drop if already late.
+                                // Origin: The sender's PTP stamp when both ends have PTP, else first_rx.
+                                if self.config.jitter_buffer {
+                                    let depth = self.phase.calculate_depth();
+                                    let origin_us = origin_ns
+                                        .and_then(|ns| ptp_origin_us(now, self.clock.ptp_ns(), ns))
+                                        .unwrap_or(first_rx_us);
+                                    let jb = session.jitter.get_or_insert_with(|| {
+                                        JitterBuffer::with_capacity(depth, JITTER_MAX_PACKETS, OverflowPolicy::DropFurthest)
+                                    });
+                                    jb.push(header.to_header(), decoded_data, origin_us, now);
+                                } else {
+                                    self.tun_rx_queue.push_back(decoded_data);
+                                }
+                            } else {
+                                warn!("Generation {} from {:?} failed to open end to end", gen_id, peer);
+                                self.decode_failures += 1;
+                            }
+                            self.data_decoders.remove(&key); 
+                            if self.completed_gens.len() >= MAX_COMPLETED_GENS { self.completed_gens.pop_front(); }
+                            self.completed_gens.push_back((peer, gen_id));
+
+                            // [BBR] Close the loop: Tell the sender this generation landed.
+                            Self::send_ack(mem, phy, cipher, session.tx_sequence, gen_id, header.symbol_id(), ce_marks, now, peer);
+                            session.tx_sequence = session.tx_sequence.wrapping_add(1);
+                            session.last_tx_us = now;
+                        } else if pending.decoder.dependent() >= DECODE_STALL_DEPENDENT {
+                            warn!("Generation {} stalled at rank {} after {} symbols, abandoned",
+                                gen_id, pending.decoder.rank(), pending.decoder.received());
+                            self.data_decoders.remove(&key);
+                            self.decode_failures += 1;
+                        } else if header.recoder_rank() & LAST_SYMBOL != 0 {
+                            // [FEC] End of a burst and still short: Ask for the rest, or stop
+                            // waiting once the sender has stopped repairing.
+                            if pending.nacks >= MAX_REPAIR_ROUNDS {
+                                warn!("Generation {} unrecoverable after {} repairs, abandoned", gen_id, pending.nacks);
+                                self.data_decoders.remove(&key);
+                                self.decode_failures += 1;
+                            } else {
+                                pending.nacks += 1;
+                                let needed = pending.decoder.needed();
+                                Self::send_nack(mem, phy, cipher, session.tx_sequence, gen_id, needed, peer);
+                                session.tx_sequence = session.tx_sequence.wrapping_add(1);
+                                session.last_tx_us = now;
                             }
                         }
                     }
-                },
-                _ => {}
+                }
+            },
+            PacketType::Ack => {
+                // [ARQ] Fragment ACK: Unsealed (see `ack_fragment`), so it never counts as liveness.
+                if payload.len() == fragment::FRAGMENT_ACK_LEN {
+                    if let Some(tx) = &mut session.fragment_tx {
+                        if tx.on_ack(&header.to_header()) && tx.is_complete() {
+                            session.fragment_tx = None;
+                        }
+                    }
+                }
+                let opened = payload.len() == ACK_PAYLOAD_LEN && (plaintext || session.open(&header, payload, next_gen_id).is_ok());
+                if opened {
+                    session.last_valid_rx_us = now;
+                    let ce_marks = u32::from_be_bytes([payload[8], payload[9], payload[10], payload[11]]);
+                    let gen_id = u16::from_be_bytes([payload[12], payload[13]]);
+                    let symbol_id = u32::from_be_bytes([payload[14], payload[15], payload[16], payload[17]]);
+                    acked = Some((gen_id, symbol_id, ce_marks));
+                }
+            },
+            PacketType::Nack => {
+                let opened = payload.len() == NACK_PAYLOAD_LEN && (plaintext || session.open(&header, payload, next_gen_id).is_ok());
+                if opened {
+                    session.last_valid_rx_us = now;
+                    nacked = Some((u16::from_be_bytes([payload[0], payload[1]]), u16::from_be_bytes([payload[2], payload[3]])));
+                }
+            },
+            PacketType::Cookie if !is_hub && from_upstream && payload.len() == COOKIE_LEN => {
+                let mut cookie = [0u8; COOKIE_LEN];
+                cookie.copy_from_slice(payload);
+                // One reply per hello fragment: Only a new cookie warrants a resend.
+                if let Some(hs) = self.handshake.as_ref().filter(|_| self.hub_cookie != Some(cookie)) {
+                    self.hub_cookie = Some(cookie);
+                    Self::send_client_hello(mem, phy, &hs.hello, self.hub_cookie, Some(peer));
+                }
+            },
+            PacketType::Goodbye => {
+                let opened = payload.is_empty() && session.open(&header, payload, next_gen_id).is_ok();
+                if opened {
+                    info!("Goodbye from {:?}: Session closed", peer);
+                    drop_session = true;
+                }
+            },
+            PacketType::KeepAlive => {
+                let opened = payload.is_empty() && session.open(&header, payload, next_gen_id).is_ok();
+                if opened {
+                    session.last_valid_rx_us = now;
+                    // [NAT] Re-pin the peer's virtual IPs to its current binding.
+                    for vip in &session.assigned_vips {
+                        routes.insert(*vip, peer);
+                    }
+                }
+            },
+            _ => {}
+        }
+
+        if !had_cipher && self.sessions.get(&peer).is_some_and(|s| s.tx_cipher.is_some()) {
+            self.handshakes_completed += 1;
+        }
+        if drop_session {
+            self.sessions.remove(&peer);
+            self.purge_peers(&[peer]);
+        }
+        if let Some((gen_id, symbol_id, ce_marks)) = acked {
+            self.process_ack(gen_id, symbol_id, ce_marks, peer, now);
+        }
+        if let Some((gen_id, needed)) = nacked {
+            self.process_nack(gen_id, needed, peer);
+        }
+        if let Some((header, code_gen, row)) = relayed {
+            self.relay_symbol(peer, header, code_gen, row, now);
+        }
+    }
+
+    /// KeepAlive/Goodbye = empty authenticated payload. The tag proves the sender; nothing else is carried.
+    fn send_control(
+        mem: &Arc<SlabAllocator>,
+        phy: &mut dyn PhysicalInterface,
+        session: &mut Session,
+        packet_type: PacketType,
+        peer: PeerAddr,
+        now: u64
+    ) {
+        let cipher = match &session.tx_cipher {
+            Some(c) => c,
+            None => return,
+        };
+        let mut header = M13Header::new(packet_type, 0, KEEPALIVE_SYMBOL_BASE | (session.tx_sequence & !KEEPALIVE_SYMBOL_BASE));
+        match cipher.encrypt_detached(&header, &mut []) {
+            Ok(tag) => header.auth_tag = tag,
+            Err(_) => return,
+        }
+        if let Some(mut lease) = mem.alloc_sized(M13Header::SIZE) {
+            if header.to_bytes(&mut lease.data).is_ok() {
+                phy.send(&lease.data[..M13Header::SIZE], Some(peer)).ok();
+            }
+        }
+        session.tx_sequence = session.tx_sequence.wrapping_add(1);
+        session.last_tx_us = now;
+    }
+
+    /// Chaff = a coded-symbol-sized frame of random plaintext, sealed like data.
+    /// Shares the KeepAlive nonce space, so it never collides with a data (gen_id, symbol_id).
+    fn send_chaff(
+        mem: &Arc<SlabAllocator>,
+        phy: &mut dyn PhysicalInterface,
+        rng: &mut ChaCha20Rng,
+        symbol_size: usize,
+        session: &mut Session,
+        peer: PeerAddr,
+        now: u64
+    ) {
+        let cipher = match &session.tx_cipher {
+            Some(c) => c,
+            None => return,
+        };
+        let (mut header, mut payload) = generate_chaff(symbol_size, 0, rng);
+        header.symbol_id = KEEPALIVE_SYMBOL_BASE | (session.tx_sequence & !KEEPALIVE_SYMBOL_BASE);
+        match cipher.encrypt_detached(&header, &mut payload) {
+            Ok(tag) => header.auth_tag = tag,
+            Err(_) => return,
+        }
+        let len = M13Header::SIZE + payload.len();
+        if let Some(mut lease) = mem.alloc_sized(len) {
+            if header.to_bytes(&mut lease.data).is_ok() {
+                lease.data[M13Header::SIZE..len].copy_from_slice(&payload);
+                phy.send(&lease.data[..len], Some(peer)).ok();
+            }
+        }
+        session.tx_sequence = session.tx_sequence.wrapping_add(1);
+        session.last_tx_us = now;
+    }
+
+    /// ACK = { receiver timestamp, CE count, gen_id, highest symbol_id } in the payload. Shares
+    /// the KeepAlive nonce space (`sequence`: The session's tx_sequence), like NACKs: Echoing the
+    /// peer's (gen_id, symbol_id) in the header would reuse our own data nonces under our key.
+    #[allow(clippy::too_many_arguments)]
+    fn send_ack(
+        mem: &Arc<SlabAllocator>,
+        phy: &mut dyn PhysicalInterface,
+        cipher: Option<&M13Cipher>,
+        sequence: u32,
+        gen_id: u16,
+        symbol_id: u32,
+        ce_marks: u32,
+        now: u64,
+        peer: PeerAddr
+    ) {
+        let mut payload = [0u8; ACK_PAYLOAD_LEN];
+        payload[..8].copy_from_slice(&now.to_be_bytes());
+        payload[8..12].copy_from_slice(&ce_marks.to_be_bytes());
+        payload[12..14].copy_from_slice(&gen_id.to_be_bytes());
+        payload[14..].copy_from_slice(&symbol_id.to_be_bytes());
+        let nonce_id = KEEPALIVE_SYMBOL_BASE | (sequence & !KEEPALIVE_SYMBOL_BASE);
+        let mut header = M13Header { payload_len: ACK_PAYLOAD_LEN as u16, ..M13Header::new(PacketType::Ack, 0, nonce_id) };
+        // [DEBUG] No cipher: Plaintext mode, the ACK goes out untagged.
+        if let Some(cipher) = cipher {
+            match cipher.encrypt_detached(&header, &mut payload) {
+                Ok(tag) => header.auth_tag = tag,
+                Err(_) => return,
+            }
+        }
+        if let Some(mut lease) = mem.alloc_sized(32 + ACK_PAYLOAD_LEN) {
+            if header.to_bytes(&mut lease.data).is_ok() {
+                lease.data[32..32 + ACK_PAYLOAD_LEN].copy_from_slice(&payload);
+                phy.send(&lease.data[..32 + ACK_PAYLOAD_LEN], Some(peer)).ok();
+            }
+        }
+    }
+
+    /// [FEC] NACK = { gen_id, independent symbols still missing } in the payload. Shares the
+    /// KeepAlive nonce space (`sequence`: The session's tx_sequence), like chaff.
+    fn send_nack(
+        mem: &Arc<SlabAllocator>,
+        phy: &mut dyn PhysicalInterface,
+        cipher: Option<&M13Cipher>,
+        sequence: u32,
+        gen_id: u16,
+        needed: usize,
+        peer: PeerAddr
+    ) {
+        let mut payload = [0u8; NACK_PAYLOAD_LEN];
+        payload[..2].copy_from_slice(&gen_id.to_be_bytes());
+        payload[2..].copy_from_slice(&(core::cmp::min(needed, u16::MAX as usize) as u16).to_be_bytes());
+        let symbol_id = KEEPALIVE_SYMBOL_BASE | (sequence & !KEEPALIVE_SYMBOL_BASE);
+        let mut header = M13Header { payload_len: NACK_PAYLOAD_LEN as u16, ..M13Header::new(PacketType::Nack, 0, symbol_id) };
+        // [DEBUG] No cipher: Plaintext mode, the NACK goes out untagged.
+        if let Some(cipher) = cipher {
+            match cipher.encrypt_detached(&header, &mut payload) {
+                Ok(tag) => header.auth_tag = tag,
+                Err(_) => return,
+            }
+        }
+        if let Some(mut lease) = mem.alloc_sized(32 + NACK_PAYLOAD_LEN) {
+            if header.to_bytes(&mut lease.data).is_ok() {
+                lease.data[32..32 + NACK_PAYLOAD_LEN].copy_from_slice(&payload);
+                phy.send(&lease.data[..32 + NACK_PAYLOAD_LEN], Some(peer)).ok();
+            }
+        }
+    }
+
+    /// [BBR] Convert an ACK into a (delivery rate, RTT, congestion) sample for the pacer.
+    /// The sample goes to `peer`'s own pacer: One path's congestion never slows another.
+    fn process_ack(&mut self, gen_id: u16, symbol_id: u32, ce_marks: u32, peer: PeerAddr, now: u64) {
+        // [FEC] Delivered: Nothing left to repair, and a repair under way can stop.
+        self.tx_history.remove(&gen_id);
+        self.data_encoders.retain(|_, g| !(g.repairs > 0 && g.enc.gen_id() == gen_id));
+        // [MESH] Relay: The upstream has the generation. Late rows from its source are dropped.
+        if Some(peer) == self.node_target {
+            if let Some(key) = self.relay_gens.iter().find(|(_, g)| g.out_gen == gen_id).map(|(&key, _)| key) {
+                self.relay_gens.remove(&key);
+                if self.completed_gens.len() >= MAX_COMPLETED_GENS { self.completed_gens.pop_front(); }
+                self.completed_gens.push_back(key);
+            }
+        }
+        let session = match self.sessions.get_mut(&peer) {
+            Some(s) => s,
+            None => return,
+        };
+        if let Some((tx_us, _)) = session.tx_gen_log.remove(&gen_id) {
+            let rtt_us = core::cmp::max(1, now.saturating_sub(tx_us));
+            // Symbols 0..=symbol_id were needed at the receiver (wire bytes incl. header).
+            let delivered_bytes = (symbol_id as u64 + 1) * (self.config.symbol_size as u64 + 32);
+            let delivered_bps = (delivered_bytes * 8 * 1_000_000) / rtt_us;
+            session.pacer.on_ack(delivered_bps, rtt_us, ce_marks > 0, now);
+            self.on_rtt_sample(rtt_us);
+        }
+    }
+
+    /// [HANDSHAKE] Node: Start, retransmit (exponential backoff) or abandon the ClientHello.
+    fn drive_handshake(&mut self, now: u64) -> bool {
+        let hs = match &mut self.handshake {
+            None => {
+                if now < self.handshake_holdoff_until { return false; }
+                info!("Client: Initiating Handshake (Cold Start)...");
+                self.initiate_handshake(None);
+                return true;
             }
+            Some(hs) => hs,
+        };
+        if now < hs.next_tx_us { return false; }
+
+        if hs.attempts >= HANDSHAKE_MAX_ATTEMPTS {
+            warn!("Handshake failed after {} attempts", hs.attempts);
+            self.handshake = None;
+            self.pending_kyber = None;
+            self.pending_x25519 = None;
+            self.handshake_failures += 1;
+            self.handshake_holdoff_until = now + HANDSHAKE_RTO_MAX_US;
+            return false;
         }
+
+        hs.attempts += 1;
+        hs.next_tx_us = now + hs.rto_us();
+        info!("Client: Retransmitting ClientHello (attempt {})", hs.attempts);
+        Self::send_client_hello(&self.mem, &mut self.phy, &hs.hello, self.hub_cookie, None);
+        true
     }
 
     fn initiate_handshake(&mut self, target: Option<PeerAddr>) {
-        if let Ok(kp) = KyberKeypair::generate(&mut self.rng) {
+        if let Ok(kp) = KyberKeypair::generate_with_profile(self.config.kem_profile, &mut self.rng) {
             let mut payload = Vec::new();
             payload.extend_from_slice(&kp.public);
+
+            let x_kp = if self.config.hybrid_kex {
+                match X25519Keypair::generate(&mut self.rng) {
+                    Ok(x) => {
+                        payload.extend_from_slice(&x.public);
+                        Some(x)
+                    }
+                    Err(_) => return,
+                }
+            } else {
+                None
+            };
             
             if let Some(t) = target {
-                let mut s = Session::new(0);
+                let mut s = Session::new(0, self.config.cbr_floor_bps);
                 s.ephemeral_key = Some(kp);
+                s.ephemeral_x25519 = x_kp;
                 self.sessions.insert(t, s);
             } else {
                 self.pending_kyber = Some(kp);
+                self.pending_x25519 = x_kp;
+            }
+            Self::send_client_hello(&self.mem, &mut self.phy, &payload, self.hub_cookie, target);
+
+            if target.is_none() {
+                let mut hs = HandshakeAttempt { hello: payload, attempts: 1, next_tx_us: 0 };
+                hs.next_tx_us = self.clock.now_us() + hs.rto_us();
+                self.handshake = Some(hs);
             }
-            Self::send_fragmented(&self.mem, &mut *self.phy, PacketType::ClientHello, &payload, target);
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn process_client_hello(
         rng: &mut ChaCha20Rng,
         identity: &DsaKeypair,
@@ -429,42 +2031,196 @@ impl M13Kernel {
         phy: &mut dyn PhysicalInterface,
         session: &mut Session,
         payload: &[u8], 
-        peer: PeerAddr
-    ) {
-        if payload.len() < KYBER_PK_LEN_1024 { return; }
-        let pk = &payload[0..KYBER_PK_LEN_1024];
-        info!("Handshaking with {:?}", peer);
-        
-        if let Ok((ct, ss)) = kyber_encapsulate(pk, rng) {
-            let sig = dsa_sign(&ct, &identity.secret);
-            let mut resp = Vec::new();
+        peer: PeerAddr,
+        next_gen_id: u16,
+        attest: bool,
+        reliable: bool,
+    ) -> M13Result<()> {
+        // [HANDSHAKE] A retransmitted ClientHello gets the identical reply. Re-keying would
+        // strand a node that already accepted the first one.
+        if let Some((hello, resp)) = &session.hello_replay {
+            if hello.as_slice() == payload {
+                let arq = reliable.then(|| (&mut session.fragment_tx, fragment_msg_id(rng)));
+                Self::send_fragmented(mem, phy, PacketType::HandshakeInit, resp, Some(peer), arq);
+                return Ok(());
+            }
+        }
+
+        // [PROFILE] ClientHello = EK (768 or 1024) [|| X25519_PK]. Every combination has a distinct length.
+        let (profile, hybrid) = match KemProfile::from_public_key_len(payload.len()) {
+            Some(p) => (p, false),
+            None => {
+                let pk_len = payload.len().checked_sub(X25519_KEY_SIZE).ok_or(M13Error::WireFormatError)?;
+                (KemProfile::from_public_key_len(pk_len).ok_or(M13Error::WireFormatError)?, true)
+            }
+        };
+        let pk_len = profile.public_key_len();
+        let pk = &payload[0..pk_len];
+        info!("Handshaking with {:?} ({:?})", peer, profile);
+
+        // [HYBRID] ClientHello = EK || X25519_PK. Pure-PQC peers send EK only.
+        // CRITICAL: Shared secret, ciphertext and reply are scrubbed on every exit path.
+        let mut resp = Zeroizing::new(Vec::new());
+        let ss = if hybrid {
+            let x_pk = &payload[pk_len..pk_len + X25519_KEY_SIZE];
+            let (ct, x_pub, key) = hybrid_encapsulate(pk, x_pk, rng)?;
+            let (ct, key) = (Zeroizing::new(ct), Zeroizing::new(key));
+            resp.extend_from_slice(&ct);
+            resp.extend_from_slice(&x_pub);
+            key
+        } else {
+            let (ct, ss) = kyber_encapsulate(pk, rng)?;
+            let (ct, ss) = (Zeroizing::new(ct), Zeroizing::new(ss));
             resp.extend_from_slice(&ct);
-            resp.extend_from_slice(&sig);
-            session.cipher = Some(M13Cipher::new(&SessionKey(ss)));
-            info!("Session Established with {:?}", peer);
-            Self::send_fragmented(mem, phy, PacketType::HandshakeInit, &resp, Some(peer));
+            ss
+        };
+
+        // [ATTEST] Challenge = 32 fresh bytes, covered by the signature below.
+        let nonce = if attest {
+            let mut challenge = [0u8; ATTEST_CHALLENGE_LEN];
+            rng.fill_bytes(&mut challenge);
+            resp.extend_from_slice(&challenge);
+            Some(bind_nonce(&challenge, &resp[..profile.ciphertext_len()]))
+        } else {
+            None
+        };
+
+        // [FIX] A signing failure only aborts this peer's handshake, never the hub.
+        let sig = dsa_sign(&resp, &identity.secret, DSA_CTX_HANDSHAKE, rng).map_err(|_| M13Error::CryptoFailure)?;
+        resp.extend_from_slice(&sig);
+        // [KDF] The session key binds the whole transcript, signature included.
+        let (tx, rx) = DirectionalKeys::derive(&derive_session_key(&ss, payload, &resp)).ciphers(true);
+        match nonce {
+            Some(nonce) => {
+                session.pending_attestation = Some((nonce, tx, rx));
+                info!("Awaiting attestation from {:?}", peer);
+            }
+            None => {
+                session.install_ciphers(tx, rx, next_gen_id);
+                info!("Session Established with {:?}", peer);
+            }
         }
+        let arq = reliable.then(|| (&mut session.fragment_tx, fragment_msg_id(rng)));
+        Self::send_fragmented(mem, phy, PacketType::HandshakeInit, &resp, Some(peer), arq);
+        session.hello_replay = Some((payload.to_vec(), resp));
+        Ok(())
+    }
+
+    /// Node: Returns the bound attestation nonce if the hub attached a challenge.
+    fn process_server_hello(
+        session: &mut Session,
+        payload: &[u8],
+        pending_key: &mut Option<KyberKeypair>,
+        pending_x25519: &mut Option<X25519Keypair>,
+        next_gen_id: u16,
+    ) -> Option<[u8; 32]> {
+        let kp = pending_key.take()?;
+        let ct_len = kp.profile.ciphertext_len();
+        if payload.len() < ct_len { return None; }
+        let ct = &payload[0..ct_len];
+        let mut offset = ct_len;
+        let x_kp = pending_x25519.take();
+        let result = match &x_kp {
+            Some(x_kp) => {
+                if payload.len() < ct_len + X25519_KEY_SIZE { return None; }
+                let x_pub = &payload[ct_len..ct_len + X25519_KEY_SIZE];
+                offset += X25519_KEY_SIZE;
+                hybrid_decapsulate(&kp, x_kp, ct, x_pub)
+            }
+            None => kyber_decapsulate(&kp, ct),
+        };
+        let ss = Zeroizing::new(result.ok()?);
+
+        // [KDF] Rebuild our ClientHello (EK [|| X25519_PK]) for the transcript.
+        let mut hello = kp.public.clone();
+        if let Some(x_kp) = &x_kp { hello.extend_from_slice(&x_kp.public); }
+        let (tx, rx) = DirectionalKeys::derive(&derive_session_key(&ss, &hello, payload)).ciphers(false);
+        session.install_ciphers(tx, rx, next_gen_id);
+        info!(">>> [NODE] v0.3.0: SECURE LINK ESTABLISHED (PQC+FEC Active).");
+
+        // [ATTEST] ServerHello = CT [|| X25519_PK] [|| CHALLENGE] || SIG. The signature length is fixed.
+        if payload.len() != offset + ATTEST_CHALLENGE_LEN + DILITHIUM_SIGNATURE_SIZE { return None; }
+        let challenge: [u8; ATTEST_CHALLENGE_LEN] = payload[offset..offset + ATTEST_CHALLENGE_LEN].try_into().ok()?;
+        Some(bind_nonce(&challenge, ct))
     }
 
-    fn process_server_hello(session: &mut Session, payload: &[u8], pending_key: &mut Option<KyberKeypair>) {
-        if let Some(kp) = pending_key.take() {
-            if payload.len() < KYBER_CT_LEN_1024 { return; }
-            let ct = &payload[0..KYBER_CT_LEN_1024];
-            if let Ok(ss) = kyber_decapsulate(&kp, ct) {
-                session.cipher = Some(M13Cipher::new(&SessionKey(ss)));
-                info!(">>> [NODE] v0.3.0: SECURE LINK ESTABLISHED (PQC+FEC Active).");
+    /// [DOS] Cookie input: The peer's address and the cookie window.
+    fn cookie_input(peer: &PeerAddr, window: u64) -> [u8; 27] {
+        let mut input = [0u8; 27];
+        match peer {
+            PeerAddr::V4(ip, port) => {
+                input[0] = 4;
+                input[1..5].copy_from_slice(ip);
+                input[17..19].copy_from_slice(&port.to_be_bytes());
+            }
+            PeerAddr::V6(ip, port) => {
+                input[0] = 6;
+                input[1..17].copy_from_slice(ip);
+                input[17..19].copy_from_slice(&port.to_be_bytes());
             }
+            PeerAddr::None => {}
+        }
+        input[19..27].copy_from_slice(&window.to_be_bytes());
+        input
+    }
+
+    fn cookie_valid(&self, peer: &PeerAddr, presented: &[u8; COOKIE_LEN], now: u64) -> bool {
+        let window = now / COOKIE_WINDOW_US;
+        [Some(window), window.checked_sub(1)].into_iter().flatten()
+            .any(|w| cookie_verify(&self.cookie_secret, &Self::cookie_input(peer, w), presented))
+    }
+
+    /// [DOS] Hub: Answer an unproven ClientHello with a cookie. 48 bytes out for ~1KB in: No amplification.
+    fn send_cookie(&mut self, peer: PeerAddr, now: u64) {
+        let mut cookie = cookie_tag(&self.cookie_secret, &Self::cookie_input(&peer, now / COOKIE_WINDOW_US));
+        let header = M13Header { payload_len: COOKIE_LEN as u16, ..M13Header::new(PacketType::Cookie, 0, 0) };
+        let mut frame = [0u8; 32 + COOKIE_LEN];
+        if header.to_bytes(&mut frame).is_ok() {
+            frame[32..].copy_from_slice(&cookie);
+            let _ = self.phy.send(&frame, Some(peer));
         }
+        cookie.fill(0);
     }
 
+    /// Node: ClientHello fragments carry the hub's cookie (zero before the first) in the tag field.
+    fn send_client_hello(
+        mem: &Arc<SlabAllocator>,
+        phy: &mut dyn PhysicalInterface,
+        hello: &[u8],
+        cookie: Option<[u8; COOKIE_LEN]>,
+        target: Option<PeerAddr>
+    ) {
+        Self::send_fragments(mem, phy, PacketType::ClientHello, hello, target, cookie.unwrap_or([0; COOKIE_LEN]));
+    }
+
+    /// Fire and forget, or with `arq` (the session's slot, a fresh message id) parked in a
+    /// `ReliableFragmentSender` for `poll` to send and resend until every fragment is ACKed.
     fn send_fragmented(
         mem: &Arc<SlabAllocator>, 
         phy: &mut dyn PhysicalInterface, 
         ptype: PacketType, 
         payload: &[u8], 
-        target: Option<PeerAddr>
+        target: Option<PeerAddr>,
+        arq: Option<(&mut Option<ReliableFragmentSender>, u16)>
     ) {
-        const CHUNK_SIZE: usize = 1000;
+        match arq {
+            Some((slot, msg_id)) => match ReliableFragmentSender::new(ptype, msg_id, payload, target) {
+                Ok(tx) => *slot = Some(tx),
+                Err(e) => warn!("{:?} not sent: {:?}", ptype, e),
+            },
+            None => Self::send_fragments(mem, phy, ptype, payload, target, [0; 16]),
+        }
+    }
+
+    fn send_fragments(
+        mem: &Arc<SlabAllocator>,
+        phy: &mut dyn PhysicalInterface,
+        ptype: PacketType,
+        payload: &[u8],
+        target: Option<PeerAddr>,
+        auth_tag: [u8; 16]
+    ) {
+        const CHUNK_SIZE: usize = fragment::FRAGMENT_CHUNK_SIZE;
         let total_len = payload.len();
         let mut offset = 0;
 
@@ -473,17 +2229,13 @@ impl M13Kernel {
             let chunk = &payload[offset..end];
             let chunk_len = chunk.len();
 
-            if let Some(mut lease) = mem.alloc() {
+            if let Some(mut lease) = mem.alloc_sized(32 + 4 + chunk_len) {
                 let mut frag_payload = Vec::with_capacity(4 + chunk_len);
                 frag_payload.extend_from_slice(&(total_len as u16).to_be_bytes());
                 frag_payload.extend_from_slice(&(offset as u16).to_be_bytes());
                 frag_payload.extend_from_slice(chunk);
 
-                let header = M13Header {
-                    magic: M13_MAGIC, version: 1, packet_type: ptype,
-                    gen_id: 0, symbol_id: 0, payload_len: frag_payload.len() as u16,
-                    recoder_rank: 0, reserved: 0, auth_tag: [0; 16]
-                };
+                let header = M13Header { payload_len: frag_payload.len() as u16, auth_tag, ..M13Header::new(ptype, 0, 0) };
                 
                 lease.data[32..32+frag_payload.len()].copy_from_slice(&frag_payload);
                 if header.to_bytes(&mut lease.data).is_ok() {
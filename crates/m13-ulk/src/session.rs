@@ -1,25 +1,127 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 use m13_cipher::M13Cipher; // [FIX] Removed unused SessionKey
-use m13_pqc::KyberKeypair;
-use crate::fragment::FragmentAssembler;
+use m13_core::{M13Error, M13HeaderRef, M13Result};
+use m13_flow::Pacer;
+use m13_pqc::{KyberKeypair, X25519Keypair};
+use m13_time::JitterBuffer;
+use zeroize::Zeroizing;
+use crate::fragment::{FragmentAssembler, ReliableFragmentSender};
+use crate::IpDest;
 
 pub struct Session {
-    pub cipher: Option<M13Cipher>,
+    // [KDF] Directional keys: We seal with `tx_cipher`, the peer with our `rx_cipher`.
+    // A reflected frame fails to open. That alone does not keep nonces unique: Every
+    // frame we seal must take its (gen_id, symbol_id) from our own space, data from our
+    // encoders and control frames (ACK included) from `tx_sequence`.
+    pub tx_cipher: Option<M13Cipher>,
+    pub rx_cipher: Option<M13Cipher>,
     pub ephemeral_key: Option<KyberKeypair>,
+    pub ephemeral_x25519: Option<X25519Keypair>,
     pub tx_sequence: u32,
     pub last_valid_rx_us: u64,
-    pub assigned_vip: Option<u32>,
+    // [NAT] Last authenticated TX (data, ACK or KeepAlive) toward this peer.
+    pub last_tx_us: u64,
+    // Hub: Tunnel source addresses learned from this peer (IPv4 and/or IPv6).
+    pub assigned_vips: Vec<IpDest>,
     pub assembler: FragmentAssembler,
+    // [ARQ] Our reliable control message to this peer, until its last fragment is ACKed.
+    pub fragment_tx: Option<ReliableFragmentSender>,
+    // [JITTER] Decoded payloads awaiting playout (only with `KernelConfig::jitter_buffer`).
+    pub jitter: Option<JitterBuffer>,
+    // [HANDSHAKE] Hub: Last (ClientHello, HandshakeInit) pair, replayed on retransmission.
+    pub hello_replay: Option<(Vec<u8>, Zeroizing<Vec<u8>>)>,
+    // [ATTEST] Hub: (bound nonce, negotiated TX/RX ciphers). The keys are withheld until
+    // the node's Epoch0Frame verifies against this nonce.
+    pub pending_attestation: Option<([u8; 32], M13Cipher, M13Cipher)>,
+
+    // [BBR] This peer's own token bucket and rate model: A slow path only throttles itself.
+    pub pacer: Pacer,
+    // [BBR] gen_id -> (first symbol TX time (us), wire bytes sent), for RTT on ACK and in-flight.
+    pub tx_gen_log: BTreeMap<u16, (u64, u64)>,
+
+    // [REKEY] Epoch state. The nonce is (gen_id, symbol_id), so one key must
+    // never see the same gen_id twice.
+    pub key_epoch: u32,
+    epoch_start_gen: u16,
+    // RX epoch n+1: Accepted from a peer that ratcheted first.
+    next_rx_cipher: Option<M13Cipher>,
+    // RX epoch n-1: Accepted for stragglers sent before the ratchet.
+    prev_rx_cipher: Option<M13Cipher>,
 }
 
 impl Session {
-    pub fn new(now: u64) -> Self {
+    pub fn new(now: u64, cbr_floor_bps: u64) -> Self {
         Self {
-            cipher: None,
+            tx_cipher: None,
+            rx_cipher: None,
             ephemeral_key: None,
+            ephemeral_x25519: None,
             tx_sequence: 1,
             last_valid_rx_us: now,
-            assigned_vip: None,
+            last_tx_us: now,
+            assigned_vips: Vec::new(),
             assembler: FragmentAssembler::new(),
+            fragment_tx: None,
+            jitter: None,
+            hello_replay: None,
+            pending_attestation: None,
+            pacer: Pacer::new(cbr_floor_bps),
+            tx_gen_log: BTreeMap::new(),
+            key_epoch: 0,
+            epoch_start_gen: 0,
+            next_rx_cipher: None,
+            prev_rx_cipher: None,
         }
     }
-}
\ No newline at end of file
+
+    /// Install freshly negotiated keys (epoch 0). `next_gen_id` is the first gen_id they will cover.
+    pub fn install_ciphers(&mut self, tx: M13Cipher, rx: M13Cipher, next_gen_id: u16) {
+        self.next_rx_cipher = Some(rx.ratchet());
+        self.tx_cipher = Some(tx);
+        self.rx_cipher = Some(rx);
+        self.prev_rx_cipher = None;
+        self.key_epoch = 0;
+        self.epoch_start_gen = next_gen_id;
+    }
+
+    /// True once the current key has covered `interval` generations.
+    pub fn needs_rekey(&self, next_gen_id: u16, interval: u16) -> bool {
+        self.tx_cipher.is_some() && next_gen_id.wrapping_sub(self.epoch_start_gen) >= interval
+    }
+
+    /// Ratchet both directions to the next epoch. The outgoing RX key stays valid one more epoch.
+    pub fn rekey(&mut self, next_gen_id: u16) {
+        let (tx, rx) = match (self.tx_cipher.take(), self.rx_cipher.take()) {
+            (Some(tx), Some(rx)) => (tx, rx),
+            _ => return,
+        };
+        let next = self.next_rx_cipher.take().unwrap_or_else(|| rx.ratchet());
+        self.next_rx_cipher = Some(next.ratchet());
+        self.tx_cipher = Some(tx.ratchet());
+        self.rx_cipher = Some(next);
+        self.prev_rx_cipher = Some(rx);
+        self.key_epoch = self.key_epoch.wrapping_add(1);
+        self.epoch_start_gen = next_gen_id;
+    }
+
+    /// Authenticate and decrypt under the current, next or previous epoch.
+    /// A packet under the next epoch means the peer ratcheted: Follow it.
+    /// ChaCha20-Poly1305 verifies before decrypting, so a failed trial leaves `payload` intact.
+    pub fn open(&mut self, header: &M13HeaderRef<'_>, payload: &mut [u8], next_gen_id: u16) -> M13Result<()> {
+        let current = self.rx_cipher.as_ref().ok_or(M13Error::InvalidState)?;
+        if current.decrypt_detached_ref(header, payload).is_ok() { return Ok(()); }
+
+        if let Some(next) = &self.next_rx_cipher {
+            if next.decrypt_detached_ref(header, payload).is_ok() {
+                self.rekey(next_gen_id);
+                return Ok(());
+            }
+        }
+
+        match &self.prev_rx_cipher {
+            Some(prev) => prev.decrypt_detached_ref(header, payload),
+            None => Err(M13Error::AuthFail),
+        }
+    }
+}
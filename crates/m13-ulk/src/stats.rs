@@ -0,0 +1,157 @@
+use alloc::boxed::Box;
+use m13_core::M13Error;
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_mem::FrameLease;
+
+/// Point-in-time snapshot of kernel counters (see `M13Kernel::stats`).
+/// Counters are cumulative since construction; the rest are current values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KernelStats {
+    /// Datagrams handed to the PHY (GSO super-packets count per segment).
+    pub packets_sent: u64,
+    /// Wire bytes handed to the PHY.
+    pub bytes_sent: u64,
+    /// Datagrams read from the PHY, before any authentication.
+    pub packets_received: u64,
+    pub bytes_received: u64,
+    /// Generations decoded and delivered.
+    pub decode_completions: u64,
+    /// Generations evicted or abandoned as stalled before they decoded.
+    pub decode_failures: u64,
+    /// [FEC] Repair bursts sent in answer to a peer's NACK.
+    pub repair_bursts: u64,
+    /// Sessions that reached an installed key (either role, attestation included).
+    pub handshakes_completed: u64,
+    /// Egress payloads with no route (hub) or no session (node), dropped.
+    pub unroutable_drops: u64,
+    pub sessions_active: usize,
+    /// Current egress pacing rate (bits/sec).
+    pub pacing_rate_bps: u64,
+    /// Slab frames leased right now, and the peak since creation.
+    pub frames_in_use: usize,
+    pub frames_high_water: usize,
+}
+
+/// Counts every datagram crossing the PHY boundary. Forwards each HAL method, so the
+/// wrapped PHY's batch/GSO/timestamp overrides stay in effect.
+pub(crate) struct MeteredPhy {
+    inner: Box<dyn PhysicalInterface>,
+    pub packets_sent: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub bytes_received: u64,
+}
+
+impl MeteredPhy {
+    pub fn new(inner: Box<dyn PhysicalInterface>) -> Self {
+        Self { inner, packets_sent: 0, bytes_sent: 0, packets_received: 0, bytes_received: 0 }
+    }
+
+    fn on_rx(&mut self, meta: &[(usize, PeerAddr)], n: usize) {
+        self.packets_received += n as u64;
+        self.bytes_received += meta.iter().take(n).map(|(len, _)| *len as u64).sum::<u64>();
+    }
+}
+
+impl PhysicalInterface for MeteredPhy {
+    fn properties(&self) -> LinkProperties {
+        self.inner.properties()
+    }
+
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        let n = self.inner.send(frame, target)?;
+        self.packets_sent += 1;
+        self.bytes_sent += n as u64;
+        Ok(n)
+    }
+
+    fn recv(&mut self, buffer: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        let (n, src) = self.inner.recv(buffer)?;
+        self.packets_received += 1;
+        self.bytes_received += n as u64;
+        Ok((n, src))
+    }
+
+    fn send_gso(
+        &mut self,
+        super_packet: &[u8],
+        target: Option<PeerAddr>,
+        segment_size: u16
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.send_gso(super_packet, target, segment_size)?;
+        self.packets_sent += n.div_ceil((segment_size as usize).max(1)) as u64;
+        self.bytes_sent += n as u64;
+        Ok(n)
+    }
+
+    fn recv_batch(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.recv_batch(buffers, meta)?;
+        self.on_rx(meta, n);
+        Ok(n)
+    }
+
+    fn recv_batch_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.recv_batch_ts(buffers, meta, timestamps_us)?;
+        self.on_rx(meta, n);
+        Ok(n)
+    }
+
+    fn recv_batch_ecn(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.recv_batch_ecn(buffers, meta, ecn)?;
+        self.on_rx(meta, n);
+        Ok(n)
+    }
+
+    fn recv_batch_ecn_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.recv_batch_ecn_ts(buffers, meta, ecn, timestamps_us)?;
+        self.on_rx(meta, n);
+        Ok(n)
+    }
+
+    fn recv_leases(
+        &mut self,
+        leases: &mut [FrameLease],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.recv_leases(leases, meta, ecn, timestamps_us)?;
+        self.on_rx(meta, n);
+        Ok(n)
+    }
+
+    fn rx_clock_us(&self) -> u64 {
+        self.inner.rx_clock_us()
+    }
+
+    fn send_batch(
+        &mut self,
+        frames: &[&[u8]],
+        targets: &[Option<PeerAddr>]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.inner.send_batch(frames, targets)?;
+        self.packets_sent += n as u64;
+        self.bytes_sent += frames.iter().take(n).map(|f| f.len() as u64).sum::<u64>();
+        Ok(n)
+    }
+}
@@ -0,0 +1,99 @@
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::{SecurityModule, PeerAddr};
+use m13_core::M13Result;
+use m13_attest::PcrBank;
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey, signature::Signer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{assemble, ipv4_packet, wire, MockClock, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: u32 = u32::from_be_bytes([10, 13, 13, 2]);
+
+// --- MOCKS ---
+/// TPM stand-in: Signs digests with a fixed P-256 AIK.
+struct AikSec { seed: u8, aik: SigningKey }
+impl SecurityModule for AikSec {
+    fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()> { buf.fill(self.seed); Ok(()) }
+    fn sign_digest(&mut self, digest: &[u8], signature: &mut [u8]) -> M13Result<usize> {
+        let sig: Signature = self.aik.sign(digest);
+        let bytes = sig.to_bytes();
+        signature[..bytes.len()].copy_from_slice(&bytes);
+        Ok(bytes.len())
+    }
+    fn panic_and_sanitize(&self) -> ! { panic!("PANIC") }
+}
+
+fn aik() -> SigningKey {
+    SigningKey::from_slice(&[0x42; 32]).unwrap()
+}
+
+fn aik_pub() -> [u8; 65] {
+    let point = VerifyingKey::from(&aik()).to_encoded_point(false);
+    point.as_bytes().try_into().unwrap()
+}
+
+fn pcrs(kernel: u8) -> PcrBank {
+    PcrBank {
+        pcr0_root: [0xAA; 32],
+        pcr1_fw: [0xBB; 32],
+        pcr2_kernel: [kernel; 32],
+        pcr4_policy: [0xDD; 32],
+        pcr7_debug: [0xEE; 32],
+    }
+}
+
+fn build_kernel(is_hub: bool, phy: WirePhy, t: &Arc<AtomicU64>, seed: u8) -> M13Kernel {
+    let config = KernelConfig { is_hub, ..Default::default() };
+    assemble(config, phy, AikSec { seed, aik: aik() }, MockClock { t: t.clone() }, seed)
+}
+
+/// Hub demanding the golden PCRs, node quoting `node_pcrs`. Runs the handshake, then one
+/// data generation (which is what teaches the hub a route).
+fn run(t: &Arc<AtomicU64>, node_pcrs: PcrBank) -> (M13Kernel, M13Kernel) {
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a }, t, 2);
+    hub.require_attestation(pcrs(0xCC));
+    node.set_attestation(node_pcrs, aik_pub());
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    node.send_payload(&ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 900, 0xAB)).unwrap();
+    for _ in 0..4 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(500, Ordering::SeqCst);
+    }
+    (hub, node)
+}
+
+#[test]
+fn test_attested_node_is_admitted() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let (mut hub, _node) = run(&t, pcrs(0xCC));
+
+    assert!(hub.session_key_epoch(&NODE_ADDR).is_some(), "Attested node never got a key");
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+    assert_eq!(hub.pop_ingress(), Some(ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 900, 0xAB)));
+}
+
+#[test]
+fn test_wrong_pcrs_are_rejected() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    // Tampered kernel measurement.
+    let (mut hub, node) = run(&t, pcrs(0x66));
+
+    // The node derived a key, but the hub never installed its half.
+    assert!(node.session_key_epoch(&HUB_ADDR).is_some());
+    assert!(!hub.has_session(&NODE_ADDR), "Session survived a failed attestation");
+    assert_eq!(hub.route(NODE_VIP), None);
+    assert_eq!(hub.pop_ingress(), None);
+}
@@ -0,0 +1,78 @@
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::{M13Error, M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+mod common;
+use common::{build_kernel, ipv4_packet, wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: [u8; 4] = [10, 13, 13, 2];
+const HUB_VIP: [u8; 4] = [10, 13, 13, 1];
+
+// --- MOCKS ---
+#[derive(Default)]
+struct TxLog {
+    /// Size of every `send_batch` call.
+    batches: Vec<usize>,
+    /// Coded symbols that went out through scalar `send`.
+    scalar_coded: usize,
+}
+
+/// `WirePhy` with its own vector TX, so the kernel's batching shows in the log.
+struct BatchPhy {
+    inner: WirePhy,
+    log: Arc<Mutex<TxLog>>,
+}
+impl PhysicalInterface for BatchPhy {
+    fn properties(&self) -> LinkProperties { self.inner.properties() }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        if M13Header::from_bytes(frame).unwrap().packet_type == PacketType::Coded {
+            self.log.lock().unwrap().scalar_coded += 1;
+        }
+        self.inner.send(frame, target)
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        self.inner.recv(buf)
+    }
+    fn send_batch(&mut self, frames: &[&[u8]], targets: &[Option<PeerAddr>]) -> nb::Result<usize, M13Error> {
+        self.log.lock().unwrap().batches.push(frames.len());
+        for (frame, target) in frames.iter().zip(targets) {
+            self.inner.send(frame, *target)?;
+        }
+        Ok(frames.len())
+    }
+}
+
+#[test]
+fn test_generation_leaves_in_one_send_batch() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let log = Arc::new(Mutex::new(TxLog::default()));
+    let node_phy = BatchPhy { inner: WirePhy { local: NODE_ADDR, rx: b.clone(), peer_rx: a.clone() }, log: log.clone() };
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a, peer_rx: b }, &t, 1);
+    let mut node = build_kernel(false, node_phy, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert!(node.has_session(&HUB_ADDR));
+    *log.lock().unwrap() = TxLog::default();
+
+    // K = 3 (3000 bytes, sealed and length-prefixed): 3 source symbols + 1 repair.
+    let packet = ipv4_packet(NODE_VIP, HUB_VIP, 3000, 0x5A);
+    node.send_payload(&packet).unwrap();
+    node.poll();
+
+    let tx = log.lock().unwrap();
+    assert_eq!(tx.scalar_coded, 0, "Coded symbols bypassed send_batch");
+    assert_eq!(tx.batches, vec![4], "The generation must leave in one vector write");
+    drop(tx);
+
+    hub.poll();
+    assert_eq!(hub.pop_ingress(), Some(packet));
+}
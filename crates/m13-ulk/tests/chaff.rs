@@ -0,0 +1,99 @@
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::PeerAddr;
+use m13_core::{M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel_with, wire, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+
+fn build_kernel(is_hub: bool, chaff: bool, phy: WirePhy, t: &Arc<AtomicU64>, seed: u8) -> M13Kernel {
+    build_kernel_with(KernelConfig { is_hub, chaff, ..Default::default() }, phy, t, seed)
+}
+
+/// Coded-symbol frame on the wire: Header + 1024-byte symbol.
+const FRAME_LEN: usize = M13Header::SIZE + 1024;
+/// What the pacer charges per frame (symbol + 64B overhead allowance).
+const FRAME_COST: usize = 1024 + 64;
+
+/// Deliver everything on `wire` to `sink` (the far kernel). Returns the frame count.
+fn forward(wire: &Wire, sink: &mut M13Kernel) -> usize {
+    let frames = {
+        let q = wire.lock().unwrap();
+        for (f, _) in q.iter() {
+            let h = M13Header::from_bytes(f).unwrap();
+            assert_eq!(f.len(), FRAME_LEN, "Chaff must match the data frame size");
+            assert_eq!(h.packet_type, PacketType::Data);
+        }
+        q.len()
+    };
+    sink.poll();
+    frames
+}
+
+#[test]
+fn test_idle_node_holds_constant_rate_egress() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, false, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, &t, 1);
+    let mut node = build_kernel(false, true, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a.clone() }, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert!(hub.has_session(&NODE_ADDR));
+    // Settle: Flush whatever the floor bucket banked during the handshake.
+    for _ in 0..20 {
+        node.poll();
+        forward(&a, &mut hub);
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    let warmup = hub.chaff_received();
+
+    // Idle: No payloads queued. 10 Mbps floor = 62,500 pacer bytes per 50ms window.
+    let mut windows = Vec::new();
+    for _ in 0..4 {
+        let mut frames = 0;
+        for _ in 0..50 {
+            node.poll();
+            frames += forward(&a, &mut hub);
+            t.fetch_add(1_000, Ordering::SeqCst);
+        }
+        windows.push(frames);
+    }
+    for &w in &windows {
+        assert!((w * FRAME_COST).abs_diff(62_500) <= FRAME_COST, "Egress not at the floor: {:?}", windows);
+    }
+
+    // The hub authenticates and discards every cover frame.
+    let frames: usize = windows.iter().sum();
+    assert_eq!(hub.chaff_received() - warmup, frames as u64);
+    assert!(hub.pop_ingress().is_none(), "Chaff leaked into the TUN queue");
+}
+
+#[test]
+fn test_chaff_disabled_idle_link_is_silent() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, false, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, &t, 1);
+    let mut node = build_kernel(false, false, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a.clone() }, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    for _ in 0..50 {
+        node.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert!(a.lock().unwrap().is_empty());
+    assert_eq!(hub.chaff_received(), 0);
+}
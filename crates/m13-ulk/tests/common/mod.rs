@@ -0,0 +1,223 @@
+//! Fixtures shared by the kernel integration tests.
+//! Each test binary compiles its own copy and uses a subset of it.
+#![allow(dead_code)]
+
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::{PhysicalInterface, SecurityModule, PlatformClock, LinkProperties, PeerAddr};
+use m13_hal::loopback::LoopbackClock;
+use m13_mem::SlabAllocator;
+use m13_pqc::{DsaKeypair, KemProfile, KyberKeypair};
+use m13_core::{M13Error, M13Header, M13Result, PacketType, M13_MAGIC};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub type Wire = Arc<Mutex<VecDeque<(Vec<u8>, PeerAddr)>>>;
+/// Selects the frames a `LossyPhy` loses, by header.
+pub type Filter = Arc<dyn Fn(&M13Header) -> bool + Send + Sync>;
+pub type HeaderLog = Arc<Mutex<Vec<M13Header>>>;
+
+pub fn wire() -> Wire {
+    Arc::new(Mutex::new(VecDeque::new()))
+}
+
+// --- MOCKS ---
+/// One end of an in-memory cable: TX into `peer_rx`, RX from `rx`.
+pub struct WirePhy {
+    pub local: PeerAddr,
+    pub rx: Wire,
+    pub peer_rx: Wire,
+}
+impl PhysicalInterface for WirePhy {
+    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true } }
+    fn send(&mut self, frame: &[u8], _: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.peer_rx.lock().unwrap().push_back((frame.to_vec(), self.local));
+        Ok(frame.len())
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        pop_into(&self.rx, buf)
+    }
+}
+
+/// Hub PHY: RX from `rx`, every send recorded in `tx` with its target.
+pub struct TapPhy {
+    pub rx: Wire,
+    pub tx: Wire,
+}
+impl PhysicalInterface for TapPhy {
+    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true } }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.tx.lock().unwrap().push_back((frame.to_vec(), target.unwrap_or(PeerAddr::None)));
+        Ok(frame.len())
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        pop_into(&self.rx, buf)
+    }
+}
+
+/// RX half of the wire mocks: The oldest queued datagram, or `WouldBlock`.
+pub fn pop_into(rx: &Wire, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+    match rx.lock().unwrap().pop_front() {
+        Some((frame, src)) => {
+            buf[..frame.len()].copy_from_slice(&frame);
+            Ok((frame.len(), src))
+        }
+        None => Err(nb::Error::WouldBlock),
+    }
+}
+
+/// Any PHY, with the header of every frame it sends logged in `sent`. Frames `drop` selects
+/// are logged, then lost.
+pub struct LossyPhy<P> {
+    pub inner: P,
+    pub drop: Filter,
+    pub sent: HeaderLog,
+}
+impl<P: PhysicalInterface> LossyPhy<P> {
+    pub fn new(inner: P, drop: Filter) -> Self {
+        Self { inner, drop, sent: Arc::new(Mutex::new(Vec::new())) }
+    }
+}
+impl<P: PhysicalInterface> PhysicalInterface for LossyPhy<P> {
+    fn properties(&self) -> LinkProperties { self.inner.properties() }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        if let Ok(h) = M13Header::from_bytes(frame) {
+            self.sent.lock().unwrap().push(h);
+            if (self.drop)(&h) { return Ok(frame.len()); }
+        }
+        self.inner.send(frame, target)
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        self.inner.recv(buf)
+    }
+}
+
+/// One PHY on several `LoopbackPair`s: TX switched on `target` to the port facing that peer,
+/// RX round-robin over the ports. Untargeted frames take the first port (the upstream).
+pub struct LoopbackSwitch {
+    ports: Vec<(PeerAddr, Box<dyn PhysicalInterface>)>,
+    next_rx: usize,
+}
+impl LoopbackSwitch {
+    /// `ports`: (remote address, the local end facing it).
+    pub fn new(ports: Vec<(PeerAddr, Box<dyn PhysicalInterface>)>) -> Self {
+        Self { ports, next_rx: 0 }
+    }
+}
+impl PhysicalInterface for LoopbackSwitch {
+    fn properties(&self) -> LinkProperties { self.ports[0].1.properties() }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        let port = match target {
+            Some(t) => self.ports.iter_mut().find(|(peer, _)| *peer == t).expect("Sent to an unknown peer"),
+            None => &mut self.ports[0],
+        };
+        port.1.send(frame, target)
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        for _ in 0..self.ports.len() {
+            let i = self.next_rx;
+            self.next_rx = (i + 1) % self.ports.len();
+            match self.ports[i].1.recv(buf) {
+                Err(nb::Error::WouldBlock) => {}
+                other => return other,
+            }
+        }
+        Err(nb::Error::WouldBlock)
+    }
+}
+
+/// Entropy is `seed` repeated; signing is a no-op.
+pub struct MockSec(pub u8);
+impl SecurityModule for MockSec {
+    fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()> { buf.fill(self.0); Ok(()) }
+    fn sign_digest(&mut self, _: &[u8], _: &mut [u8]) -> M13Result<usize> { Ok(0) }
+    fn panic_and_sanitize(&self) -> ! { panic!("PANIC") }
+}
+
+pub struct MockClock { pub t: Arc<AtomicU64> }
+impl PlatformClock for MockClock {
+    fn now_us(&self) -> u64 { self.t.load(Ordering::SeqCst) }
+    fn ptp_ns(&self) -> Option<u64> { None }
+}
+
+// --- KERNELS ---
+/// Default config, `MockSec(seed)`, and a `MockClock` on `t`.
+pub fn build_kernel(is_hub: bool, phy: impl PhysicalInterface + 'static, t: &Arc<AtomicU64>, seed: u8) -> M13Kernel {
+    build_kernel_with(KernelConfig { is_hub, ..Default::default() }, phy, t, seed)
+}
+
+pub fn build_kernel_with(config: KernelConfig, phy: impl PhysicalInterface + 'static, t: &Arc<AtomicU64>, seed: u8) -> M13Kernel {
+    assemble(config, phy, MockSec(seed), MockClock { t: t.clone() }, seed)
+}
+
+/// A kernel on `LoopbackPair` ends (bare, or wrapped), timed by the link's own clock.
+pub fn loopback_kernel(config: KernelConfig, phy: impl PhysicalInterface + 'static, clock: &LoopbackClock, seed: u8) -> M13Kernel {
+    assemble(config, phy, MockSec(seed), clock.clone(), seed)
+}
+
+/// Any PHY, HSM and clock. The DSA identity derives from `seed`.
+pub fn assemble(
+    config: KernelConfig,
+    phy: impl PhysicalInterface + 'static,
+    sec: impl SecurityModule + 'static,
+    clock: impl PlatformClock + 'static,
+    seed: u8,
+) -> M13Kernel {
+    M13Kernel::new(
+        Box::new(phy), Box::new(sec), Box::new(clock),
+        SlabAllocator::new(512), config, identity(seed)
+    )
+}
+
+pub fn identity(seed: u8) -> DsaKeypair {
+    DsaKeypair::generate(&mut ChaCha20Rng::from_seed([seed; 32])).unwrap()
+}
+
+// --- HANDSHAKE ---
+/// A complete ClientHello (fresh ML-KEM key) carrying `cookie`, fragmented as the node sends it.
+pub fn client_hello(cookie: [u8; 16]) -> Vec<Vec<u8>> {
+    let mut rng = ChaCha20Rng::from_seed([9; 32]);
+    let kp = KyberKeypair::generate_with_profile(KemProfile::default(), &mut rng).unwrap();
+    let hello = &kp.public;
+    hello.chunks(1000).enumerate().map(|(i, chunk)| {
+        let mut body = Vec::with_capacity(4 + chunk.len());
+        body.extend_from_slice(&(hello.len() as u16).to_be_bytes());
+        body.extend_from_slice(&((i * 1000) as u16).to_be_bytes());
+        body.extend_from_slice(chunk);
+        let header = M13Header {
+            magic: M13_MAGIC, version: 1, packet_type: PacketType::ClientHello,
+            gen_id: 0, symbol_id: 0, payload_len: body.len() as u16,
+            recoder_rank: 0, reserved: 0, auth_tag: cookie
+        };
+        let mut frame = vec![0u8; 32];
+        header.to_bytes(&mut frame).unwrap();
+        frame.extend_from_slice(&body);
+        frame
+    }).collect()
+}
+
+/// Queue `frames` on `rx` as if from `from`, then poll once.
+pub fn deliver(kernel: &mut M13Kernel, rx: &Wire, frames: &[Vec<u8>], from: PeerAddr) {
+    for f in frames { rx.lock().unwrap().push_back((f.clone(), from)); }
+    kernel.poll();
+}
+
+/// Everything sent on `tx` since the last call: (packet type, payload, target).
+pub fn sent(tx: &Wire) -> Vec<(PacketType, Vec<u8>, PeerAddr)> {
+    tx.lock().unwrap().drain(..).map(|(f, to)| {
+        let h = M13Header::from_bytes(&f).unwrap();
+        (h.packet_type, f[32..32 + h.payload_len as usize].to_vec(), to)
+    }).collect()
+}
+
+// --- TRAFFIC ---
+/// Minimal IPv4 datagram `src -> dst`, `len` bytes: Enough header for the kernel to route it.
+pub fn ipv4_packet(src: [u8; 4], dst: [u8; 4], len: usize, fill: u8) -> Vec<u8> {
+    let mut p = vec![fill; len];
+    p[0] = 0x45;
+    p[12..16].copy_from_slice(&src);
+    p[16..20].copy_from_slice(&dst);
+    p
+}
@@ -0,0 +1,82 @@
+use m13_ulk::KernelConfig;
+use m13_ulk::{MIN_SYMBOL_SIZE, MAX_SYMBOL_SIZE};
+use m13_hal::PeerAddr;
+use m13_core::{M13Error, M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel_with, ipv4_packet, wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: u32 = u32::from_be_bytes([10, 13, 13, 2]);
+
+#[test]
+fn test_tuned_node_interoperates_with_default_hub() {
+    const SYMBOL: usize = 512;
+
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let staging = wire();
+
+    let tuned = KernelConfig { symbol_size: SYMBOL, cbr_floor_bps: 2_000_000, gso_segment_size: 600, ..Default::default() };
+    assert!(tuned.validate().is_ok());
+    let mut hub = build_kernel_with(KernelConfig { is_hub: true, ..Default::default() }, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, &t, 1);
+    let mut node = build_kernel_with(tuned, WirePhy { local: NODE_ADDR, rx: b, peer_rx: staging.clone() }, &t, 2);
+
+    let forward = |seen: &mut Vec<(PacketType, usize)>| {
+        let frames: Vec<_> = staging.lock().unwrap().drain(..).collect();
+        for (f, src) in frames {
+            if let Ok(h) = M13Header::from_bytes(&f) { seen.push((h.packet_type, f.len())); }
+            a.lock().unwrap().push_back((f, src));
+        }
+    };
+
+    let mut seen = Vec::new();
+    for _ in 0..10 {
+        node.poll();
+        forward(&mut seen);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    seen.clear();
+    let packet = ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 1400, 0xAB);
+    node.send_payload(&packet).unwrap();
+    for _ in 0..20 {
+        node.poll();
+        forward(&mut seen);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // The node's symbols carry its size; the default hub decodes them all the same.
+    let coded: Vec<usize> = seen.iter().filter(|(ty, _)| *ty == PacketType::Coded).map(|(_, len)| *len).collect();
+    assert!(!coded.is_empty(), "No coded symbols sent");
+    assert!(coded.iter().all(|&len| len == 32 + SYMBOL), "Symbol sizes {:?}", coded);
+    assert_eq!(hub.pop_ingress(), Some(packet));
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+}
+
+#[test]
+fn test_validate_rejects_unusable_tunables() {
+    let ok = KernelConfig::default();
+    assert!(ok.validate().is_ok());
+    assert!(KernelConfig { symbol_size: MIN_SYMBOL_SIZE, ..ok }.validate().is_ok());
+    assert!(KernelConfig { symbol_size: MAX_SYMBOL_SIZE, ..ok }.validate().is_ok());
+    assert!(KernelConfig { relay: true, ..ok }.validate().is_ok());
+
+    for bad in [
+        KernelConfig { symbol_size: 0, ..ok },
+        KernelConfig { symbol_size: MIN_SYMBOL_SIZE - 1, ..ok },
+        KernelConfig { symbol_size: MAX_SYMBOL_SIZE + 1, ..ok },
+        KernelConfig { gso_segment_size: 100, ..ok },
+        KernelConfig { session_idle_timeout_us: 0, ..ok },
+        KernelConfig { relay: true, is_hub: true, ..ok },
+        KernelConfig { relay: true, enable_encryption: false, ..ok },
+    ] {
+        assert!(matches!(bad.validate(), Err(M13Error::InvalidState)), "Accepted {:?}", bad);
+    }
+}
@@ -0,0 +1,68 @@
+use m13_ulk::M13Kernel;
+use m13_hal::PeerAddr;
+use m13_core::PacketType;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, client_hello, deliver, sent, wire, TapPhy, Wire};
+
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const SPOOF_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 66], 6666);
+
+fn build_hub(rx: &Wire, tx: &Wire, t: &Arc<AtomicU64>) -> M13Kernel {
+    build_kernel(true, TapPhy { rx: rx.clone(), tx: tx.clone() }, t, 1)
+}
+
+#[test]
+fn test_hello_without_cookie_gets_only_a_cookie() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let rx = wire();
+    let tx = wire();
+    let mut hub = build_hub(&rx, &tx, &t);
+
+    deliver(&mut hub, &rx, &client_hello([0; 16]), NODE_ADDR);
+    assert!(!hub.has_session(&NODE_ADDR), "Unproven hello allocated a session");
+    let replies = sent(&tx);
+    assert!(!replies.is_empty());
+    let hello_len: usize = client_hello([0; 16]).iter().map(|f| f.len()).sum();
+    for (ptype, payload, to) in &replies {
+        assert_eq!(*ptype, PacketType::Cookie, "Hub answered an unproven hello with {:?}", ptype);
+        assert_eq!(*to, NODE_ADDR);
+        assert!(32 + payload.len() < hello_len / 10, "Cookie reply amplifies");
+    }
+    let cookie: [u8; 16] = replies[0].1.as_slice().try_into().unwrap();
+
+    // A forged cookie, or another address's cookie, still gets nothing but a cookie.
+    let mut forged = cookie;
+    forged[0] ^= 1;
+    deliver(&mut hub, &rx, &client_hello(forged), NODE_ADDR);
+    deliver(&mut hub, &rx, &client_hello(cookie), SPOOF_ADDR);
+    assert!(!hub.has_session(&NODE_ADDR) && !hub.has_session(&SPOOF_ADDR));
+    assert!(sent(&tx).iter().all(|(ptype, _, _)| *ptype == PacketType::Cookie));
+
+    // Echoed from the address it was issued to: The full handshake runs.
+    deliver(&mut hub, &rx, &client_hello(cookie), NODE_ADDR);
+    assert!(hub.has_session(&NODE_ADDR));
+    let replies = sent(&tx);
+    assert!(replies.iter().any(|(ptype, _, to)| *ptype == PacketType::HandshakeInit && *to == NODE_ADDR));
+    assert!(replies.iter().all(|(ptype, _, _)| *ptype != PacketType::Cookie));
+}
+
+#[test]
+fn test_cookie_expires_after_two_windows() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let rx = wire();
+    let tx = wire();
+    let mut hub = build_hub(&rx, &tx, &t);
+
+    deliver(&mut hub, &rx, &client_hello([0; 16]), NODE_ADDR);
+    let cookie: [u8; 16] = sent(&tx)[0].1.as_slice().try_into().unwrap();
+
+    t.fetch_add(60_000_000, Ordering::SeqCst);
+    deliver(&mut hub, &rx, &client_hello(cookie), NODE_ADDR);
+    assert!(!hub.has_session(&NODE_ADDR), "Stale cookie accepted");
+    let fresh = sent(&tx);
+    assert_eq!(fresh[0].0, PacketType::Cookie);
+    assert_ne!(fresh[0].1, cookie.to_vec(), "Cookie did not rotate");
+}
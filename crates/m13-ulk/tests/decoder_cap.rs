@@ -0,0 +1,71 @@
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::{M13Error, M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, pop_into, wire, Wire};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+
+// --- MOCKS ---
+/// One end of an in-memory cable: TX into `peer_rx`, RX from `rx`.
+/// `tail_loss` drops every coded symbol after the first of each generation.
+struct WirePhy {
+    local: PeerAddr,
+    rx: Wire,
+    peer_rx: Wire,
+    tail_loss: bool,
+}
+impl PhysicalInterface for WirePhy {
+    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true } }
+    fn send(&mut self, frame: &[u8], _: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        if self.tail_loss {
+            if let Ok(h) = M13Header::from_bytes(frame) {
+                if h.packet_type == PacketType::Coded && { h.symbol_id } > 0 { return Ok(frame.len()); }
+            }
+        }
+        self.peer_rx.lock().unwrap().push_back((frame.to_vec(), self.local));
+        Ok(frame.len())
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        pop_into(&self.rx, buf)
+    }
+}
+
+#[test]
+fn test_partial_generations_are_capped() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone(), tail_loss: false }, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a, tail_loss: true }, &t, 2);
+
+    // Handshake (ClientHello -> HandshakeInit).
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // 200 generations of K=2; only symbol 0 of each survives, so none can decode.
+    let mut peak = 0;
+    for i in 0..200u32 {
+        node.send_payload(&vec![i as u8; 1500]).unwrap();
+        for _ in 0..2 {
+            node.poll();
+            hub.poll();
+            t.fetch_add(5_000, Ordering::SeqCst);
+        }
+        peak = peak.max(hub.decode_progress().len());
+    }
+
+    let pending = hub.decode_progress();
+    assert!(peak <= 64, "Decoder map grew to {}", peak);
+    assert_eq!(pending.len(), 64);
+    assert!(pending.iter().all(|p| p.rank > 0 && p.needed > 0));
+    assert_eq!(hub.decode_failures(), 200 - 64, "Every eviction is a decode failure");
+    assert!(hub.pop_ingress().is_none());
+}
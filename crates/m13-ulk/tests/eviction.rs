@@ -0,0 +1,131 @@
+use m13_ulk::{M13Kernel, DEFAULT_SESSION_IDLE_TIMEOUT_US};
+use m13_hal::PeerAddr;
+use m13_core::{M13Header, PacketType, M13_MAGIC};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, ipv4_packet, wire, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const SPOOF_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 66], 6666);
+const NODE_VIP: u32 = u32::from_be_bytes([10, 13, 13, 2]);
+
+/// First fragment of a ClientHello that never completes, echoing `cookie`.
+fn half_open_hello(cookie: [u8; 16]) -> Vec<u8> {
+    let mut frag = vec![0u8; 4 + 100];
+    frag[0..2].copy_from_slice(&1600u16.to_be_bytes()); // total_len
+    frag[2..4].copy_from_slice(&0u16.to_be_bytes()); // offset
+    let header = M13Header {
+        magic: M13_MAGIC, version: 1, packet_type: PacketType::ClientHello,
+        gen_id: 0, symbol_id: 0, payload_len: frag.len() as u16,
+        recoder_rank: 0, reserved: 0, auth_tag: cookie
+    };
+    let mut frame = vec![0u8; 32 + frag.len()];
+    header.to_bytes(&mut frame).unwrap();
+    frame[32..].copy_from_slice(&frag);
+    frame
+}
+
+/// Handshake, then one data generation so the hub learns the node's route.
+/// Returns (hub, node, hub RX wire, node RX wire).
+fn establish(t: &Arc<AtomicU64>) -> (M13Kernel, M13Kernel, Wire, Wire) {
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b.clone(), peer_rx: a.clone() }, t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    node.send_payload(&ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 900, 0xAB)).unwrap();
+    for _ in 0..4 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(500, Ordering::SeqCst);
+    }
+    assert!(hub.has_session(&NODE_ADDR));
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+    (hub, node, a, b)
+}
+
+#[test]
+fn test_idle_session_and_route_evicted() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let (mut hub, _node, _hub_rx, _node_rx) = establish(&t);
+
+    // Just inside the timeout: Kept.
+    t.fetch_add(DEFAULT_SESSION_IDLE_TIMEOUT_US - 1_000_000, Ordering::SeqCst);
+    hub.poll();
+    assert!(hub.has_session(&NODE_ADDR));
+
+    // Node goes silent past the idle timeout.
+    t.fetch_add(2_000_000, Ordering::SeqCst);
+    hub.poll();
+    assert!(!hub.has_session(&NODE_ADDR), "Idle session survived");
+    assert_eq!(hub.route(NODE_VIP), None, "Route to evicted peer survived");
+}
+
+#[test]
+fn test_half_open_session_expires_first() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let (mut hub, _node, hub_rx, node_rx) = establish(&t);
+
+    // Cookie round trip first: An unproven hello allocates nothing.
+    hub_rx.lock().unwrap().push_back((half_open_hello([0; 16]), SPOOF_ADDR));
+    hub.poll();
+    assert!(!hub.has_session(&SPOOF_ADDR));
+    let (reply, _) = node_rx.lock().unwrap().pop_back().unwrap();
+    assert_eq!(M13Header::from_bytes(&reply).unwrap().packet_type, PacketType::Cookie);
+    let cookie: [u8; 16] = reply[32..48].try_into().unwrap();
+
+    hub_rx.lock().unwrap().push_back((half_open_hello(cookie), SPOOF_ADDR));
+    hub.poll();
+    assert!(hub.has_session(&SPOOF_ADDR));
+
+    // Well short of the idle timeout, past the half-open one.
+    t.fetch_add(6_000_000, Ordering::SeqCst);
+    hub.poll();
+    assert!(!hub.has_session(&SPOOF_ADDR), "Half-open session survived");
+    assert!(hub.has_session(&NODE_ADDR), "Established session evicted early");
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+}
+
+#[test]
+fn test_goodbye_evicts_session_synchronously() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let (mut hub, mut node, hub_rx, _node_rx) = establish(&t);
+
+    // Leave a generation half-delivered: Only its first symbol reaches the hub.
+    node.send_payload(&ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 1500, 0xAB)).unwrap();
+    node.poll();
+    {
+        let mut q = hub_rx.lock().unwrap();
+        q.truncate(1);
+    }
+    hub.poll();
+    assert_eq!(hub.decode_progress().len(), 1);
+
+    // An unauthenticated Goodbye from the node's address changes nothing.
+    let forged = M13Header {
+        magic: M13_MAGIC, version: 1, packet_type: PacketType::Goodbye,
+        gen_id: 0, symbol_id: 0x8000_0000, payload_len: 0,
+        recoder_rank: 0, reserved: 0, auth_tag: [0; 16]
+    };
+    let mut frame = vec![0u8; 32];
+    forged.to_bytes(&mut frame).unwrap();
+    hub_rx.lock().unwrap().push_back((frame, NODE_ADDR));
+    hub.poll();
+    assert!(hub.has_session(&NODE_ADDR), "Forged Goodbye closed the session");
+
+    // The real one: Gone on the very next poll, no clock advance.
+    node.shutdown();
+    assert!(!node.has_session(&HUB_ADDR));
+    hub.poll();
+    assert!(!hub.has_session(&NODE_ADDR), "Session survived Goodbye");
+    assert_eq!(hub.route(NODE_VIP), None, "Route survived Goodbye");
+    assert!(hub.decode_progress().is_empty(), "Decoder survived Goodbye");
+}
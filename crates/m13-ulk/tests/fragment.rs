@@ -0,0 +1,109 @@
+use m13_ulk::fragment::{FragmentAssembler, DEFAULT_MAX_REASSEMBLY_LEN};
+use m13_core::M13Error;
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/// `[total_len][offset][chunk]` for `msg[offset..offset + len]`.
+fn fragment(msg: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut f = Vec::with_capacity(4 + len);
+    f.extend_from_slice(&(msg.len() as u16).to_be_bytes());
+    f.extend_from_slice(&(offset as u16).to_be_bytes());
+    f.extend_from_slice(&msg[offset..offset + len]);
+    f
+}
+
+#[test]
+fn test_out_of_order_fragments_reassemble() {
+    let msg = message(2_500);
+    let mut rx = FragmentAssembler::new();
+
+    // Final fragment first: Must not complete early.
+    assert_eq!(rx.ingest(&fragment(&msg, 2_000, 500), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 0, 1_000), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 1_000, 1_000), 0).unwrap(), Some(msg));
+}
+
+#[test]
+fn test_duplicate_and_overlapping_fragments_are_idempotent() {
+    let msg = message(2_500);
+    let mut rx = FragmentAssembler::new();
+
+    assert_eq!(rx.ingest(&fragment(&msg, 0, 1_000), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 0, 1_000), 0).unwrap(), None);
+    // Overlaps both neighbours' ranges with identical bytes.
+    assert_eq!(rx.ingest(&fragment(&msg, 500, 1_000), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 1_500, 1_000), 0).unwrap(), Some(msg.clone()));
+
+    // An overlap that disagrees is corruption: Reassembly resets instead of overwriting.
+    let mut forged = fragment(&msg, 500, 1_000);
+    forged[4] ^= 0xFF;
+    assert_eq!(rx.ingest(&fragment(&msg, 0, 1_000), 0).unwrap(), None);
+    assert!(matches!(rx.ingest(&forged, 0), Err(M13Error::InvalidState)));
+    assert_eq!(rx.ingest(&fragment(&msg, 1_000, 1_000), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 2_000, 500), 0).unwrap(), None, "Reset lost bytes 0..1000, yet completed");
+}
+
+#[test]
+fn test_gap_never_completes() {
+    let msg = message(3_000);
+    let mut rx = FragmentAssembler::new();
+
+    assert_eq!(rx.ingest(&fragment(&msg, 0, 1_000), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 2_000, 1_000), 0).unwrap(), None);
+    // Bytes 1000..1999 are missing except for one.
+    assert_eq!(rx.ingest(&fragment(&msg, 1_500, 1), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&msg, 2_000, 1_000), 0).unwrap(), None);
+
+    assert_eq!(rx.ingest(&fragment(&msg, 1_000, 1_000), 0).unwrap(), Some(msg));
+}
+
+#[test]
+fn test_total_len_mismatch_resets_cleanly() {
+    let old = message(2_500);
+    let new = message(1_800);
+    let mut rx = FragmentAssembler::new();
+
+    assert_eq!(rx.ingest(&fragment(&old, 0, 1_000), 0).unwrap(), None);
+    assert!(matches!(rx.ingest(&fragment(&new, 1_000, 800), 0), Err(M13Error::InvalidState)));
+
+    // Nothing of the abandoned message leaks into the next one.
+    assert_eq!(rx.ingest(&fragment(&new, 1_000, 800), 0).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&new, 0, 1_000), 0).unwrap(), Some(new));
+}
+
+#[test]
+fn test_stale_reassembly_resets_after_timeout() {
+    const TIMEOUT_US: u64 = 1_000_000;
+    let stale = message(2_500);
+    let fresh = message(2_200);
+    let mut rx = FragmentAssembler::new();
+
+    assert!(!rx.is_expired(0, TIMEOUT_US), "Idle assembler reported expired");
+    assert_eq!(rx.ingest(&fragment(&stale, 0, 1_000), 5_000).unwrap(), None);
+    assert!(!rx.is_expired(5_000 + TIMEOUT_US - 1, TIMEOUT_US));
+    // Later fragments do not extend the deadline.
+    assert_eq!(rx.ingest(&fragment(&stale, 1_000, 1_000), 900_000).unwrap(), None);
+    assert!(rx.is_expired(5_000 + TIMEOUT_US, TIMEOUT_US));
+
+    rx.reset();
+    assert!(!rx.is_expired(10_000_000, TIMEOUT_US));
+    assert_eq!(rx.ingest(&fragment(&fresh, 0, 1_000), 10_000_000).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&fresh, 1_000, 1_000), 10_000_000).unwrap(), None);
+    assert_eq!(rx.ingest(&fragment(&fresh, 2_000, 200), 10_000_000).unwrap(), Some(fresh));
+}
+
+#[test]
+fn test_max_len_is_configurable() {
+    let msg = message(1_500);
+    assert!(matches!(FragmentAssembler::new().ingest(&fragment(&message(DEFAULT_MAX_REASSEMBLY_LEN + 1), 0, 10), 0), Err(M13Error::WireFormatError)));
+
+    let mut small = FragmentAssembler::with_max_len(1_024);
+    assert!(matches!(small.ingest(&fragment(&msg, 0, 1_000), 0), Err(M13Error::WireFormatError)));
+    assert!(!small.is_expired(u64::MAX, 0), "Rejected message left state behind");
+
+    let mut roomy = FragmentAssembler::with_max_len(2_048);
+    assert_eq!(roomy.ingest(&fragment(&msg, 0, 1_000), 0).unwrap(), None);
+    assert_eq!(roomy.ingest(&fragment(&msg, 1_000, 500), 0).unwrap(), Some(msg));
+}
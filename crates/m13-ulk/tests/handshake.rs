@@ -0,0 +1,105 @@
+use m13_ulk::M13Kernel;
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::{M13Error, M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, pop_into, wire, Wire};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+
+// --- MOCKS ---
+/// One end of an in-memory cable: TX into `peer_rx`, RX from `rx`.
+/// Counts ClientHello attempts (fragment offset 0) and drops the first `drop_attempts`.
+struct WirePhy {
+    local: PeerAddr,
+    rx: Wire,
+    peer_rx: Wire,
+    hello_attempts: Arc<AtomicU64>,
+    drop_attempts: u64,
+}
+impl PhysicalInterface for WirePhy {
+    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true } }
+    fn send(&mut self, frame: &[u8], _: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        if let Ok(h) = M13Header::from_bytes(frame) {
+            if h.packet_type == PacketType::ClientHello {
+                if frame[34..36] == [0, 0] { self.hello_attempts.fetch_add(1, Ordering::SeqCst); }
+                if self.hello_attempts.load(Ordering::SeqCst) <= self.drop_attempts { return Ok(frame.len()); }
+            }
+        }
+        self.peer_rx.lock().unwrap().push_back((frame.to_vec(), self.local));
+        Ok(frame.len())
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        pop_into(&self.rx, buf)
+    }
+}
+
+fn pair(t: &Arc<AtomicU64>, drop_attempts: u64) -> (M13Kernel, M13Kernel, Arc<AtomicU64>) {
+    let a = wire();
+    let b = wire();
+    let attempts = Arc::new(AtomicU64::new(0));
+    let hub_phy = WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone(), hello_attempts: Arc::new(AtomicU64::new(0)), drop_attempts: 0 };
+    let node_phy = WirePhy { local: NODE_ADDR, rx: b, peer_rx: a, hello_attempts: attempts.clone(), drop_attempts };
+    (build_kernel(true, hub_phy, t, 1), build_kernel(false, node_phy, t, 2), attempts)
+}
+
+#[test]
+fn test_handshake_survives_two_lost_attempts() {
+    let t0 = 3_000_000;
+    let t = Arc::new(AtomicU64::new(t0));
+    let (mut hub, mut node, attempts) = pair(&t, 2);
+
+    // Attempts go out at +0, +200ms, +600ms (RTO 200ms, then 400ms).
+    let mut established_at = None;
+    while t.load(Ordering::SeqCst) < t0 + 2_000_000 {
+        node.poll();
+        hub.poll();
+        node.poll();
+        if node.session_key_epoch(&HUB_ADDR).is_some() {
+            established_at = Some(t.load(Ordering::SeqCst) - t0);
+            break;
+        }
+        t.fetch_add(10_000, Ordering::SeqCst);
+    }
+
+    let at = established_at.expect("Handshake never completed");
+    assert!((600_000..700_000).contains(&at), "Established at +{}us, outside the backoff schedule", at);
+    // Three timed attempts, plus the immediate echo of the hub's retry cookie.
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    assert_eq!(node.handshake_failures(), 0);
+    assert!(hub.session_key_epoch(&NODE_ADDR).is_some());
+
+    // No further ClientHellos once the link is up.
+    for _ in 0..100 {
+        t.fetch_add(100_000, Ordering::SeqCst);
+        node.poll();
+        hub.poll();
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn test_handshake_gives_up_then_restarts() {
+    let t0 = 3_000_000;
+    let t = Arc::new(AtomicU64::new(t0));
+    let (_hub, mut node, attempts) = pair(&t, u64::MAX);
+
+    // 8 attempts: RTOs 200, 400, 800, 1600, 3200, 3200, 3200, 3200 ms.
+    let schedule_us = 200_000 + 400_000 + 800_000 + 1_600_000 + 4 * 3_200_000;
+    while t.load(Ordering::SeqCst) <= t0 + schedule_us {
+        node.poll();
+        t.fetch_add(10_000, Ordering::SeqCst);
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 8);
+    assert_eq!(node.handshake_failures(), 1);
+
+    // Fresh handshake after the hold-off.
+    for _ in 0..(3_200_000 / 10_000 + 1) {
+        node.poll();
+        t.fetch_add(10_000, Ordering::SeqCst);
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 9);
+}
@@ -0,0 +1,275 @@
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::{PhysicalInterface, PlatformClock, LinkProperties, PeerAddr};
+use m13_core::{M13Error, M13Header};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{assemble, wire, MockClock, MockSec, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+
+// --- MOCKS ---
+/// A WirePhy whose frames carry a kernel RX timestamp `age_us` in the past.
+struct StampedPhy {
+    inner: WirePhy,
+    age_us: u64,
+}
+const RX_EPOCH_US: u64 = 1_700_000_000_000_000;
+impl PhysicalInterface for StampedPhy {
+    fn properties(&self) -> LinkProperties { self.inner.properties() }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> { self.inner.send(frame, target) }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> { self.inner.recv(buf) }
+    fn recv_batch_ecn_ts(
+        &mut self,
+        buffers: &mut [&mut [u8]],
+        meta: &mut [(usize, PeerAddr)],
+        ecn: &mut [u8],
+        timestamps_us: &mut [u64]
+    ) -> nb::Result<usize, M13Error> {
+        let n = self.recv_batch_ecn(buffers, meta, ecn)?;
+        for ts in timestamps_us.iter_mut().take(n) { *ts = RX_EPOCH_US - self.age_us; }
+        Ok(n)
+    }
+    fn rx_clock_us(&self) -> u64 { RX_EPOCH_US }
+}
+
+/// Disciplined to the shared grandmaster: PTP time runs with the mock clock, `offset_us` ahead.
+struct PtpClock { t: Arc<AtomicU64>, offset_us: u64 }
+impl PlatformClock for PtpClock {
+    fn now_us(&self) -> u64 { self.t.load(Ordering::SeqCst) }
+    fn ptp_ns(&self) -> Option<u64> { Some((self.t.load(Ordering::SeqCst) + self.offset_us) * 1_000) }
+}
+const PTP_OFFSET_US: u64 = 1_700_000_000_000_000;
+
+/// Jitter-buffered kernel on `clock`.
+fn build_kernel_with_clock(is_hub: bool, phy: impl PhysicalInterface + 'static, clock: impl PlatformClock + 'static, seed: u8) -> M13Kernel {
+    assemble(KernelConfig { is_hub, jitter_buffer: true, ..Default::default() }, phy, MockSec(seed), clock, seed)
+}
+
+fn build_kernel(is_hub: bool, phy: impl PhysicalInterface + 'static, t: &Arc<AtomicU64>, seed: u8) -> M13Kernel {
+    build_kernel_with_clock(is_hub, phy, MockClock { t: t.clone() }, seed)
+}
+
+// PhaseMonitor default before any RTT sample.
+const DEPTH_US: u64 = 100_000;
+
+/// Move frames from the staging wire to the hub, optionally only those matching `pick`.
+fn forward(staging: &Wire, hub_rx: &Wire, pick: impl Fn(u16, u32) -> bool) {
+    let mut staging = staging.lock().unwrap();
+    let mut kept = VecDeque::new();
+    while let Some((frame, src)) = staging.pop_front() {
+        let h = M13Header::from_bytes(&frame).unwrap();
+        if pick(h.gen_id, h.symbol_id) {
+            hub_rx.lock().unwrap().push_back((frame, src));
+        } else {
+            kept.push_back((frame, src));
+        }
+    }
+    *staging = kept;
+}
+
+/// Node emits one K=2 generation (3 symbols) into the staging wire.
+fn emit(node: &mut M13Kernel, staging: &Wire, t: &Arc<AtomicU64>, tag: u8) {
+    let before = staging.lock().unwrap().len();
+    node.send_payload(&vec![tag; 1500]).unwrap();
+    for _ in 0..20 {
+        node.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+        if staging.lock().unwrap().len() >= before + 3 { return; }
+    }
+    panic!("Generation {} never left the node", tag);
+}
+
+fn tick(hub: &mut M13Kernel, t: &Arc<AtomicU64>, us: u64) {
+    t.fetch_add(us, Ordering::SeqCst);
+    hub.poll();
+}
+
+#[test]
+fn test_reordered_generations_release_in_order() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let node_rx = wire();
+    let staging = wire();
+
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: hub_rx.clone(), peer_rx: node_rx.clone() }, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: node_rx, peer_rx: staging.clone() }, &t, 2);
+
+    // Handshake (ClientHello -> HandshakeInit).
+    for _ in 0..10 {
+        node.poll();
+        forward(&staging, &hub_rx, |_, _| true);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // Gen 1 ('A') and gen 2 ('B'). A loses its second symbol in flight, so B decodes first.
+    emit(&mut node, &staging, &t, b'A');
+    emit(&mut node, &staging, &t, b'B');
+
+    forward(&staging, &hub_rx, |g, s| g == 1 && s == 0);
+    tick(&mut hub, &t, 0);
+    let t_a = t.load(Ordering::SeqCst);
+    forward(&staging, &hub_rx, |g, s| g == 2 && s <= 1);
+    tick(&mut hub, &t, 1_000);
+    forward(&staging, &hub_rx, |g, s| g == 1 && s == 1);
+    tick(&mut hub, &t, 1_000);
+    assert_eq!(hub.decode_progress().len(), 0, "Both generations decoded");
+
+    // Held until A's playout deadline even though both are decoded.
+    t.store(t_a + DEPTH_US - 1, Ordering::SeqCst);
+    hub.poll();
+    assert!(hub.pop_ingress().is_none(), "Released before the playout deadline");
+
+    // A first (earlier first-symbol arrival), then B one millisecond later.
+    t.store(t_a + DEPTH_US, Ordering::SeqCst);
+    hub.poll();
+    assert_eq!(hub.pop_ingress().map(|p| p[0]), Some(b'A'));
+    assert!(hub.pop_ingress().is_none());
+
+    tick(&mut hub, &t, 1_000);
+    assert_eq!(hub.pop_ingress().map(|p| p[0]), Some(b'B'));
+    assert!(hub.pop_ingress().is_none());
+}
+
+#[test]
+fn test_generation_decoded_past_deadline_is_dropped() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let node_rx = wire();
+    let staging = wire();
+
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: hub_rx.clone(), peer_rx: node_rx.clone() }, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: node_rx, peer_rx: staging.clone() }, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        forward(&staging, &hub_rx, |_, _| true);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // The repair symbol arrives after the whole playout budget is spent.
+    emit(&mut node, &staging, &t, b'C');
+    forward(&staging, &hub_rx, |_, s| s == 0);
+    tick(&mut hub, &t, 0);
+    tick(&mut hub, &t, DEPTH_US + 1_000);
+    forward(&staging, &hub_rx, |_, s| s == 1);
+    tick(&mut hub, &t, 0);
+    assert_eq!(hub.decode_progress().len(), 0, "Generation decoded");
+
+    tick(&mut hub, &t, 10 * DEPTH_US);
+    assert!(hub.pop_ingress().is_none(), "Late payload reached the control loop");
+}
+
+#[test]
+fn test_playout_deadline_anchors_on_kernel_rx_timestamp() {
+    const AGE_US: u64 = 40_000;
+
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let node_rx = wire();
+    let staging = wire();
+
+    let hub_phy = StampedPhy { inner: WirePhy { local: HUB_ADDR, rx: hub_rx.clone(), peer_rx: node_rx.clone() }, age_us: AGE_US };
+    let mut hub = build_kernel(true, hub_phy, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: node_rx, peer_rx: staging.clone() }, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        forward(&staging, &hub_rx, |_, _| true);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // The whole generation sat in the socket buffer for AGE_US before this poll.
+    emit(&mut node, &staging, &t, b'D');
+    forward(&staging, &hub_rx, |_, s| s <= 1);
+    tick(&mut hub, &t, 0);
+    let t_rx = t.load(Ordering::SeqCst);
+    assert_eq!(hub.decode_progress().len(), 0, "Generation decoded");
+
+    // The deadline runs from the kernel stamp, not from the poll that read it.
+    t.store(t_rx - AGE_US + DEPTH_US - 1, Ordering::SeqCst);
+    hub.poll();
+    assert!(hub.pop_ingress().is_none(), "Released before the playout deadline");
+
+    t.store(t_rx - AGE_US + DEPTH_US, Ordering::SeqCst);
+    hub.poll();
+    assert_eq!(hub.pop_ingress().map(|p| p[0]), Some(b'D'));
+}
+
+#[test]
+fn test_playout_deadline_anchors_on_ptp_origin() {
+    const TRANSIT_US: u64 = 30_000;
+
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let node_rx = wire();
+    let staging = wire();
+
+    let ptp = |t: &Arc<AtomicU64>| PtpClock { t: t.clone(), offset_us: PTP_OFFSET_US };
+    let mut hub = build_kernel_with_clock(true, WirePhy { local: HUB_ADDR, rx: hub_rx.clone(), peer_rx: node_rx.clone() }, ptp(&t), 1);
+    let mut node = build_kernel_with_clock(false, WirePhy { local: NODE_ADDR, rx: node_rx, peer_rx: staging.clone() }, ptp(&t), 2);
+
+    for _ in 0..10 {
+        node.poll();
+        forward(&staging, &hub_rx, |_, _| true);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // Stamped as it is encoded; spends TRANSIT_US on the path before the hub sees it.
+    let t_origin = t.load(Ordering::SeqCst);
+    emit(&mut node, &staging, &t, b'P');
+    t.store(t_origin + TRANSIT_US, Ordering::SeqCst);
+    forward(&staging, &hub_rx, |_, s| s <= 1);
+    tick(&mut hub, &t, 0);
+    assert_eq!(hub.decode_progress().len(), 0, "Generation decoded");
+
+    // The deadline runs from the sender's origin, not from arrival.
+    t.store(t_origin + DEPTH_US - 1, Ordering::SeqCst);
+    hub.poll();
+    assert!(hub.pop_ingress().is_none(), "Released before the playout deadline");
+
+    t.store(t_origin + DEPTH_US, Ordering::SeqCst);
+    hub.poll();
+    assert_eq!(hub.pop_ingress(), Some(vec![b'P'; 1500]), "Origin stamp stripped");
+}
+
+#[test]
+fn test_ptp_stamp_without_local_ptp_falls_back_to_arrival() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let node_rx = wire();
+    let staging = wire();
+
+    // Only the sender has PTP.
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: hub_rx.clone(), peer_rx: node_rx.clone() }, &t, 1);
+    let node_clock = PtpClock { t: t.clone(), offset_us: PTP_OFFSET_US };
+    let mut node = build_kernel_with_clock(false, WirePhy { local: NODE_ADDR, rx: node_rx, peer_rx: staging.clone() }, node_clock, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        forward(&staging, &hub_rx, |_, _| true);
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    emit(&mut node, &staging, &t, b'Q');
+    tick(&mut hub, &t, 50_000);
+    forward(&staging, &hub_rx, |_, s| s <= 1);
+    tick(&mut hub, &t, 0);
+    let t_rx = t.load(Ordering::SeqCst);
+
+    t.store(t_rx + DEPTH_US - 1, Ordering::SeqCst);
+    hub.poll();
+    assert!(hub.pop_ingress().is_none(), "Released before the playout deadline");
+
+    t.store(t_rx + DEPTH_US, Ordering::SeqCst);
+    hub.poll();
+    assert_eq!(hub.pop_ingress(), Some(vec![b'Q'; 1500]));
+}
@@ -0,0 +1,139 @@
+use m13_ulk::DEFAULT_KEEPALIVE_INTERVAL_US;
+use m13_hal::PeerAddr;
+use m13_core::{M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, ipv4_packet, wire, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: u32 = u32::from_be_bytes([10, 13, 13, 2]);
+
+fn keepalives(wire: &Wire) -> usize {
+    wire.lock().unwrap().iter()
+        .filter(|(f, _)| M13Header::from_bytes(f).map(|h| h.packet_type == PacketType::KeepAlive).unwrap_or(false))
+        .count()
+}
+
+#[test]
+fn test_idle_node_emits_keepalive_and_hub_stays_alive() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a.clone() }, &t, 2);
+
+    // Handshake, then one generation so the hub learns the node's route.
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    node.send_payload(&ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 900, 0xAB)).unwrap();
+    for _ in 0..4 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(500, Ordering::SeqCst);
+    }
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+
+    // Idle, but short of the interval: Nothing on the wire.
+    t.fetch_add(DEFAULT_KEEPALIVE_INTERVAL_US - 1_000_000, Ordering::SeqCst);
+    node.poll();
+    assert_eq!(keepalives(&a), 0, "KeepAlive before the idle interval");
+
+    // Past the interval: Exactly one heartbeat, not one per poll.
+    t.fetch_add(1_000_000, Ordering::SeqCst);
+    node.poll();
+    node.poll();
+    assert_eq!(keepalives(&a), 1, "No KeepAlive after the idle interval");
+
+    // The hub authenticates it and keeps the session past its own idle timeout.
+    for _ in 0..3 {
+        hub.poll();
+        t.fetch_add(DEFAULT_KEEPALIVE_INTERVAL_US, Ordering::SeqCst);
+        node.poll();
+    }
+    hub.poll();
+    assert!(hub.has_session(&NODE_ADDR), "Heartbeats did not keep the session alive");
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+}
+
+#[test]
+fn test_forged_keepalive_is_ignored() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a.clone() }, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+
+    // Node falls silent; an off-path attacker spoofs its address with bad tags.
+    let forged = {
+        let h = M13Header {
+            magic: m13_core::M13_MAGIC, version: 1, packet_type: PacketType::KeepAlive,
+            gen_id: 0, symbol_id: 0x8000_0001, payload_len: 0,
+            recoder_rank: 0, reserved: 0, auth_tag: [0x42; 16]
+        };
+        let mut f = vec![0u8; 32];
+        h.to_bytes(&mut f).unwrap();
+        f
+    };
+    for _ in 0..4 {
+        t.fetch_add(10_000_000, Ordering::SeqCst);
+        a.lock().unwrap().push_back((forged.clone(), NODE_ADDR));
+        hub.poll();
+    }
+    assert!(!hub.has_session(&NODE_ADDR), "Unauthenticated KeepAlive refreshed the session");
+}
+
+#[test]
+fn test_next_deadline_tracks_handshake_and_keepalive() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, &t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b, peer_rx: a.clone() }, &t, 2);
+
+    // No sessions: The hub has nothing timed; the node owes a ClientHello now.
+    assert_eq!(hub.next_deadline_us(), None);
+    assert!(node.next_deadline_us().unwrap() <= node.now_us());
+
+    // Hello sent, hub silent: Sleep until the retransmit.
+    node.poll();
+    let rto = node.next_deadline_us().unwrap();
+    assert!(rto > node.now_us(), "Deadline not pushed past the hello");
+    a.lock().unwrap().clear();
+    t.store(rto - 1, Ordering::SeqCst);
+    node.poll();
+    assert!(a.lock().unwrap().is_empty(), "Retransmitted before the deadline");
+    t.store(rto, Ordering::SeqCst);
+    node.poll();
+    assert!(!a.lock().unwrap().is_empty(), "No retransmit at the deadline");
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert!(hub.has_session(&NODE_ADDR));
+    assert!(hub.next_deadline_us().is_some(), "Hub must wake for its session sweep");
+
+    // Established and idle: The next timer is the KeepAlive.
+    let deadline = node.next_deadline_us().unwrap();
+    assert!(deadline > node.now_us() + DEFAULT_KEEPALIVE_INTERVAL_US / 2);
+    t.store(deadline - 1, Ordering::SeqCst);
+    node.poll();
+    assert_eq!(keepalives(&a), 0, "KeepAlive before the deadline");
+    t.store(deadline, Ordering::SeqCst);
+    node.poll();
+    assert_eq!(keepalives(&a), 1, "No KeepAlive at the deadline");
+    assert!(node.next_deadline_us().unwrap() >= deadline + DEFAULT_KEEPALIVE_INTERVAL_US);
+}
@@ -1,49 +1,51 @@
-use m13_ulk::{M13Kernel};
-use m13_hal::{PhysicalInterface, SecurityModule, PlatformClock, LinkProperties};
-use m13_mem::SlabAllocator;
-use m13_core::{M13Error};
-use std::boxed::Box;
-// FIX: Use AtomicU64 for Thread-Safe Mocking
-use std::sync::atomic::{AtomicU64, Ordering};
+use m13_ulk::M13Kernel;
+use m13_hal::PeerAddr;
+use m13_core::PacketType;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 
-// --- MOCKS ---
-struct MockPhy;
-impl PhysicalInterface for MockPhy {
-    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: false } }
-    fn send(&mut self, _: &[u8]) -> nb::Result<usize, M13Error> { Ok(0) }
-    fn recv<'a>(&mut self, _: &'a mut [u8]) -> nb::Result<usize, M13Error> { Err(nb::Error::WouldBlock) }
-}
-struct MockSec;
-impl SecurityModule for MockSec {
-    fn get_random_bytes(&mut self, _: &mut [u8]) -> m13_core::M13Result<()> { Ok(()) }
-    fn sign_digest(&mut self, _: &[u8], _: &mut [u8]) -> m13_core::M13Result<usize> { Ok(0) }
-    fn panic_and_sanitize(&self) -> ! { panic!("PANIC") }
-}
+mod common;
+use common::{build_kernel, client_hello, sent, wire, TapPhy, Wire};
 
-// Thread-safe Mock Clock
-struct MockClock { t: Arc<AtomicU64> }
-impl MockClock {
-    fn new(start: u64) -> Self { Self { t: Arc::new(AtomicU64::new(start)) } }
-}
-impl PlatformClock for MockClock {
-    fn now_us(&self) -> u64 { self.t.load(Ordering::SeqCst) }
-    fn ptp_ns(&self) -> Option<u64> { None }
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+
+fn build_hub(rx: &Wire, tx: &Wire) -> M13Kernel {
+    build_kernel(true, TapPhy { rx: rx.clone(), tx: tx.clone() }, &Arc::new(AtomicU64::new(1000)), 1)
 }
 
 #[test]
 fn test_kernel_cycle() {
-    let slab = SlabAllocator::new(10);
-    let clock = Box::new(MockClock::new(1000));
-    
-    let mut kernel = M13Kernel::new(
-        Box::new(MockPhy),
-        Box::new(MockSec),
-        clock,
-        slab
-    );
-    
+    let rx = wire();
+    let tx = wire();
+    let mut kernel = build_hub(&rx, &tx);
+
     // Run one cycle. Expect false (Idle) because Phy returns WouldBlock.
     let work_done = kernel.poll();
-    assert_eq!(work_done, false);
-}
\ No newline at end of file
+    assert!(!work_done);
+    assert!(tx.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_kernel_cycle_handles_client_hello() {
+    let rx = wire();
+    let tx = wire();
+    let mut kernel = build_hub(&rx, &tx);
+
+    // First contact: The hub answers with a cookie, nothing more.
+    rx.lock().unwrap().extend(client_hello([0; 16]).into_iter().map(|f| (f, NODE_ADDR)));
+    assert!(kernel.poll());
+    let replies = sent(&tx);
+    assert!(!replies.is_empty());
+    assert!(replies.iter().all(|(ptype, _, to)| *ptype == PacketType::Cookie && *to == NODE_ADDR));
+    assert!(!kernel.has_session(&NODE_ADDR));
+
+    // Echoing it runs the handshake: Session plus server hello.
+    let cookie: [u8; 16] = replies[0].1.as_slice().try_into().unwrap();
+    rx.lock().unwrap().extend(client_hello(cookie).into_iter().map(|f| (f, NODE_ADDR)));
+    assert!(kernel.poll());
+    assert!(kernel.has_session(&NODE_ADDR));
+    assert!(sent(&tx).iter().any(|(ptype, _, to)| *ptype == PacketType::HandshakeInit && *to == NODE_ADDR));
+
+    // Drained: Idle again.
+    assert!(!kernel.poll());
+}
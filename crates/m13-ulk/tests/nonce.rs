@@ -0,0 +1,91 @@
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::{M13Error, M13Header, PacketType};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+mod common;
+use common::{build_kernel, ipv4_packet, wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const HUB_VIP: [u8; 4] = [10, 13, 13, 1];
+const NODE_VIP: [u8; 4] = [10, 13, 13, 2];
+
+// --- MOCKS ---
+/// `WirePhy` that logs the header of every frame it sends.
+struct LogPhy {
+    inner: WirePhy,
+    log: Arc<Mutex<Vec<M13Header>>>,
+}
+impl PhysicalInterface for LogPhy {
+    fn properties(&self) -> LinkProperties { self.inner.properties() }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.log.lock().unwrap().push(M13Header::from_bytes(frame).unwrap());
+        self.inner.send(frame, target)
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        self.inner.recv(buf)
+    }
+}
+
+/// The first (gen_id, symbol_id) one side sealed twice under its TX key, if any.
+fn first_reused_nonce(log: &[M13Header]) -> Option<(PacketType, u16, u32)> {
+    let mut seen = HashSet::new();
+    log.iter()
+        .filter(|h| matches!(h.packet_type,
+            PacketType::Data | PacketType::Coded | PacketType::Recoded | PacketType::Ack
+            | PacketType::Nack | PacketType::KeepAlive | PacketType::Goodbye))
+        .find(|h| !seen.insert((h.gen_id, h.symbol_id)))
+        .map(|h| (h.packet_type, h.gen_id, h.symbol_id))
+}
+
+#[test]
+fn test_no_nonce_reuse_under_one_key() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let a = wire();
+    let b = wire();
+    let hub_log = Arc::new(Mutex::new(Vec::new()));
+    let node_log = Arc::new(Mutex::new(Vec::new()));
+
+    let hub_phy = LogPhy { inner: WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, log: hub_log.clone() };
+    let node_phy = LogPhy { inner: WirePhy { local: NODE_ADDR, rx: b, peer_rx: a }, log: node_log.clone() };
+    let mut hub = build_kernel(true, hub_phy, &t, 1);
+    let mut node = build_kernel(false, node_phy, &t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    // The node's first generation teaches the hub its route.
+    node.send_payload(&ipv4_packet(NODE_VIP, HUB_VIP, 900, 0xFF)).unwrap();
+    for _ in 0..4 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(500, Ordering::SeqCst);
+    }
+
+    // Both directions carry data, so each side seals its own generations and ACKs
+    // for the peer's, from overlapping gen_id ranges.
+    for round in 0..8u8 {
+        node.send_payload(&ipv4_packet(NODE_VIP, HUB_VIP, 900, round)).unwrap();
+        hub.send_payload(&ipv4_packet(HUB_VIP, NODE_VIP, 900, round)).unwrap();
+        for _ in 0..4 {
+            node.poll();
+            hub.poll();
+            t.fetch_add(500, Ordering::SeqCst);
+        }
+    }
+    let mut delivered = (0, 0);
+    while hub.pop_ingress().is_some() { delivered.0 += 1; }
+    while node.pop_ingress().is_some() { delivered.1 += 1; }
+    assert!(delivered.0 >= 9 && delivered.1 >= 8, "Traffic never flowed: {:?}", delivered);
+
+    let hub_log = hub_log.lock().unwrap();
+    let node_log = node_log.lock().unwrap();
+    assert!(hub_log.iter().any(|h| h.packet_type == PacketType::Ack));
+    assert!(node_log.iter().any(|h| h.packet_type == PacketType::Ack));
+    assert_eq!(first_reused_nonce(&hub_log), None, "Hub reused a nonce");
+    assert_eq!(first_reused_nonce(&node_log), None, "Node reused a nonce");
+}
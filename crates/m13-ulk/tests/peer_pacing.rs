@@ -0,0 +1,89 @@
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::PeerAddr;
+use m13_hal::loopback::{LinkConditions, LoopbackClock, LoopbackPair};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{ipv4_packet, loopback_kernel, LoopbackSwitch, LossyPhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const SLOW_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const FAST_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 3], 5000);
+const HUB_VIP: [u8; 4] = [10, 13, 13, 1];
+const SLOW_VIP: [u8; 4] = [10, 13, 13, 2];
+const FAST_VIP: [u8; 4] = [10, 13, 13, 3];
+
+fn run(kernels: &mut [&mut M13Kernel], clock: &LoopbackClock, rounds: usize, step_us: u64) {
+    for _ in 0..rounds {
+        for k in kernels.iter_mut() { k.poll(); }
+        clock.advance(step_us);
+    }
+}
+
+fn drain(kernel: &mut M13Kernel) -> usize {
+    let mut n = 0;
+    while kernel.pop_ingress().is_some() { n += 1; }
+    n
+}
+
+#[test]
+fn test_stalled_peer_does_not_throttle_the_others() {
+    let clock = LoopbackClock::new(3_000_000);
+    let slow_link = LoopbackPair::new(&clock, HUB_ADDR, SLOW_ADDR, LinkConditions::default());
+    let fast_link = LoopbackPair::new(&clock, HUB_ADDR, FAST_ADDR, LinkConditions::default());
+    // While `slow_cut` is set the slow node's uplink loses everything (the hub hears no ACKs).
+    let slow_cut = Arc::new(AtomicBool::new(false));
+    let cut = slow_cut.clone();
+    let slow_phy = LossyPhy::new(slow_link.b, Arc::new(move |_| cut.load(Ordering::SeqCst)));
+
+    let hub_phy = LoopbackSwitch::new(vec![(SLOW_ADDR, Box::new(slow_link.a)), (FAST_ADDR, Box::new(fast_link.a))]);
+    let mut hub = loopback_kernel(KernelConfig { is_hub: true, ..Default::default() }, hub_phy, &clock, 1);
+    let mut slow = loopback_kernel(KernelConfig::default(), slow_phy, &clock, 2);
+    let mut fast = loopback_kernel(KernelConfig::default(), fast_link.b, &clock, 3);
+
+    // Handshakes, then one uplink each so the hub learns both routes.
+    run(&mut [&mut slow, &mut fast, &mut hub], &clock, 10, 1_000);
+    slow.send_payload(&ipv4_packet(SLOW_VIP, HUB_VIP, 900, 0)).unwrap();
+    fast.send_payload(&ipv4_packet(FAST_VIP, HUB_VIP, 900, 0)).unwrap();
+    run(&mut [&mut slow, &mut fast, &mut hub], &clock, 4, 500);
+    assert_eq!(drain(&mut hub), 2);
+
+    // ACKed downlink rounds: Both paths get their own rate and BDP model.
+    for round in 0..4u8 {
+        hub.send_payload(&ipv4_packet(HUB_VIP, SLOW_VIP, 900, round)).unwrap();
+        hub.send_payload(&ipv4_packet(HUB_VIP, FAST_VIP, 900, round)).unwrap();
+        run(&mut [&mut hub, &mut slow, &mut fast], &clock, 4, 500);
+    }
+    assert_eq!((drain(&mut slow), drain(&mut fast)), (4, 4));
+    let slow_cap = hub.peer_inflight_cap_bytes(&SLOW_ADDR).unwrap();
+    assert!(slow_cap < u64::MAX);
+    assert_eq!(hub.peer_bytes_in_flight(&FAST_ADDR), Some(0));
+    let fast_rate = hub.peer_pacing_rate_bps(&FAST_ADDR).unwrap();
+
+    // The slow node's ACKs stop reaching the hub. Its backlog sits at the head of the queue.
+    slow_cut.store(true, Ordering::SeqCst);
+    for i in 0..32u8 {
+        hub.send_payload(&ipv4_packet(HUB_VIP, SLOW_VIP, 900, i)).unwrap();
+    }
+    for i in 0..32u8 {
+        hub.send_payload(&ipv4_packet(HUB_VIP, FAST_VIP, 900, i)).unwrap();
+    }
+    let (mut to_slow, mut to_fast) = (0, 0);
+    for _ in 0..40 {
+        run(&mut [&mut hub, &mut slow, &mut fast], &clock, 1, 500);
+        to_slow += drain(&mut slow);
+        to_fast += drain(&mut fast);
+    }
+
+    // Slow path: Held at its own cap.
+    let generation = 2 * (32 + 1024) as u64;
+    let stalled = hub.peer_bytes_in_flight(&SLOW_ADDR).unwrap();
+    assert!(stalled >= slow_cap && stalled < slow_cap + generation, "In flight {} vs cap {}", stalled, slow_cap);
+    assert!(to_slow < 32, "Cap never engaged");
+
+    // Fast path: Everything through, its pacer untouched by the stall.
+    assert_eq!(to_fast, 32, "Fast peer starved behind the stalled one");
+    assert_eq!(hub.peer_bytes_in_flight(&FAST_ADDR), Some(0));
+    assert!(hub.peer_pacing_rate_bps(&FAST_ADDR).unwrap() >= fast_rate);
+}
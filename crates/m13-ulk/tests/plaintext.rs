@@ -0,0 +1,86 @@
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::PeerAddr;
+use m13_core::{M13Header, PacketType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel_with, ipv4_packet, wire, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: u32 = u32::from_be_bytes([10, 13, 13, 2]);
+
+fn build_kernel(is_hub: bool, enable_encryption: bool, phy: WirePhy, t: &Arc<AtomicU64>, seed: u8) -> M13Kernel {
+    build_kernel_with(KernelConfig { is_hub, enable_encryption, ..Default::default() }, phy, t, seed)
+}
+
+/// Moves `from` into `to`, recording each frame's header.
+fn forward(from: &Wire, to: &Wire, seen: &mut Vec<M13Header>) {
+    let frames: Vec<_> = from.lock().unwrap().drain(..).collect();
+    for (f, src) in frames {
+        if let Ok(h) = M13Header::from_bytes(&f) { seen.push(h); }
+        to.lock().unwrap().push_back((f, src));
+    }
+}
+
+#[test]
+fn test_plaintext_kernels_exchange_without_handshake() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let to_hub = wire();
+    let to_node = wire();
+    let hub_out = wire();
+    let node_out = wire();
+    let mut hub = build_kernel(true, false, WirePhy { local: HUB_ADDR, rx: to_hub.clone(), peer_rx: hub_out.clone() }, &t, 1);
+    let mut node = build_kernel(false, false, WirePhy { local: NODE_ADDR, rx: to_node.clone(), peer_rx: node_out.clone() }, &t, 2);
+
+    // First thing on the wire is data: No hello to wait for.
+    let uplink = ipv4_packet([10, 13, 13, 2], [10, 13, 13, 1], 1200, 0x5A);
+    node.send_payload(&uplink).unwrap();
+    let mut seen = Vec::new();
+    for _ in 0..10 {
+        node.poll();
+        forward(&node_out, &to_hub, &mut seen);
+        hub.poll();
+        forward(&hub_out, &to_node, &mut seen);
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert_eq!(hub.pop_ingress(), Some(uplink));
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR), "Route not learned from plaintext data");
+
+    let downlink = ipv4_packet([10, 13, 13, 1], [10, 13, 13, 2], 700, 0xC3);
+    hub.send_payload(&downlink).unwrap();
+    for _ in 0..10 {
+        hub.poll();
+        forward(&hub_out, &to_node, &mut seen);
+        node.poll();
+        forward(&node_out, &to_hub, &mut seen);
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert_eq!(node.pop_ingress(), Some(downlink));
+
+    // Only the AEAD layer is gone: Same frame types, no tags, no handshake.
+    assert!(seen.iter().any(|h| h.packet_type == PacketType::Coded));
+    assert!(seen.iter().any(|h| h.packet_type == PacketType::Ack), "ACKs must still close the loop");
+    assert!(seen.iter().all(|h| matches!(h.packet_type, PacketType::Coded | PacketType::Data | PacketType::Ack)), "Unexpected control traffic");
+    assert!(seen.iter().all(|h| h.auth_tag == [0u8; 16]));
+    assert_eq!(node.stats().handshakes_completed, 0);
+}
+
+#[test]
+fn test_encrypted_hub_ignores_plaintext_data() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let to_hub = wire();
+    let to_node = wire();
+    let mut hub = build_kernel(true, true, WirePhy { local: HUB_ADDR, rx: to_hub.clone(), peer_rx: to_node.clone() }, &t, 1);
+    let mut node = build_kernel(false, false, WirePhy { local: NODE_ADDR, rx: to_node, peer_rx: to_hub }, &t, 2);
+
+    node.send_payload(&ipv4_packet([10, 13, 13, 2], [10, 13, 13, 1], 600, 0x11)).unwrap();
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    assert!(hub.pop_ingress().is_none(), "Plaintext accepted by an encrypting hub");
+    assert!(!hub.has_session(&NODE_ADDR));
+}
@@ -0,0 +1,63 @@
+use m13_ulk::M13Kernel;
+use m13_hal::PeerAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, ipv4_packet, wire, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: u32 = u32::from_be_bytes([10, 13, 13, 2]);
+
+/// Handshake, then one data generation so the hub learns the node's route.
+/// Returns (hub, node, hub RX wire, node RX wire).
+fn establish(t: &Arc<AtomicU64>) -> (M13Kernel, M13Kernel, Wire, Wire) {
+    let a = wire();
+    let b = wire();
+    let mut hub = build_kernel(true, WirePhy { local: HUB_ADDR, rx: a.clone(), peer_rx: b.clone() }, t, 1);
+    let mut node = build_kernel(false, WirePhy { local: NODE_ADDR, rx: b.clone(), peer_rx: a.clone() }, t, 2);
+
+    for _ in 0..10 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(1_000, Ordering::SeqCst);
+    }
+    node.send_payload(&ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 900, 0xAB)).unwrap();
+    for _ in 0..4 {
+        node.poll();
+        hub.poll();
+        t.fetch_add(500, Ordering::SeqCst);
+    }
+    assert!(hub.has_session(&NODE_ADDR));
+    assert_eq!(hub.route(NODE_VIP), Some(NODE_ADDR));
+    (hub, node, a, b)
+}
+
+#[test]
+fn test_reflected_data_does_not_open() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let (mut hub, mut node, hub_rx, node_rx) = establish(&t);
+    hub_rx.lock().unwrap().clear();
+    node_rx.lock().unwrap().clear();
+    while hub.pop_ingress().is_some() {}
+
+    // Hub -> node data, captured on the wire.
+    let mut packet = ipv4_packet(NODE_VIP.to_be_bytes(), [10, 13, 13, 1], 600, 0xAB);
+    packet[12..16].copy_from_slice(&[10, 13, 13, 1]);
+    packet[16..20].copy_from_slice(&NODE_VIP.to_be_bytes());
+    hub.send_payload(&packet).unwrap();
+    hub.poll();
+    let frames: Vec<(Vec<u8>, PeerAddr)> = node_rx.lock().unwrap().drain(..).collect();
+    assert!(!frames.is_empty());
+
+    // Reflected at the hub as if the node had sent it: The hub's RX key is the node's TX key.
+    for (f, _) in &frames { hub_rx.lock().unwrap().push_back((f.clone(), NODE_ADDR)); }
+    hub.poll();
+    assert!(hub.pop_ingress().is_none(), "Hub accepted its own reflected traffic");
+
+    // Delivered to the node as intended: It opens.
+    for (f, from) in frames { node_rx.lock().unwrap().push_back((f, from)); }
+    node.poll();
+    assert_eq!(node.pop_ingress(), Some(packet));
+}
@@ -0,0 +1,114 @@
+use m13_ulk::{M13Kernel, KernelConfig};
+use m13_hal::{PhysicalInterface, PeerAddr};
+use m13_hal::loopback::{LinkConditions, LoopbackClock, LoopbackPair};
+use m13_core::{M13Header, PacketType};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{ipv4_packet, loopback_kernel, HeaderLog, LoopbackSwitch, LossyPhy};
+
+const SOURCE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const RELAY_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 3], 5000);
+const SINK_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const SOURCE_VIP: [u8; 4] = [10, 13, 13, 2];
+const SINK_VIP: [u8; 4] = [10, 13, 13, 1];
+const MESH_KEY: [u8; 32] = [0x3C; 32];
+
+fn build_kernel(is_hub: bool, relay: bool, phy: impl PhysicalInterface + 'static, clock: &LoopbackClock, seed: u8) -> M13Kernel {
+    let config = KernelConfig { is_hub, relay, ..Default::default() };
+    config.validate().unwrap();
+    loopback_kernel(config, phy, clock, seed)
+}
+
+/// Source -> relay -> sink on two `LoopbackPair`s, the ends sharing the mesh key. The relay's
+/// upstream port (the sink) comes first, so its untargeted frames go there. Upstream headers
+/// are logged; the first `drop_recoded` Recoded frames toward the sink are lost.
+/// Returns (source, relay, sink, upstream headers).
+fn mesh(clock: &LoopbackClock, drop_recoded: &Arc<AtomicUsize>) -> (M13Kernel, M13Kernel, M13Kernel, HeaderLog) {
+    let downstream = LoopbackPair::new(clock, SOURCE_ADDR, RELAY_ADDR, LinkConditions::default());
+    let upstream = LoopbackPair::new(clock, RELAY_ADDR, SINK_ADDR, LinkConditions::default());
+    let drops = drop_recoded.clone();
+    let uplink = LossyPhy::new(upstream.a, Arc::new(move |h: &M13Header| {
+        h.packet_type == PacketType::Recoded && drops.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok()
+    }));
+    let log = uplink.sent.clone();
+
+    let mut source = build_kernel(false, false, downstream.a, clock, 2);
+    let relay_phy = LoopbackSwitch::new(vec![(SINK_ADDR, Box::new(uplink)), (SOURCE_ADDR, Box::new(downstream.b))]);
+    let relay = build_kernel(false, true, relay_phy, clock, 3);
+    let mut sink = build_kernel(true, false, upstream.b, clock, 1);
+    source.set_mesh_key(MESH_KEY);
+    sink.set_mesh_key(MESH_KEY);
+    (source, relay, sink, log)
+}
+
+/// Several symbols' worth of source -> sink datagram, its body patterned so a misplaced
+/// symbol cannot decode to the same bytes.
+fn patterned_packet() -> Vec<u8> {
+    let mut p = ipv4_packet(SOURCE_VIP, SINK_VIP, 3000, 0);
+    for (i, b) in p.iter_mut().enumerate().skip(20) { *b = (i * 7) as u8; }
+    p
+}
+
+fn run(kernels: &mut [&mut M13Kernel], clock: &LoopbackClock, rounds: usize) {
+    for _ in 0..rounds {
+        for k in kernels.iter_mut() { k.poll(); }
+        clock.advance(1_000);
+    }
+}
+
+#[test]
+fn test_sink_decodes_through_a_relay_that_never_decodes() {
+    let clock = LoopbackClock::new(3_000_000);
+    let (mut source, mut relay, mut sink, upstream) = mesh(&clock, &Arc::new(AtomicUsize::new(0)));
+
+    // The relay keys its upstream first, then admits the source like a hub would.
+    run(&mut [&mut relay, &mut sink, &mut source], &clock, 20);
+    assert!(relay.has_session(&SINK_ADDR) && relay.has_session(&SOURCE_ADDR));
+    assert!(sink.has_session(&RELAY_ADDR) && !sink.has_session(&SOURCE_ADDR));
+    assert_eq!(source.session_key_epoch(&RELAY_ADDR), Some(0));
+
+    // Several symbols' worth: The relay mixes a real basis.
+    let packet = patterned_packet();
+    source.send_payload(&packet).unwrap();
+    run(&mut [&mut source, &mut relay, &mut sink], &clock, 10);
+
+    assert_eq!(sink.pop_ingress(), Some(packet));
+    assert_eq!(sink.pop_ingress(), None);
+    assert_eq!(sink.route(u32::from_be_bytes(SOURCE_VIP)), Some(RELAY_ADDR));
+
+    // The relay only recoded: Nothing decoded, nothing delivered.
+    assert_eq!(relay.stats().decode_completions, 0);
+    assert_eq!(relay.pop_ingress(), None);
+    let recoded: Vec<M13Header> = upstream.lock().unwrap().iter().filter(|h| h.packet_type == PacketType::Recoded).copied().collect();
+    assert!(!recoded.is_empty());
+    // K = 3 (3000 bytes, sealed and length-prefixed), sent as 3 source symbols + 1 repair.
+    assert!(recoded.iter().all(|h| h.advertised_rank() > 0 && h.reserved == 3));
+    assert_eq!(recoded.iter().map(|h| h.advertised_rank()).max(), Some(4), "Every symbol of the burst is innovative");
+
+    // Both hops closed their loops: The source heard from the relay, the relay from the sink.
+    assert_eq!(source.bytes_in_flight(), 0);
+    assert!(source.last_rtt_us().is_some() && relay.last_rtt_us().is_some());
+}
+
+#[test]
+fn test_relay_repairs_upstream_loss_from_its_basis() {
+    let clock = LoopbackClock::new(3_000_000);
+    let drop_recoded = Arc::new(AtomicUsize::new(0));
+    let (mut source, mut relay, mut sink, _) = mesh(&clock, &drop_recoded);
+    run(&mut [&mut relay, &mut sink, &mut source], &clock, 20);
+
+    // The relay -> sink hop loses the first two combinations. The source -> relay hop is clean,
+    // so the source is ACKed and never repairs: The sink's NACK is the relay's to answer.
+    drop_recoded.store(2, Ordering::SeqCst);
+    let packet = patterned_packet();
+    source.send_payload(&packet).unwrap();
+    run(&mut [&mut source, &mut relay, &mut sink], &clock, 10);
+
+    assert_eq!(sink.pop_ingress(), Some(packet));
+    assert_eq!(drop_recoded.load(Ordering::SeqCst), 0);
+    assert_eq!(source.stats().repair_bursts, 0);
+    assert_eq!(relay.stats().repair_bursts, 1);
+    assert_eq!(relay.stats().decode_completions, 0);
+}
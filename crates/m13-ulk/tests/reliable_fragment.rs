@@ -0,0 +1,158 @@
+use m13_ulk::{KernelConfig, ReliableFragmentSender, ack_fragment};
+use m13_ulk::fragment::FragmentAssembler;
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_hal::loopback::{LinkConditions, LoopbackClock, LoopbackPair};
+use m13_core::{M13Error, M13Header, PacketType};
+use m13_time::PhaseMonitor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{loopback_kernel, Filter, LossyPhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+
+/// Records every frame sent.
+#[derive(Default)]
+struct CapturePhy {
+    sent: Vec<Vec<u8>>,
+}
+impl PhysicalInterface for CapturePhy {
+    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: false } }
+    fn send(&mut self, frame: &[u8], _: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        self.sent.push(frame.to_vec());
+        Ok(frame.len())
+    }
+    fn recv(&mut self, _: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        Err(nb::Error::WouldBlock)
+    }
+}
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/// Receiver: Reassemble `frames`, ACKing each. Returns the message once complete.
+fn receive(frames: &[Vec<u8>], rx: &mut FragmentAssembler, acks: &mut CapturePhy) -> Option<Vec<u8>> {
+    let mut done = None;
+    for frame in frames {
+        let header = M13Header::from_bytes(frame).unwrap();
+        let payload = &frame[32..32 + header.payload_len as usize];
+        ack_fragment(acks, &header, payload, Some(HUB_ADDR)).unwrap();
+        if let Some(msg) = rx.ingest(payload, 0).unwrap() { done = Some(msg); }
+    }
+    done
+}
+
+#[test]
+fn test_lost_fragment_is_retransmitted_after_rto() {
+    let msg = message(2_500); // 3 fragments
+    let mut tx = ReliableFragmentSender::new(PacketType::HandshakeInit, 7, &msg, Some(HUB_ADDR)).unwrap();
+    let mut phase = PhaseMonitor::new();
+    for _ in 0..8 { phase.add_sample(80_000); }
+    tx.update_rto(&phase);
+    let rto = tx.rto_us();
+    assert!((80_000..=100_000).contains(&rto), "RTO {} not derived from RTT", rto);
+
+    let mut wire = CapturePhy::default();
+    tx.poll(&mut wire, 1_000).unwrap();
+    assert_eq!(wire.sent.len(), 3);
+
+    // The final fragment is lost.
+    let mut delivered: Vec<Vec<u8>> = wire.sent.drain(..).collect();
+    delivered.remove(2);
+    let mut rx = FragmentAssembler::new();
+    let mut acks = CapturePhy::default();
+    assert_eq!(receive(&delivered, &mut rx, &mut acks), None);
+    for ack in acks.sent.drain(..) {
+        assert!(tx.on_ack(&M13Header::from_bytes(&ack).unwrap()));
+    }
+    assert_eq!(tx.outstanding(), 1);
+
+    // Before the RTO: Nothing resent.
+    tx.poll(&mut wire, 1_000 + rto - 1).unwrap();
+    assert!(wire.sent.is_empty());
+
+    // After it: Only the lost fragment.
+    tx.poll(&mut wire, 1_000 + rto).unwrap();
+    assert_eq!(wire.sent.len(), 1);
+    let resent = M13Header::from_bytes(&wire.sent[0]).unwrap();
+    assert_eq!((resent.gen_id, resent.symbol_id), (7, 2_000));
+
+    let retransmitted: Vec<Vec<u8>> = wire.sent.drain(..).collect();
+    assert_eq!(receive(&retransmitted, &mut rx, &mut acks), Some(msg));
+    assert!(tx.on_ack(&M13Header::from_bytes(&acks.sent[0]).unwrap()));
+    assert!(tx.is_complete());
+
+    // Complete: Silent from here on.
+    tx.poll(&mut wire, 10_000_000).unwrap();
+    assert!(wire.sent.is_empty());
+}
+
+#[test]
+fn test_unacked_message_is_abandoned() {
+    let mut tx = ReliableFragmentSender::new(PacketType::ClientHello, 1, &message(100), None).unwrap();
+    let mut wire = CapturePhy::default();
+    let mut now = 0;
+    let mut result = Ok(0);
+    for _ in 0..64 {
+        result = tx.poll(&mut wire, now);
+        if result.is_err() { break; }
+        now += 10_000_000;
+    }
+    assert!(matches!(result, Err(M13Error::InvalidState)));
+    assert!(wire.sent.len() > 1, "Never retransmitted");
+
+    // ACKs for another message id are ignored.
+    let mut other = CapturePhy::default();
+    let header = M13Header::from_bytes(&wire.sent[0]).unwrap();
+    let mut foreign = header;
+    foreign.gen_id = 2;
+    ack_fragment(&mut other, &foreign, &wire.sent[0][32..], None).unwrap();
+    assert!(!tx.on_ack(&M13Header::from_bytes(&other.sent[0]).unwrap()));
+}
+
+#[test]
+fn test_hub_resends_only_the_lost_handshake_fragment() {
+    let clock = LoopbackClock::new(3_000_000);
+    let pair = LoopbackPair::new(&clock, HUB_ADDR, NODE_ADDR, LinkConditions::default());
+    // The hub's reply loses its second fragment, once.
+    let lost = Arc::new(AtomicBool::new(false));
+    let once = lost.clone();
+    let drop: Filter = Arc::new(move |h| {
+        h.packet_type == PacketType::HandshakeInit && h.symbol_id == 1_000 && !once.swap(true, Ordering::SeqCst)
+    });
+    let hub_phy = LossyPhy::new(pair.a, drop);
+    let hub_sent = hub_phy.sent.clone();
+    let config = KernelConfig { is_hub: true, reliable_fragments: true, ..Default::default() };
+    let mut hub = loopback_kernel(config, hub_phy, &clock, 1);
+    let mut node = loopback_kernel(KernelConfig::default(), pair.b, &clock, 2);
+
+    // Past one RTO (100ms before any RTT sample), short of the node's 200ms hello retransmit.
+    for _ in 0..150 {
+        node.poll();
+        hub.poll();
+        clock.advance(1_000);
+    }
+    assert!(lost.load(Ordering::SeqCst));
+    assert_eq!(node.stats().handshakes_completed, 1, "The lost fragment was never resent");
+
+    // Every fragment went out once under one message id, the lost one twice.
+    let reply: Vec<M13Header> = hub_sent.lock().unwrap().iter()
+        .filter(|h| h.packet_type == PacketType::HandshakeInit).copied().collect();
+    assert!(reply.len() > 2 && reply.iter().all(|h| h.gen_id != 0 && h.gen_id == reply[0].gen_id));
+    let mut offsets: Vec<u32> = reply.iter().map(|h| h.symbol_id).collect();
+    offsets.sort();
+    let mut expected: Vec<u32> = (0..reply.len() as u32 - 1).map(|i| i * 1_000).collect();
+    expected.insert(2, 1_000);
+    assert_eq!(offsets, expected);
+
+    // All ACKed: Silent from here on.
+    for _ in 0..40 {
+        node.poll();
+        hub.poll();
+        clock.advance(100_000);
+    }
+    assert_eq!(hub_sent.lock().unwrap().iter().filter(|h| h.packet_type == PacketType::HandshakeInit).count(), reply.len());
+}
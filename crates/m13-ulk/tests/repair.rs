@@ -0,0 +1,100 @@
+use m13_ulk::{M13Kernel, KernelConfig, LAST_SYMBOL};
+use m13_hal::PeerAddr;
+use m13_hal::loopback::{LinkConditions, LoopbackClock, LoopbackPair};
+use m13_core::{M13Header, PacketType};
+use std::sync::Arc;
+
+mod common;
+use common::{ipv4_packet, loopback_kernel, Filter, HeaderLog, LossyPhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const NODE_VIP: [u8; 4] = [10, 13, 13, 2];
+const HUB_VIP: [u8; 4] = [10, 13, 13, 1];
+
+/// Hub and node on a `LoopbackPair` whose uplink also loses what `drop` selects.
+/// Returns (hub, node, headers the node sent, headers the hub sent).
+fn build_link(clock: &LoopbackClock, drop: Filter) -> (M13Kernel, M13Kernel, HeaderLog, HeaderLog) {
+    let pair = LoopbackPair::new(clock, HUB_ADDR, NODE_ADDR, LinkConditions::default());
+    let hub_phy = LossyPhy::new(pair.a, Arc::new(|_| false));
+    let node_phy = LossyPhy::new(pair.b, drop);
+    let (hub_sent, node_sent) = (hub_phy.sent.clone(), node_phy.sent.clone());
+    let hub = loopback_kernel(KernelConfig { is_hub: true, ..Default::default() }, hub_phy, clock, 1);
+    let node = loopback_kernel(KernelConfig::default(), node_phy, clock, 2);
+    (hub, node, node_sent, hub_sent)
+}
+
+fn step(node: &mut M13Kernel, hub: &mut M13Kernel, clock: &LoopbackClock, rounds: usize) {
+    for _ in 0..rounds {
+        node.poll();
+        hub.poll();
+        clock.advance(500);
+    }
+}
+
+fn coded(headers: &HeaderLog, gen_id: u16) -> Vec<M13Header> {
+    headers.lock().unwrap().iter().filter(|h| h.packet_type == PacketType::Coded && h.gen_id == gen_id).copied().collect()
+}
+
+#[test]
+fn test_loss_triggers_extended_repair_burst() {
+    let clock = LoopbackClock::new(3_000_000);
+    // First data generation (gen_id 1): Lose the first two symbols of its first burst.
+    let drop: Filter = Arc::new(|h| h.packet_type == PacketType::Coded && h.gen_id == 1 && h.symbol_id < 2);
+    let (mut hub, mut node, node_sent, hub_sent) = build_link(&clock, drop);
+    step(&mut node, &mut hub, &clock, 10);
+    assert_eq!(node.stats().handshakes_completed, 1);
+
+    // K = 3 (+1 overhead): Two of four symbols arrive, the end marker among them.
+    let payload = ipv4_packet(NODE_VIP, HUB_VIP, 3000, 0x5A);
+    node.send_payload(&payload).unwrap();
+    step(&mut node, &mut hub, &clock, 20);
+
+    assert_eq!(hub.pop_ingress(), Some(payload), "Repair never completed the generation");
+    let nacks: Vec<_> = hub_sent.lock().unwrap().iter().filter(|h| h.packet_type == PacketType::Nack).copied().collect();
+    assert_eq!(nacks.len(), 1, "One NACK per short end marker");
+    assert_eq!(node.stats().repair_bursts, 1);
+
+    // Both bursts end on a marked symbol; repair symbols are fresh, never resent ones.
+    let sent = coded(&node_sent, 1);
+    let marked: Vec<_> = sent.iter().filter(|h| h.recoder_rank & LAST_SYMBOL != 0).map(|h| h.symbol_id).collect();
+    assert_eq!(marked.len(), 2, "Marked symbols: {:?}", marked);
+    assert_eq!(marked[0], 3);
+    assert_eq!(marked[1], *sent.iter().map(|h| h.symbol_id).collect::<Vec<_>>().iter().max().unwrap());
+    let mut ids: Vec<u32> = sent.iter().map(|h| h.symbol_id).collect();
+    ids.dedup();
+    assert_eq!(ids.len(), sent.len());
+
+    // Delivered and ACKed: A stray NACK can no longer restart it.
+    assert_eq!(hub.decode_failures(), 0);
+    step(&mut node, &mut hub, &clock, 10);
+    assert_eq!(coded(&node_sent, 1).len(), sent.len());
+    assert_eq!(hub.pop_ingress(), None, "Late symbols re-delivered the generation");
+}
+
+#[test]
+fn test_receiver_abandons_after_max_repairs() {
+    let clock = LoopbackClock::new(3_000_000);
+    // Only each burst's end marker gets through: One new equation per round, never enough.
+    let drop: Filter = Arc::new(|h| h.packet_type == PacketType::Coded && h.gen_id == 1 && h.recoder_rank & LAST_SYMBOL == 0);
+    let (mut hub, mut node, node_sent, hub_sent) = build_link(&clock, drop);
+    step(&mut node, &mut hub, &clock, 10);
+
+    // K = 10.
+    node.send_payload(&ipv4_packet(NODE_VIP, HUB_VIP, 10_000, 0x11)).unwrap();
+    step(&mut node, &mut hub, &clock, 60);
+
+    assert_eq!(hub.pop_ingress(), None);
+    assert_eq!(node.stats().repair_bursts, 3, "Sender must stop repairing");
+    let nacks = hub_sent.lock().unwrap().iter().filter(|h| h.packet_type == PacketType::Nack).count();
+    assert_eq!(nacks, 3);
+    assert_eq!(hub.decode_failures(), 1, "Receiver kept waiting on a sender that gave up");
+    assert!(hub.decode_progress().is_empty());
+    assert_eq!(coded(&node_sent, 1).iter().filter(|h| h.recoder_rank & LAST_SYMBOL != 0).count(), 4);
+
+    // The link itself is fine: The next generation goes straight through.
+    let next = ipv4_packet(NODE_VIP, HUB_VIP, 900, 0x22);
+    node.send_payload(&next).unwrap();
+    step(&mut node, &mut hub, &clock, 10);
+    assert_eq!(hub.pop_ingress(), Some(next));
+}
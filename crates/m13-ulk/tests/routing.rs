@@ -0,0 +1,139 @@
+use m13_ulk::{M13Kernel, IpDest};
+use m13_hal::{PhysicalInterface, LinkProperties, PeerAddr};
+use m13_core::M13Error;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+mod common;
+use common::{build_kernel, ipv4_packet, pop_into, wire, Wire, WirePhy};
+
+const HUB_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 1], 443);
+const V4_NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 2], 5000);
+const V6_NODE_ADDR: PeerAddr = PeerAddr::V4([10, 0, 0, 3], 5000);
+const V4_VIP: [u8; 4] = [10, 13, 13, 2];
+const V6_VIP: [u8; 16] = [0xfd, 0x13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+const V6_HUB_VIP: [u8; 16] = [0xfd, 0x13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+const V6_UNKNOWN: [u8; 16] = [0xfd, 0x13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9];
+
+// --- MOCKS ---
+/// Hub end: One RX wire shared by all nodes, TX switched on `target`.
+struct SwitchPhy {
+    rx: Wire,
+    ports: BTreeMap<PeerAddr, Wire>,
+}
+impl PhysicalInterface for SwitchPhy {
+    fn properties(&self) -> LinkProperties { LinkProperties { mtu: 1500, bandwidth_bps: 0, is_reliable: true } }
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        let port = target.and_then(|t| self.ports.get(&t)).expect("Hub sent to an unknown peer");
+        port.lock().unwrap().push_back((frame.to_vec(), HUB_ADDR));
+        Ok(frame.len())
+    }
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        pop_into(&self.rx, buf)
+    }
+}
+
+fn ipv6_packet(src: [u8; 16], dst: [u8; 16], fill: u8) -> Vec<u8> {
+    let mut p = vec![fill; 600];
+    p[0] = 0x60;
+    p[8..24].copy_from_slice(&src);
+    p[24..40].copy_from_slice(&dst);
+    p
+}
+
+/// Reorder queued frames so the two senders alternate.
+fn interleave(rx: &Wire) {
+    let mut q = rx.lock().unwrap();
+    let first = q.front().map(|(_, src)| *src);
+    let (x, y): (Vec<_>, Vec<_>) = q.drain(..).partition(|(_, src)| Some(*src) == first);
+    let (mut x, mut y) = (x.into_iter(), y.into_iter());
+    loop {
+        match (x.next(), y.next()) {
+            (None, None) => break,
+            (f, g) => q.extend(f.into_iter().chain(g)),
+        }
+    }
+}
+
+fn run(kernels: &mut [&mut M13Kernel], t: &Arc<AtomicU64>, rounds: usize, step_us: u64) {
+    for _ in 0..rounds {
+        for k in kernels.iter_mut() { k.poll(); }
+        t.fetch_add(step_us, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_hub_routes_ipv4_and_ipv6_to_their_peers() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let v4_rx = wire();
+    let v6_rx = wire();
+
+    let ports = BTreeMap::from([(V4_NODE_ADDR, v4_rx.clone()), (V6_NODE_ADDR, v6_rx.clone())]);
+    let mut hub = build_kernel(true, SwitchPhy { rx: hub_rx.clone(), ports }, &t, 1);
+    let mut v4_node = build_kernel(false, WirePhy { local: V4_NODE_ADDR, rx: v4_rx, peer_rx: hub_rx.clone() }, &t, 2);
+    let mut v6_node = build_kernel(false, WirePhy { local: V6_NODE_ADDR, rx: v6_rx, peer_rx: hub_rx }, &t, 3);
+
+    run(&mut [&mut v4_node, &mut v6_node, &mut hub], &t, 10, 1_000);
+    assert!(hub.has_session(&V4_NODE_ADDR) && hub.has_session(&V6_NODE_ADDR));
+
+    // Each node teaches the hub its tunnel address, one per family.
+    v4_node.send_payload(&ipv4_packet(V4_VIP, [10, 13, 13, 1], 600, 0x11)).unwrap();
+    v6_node.send_payload(&ipv6_packet(V6_VIP, V6_HUB_VIP, 0x22)).unwrap();
+    run(&mut [&mut v4_node, &mut v6_node, &mut hub], &t, 4, 500);
+    while hub.pop_ingress().is_some() {}
+
+    assert_eq!(hub.route(u32::from_be_bytes(V4_VIP)), Some(V4_NODE_ADDR));
+    assert_eq!(hub.route_to(IpDest::V6(V6_VIP)), Some(V6_NODE_ADDR));
+    assert_eq!(hub.route_to(IpDest::V6(V6_UNKNOWN)), None);
+
+    hub.send_payload(&ipv4_packet([10, 13, 13, 1], V4_VIP, 600, 0x44)).unwrap();
+    hub.send_payload(&ipv6_packet(V6_HUB_VIP, V6_VIP, 0x66)).unwrap();
+    hub.send_payload(&ipv6_packet(V6_HUB_VIP, V6_UNKNOWN, 0x99)).unwrap();
+    run(&mut [&mut hub, &mut v4_node, &mut v6_node], &t, 4, 500);
+
+    let drain = |k: &mut M13Kernel| {
+        let mut fills = Vec::new();
+        while let Some(p) = k.pop_ingress() { fills.push(p[p.len() - 1]); }
+        fills
+    };
+    let v4_got = drain(&mut v4_node);
+    let v6_got = drain(&mut v6_node);
+    assert!(!v4_got.is_empty() && v4_got.iter().all(|&f| f == 0x44), "IPv4 node got {:02x?}", v4_got);
+    assert!(!v6_got.is_empty() && v6_got.iter().all(|&f| f == 0x66), "IPv6 node got {:02x?}", v6_got);
+
+    assert_eq!(hub.unroutable_drops(), 1);
+    assert_eq!(hub.stats().unroutable_drops, 1);
+}
+
+#[test]
+fn test_hub_decodes_the_same_gen_id_from_two_peers() {
+    let t = Arc::new(AtomicU64::new(3_000_000));
+    let hub_rx = wire();
+    let a_rx = wire();
+    let b_rx = wire();
+
+    let ports = BTreeMap::from([(V4_NODE_ADDR, a_rx.clone()), (V6_NODE_ADDR, b_rx.clone())]);
+    let mut hub = build_kernel(true, SwitchPhy { rx: hub_rx.clone(), ports }, &t, 1);
+    let mut a = build_kernel(false, WirePhy { local: V4_NODE_ADDR, rx: a_rx, peer_rx: hub_rx.clone() }, &t, 2);
+    let mut b = build_kernel(false, WirePhy { local: V6_NODE_ADDR, rx: b_rx, peer_rx: hub_rx.clone() }, &t, 3);
+    run(&mut [&mut a, &mut b, &mut hub], &t, 10, 1_000);
+
+    // Both nodes number their first generation 1, and its symbols reach the hub interleaved.
+    let from_a = ipv4_packet(V4_VIP, [10, 13, 13, 1], 3000, 0xAA);
+    let from_b = ipv4_packet([10, 13, 13, 3], [10, 13, 13, 1], 3000, 0xBB);
+    a.send_payload(&from_a).unwrap();
+    b.send_payload(&from_b).unwrap();
+    a.poll();
+    b.poll();
+    interleave(&hub_rx);
+    hub.poll();
+
+    // Each sender's symbols land in its own decoder: Both decode from the first burst.
+    let mut got = Vec::new();
+    while let Some(p) = hub.pop_ingress() { got.push(p); }
+    assert!(got.contains(&from_a) && got.contains(&from_b), "Delivered {} packets", got.len());
+    assert!(hub.decode_progress().is_empty(), "Stuck: {:?}", hub.decode_progress());
+    assert_eq!(hub.decode_failures(), 0);
+}
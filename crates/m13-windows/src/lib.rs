@@ -0,0 +1,164 @@
+#![cfg(windows)]
+
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{SocketAddr, IpAddr};
+use socket2::{Socket, Domain, Type, Protocol, SockAddr};
+use windows_sys::Win32::Security::Cryptography::{BCryptGenRandom, BCRYPT_USE_SYSTEM_PREFERRED_RNG};
+use windows_sys::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
+
+use m13_hal::{PhysicalInterface, LinkProperties, SecurityModule, PlatformClock, PeerAddr};
+use m13_core::{M13Error, M13Result};
+
+fn to_peer_addr(addr: SocketAddr) -> PeerAddr {
+    match addr {
+        SocketAddr::V4(v4) => PeerAddr::V4(v4.ip().octets(), v4.port()),
+        SocketAddr::V6(v6) => PeerAddr::V6(v6.ip().octets(), v6.port()),
+    }
+}
+
+fn to_socket_addr(peer: &PeerAddr) -> Option<SocketAddr> {
+    match peer {
+        PeerAddr::V4(ip, port) => Some(SocketAddr::new(IpAddr::from(*ip), *port)),
+        PeerAddr::V6(ip, port) => Some(SocketAddr::new(IpAddr::from(*ip), *port)),
+        PeerAddr::None => None,
+    }
+}
+
+/// Winsock UDP. Scalar non-blocking `recv_from`/`send_to`: The batch/GSO paths use the HAL defaults.
+pub struct WindowsUdp {
+    socket: Socket,
+    default_target: Option<PeerAddr>,
+}
+
+impl WindowsUdp {
+    pub fn new(bind_addr: &str, target_addr: Option<&str>) -> anyhow::Result<Self> {
+        let addr: SocketAddr = bind_addr.parse()?;
+        let domain = if addr.is_ipv4() { Domain::IPV4 } else { Domain::IPV6 };
+
+        let socket = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))?;
+
+        // PHYSICS FIX: 4MB Buffers
+        let buf_size = 4 * 1024 * 1024;
+        let _ = socket.set_recv_buffer_size(buf_size);
+        let _ = socket.set_send_buffer_size(buf_size);
+
+        socket.set_nonblocking(true)?;
+
+        let sa: SockAddr = addr.into();
+        socket.bind(&sa)?;
+
+        let default_target = if let Some(t) = target_addr {
+            let sa: SocketAddr = t.parse()?;
+            Some(to_peer_addr(sa))
+        } else {
+            None
+        };
+
+        Ok(Self { socket, default_target })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()?
+            .as_socket()
+            .ok_or_else(|| io::Error::other("non-IP socket"))
+    }
+}
+
+impl PhysicalInterface for WindowsUdp {
+    fn properties(&self) -> LinkProperties {
+        LinkProperties { mtu: 1400, bandwidth_bps: 1_000_000_000, is_reliable: false }
+    }
+
+    fn send(&mut self, frame: &[u8], target: Option<PeerAddr>) -> nb::Result<usize, M13Error> {
+        let dest_peer = match target.or(self.default_target) {
+            Some(t) => t,
+            None => return Ok(0),
+        };
+
+        let dest_sock = to_socket_addr(&dest_peer).ok_or(nb::Error::Other(M13Error::HalError))?;
+        let addr: SockAddr = dest_sock.into();
+
+        match self.socket.send_to(frame, &addr) {
+            Ok(n) => Ok(n),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Err(nb::Error::WouldBlock),
+            Err(_) => Err(nb::Error::Other(M13Error::HalError)),
+        }
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) -> nb::Result<(usize, PeerAddr), M13Error> {
+        // SAFETY: recv_from only writes into the buffer; u8 has no invalid bit patterns.
+        let buf_uninit = unsafe {
+            std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut MaybeUninit<u8>, buf.len())
+        };
+
+        loop {
+            match self.socket.recv_from(buf_uninit) {
+                Ok((n, src)) => {
+                    let src = src.as_socket().map(to_peer_addr).unwrap_or(PeerAddr::None);
+                    return Ok((n, src));
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Err(nb::Error::WouldBlock),
+                // [PHYSICS] WSAECONNRESET: Winsock surfaces an ICMP Port Unreachable from an
+                // earlier send_to on the next recv. One per ICMP, nothing lost: Read on.
+                Err(ref e) if e.kind() == io::ErrorKind::ConnectionReset => continue,
+                Err(_) => return Err(nb::Error::Other(M13Error::HalError)),
+            }
+        }
+    }
+}
+
+pub type WindowsPhy = WindowsUdp;
+
+/// CNG system-preferred RNG (BCryptGenRandom). Holds no signing key: `sign_digest` fails
+/// until an NCrypt (TPM/KSP) key backs it.
+pub struct WindowsHsm;
+impl SecurityModule for WindowsHsm {
+    fn get_random_bytes(&mut self, buf: &mut [u8]) -> M13Result<()> {
+        // BCryptGenRandom takes a u32 length.
+        for chunk in buf.chunks_mut(u32::MAX as usize) {
+            // SAFETY: Valid buffer of `chunk.len()` bytes; a null algorithm handle is
+            // required with BCRYPT_USE_SYSTEM_PREFERRED_RNG.
+            let status = unsafe {
+                BCryptGenRandom(std::ptr::null_mut(), chunk.as_mut_ptr(), chunk.len() as u32, BCRYPT_USE_SYSTEM_PREFERRED_RNG)
+            };
+            if status < 0 { return Err(M13Error::HalError); }
+        }
+        Ok(())
+    }
+    fn sign_digest(&mut self, _: &[u8], _: &mut [u8]) -> M13Result<usize> {
+        Err(M13Error::HalError)
+    }
+    fn panic_and_sanitize(&self) -> ! { std::process::abort(); }
+}
+
+/// QueryPerformanceCounter, relative to construction.
+pub struct WindowsClock {
+    origin: i64,
+    freq: i64,
+}
+impl WindowsClock {
+    pub fn new() -> Self {
+        let mut origin = 0i64;
+        let mut freq = 0i64;
+        // SAFETY: Both write a single i64. Cannot fail on XP and later.
+        unsafe {
+            QueryPerformanceFrequency(&mut freq);
+            QueryPerformanceCounter(&mut origin);
+        }
+        Self { origin, freq: freq.max(1) }
+    }
+}
+impl Default for WindowsClock {
+    fn default() -> Self { Self::new() }
+}
+impl PlatformClock for WindowsClock {
+    fn now_us(&self) -> u64 {
+        let mut ticks = 0i64;
+        // SAFETY: Writes a single i64.
+        unsafe { QueryPerformanceCounter(&mut ticks); }
+        let elapsed = ticks.saturating_sub(self.origin).max(0) as u128;
+        (elapsed * 1_000_000 / self.freq as u128) as u64
+    }
+    fn ptp_ns(&self) -> Option<u64> { None }
+}